@@ -0,0 +1,101 @@
+/*
+which of the optional parts of the crate were compiled in
+each one is an additive cargo feature and none of them are on by default
+the fields are only read through the methods
+    so another feature can be added without breaking anyone
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    serde: bool,
+    ordered: bool,
+    parallel: bool,
+}
+
+impl Capabilities {
+    #[allow(dead_code)]
+    pub fn serde(&self) -> bool {
+        self.serde
+    }
+
+    #[allow(dead_code)]
+    pub fn ordered(&self) -> bool {
+        self.ordered
+    }
+
+    #[allow(dead_code)]
+    pub fn parallel(&self) -> bool {
+        self.parallel
+    }
+
+    #[allow(dead_code)]
+    pub fn enabled(&self) -> Vec<&'static str> {
+        /*
+        the names of the features that are on
+        same as the names to give cargo
+        */
+        let Self {
+            serde,
+            ordered,
+            parallel,
+        } = *self;
+        [
+            ("serde", serde),
+            ("ordered", ordered),
+            ("parallel", parallel),
+        ]
+        .into_iter()
+        .filter_map(|(name, on)| on.then_some(name))
+        .collect()
+    }
+}
+
+#[allow(dead_code)]
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        serde: cfg!(feature = "serde"),
+        ordered: cfg!(feature = "ordered"),
+        parallel: cfg!(feature = "parallel"),
+    }
+}
+
+mod test {
+
+    #[test]
+    fn matches_features() {
+        use super::capabilities;
+        let found = capabilities();
+
+        #[cfg(feature = "serde")]
+        assert!(found.serde());
+        #[cfg(not(feature = "serde"))]
+        assert!(!found.serde());
+
+        #[cfg(feature = "ordered")]
+        assert!(found.ordered());
+        #[cfg(not(feature = "ordered"))]
+        assert!(!found.ordered());
+
+        #[cfg(feature = "parallel")]
+        assert!(found.parallel());
+        #[cfg(not(feature = "parallel"))]
+        assert!(!found.parallel());
+
+        #[cfg(all(feature = "serde", feature = "ordered", feature = "parallel"))]
+        assert_eq!(found.enabled(), vec!["serde", "ordered", "parallel"]);
+        #[cfg(not(any(feature = "serde", feature = "ordered", feature = "parallel")))]
+        assert!(found.enabled().is_empty());
+        #[cfg(all(feature = "serde", not(feature = "ordered"), not(feature = "parallel")))]
+        assert_eq!(found.enabled(), vec!["serde"]);
+        #[cfg(all(not(feature = "serde"), feature = "ordered", not(feature = "parallel")))]
+        assert_eq!(found.enabled(), vec!["ordered"]);
+        #[cfg(all(not(feature = "serde"), not(feature = "ordered"), feature = "parallel"))]
+        assert_eq!(found.enabled(), vec!["parallel"]);
+        #[cfg(all(feature = "serde", feature = "ordered", not(feature = "parallel")))]
+        assert_eq!(found.enabled(), vec!["serde", "ordered"]);
+        #[cfg(all(feature = "serde", not(feature = "ordered"), feature = "parallel"))]
+        assert_eq!(found.enabled(), vec!["serde", "parallel"]);
+        #[cfg(all(not(feature = "serde"), feature = "ordered", feature = "parallel"))]
+        assert_eq!(found.enabled(), vec!["ordered", "parallel"]);
+    }
+}
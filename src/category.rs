@@ -1,29 +1,684 @@
+use {crate::error::Error, num::One, std::ops::Mul};
+
 pub trait HasIdentity<T>: Sized {
     fn identity(on_this: &T) -> Self;
 }
 
 pub trait Composable<T: Eq>: Sized {
-    fn compose(&self, other: &Self) -> Result<Self, String>;
+    fn compose(&self, other: &Self) -> Result<Self, Error>;
     fn domain(&self) -> T;
     fn codomain(&self) -> T;
-    fn composable(&self, other: &Self) -> Result<(), String> {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
         if self.codomain() == other.domain() {
             Ok(())
         } else {
-            Err("Not composable. No details on how domain and codomain mismatched".to_string())
+            Err(Error::NotComposable)
         }
     }
 }
 
 pub trait ComposableMutating<T: Eq>: Sized {
-    fn compose(&mut self, other: Self) -> Result<(), String>;
+    fn compose(&mut self, other: Self) -> Result<(), Error>;
     fn domain(&self) -> T;
     fn codomain(&self) -> T;
-    fn composable(&self, other: &Self) -> Result<(), String> {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
         if self.codomain() == other.domain() {
             Ok(())
         } else {
-            Err("Not composable. No details on how domain and codomain mismatched".to_string())
+            Err(Error::NotComposable)
         }
     }
 }
+
+#[allow(dead_code)]
+pub fn compose_all<T, M, I>(morphisms: I) -> Result<M, Error>
+where
+    T: Eq,
+    M: Composable<T>,
+    I: IntoIterator<Item = M>,
+{
+    /*
+    the first morphism then the second and so on
+    stopping at the first pair that do not compose
+    there is no identity to give back when there are none so that is an error
+    */
+    let mut morphisms = morphisms.into_iter();
+    let first = morphisms.next().ok_or(Error::EmptyComposition)?;
+    morphisms.try_fold(first, |so_far, next| so_far.compose(&next))
+}
+
+#[allow(dead_code)]
+pub fn compose_all_mutating<T, M, I>(morphisms: I) -> Result<M, Error>
+where
+    T: Eq,
+    M: ComposableMutating<T>,
+    I: IntoIterator<Item = M>,
+{
+    /*
+    the same as compose_all but building up the composite in place
+    */
+    let mut morphisms = morphisms.into_iter();
+    let mut answer = morphisms.next().ok_or(Error::EmptyComposition)?;
+    for next in morphisms {
+        answer.compose(next)?;
+    }
+    Ok(answer)
+}
+
+#[allow(dead_code)]
+pub trait Dagger<T: Eq>: Sized {
+    /*
+    an involution that reverses morphisms
+    so the domain of self.dagger() is the codomain of self and vice versa
+    and (f;g).dagger() = g.dagger();f.dagger()
+    no composition is required so that ComposableMutating morphisms can have one too
+    */
+    fn dagger(&self) -> Self;
+
+    fn is_unitary(&self) -> Result<bool, Error>
+    where
+        Self: Composable<T> + HasIdentity<T> + PartialEq,
+    {
+        /*
+        f;f.dagger() is the identity on the domain
+        and f.dagger();f is the identity on the codomain
+        an error if those could not even be composed
+        */
+        let dagger = self.dagger();
+        Ok(self.compose(&dagger)? == Self::identity(&self.domain())
+            && dagger.compose(self)? == Self::identity(&self.codomain()))
+    }
+}
+
+/*
+the opposite category
+the same morphisms but with domain and codomain swapped
+so composing in the opposite order
+useful for contravariant functors without needing a dagger
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Op<M>(pub M);
+
+impl<T, M> HasIdentity<T> for Op<M>
+where
+    M: HasIdentity<T>,
+{
+    fn identity(on_this: &T) -> Self {
+        Self(M::identity(on_this))
+    }
+}
+
+impl<T, M> Composable<T> for Op<M>
+where
+    T: Eq,
+    M: Composable<T>,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        other.0.compose(&self.0).map(Self)
+    }
+
+    fn domain(&self) -> T {
+        self.0.codomain()
+    }
+
+    fn codomain(&self) -> T {
+        self.0.domain()
+    }
+
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        other.0.composable(&self.0)
+    }
+}
+
+impl<T, M> ComposableMutating<T> for Op<M>
+where
+    T: Eq,
+    M: ComposableMutating<T>,
+{
+    fn compose(&mut self, other: Self) -> Result<(), Error> {
+        /*
+        self becomes other.0;self.0 underneath
+        left alone if that fails
+        */
+        other.0.composable(&self.0)?;
+        let first = std::mem::replace(&mut self.0, other.0);
+        self.0.compose(first)
+    }
+
+    fn domain(&self) -> T {
+        self.0.codomain()
+    }
+
+    fn codomain(&self) -> T {
+        self.0.domain()
+    }
+
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        other.0.composable(&self.0)
+    }
+}
+
+impl<T, M> Dagger<T> for Op<M>
+where
+    T: Eq,
+    M: Dagger<T>,
+{
+    fn dagger(&self) -> Self {
+        Self(self.0.dagger())
+    }
+}
+
+/*
+the product of two categories
+a pair of morphisms composed and tensored componentwise
+the objects are pairs of objects
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ProductMorphism<A, B>(pub A, pub B);
+
+impl<TA, TB, A, B> HasIdentity<(TA, TB)> for ProductMorphism<A, B>
+where
+    A: HasIdentity<TA>,
+    B: HasIdentity<TB>,
+{
+    fn identity(on_this: &(TA, TB)) -> Self {
+        Self(A::identity(&on_this.0), B::identity(&on_this.1))
+    }
+}
+
+impl<TA, TB, A, B> Composable<(TA, TB)> for ProductMorphism<A, B>
+where
+    TA: Eq,
+    TB: Eq,
+    A: Composable<TA>,
+    B: Composable<TB>,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        Ok(Self(self.0.compose(&other.0)?, self.1.compose(&other.1)?))
+    }
+
+    fn domain(&self) -> (TA, TB) {
+        (self.0.domain(), self.1.domain())
+    }
+
+    fn codomain(&self) -> (TA, TB) {
+        (self.0.codomain(), self.1.codomain())
+    }
+
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        self.0.composable(&other.0)?;
+        self.1.composable(&other.1)
+    }
+}
+
+impl<TA, TB, A, B> ComposableMutating<(TA, TB)> for ProductMorphism<A, B>
+where
+    TA: Eq,
+    TB: Eq,
+    A: ComposableMutating<TA>,
+    B: ComposableMutating<TB>,
+{
+    fn compose(&mut self, other: Self) -> Result<(), Error> {
+        /*
+        both are checked first so that a failure leaves self as it was
+        */
+        self.composable(&other)?;
+        self.0.compose(other.0)?;
+        self.1.compose(other.1)
+    }
+
+    fn domain(&self) -> (TA, TB) {
+        (self.0.domain(), self.1.domain())
+    }
+
+    fn codomain(&self) -> (TA, TB) {
+        (self.0.codomain(), self.1.codomain())
+    }
+
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        self.0.composable(&other.0)?;
+        self.1.composable(&other.1)
+    }
+}
+
+impl<TA, TB, A, B> Dagger<(TA, TB)> for ProductMorphism<A, B>
+where
+    TA: Eq,
+    TB: Eq,
+    A: Dagger<TA>,
+    B: Dagger<TB>,
+{
+    fn dagger(&self) -> Self {
+        Self(self.0.dagger(), self.1.dagger())
+    }
+}
+
+pub trait Effect: Clone {
+    /*
+    something composition produces besides the composite itself
+    pure is having produced nothing
+    then is having produced self and afterwards next
+    these should make a monoid
+    */
+    fn pure() -> Self;
+    fn then(&self, next: &Self) -> Self;
+}
+
+impl Effect for () {
+    fn pure() -> Self {}
+
+    fn then(&self, _next: &Self) -> Self {}
+}
+
+impl Effect for bool {
+    /*
+    whether nothing has gone wrong yet
+    for example whether a composite of partial functions stayed as defined as the first one
+    */
+    fn pure() -> Self {
+        true
+    }
+
+    fn then(&self, next: &Self) -> Self {
+        *self && *next
+    }
+}
+
+/*
+an effect that adds up like closed loops removed or some cost of composing
+*/
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Count(pub usize);
+
+impl Effect for Count {
+    fn pure() -> Self {
+        Self(0)
+    }
+
+    fn then(&self, next: &Self) -> Self {
+        Self(self.0 + next.0)
+    }
+}
+
+/*
+an effect that multiplies like probabilities or scalars pulled out of a composite
+*/
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Weight<R>(pub R);
+
+impl<R> Effect for Weight<R>
+where
+    R: One + Mul<Output = R> + Clone,
+{
+    fn pure() -> Self {
+        Self(R::one())
+    }
+
+    fn then(&self, next: &Self) -> Self {
+        Self(self.0.clone() * next.0.clone())
+    }
+}
+
+pub trait ComposableFallible<T: Eq>: Sized {
+    /*
+    like Composable but a composite comes with an Effect
+    the error is still for when the two could not be composed at all
+    */
+    type Effect: Effect;
+
+    fn compose_effectful(&self, other: &Self) -> Result<(Self, Self::Effect), Error>;
+    fn domain(&self) -> T;
+    fn codomain(&self) -> T;
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        if self.codomain() == other.domain() {
+            Ok(())
+        } else {
+            Err(Error::NotComposable)
+        }
+    }
+}
+
+/*
+any Composable as a ComposableFallible with no effect
+*/
+#[allow(dead_code)]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Pure<M>(pub M);
+
+impl<T, M> ComposableFallible<T> for Pure<M>
+where
+    T: Eq,
+    M: Composable<T>,
+{
+    type Effect = ();
+
+    fn compose_effectful(&self, other: &Self) -> Result<(Self, ()), Error> {
+        Ok((Self(self.0.compose(&other.0)?), ()))
+    }
+
+    fn domain(&self) -> T {
+        self.0.domain()
+    }
+
+    fn codomain(&self) -> T {
+        self.0.codomain()
+    }
+
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        self.0.composable(&other.0)
+    }
+}
+
+/*
+a ComposableFallible as a Composable
+by carrying along everything its compositions have produced so far
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Effectful<M, E> {
+    pub morphism: M,
+    pub effect: E,
+}
+
+impl<M, E: Effect> Effectful<M, E> {
+    #[allow(dead_code)]
+    pub fn new(morphism: M) -> Self {
+        Self {
+            morphism,
+            effect: E::pure(),
+        }
+    }
+}
+
+impl<T, M, E> HasIdentity<T> for Effectful<M, E>
+where
+    M: HasIdentity<T>,
+    E: Effect,
+{
+    fn identity(on_this: &T) -> Self {
+        Self::new(M::identity(on_this))
+    }
+}
+
+impl<T, M, E> Composable<T> for Effectful<M, E>
+where
+    T: Eq,
+    M: ComposableFallible<T, Effect = E>,
+    E: Effect,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        let (morphism, produced) = self.morphism.compose_effectful(&other.morphism)?;
+        Ok(Self {
+            morphism,
+            effect: self.effect.then(&other.effect).then(&produced),
+        })
+    }
+
+    fn domain(&self) -> T {
+        self.morphism.domain()
+    }
+
+    fn codomain(&self) -> T {
+        self.morphism.codomain()
+    }
+
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        self.morphism.composable(&other.morphism)
+    }
+}
+
+mod test {
+
+    #[test]
+    fn opposite_relations() {
+        use super::{Composable, Dagger, HasIdentity, Op};
+        use crate::monoidal::Monoidal;
+        use crate::span::Rel;
+        use crate::symmetric_monoidal::SymmetricMonoidalMorphism;
+        use permutations::Permutation;
+        let types = ['a', 'b', 'c'];
+        let p = Permutation::try_from(vec![1, 2, 0]).unwrap();
+        let q = Permutation::try_from(vec![0, 2, 1]).unwrap();
+        let f = Rel::from_permutation(p.clone(), &types, true);
+        let g = Rel::from_permutation(q.clone(), &f.codomain(), true);
+        let (op_f, op_g) = (Op(f.clone()), Op(g.clone()));
+        assert_eq!(op_f.domain(), f.codomain());
+        assert_eq!(op_f.codomain(), f.domain());
+        assert!(op_g.compose(&op_f).unwrap() == Op(f.compose(&g).unwrap()));
+        assert_eq!(op_f.compose(&op_g).is_ok(), g.compose(&f).is_ok());
+        assert!(op_g.composable(&op_f).is_ok());
+        assert!(Op::<Rel<char>>::identity(&types.to_vec()) == Op(Rel::identity(&types.to_vec())));
+
+        /*
+        the dagger is a functor from the opposite back to Rel
+        so it should take the permutations and relabellings in the opposite
+            to the same ones in Rel
+        */
+        for types_as_on_domain in [true, false] {
+            let op_p = Op::<Rel<char>>::from_permutation(p.clone(), &types, types_as_on_domain);
+            let p_itself = Rel::from_permutation(p.clone(), &types, types_as_on_domain);
+            assert!(op_p.0.dagger() == p_itself);
+            for of_codomain in [true, false] {
+                let mut op_relabelled = op_p.clone();
+                op_relabelled.permute_side(&q, of_codomain);
+                let mut relabelled = p_itself.clone();
+                relabelled.permute_side(&q, of_codomain);
+                assert!(op_relabelled.0.dagger() == relabelled);
+            }
+        }
+        let mut tensored = op_f.clone();
+        tensored.monoidal(op_g);
+        let mut f_tensor_g = f;
+        f_tensor_g.monoidal(g);
+        assert!(tensored == Op(f_tensor_g));
+    }
+
+    #[test]
+    fn opposite_mutating() {
+        use super::{ComposableMutating, Op};
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        use crate::monoidal::GenericMonoidalMorphism;
+        type Generic = GenericMonoidalMorphism<FrobeniusOperation<char, ()>, char>;
+        let merge: Generic = monoidal!([FrobeniusOperation::Multiplication('a')]).unwrap();
+        let split: Generic = monoidal!([FrobeniusOperation::Comultiplication('a')]).unwrap();
+        let mut op_merge = Op(merge.clone());
+        assert_eq!(op_merge.domain(), vec!['a']);
+        assert_eq!(op_merge.codomain(), vec!['a', 'a']);
+        let wrong_way = Op(merge.clone());
+        assert!(op_merge.compose(wrong_way).is_err());
+        assert!(op_merge == Op(merge.clone()));
+        op_merge.compose(Op(split.clone())).unwrap();
+        let mut expected = split;
+        expected.compose(merge).unwrap();
+        assert!(op_merge == Op(expected));
+    }
+
+    #[test]
+    fn product_category() {
+        use super::{Composable, ComposableMutating, Dagger, HasIdentity, ProductMorphism};
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        use crate::monoidal::{GenericMonoidalMorphism, Monoidal};
+        use crate::span::Rel;
+        use crate::symmetric_monoidal::SymmetricMonoidalMorphism;
+        use permutations::Permutation;
+        let swap = Rel::from_permutation(
+            Permutation::try_from(vec![1, 0]).unwrap(),
+            &['a', 'b'],
+            true,
+        );
+        let id_ab = Rel::identity(&vec!['a', 'b']);
+        let pair = ProductMorphism(swap.clone(), id_ab.clone());
+        assert_eq!(pair.domain(), (vec!['a', 'b'], vec!['a', 'b']));
+        assert_eq!(pair.codomain(), (vec!['b', 'a'], vec!['a', 'b']));
+        let back = ProductMorphism(swap.dagger(), id_ab.clone());
+        assert!(pair.compose(&back).unwrap() == ProductMorphism::identity(&pair.domain()));
+        assert!(pair.dagger() == back);
+        assert!(pair.compose(&pair).is_err());
+        let mut doubled = pair.clone();
+        doubled.monoidal(pair.clone());
+        assert_eq!(doubled.codomain().0, vec!['b', 'a', 'b', 'a']);
+
+        type Generic = GenericMonoidalMorphism<FrobeniusOperation<char, ()>, char>;
+        let merge: Generic = monoidal!([FrobeniusOperation::Multiplication('a')]).unwrap();
+        let split: Generic = monoidal!([FrobeniusOperation::Comultiplication('a')]).unwrap();
+        let mut mutated = ProductMorphism(merge.clone(), split.clone());
+        let untouched = mutated.clone();
+        assert!(mutated
+            .compose(ProductMorphism(split.clone(), split.clone()))
+            .is_err());
+        assert!(mutated == untouched);
+        mutated
+            .compose(ProductMorphism(split.clone(), merge.clone()))
+            .unwrap();
+        assert_eq!(mutated.domain(), (vec!['a', 'a'], vec!['a']));
+        assert_eq!(mutated.codomain(), (vec!['a', 'a'], vec!['a']));
+    }
+
+    #[test]
+    fn effectful_composition() {
+        use super::{
+            Composable, ComposableFallible, Count, Effect, Effectful, HasIdentity, Pure, Weight,
+        };
+        use crate::error::Error;
+        use crate::span::Rel;
+        use num::rational::Ratio;
+
+        #[derive(Clone, PartialEq, Debug)]
+        struct PartialFunction {
+            /*
+            from 0..targets.len() to 0..codomain
+            with None where it is not defined
+            */
+            targets: Vec<Option<usize>>,
+            codomain: usize,
+        }
+        impl ComposableFallible<usize> for PartialFunction {
+            /*
+            whether everything self was defined on stays defined
+            */
+            type Effect = bool;
+
+            fn compose_effectful(&self, other: &Self) -> Result<(Self, bool), Error> {
+                ComposableFallible::composable(self, other)?;
+                let targets: Vec<_> = self
+                    .targets
+                    .iter()
+                    .map(|t| t.and_then(|j| other.targets[j]))
+                    .collect();
+                let kept = self
+                    .targets
+                    .iter()
+                    .zip(&targets)
+                    .all(|(before, after)| before.is_none() || after.is_some());
+                Ok((
+                    Self {
+                        targets,
+                        codomain: other.codomain,
+                    },
+                    kept,
+                ))
+            }
+
+            fn domain(&self) -> usize {
+                self.targets.len()
+            }
+
+            fn codomain(&self) -> usize {
+                self.codomain
+            }
+        }
+        impl HasIdentity<usize> for PartialFunction {
+            fn identity(on_this: &usize) -> Self {
+                Self {
+                    targets: (0..*on_this).map(Some).collect(),
+                    codomain: *on_this,
+                }
+            }
+        }
+
+        type Tracked = Effectful<PartialFunction, bool>;
+        let f = Tracked::new(PartialFunction {
+            targets: vec![Some(1), None, Some(0)],
+            codomain: 2,
+        });
+        let total = Tracked::new(PartialFunction {
+            targets: vec![Some(1), Some(0)],
+            codomain: 2,
+        });
+        let drops_one = Tracked::new(PartialFunction {
+            targets: vec![Some(0), None],
+            codomain: 1,
+        });
+        let f_total = f.compose(&total).unwrap();
+        assert!(f_total.effect);
+        assert_eq!(f_total.morphism.targets, vec![Some(0), None, Some(1)]);
+        let f_total_drops = f_total.compose(&drops_one).unwrap();
+        assert!(!f_total_drops.effect);
+        assert_eq!(f_total_drops.morphism.targets, vec![Some(0), None, None]);
+        assert!(
+            !f_total_drops
+                .compose(&Tracked::identity(&1))
+                .unwrap()
+                .effect
+        );
+        assert!(f.compose(&f).is_err());
+
+        let id_ab = Pure(Rel::<char>::identity(&vec!['a', 'b']));
+        let (composite, ()) = id_ab.compose_effectful(&id_ab).unwrap();
+        assert!(composite.0 == id_ab.0);
+        let pure_tracked = Effectful::<_, ()>::new(id_ab.clone());
+        assert!(pure_tracked.compose(&pure_tracked).unwrap().morphism.0 == id_ab.0);
+
+        assert_eq!(Count(2).then(&Count::pure()).then(&Count(3)), Count(5));
+        let half = Weight(Ratio::new(1, 2));
+        assert_eq!(
+            half.then(&half).then(&Weight::pure()),
+            Weight(Ratio::new(1, 4))
+        );
+    }
+
+    #[test]
+    fn composing_many() {
+        use super::{compose_all, compose_all_mutating, Composable};
+        use crate::error::Error;
+        use crate::monoidal::GenericMonoidalMorphism;
+        use crate::span::Rel;
+        use crate::symmetric_monoidal::SymmetricMonoidalMorphism;
+        use permutations::Permutation;
+        type Op = crate::frobenius::FrobeniusOperation<char, ()>;
+        let types = ['a', 'b', 'c'];
+        let p = Permutation::try_from(vec![1, 2, 0]).unwrap();
+        let q = Permutation::try_from(vec![0, 2, 1]).unwrap();
+        let f = Rel::from_permutation(p.clone(), &types, true);
+        let g = Rel::from_permutation(q.clone(), &f.codomain(), true);
+        let h = Rel::from_permutation(p.clone(), &g.codomain(), true);
+        let by_hand = f.compose(&g).and_then(|z| z.compose(&h)).unwrap();
+        assert!(compose_all([f.clone(), g.clone(), h.clone()]).unwrap() == by_hand);
+        assert!(compose_all([f.clone()]).unwrap() == f);
+        assert_eq!(
+            compose_all(Vec::<Rel<char>>::new()).err(),
+            Some(Error::EmptyComposition)
+        );
+        assert_eq!(
+            compose_all([f.clone(), f.clone()]).err(),
+            f.compose(&f).err()
+        );
+        assert!(compose_all([f.clone(), f.clone()]).is_err());
+
+        let merge = crate::monoidal!([Op::Multiplication('a')]).unwrap();
+        let merges: Vec<GenericMonoidalMorphism<Op, char>> = vec![
+            crate::monoidal!([Op::Multiplication('a'), id('a')]).unwrap(),
+            merge.clone(),
+        ];
+        let composed = compose_all_mutating(merges.clone()).unwrap();
+        assert_eq!(composed.depth(), 2);
+        assert!(compose_all_mutating(merges.into_iter().rev()).is_err());
+        assert!(matches!(
+            compose_all_mutating(Vec::<GenericMonoidalMorphism<Op, char>>::new()),
+            Err(Error::EmptyComposition)
+        ));
+    }
+}
@@ -1,19 +1,26 @@
 use {
     crate::{
-        category::{Composable, HasIdentity},
-        finset::FinSetMap,
-        monoidal::{GenericMonoidalInterpretable, Monoidal, MonoidalMorphism},
+        category::{Composable, Dagger, HasIdentity},
+        error::Error,
+        finset::{epi_mono_factorization, finset_morphism, is_epi, FinSetMap, FinSetMorphism},
+        frobenius::HypergraphCategory,
+        monoidal::Monoidal,
         symmetric_monoidal::SymmetricMonoidalMorphism,
-        utils::{in_place_permute, represents_id, EitherExt},
+        utils::{compact_list, in_place_permute, represents_id, EitherExt},
     },
     either::Either::{self, Left, Right},
+    itertools::Itertools,
     log::warn,
     permutations::Permutation,
     petgraph::{
         prelude::Graph,
         stable_graph::{DefaultIx, NodeIndex},
     },
-    std::{collections::HashMap, fmt::Debug},
+    std::{
+        cmp::Reverse,
+        collections::{BinaryHeap, HashMap},
+        fmt::Debug,
+    },
     union_find::{UnionBySize, UnionFind},
 };
 
@@ -101,6 +108,43 @@ where
         &self.right
     }
 
+    pub fn middle(&self) -> &[Lambda] {
+        &self.middle
+    }
+
+    #[allow(dead_code)]
+    pub fn from_legs(
+        left: FinSetMorphism,
+        right: FinSetMorphism,
+        middle: Vec<Lambda>,
+    ) -> Result<Self, Error> {
+        /*
+        the two functions into the middle as morphisms in FinSet
+        which must both land in a set with one element for each label of middle
+        */
+        for leg in [&left, &right] {
+            if leg.codomain() != middle.len() {
+                return Err(Error::InterfaceLengthMismatch {
+                    left: middle.len(),
+                    right: leg.codomain(),
+                });
+            }
+        }
+        Ok(Self::new(left.0, right.0, middle))
+    }
+
+    #[allow(dead_code)]
+    pub fn left_leg(&self) -> FinSetMorphism {
+        finset_morphism(self.left.clone(), self.middle.len())
+            .expect("the left leg lands in the middle")
+    }
+
+    #[allow(dead_code)]
+    pub fn right_leg(&self) -> FinSetMorphism {
+        finset_morphism(self.right.clone(), self.middle.len())
+            .expect("the right leg lands in the middle")
+    }
+
     #[allow(dead_code)]
     pub fn add_boundary_node_known_target(
         &mut self,
@@ -213,10 +257,18 @@ where
         let _ = self.middle.swap_remove(mid_for_node_2);
         let old_last = self.middle.len();
         let last_removed = mid_for_node_2 == old_last;
+        /*
+        if the middle node of node_1 was the last one it has just been moved
+        */
+        let merged = if mid_for_node_1 == old_last {
+            mid_for_node_2
+        } else {
+            mid_for_node_1
+        };
         self.left.iter_mut().for_each(|v| {
             #[allow(clippy::needless_else)]
             if mid_for_node_2 == *v {
-                *v = mid_for_node_1;
+                *v = merged;
             } else if *v == old_last && !last_removed {
                 *v = mid_for_node_2;
             } else {
@@ -225,12 +277,14 @@ where
         self.right.iter_mut().for_each(|v| {
             #[allow(clippy::needless_else)]
             if mid_for_node_2 == *v {
-                *v = mid_for_node_1;
+                *v = merged;
             } else if *v == old_last && !last_removed {
                 *v = mid_for_node_2;
             } else {
             }
         });
+        self.is_left_id = represents_id(self.left.iter().cloned());
+        self.is_right_id = represents_id(self.right.iter().cloned());
     }
 
     pub fn add_middle(&mut self, new_middle: Lambda) -> MiddleIndex {
@@ -243,6 +297,56 @@ where
         self.middle.len() - 1
     }
 
+    #[allow(dead_code)]
+    pub fn merge_middle(
+        &mut self,
+        pairs: &[(MiddleIndex, MiddleIndex)],
+    ) -> Result<FinSetMap, Error> {
+        /*
+        quotient the sink by the equivalence relation generated by pairs
+        each class becomes a single middle node with the shared label
+            and the classes are in the order of their first members
+        returns where each of the old middle nodes went
+        an error and no change if a pair is out of range
+            or would merge middle nodes with different labels
+        */
+        let middle_count = self.middle.len();
+        let mut uf = crate::QuickUnionUf::<UnionBySize>::new(middle_count);
+        for (mid_1, mid_2) in pairs {
+            if *mid_1 >= middle_count || *mid_2 >= middle_count {
+                return Err(format!(
+                    "({}, {}) are not both middle nodes of {}",
+                    mid_1, mid_2, middle_count
+                )
+                .into());
+            }
+            if self.middle[*mid_1] != self.middle[*mid_2] {
+                return Err(format!(
+                    "Can not merge a middle node labelled {:?} with one labelled {:?}",
+                    self.middle[*mid_1], self.middle[*mid_2]
+                )
+                .into());
+            }
+            uf.union(*mid_1, *mid_2);
+        }
+        let mut set_to_part_num = HashMap::new();
+        let mut new_middle = Vec::with_capacity(middle_count);
+        let quotient: FinSetMap = (0..middle_count)
+            .map(|idx| {
+                *set_to_part_num.entry(uf.find(idx)).or_insert_with(|| {
+                    new_middle.push(self.middle[idx]);
+                    new_middle.len() - 1
+                })
+            })
+            .collect();
+        self.left.iter_mut().for_each(|v| *v = quotient[*v]);
+        self.right.iter_mut().for_each(|v| *v = quotient[*v]);
+        self.middle = new_middle;
+        self.is_left_id = represents_id(self.left.iter().cloned());
+        self.is_right_id = represents_id(self.right.iter().cloned());
+        Ok(quotient)
+    }
+
     pub fn map<F, Mu>(&self, f: F) -> Cospan<Mu>
     where
         F: Fn(Lambda) -> Mu,
@@ -258,6 +362,338 @@ where
         )
     }
 
+    #[allow(dead_code)]
+    pub fn substitute_strand_label<F, Mu>(&self, f: F) -> Cospan<Mu>
+    where
+        F: Fn(Lambda) -> Vec<Mu>,
+        Mu: Sized + Eq + Copy + Debug,
+    {
+        /*
+        replace each middle node with label lambda by f(lambda).len() middle nodes with those labels
+        each boundary node that went to it is replaced by the same number of boundary nodes
+        going to the new middle nodes in order
+        this is cabling where how many strands and their labels depend on the old label
+        */
+        let expansions: Vec<Vec<Mu>> = self.middle.iter().map(|l| f(*l)).collect();
+        let offsets: Vec<usize> = expansions
+            .iter()
+            .scan(0, |so_far, expansion| {
+                let offset = *so_far;
+                *so_far += expansion.len();
+                Some(offset)
+            })
+            .collect();
+        let expand_side = |side: &[MiddleIndex]| {
+            side.iter()
+                .flat_map(|m| (0..expansions[*m].len()).map(|s| offsets[*m] + s))
+                .collect::<Vec<_>>()
+        };
+        Cospan::new(
+            expand_side(&self.left),
+            expand_side(&self.right),
+            expansions.iter().flatten().cloned().collect(),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn is_isomorphic_to(&self, other: &Self) -> bool {
+        /*
+        whether the two are the same up to renumbering the middle
+        that is a bijection of the middles that keeps the labels
+            and takes where each boundary node goes in self to where it goes in other
+        the boundary nodes are matched up by their positions
+        the middle nodes that no boundary node goes to can only be matched by their labels
+        */
+        if self.left.len() != other.left.len()
+            || self.right.len() != other.right.len()
+            || self.middle.len() != other.middle.len()
+        {
+            return false;
+        }
+        let mut forward: Vec<Option<MiddleIndex>> = vec![None; self.middle.len()];
+        let mut backward: Vec<Option<MiddleIndex>> = vec![None; other.middle.len()];
+        let boundary = self
+            .left
+            .iter()
+            .zip(&other.left)
+            .chain(self.right.iter().zip(&other.right));
+        for (mine, theirs) in boundary {
+            match (forward[*mine], backward[*theirs]) {
+                (None, None) => {
+                    if self.middle[*mine] != other.middle[*theirs] {
+                        return false;
+                    }
+                    forward[*mine] = Some(*theirs);
+                    backward[*theirs] = Some(*mine);
+                }
+                (Some(image), _) if image == *theirs => {}
+                _ => return false,
+            }
+        }
+        let mut unmatched_theirs: Vec<Lambda> = backward
+            .iter()
+            .zip(&other.middle)
+            .filter(|(preimage, _)| preimage.is_none())
+            .map(|(_, label)| *label)
+            .collect();
+        for (_, label) in forward
+            .iter()
+            .zip(&self.middle)
+            .filter(|(image, _)| image.is_none())
+        {
+            let Some(idx) = unmatched_theirs.iter().position(|z| z == label) else {
+                return false;
+            };
+            unmatched_theirs.swap_remove(idx);
+        }
+        true
+    }
+
+    #[allow(dead_code)]
+    pub fn compose_with_middle_maps(
+        &self,
+        other: &Self,
+    ) -> Result<(Self, FinSetMap, FinSetMap), Error> {
+        /*
+        the composition along with where the middle nodes of self and of other
+            went in the middle of the composition
+        for anything else that was attached to the middles and has to be carried along
+        */
+        self.composable(other)?;
+        let (pushout_target, left_to_pushout, right_to_pushout, representative) =
+            perform_pushout::<crate::QuickUnionUf<crate::UnionBySize>>(
+                &self.right,
+                self.middle.len(),
+                self.is_right_id,
+                &other.left,
+                other.middle.len(),
+                other.is_left_id,
+            )?;
+        let mut composition =
+            Self::with_capacity(self.left.len(), other.right.len(), pushout_target);
+        for repr in representative {
+            composition.add_middle(match repr {
+                Left(z) => self.middle[z],
+                Right(z) => other.middle[z],
+            });
+        }
+        for target_in_self_middle in &self.left {
+            let target_in_pushout = left_to_pushout[*target_in_self_middle];
+            composition.add_boundary_node(Left(Left(target_in_pushout)));
+        }
+        for target_in_other_middle in &other.right {
+            let target_in_pushout = right_to_pushout[*target_in_other_middle];
+            composition.add_boundary_node(Right(Left(target_in_pushout)));
+        }
+        Ok((composition, left_to_pushout, right_to_pushout))
+    }
+
+    #[allow(dead_code)]
+    fn copairing(&self) -> FinSetMorphism {
+        /*
+        both legs at once as the function from the disjoint union of the two sides
+        */
+        let both_sides = self.left.iter().chain(&self.right).copied().collect();
+        finset_morphism(both_sides, self.middle.len()).expect("both legs land in the middle")
+    }
+
+    #[allow(dead_code)]
+    pub fn is_jointly_epic(&self) -> bool {
+        /*
+        every middle node is reached from one side or the other
+        which is what makes this a corelation
+        */
+        is_epi(&self.copairing())
+    }
+
+    #[allow(dead_code)]
+    pub fn joint_image_factorization(&self) -> (Self, FinSetMorphism) {
+        /*
+        the legs factored through their joint image
+        the first part is the corelation with only the middle nodes either side reaches
+            in the order they were in before
+        the second is the inclusion of that into the old middle
+            so following each leg of the corelation by it gives the old leg back
+        */
+        let (surjection, inclusion) = epi_mono_factorization(&self.copairing());
+        let (left, right) = surjection.0.split_at(self.left.len());
+        let middle = inclusion.0.iter().map(|old| self.middle[*old]).collect();
+        (Self::new(left.to_vec(), right.to_vec(), middle), inclusion)
+    }
+
+    #[allow(dead_code)]
+    pub fn to_corelation(&self) -> Self {
+        self.joint_image_factorization().0
+    }
+
+    pub fn middle_order_crossings(&self, middle_order: &[MiddleIndex]) -> usize {
+        /*
+        draw this as three columns, left then middle then right
+        with the left and right in their given order
+        and the middle in the order given by middle_order
+            middle_order[p] is the middle node drawn in position p
+        how many pairs of wires cross
+        */
+        let mut position = vec![0; self.middle.len()];
+        for (p, m) in middle_order.iter().enumerate() {
+            position[*m] = p;
+        }
+        let count_side = |side: &[MiddleIndex]| {
+            side.iter()
+                .map(|m| position[*m])
+                .enumerate()
+                .tuple_combinations()
+                .filter(|((_, m1), (_, m2))| m1 > m2)
+                .count()
+        };
+        count_side(&self.left) + count_side(&self.right)
+    }
+
+    pub fn planar_middle_order(&self) -> Option<Vec<MiddleIndex>> {
+        /*
+        an order for the middle column with no crossings at all
+            with the left and right columns kept as they are
+        or None when there is no such order
+        there are no crossings exactly when going down either side
+            the middle nodes reached never go back up
+        so each consecutive pair on a side that go to different middle nodes
+            says the first of those has to be above the second
+        and a crossing free order exists exactly when those constraints have no cycle
+        a topological sort finds it, taking the lowest numbered middle node when there is a choice
+        */
+        let middle_len = self.middle.len();
+        let mut below: Vec<Vec<MiddleIndex>> = vec![vec![]; middle_len];
+        let mut above_count = vec![0; middle_len];
+        for side in [&self.left, &self.right] {
+            for (upper, lower) in side.iter().tuple_windows() {
+                if upper != lower {
+                    below[*upper].push(*lower);
+                    above_count[*lower] += 1;
+                }
+            }
+        }
+        let mut ready: BinaryHeap<Reverse<MiddleIndex>> = (0..middle_len)
+            .filter(|m| above_count[*m] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(middle_len);
+        while let Some(Reverse(m)) = ready.pop() {
+            order.push(m);
+            for lower in &below[m] {
+                above_count[*lower] -= 1;
+                if above_count[*lower] == 0 {
+                    ready.push(Reverse(*lower));
+                }
+            }
+        }
+        (order.len() == middle_len).then_some(order)
+    }
+
+    #[allow(dead_code)]
+    pub fn layout_middle_order(&self) -> Vec<MiddleIndex> {
+        /*
+        an order for the middle column which tries to make few crossings
+        with the left and right columns kept as they are
+        when there is an order with no crossings it is the one from planar_middle_order
+        otherwise this is a heuristic so it need not find the fewest possible crossings
+        */
+        self.layout_middle_order_pinned(&[])
+            .expect("With nothing pinned there is nothing to conflict")
+    }
+
+    pub fn layout_middle_order_pinned(
+        &self,
+        pinned: &[(MiddleIndex, usize)],
+    ) -> Result<Vec<MiddleIndex>, Error> {
+        /*
+        same as layout_middle_order but each (m,p) in pinned
+        says middle node m has to be drawn at position p
+        so a drawing can stay the same in those places after the cospan is edited
+        the rest are placed in the free positions
+        if the order from planar_middle_order has every pinned node in its place use that
+        otherwise start by sorting by the average height of the boundary nodes connected to each middle node
+            with the heights on both sides scaled to 0..1
+            and unconnected middle nodes kept where they were
+        then keep swapping middle nodes in consecutive free positions
+            while that lowers the number of crossings
+        */
+        let middle_len = self.middle.len();
+        let mut order: Vec<Option<MiddleIndex>> = vec![None; middle_len];
+        let mut is_pinned = vec![false; middle_len];
+        for (m, p) in pinned {
+            if *m >= middle_len || *p >= middle_len {
+                return Err(format!(
+                    "Pinned middle node {} at position {} but there are only {}",
+                    m, p, middle_len
+                )
+                .into());
+            }
+            if is_pinned[*m] || order[*p].is_some() {
+                return Err(format!(
+                    "Pinning middle node {} at position {} conflicted with another pin",
+                    m, p
+                )
+                .into());
+            }
+            is_pinned[*m] = true;
+            order[*p] = Some(*m);
+        }
+        if let Some(planar) = self.planar_middle_order() {
+            if pinned.iter().all(|(m, p)| planar[*p] == *m) {
+                return Ok(planar);
+            }
+        }
+        let mut height_sums = vec![(0.0, 0); self.middle.len()];
+        for (side, side_len) in [
+            (&self.left, self.left.len()),
+            (&self.right, self.right.len()),
+        ] {
+            for (idx, m) in side.iter().enumerate() {
+                height_sums[*m].0 += (idx as f64 + 0.5) / (side_len as f64);
+                height_sums[*m].1 += 1;
+            }
+        }
+        let barycenter = |m: usize| {
+            let (sum, count) = height_sums[m];
+            if count == 0 {
+                (m as f64 + 0.5) / (self.middle.len() as f64)
+            } else {
+                sum / (count as f64)
+            }
+        };
+        let mut unpinned: Vec<MiddleIndex> = (0..middle_len).filter(|m| !is_pinned[*m]).collect();
+        unpinned.sort_by(|a, b| barycenter(*a).total_cmp(&barycenter(*b)));
+        let free_positions: Vec<usize> = (0..middle_len).filter(|p| order[*p].is_none()).collect();
+        for (p, m) in free_positions.iter().zip(unpinned) {
+            order[*p] = Some(m);
+        }
+        let mut order: Vec<MiddleIndex> = order.into_iter().flatten().collect();
+        let mut current = self.middle_order_crossings(&order);
+        let mut improved = true;
+        while improved && current > 0 {
+            improved = false;
+            for (p, q) in free_positions.iter().tuple_windows() {
+                order.swap(*p, *q);
+                let attempt = self.middle_order_crossings(&order);
+                if attempt < current {
+                    current = attempt;
+                    improved = true;
+                } else {
+                    order.swap(*p, *q);
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_layout_planar(&self) -> bool {
+        /*
+        whether some order of the middle column gives a drawing with no crossings
+        */
+        self.planar_middle_order().is_some()
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn to_graph<T, U, F>(
         &self,
@@ -344,41 +780,16 @@ impl<Lambda> Composable<Vec<Lambda>> for Cospan<Lambda>
 where
     Lambda: Eq + Sized + Copy + Debug,
 {
-    fn composable(&self, other: &Self) -> Result<(), String> {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
         let self_interface = self.right.iter().map(|mid| self.middle[*mid]);
         let other_interface = other.left.iter().map(|mid| other.middle[*mid]);
 
         crate::utils::same_labels_check(self_interface, other_interface)
     }
 
-    fn compose(&self, other: &Self) -> Result<Self, String> {
-        self.composable(other)?;
-        let (pushout_target, left_to_pushout, right_to_pushout, representative) =
-            perform_pushout::<crate::QuickUnionUf<crate::UnionBySize>>(
-                &self.right,
-                self.middle.len(),
-                self.is_right_id,
-                &other.left,
-                other.middle.len(),
-                other.is_left_id,
-            )?;
-        let mut composition =
-            Self::with_capacity(self.left.len(), other.right.len(), pushout_target);
-        for repr in representative {
-            composition.add_middle(match repr {
-                Left(z) => self.middle[z],
-                Right(z) => other.middle[z],
-            });
-        }
-        for target_in_self_middle in &self.left {
-            let target_in_pushout = left_to_pushout[*target_in_self_middle];
-            composition.add_boundary_node(Left(Left(target_in_pushout)));
-        }
-        for target_in_other_middle in &other.right {
-            let target_in_pushout = right_to_pushout[*target_in_other_middle];
-            composition.add_boundary_node(Right(Left(target_in_pushout)));
-        }
-        Ok(composition)
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        self.compose_with_middle_maps(other)
+            .map(|(composition, _, _)| composition)
     }
 
     fn domain(&self) -> Vec<Lambda> {
@@ -390,10 +801,47 @@ where
     }
 }
 
-impl<Lambda> MonoidalMorphism<Vec<Lambda>> for Cospan<Lambda> where Lambda: Eq + Sized + Copy + Debug
-{}
+impl<Lambda> HypergraphCategory<Lambda> for Cospan<Lambda>
+where
+    Lambda: Eq + Sized + Copy + Debug,
+{
+    /*
+    each of these has a single middle node that all the legs go to
+    */
+    fn split(z: Lambda) -> Self {
+        Self::new(vec![0], vec![0, 0], vec![z])
+    }
+
+    fn merge(z: Lambda) -> Self {
+        Self::new(vec![0, 0], vec![0], vec![z])
+    }
+
+    fn unit(z: Lambda) -> Self {
+        Self::new(vec![], vec![0], vec![z])
+    }
+
+    fn counit(z: Lambda) -> Self {
+        Self::new(vec![0], vec![], vec![z])
+    }
+}
 
-impl<Lambda> GenericMonoidalInterpretable<Lambda> for Cospan<Lambda> where Lambda: Eq + Copy + Debug {}
+impl<Lambda> Dagger<Vec<Lambda>> for Cospan<Lambda>
+where
+    Lambda: Eq + Sized + Copy + Debug,
+{
+    fn dagger(&self) -> Self {
+        /*
+        the same middle with the two legs swapped
+        */
+        Self {
+            left: self.right.clone(),
+            right: self.left.clone(),
+            middle: self.middle.clone(),
+            is_left_id: self.is_right_id,
+            is_right_id: self.is_left_id,
+        }
+    }
+}
 
 impl<Lambda> SymmetricMonoidalMorphism<Lambda> for Cospan<Lambda>
 where
@@ -460,7 +908,11 @@ where
     if left_leg.len() != right_leg.len() {
         return Err("Mismatch in cardinalities of common interface");
     }
-    if left_leg_id {
+    /*
+    the shortcuts are only right when the leg is a bijection
+        not just the identity on the first few middle nodes
+    */
+    if left_leg_id && left_leg.len() == left_leg_max_target {
         let pushout_target = right_leg_max_target;
         let left_to_pushout = right_leg.to_vec();
         let right_to_pushout = (0..right_leg_max_target).collect::<FinSetMap>();
@@ -472,7 +924,7 @@ where
             representative.collect(),
         ));
     }
-    if right_leg_id {
+    if right_leg_id && right_leg.len() == right_leg_max_target {
         let pushout_target = left_leg_max_target;
         let right_to_pushout = left_leg.to_vec();
         let left_to_pushout = (0..left_leg_max_target).collect::<FinSetMap>();
@@ -528,6 +980,28 @@ where
     ))
 }
 
+impl<Lambda> Cospan<Lambda>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+{
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like Cospan(2→1): [0,0]→[0] / ['a']
+        the two lists are where the domain and codomain go in the middle
+        and then the labels of the middle
+        */
+        format!(
+            "Cospan({}→{}): [{}]→[{}] / [{}]",
+            self.left.len(),
+            self.right.len(),
+            compact_list(&self.left),
+            compact_list(&self.right),
+            compact_list(&self.middle)
+        )
+    }
+}
+
 mod test {
     #[allow(unused_imports)]
     use crate::{
@@ -543,6 +1017,149 @@ mod test {
         assert!(empty_cospan.is_empty());
     }
 
+    #[test]
+    fn dagger_cospan() {
+        use super::Cospan;
+        use crate::category::Dagger;
+        let cospan = Cospan::<char>::new(vec![0, 0], vec![1], vec!['a', 'b']);
+        let flipped = cospan.dagger();
+        assert_eq!(flipped.domain(), vec!['b']);
+        assert_eq!(flipped.codomain(), vec!['a', 'a']);
+        assert_eq!(
+            flipped.dagger().to_compact_string(),
+            cospan.to_compact_string()
+        );
+        /*
+        only the 'b' is glued so the two copies of 'a' stay apart
+        */
+        let round_trip = cospan.compose(&flipped).unwrap();
+        assert_eq!(
+            round_trip.to_compact_string(),
+            "Cospan(2→2): [0,0]→[2,2] / ['a','b','a']"
+        );
+    }
+
+    #[test]
+    fn compact_cospan() {
+        use super::Cospan;
+        let cospan = Cospan::<char>::new(vec![0, 0], vec![1], vec!['a', 'b']);
+        assert_eq!(
+            cospan.to_compact_string(),
+            "Cospan(2→1): [0,0]→[1] / ['a','b']"
+        );
+    }
+
+    #[test]
+    fn substituting_labels() {
+        use super::Cospan;
+        let cospan = Cospan::<char>::new(vec![0, 1, 0], vec![1], vec!['a', 'b']);
+        let cabled =
+            cospan.substitute_strand_label(|l| if l == 'a' { vec![1, 2] } else { vec![3] });
+        assert_eq!(cabled.left, vec![0, 1, 2, 0, 1]);
+        assert_eq!(cabled.right, vec![2]);
+        assert_eq!(cabled.middle, vec![1, 2, 3]);
+        let dropped =
+            cospan.substitute_strand_label(|l| if l == 'a' { vec![] } else { vec![l, l] });
+        assert_eq!(dropped.left, vec![0, 1]);
+        assert_eq!(dropped.right, vec![0, 1]);
+        assert_eq!(dropped.middle, vec!['b', 'b']);
+        let same = cospan.substitute_strand_label(|l| vec![l]);
+        assert_eq!(same.to_compact_string(), cospan.to_compact_string());
+    }
+
+    #[test]
+    fn layout_crossings() {
+        use super::Cospan;
+        let untangle = Cospan::<()>::new(vec![2, 0, 1], vec![1, 2, 0], vec![(); 3]);
+        assert_eq!(untangle.middle_order_crossings(&[0, 1, 2]), 4);
+        /*
+        the left and right columns disagree by 2 inversions so 2 is the best possible
+        */
+        let order = untangle.layout_middle_order();
+        assert_eq!(untangle.middle_order_crossings(&order), 2);
+        let reversed = Cospan::<()>::new(vec![1, 0], vec![1, 0, 2], vec![(); 3]);
+        assert_eq!(reversed.middle_order_crossings(&[0, 1, 2]), 2);
+        assert!(reversed.is_layout_planar());
+        assert_eq!(reversed.layout_middle_order(), vec![1, 0, 2]);
+        let twisted = Cospan::<()>::new(vec![0, 1], vec![1, 0], vec![(); 2]);
+        assert_eq!(
+            twisted.middle_order_crossings(&twisted.layout_middle_order()),
+            1
+        );
+        assert!(!twisted.is_layout_planar());
+        assert!(Cospan::<()>::empty().is_layout_planar());
+    }
+
+    #[test]
+    fn planar_layouts() {
+        use super::Cospan;
+        use itertools::Itertools;
+        /*
+        planar_middle_order agrees with trying every order of the middle
+        on every cospan with at most 3 nodes in each column
+        */
+        for middle_len in 0..4 {
+            for left_len in 0..4 {
+                for right_len in 0..4 {
+                    let maps = |len: usize| {
+                        (0..len)
+                            .map(|_| 0..middle_len)
+                            .multi_cartesian_product()
+                            .collect::<Vec<_>>()
+                    };
+                    for (left, right) in maps(left_len)
+                        .into_iter()
+                        .cartesian_product(maps(right_len))
+                    {
+                        let cospan = Cospan::<()>::new(left, right, vec![(); middle_len]);
+                        let by_brute_force = (0..middle_len)
+                            .permutations(middle_len)
+                            .any(|order| cospan.middle_order_crossings(&order) == 0);
+                        let found = cospan.planar_middle_order();
+                        assert_eq!(found.is_some(), by_brute_force);
+                        assert_eq!(cospan.is_layout_planar(), by_brute_force);
+                        if let Some(order) = found {
+                            assert_eq!(cospan.middle_order_crossings(&order), 0);
+                            assert_eq!(cospan.layout_middle_order(), order);
+                        }
+                    }
+                }
+            }
+        }
+        let back_and_forth = Cospan::<()>::new(vec![0, 1, 0], vec![], vec![(); 2]);
+        assert_eq!(back_and_forth.planar_middle_order(), None);
+        let chained = Cospan::<()>::new(vec![2, 2, 0], vec![3, 0, 1], vec![(); 4]);
+        assert_eq!(chained.planar_middle_order(), Some(vec![2, 3, 0, 1]));
+    }
+
+    #[test]
+    fn pinned_layout() {
+        use super::Cospan;
+        let reversed = Cospan::<()>::new(vec![1, 0], vec![1, 0, 2], vec![(); 3]);
+        assert_eq!(
+            reversed.layout_middle_order_pinned(&[(2, 2)]),
+            Ok(vec![1, 0, 2])
+        );
+        assert_eq!(
+            reversed.layout_middle_order_pinned(&[]),
+            Ok(reversed.planar_middle_order().unwrap())
+        );
+        let forced = reversed.layout_middle_order_pinned(&[(0, 0)]).unwrap();
+        assert_eq!(forced[0], 0);
+        assert_eq!(reversed.middle_order_crossings(&forced), 2);
+        let all_pinned = reversed
+            .layout_middle_order_pinned(&[(0, 2), (1, 0), (2, 1)])
+            .unwrap();
+        assert_eq!(all_pinned, vec![1, 2, 0]);
+        assert!(reversed.layout_middle_order_pinned(&[(0, 3)]).is_err());
+        assert!(reversed
+            .layout_middle_order_pinned(&[(0, 1), (1, 1)])
+            .is_err());
+        assert!(reversed
+            .layout_middle_order_pinned(&[(0, 1), (0, 2)])
+            .is_err());
+    }
+
     #[test]
     fn left_only_cospan() {
         use super::Cospan;
@@ -593,6 +1210,21 @@ mod test {
         );
     }
 
+    #[test]
+    fn symmetric_braiding() {
+        use super::Cospan;
+        use crate::monoidal::Braided;
+        let a = vec!['a'];
+        let b = vec!['b', 'c'];
+        let braid = Cospan::braiding(&a, &b);
+        assert_eq!(braid.domain(), vec!['a', 'b', 'c']);
+        assert_eq!(braid.codomain(), vec!['b', 'c', 'a']);
+        let round_trip = braid.compose(&Cospan::inverse_braiding(&a, &b)).unwrap();
+        assert_eq!(round_trip.domain(), vec!['a', 'b', 'c']);
+        assert_eq!(round_trip.codomain(), vec!['a', 'b', 'c']);
+        assert_eq!(round_trip.left, round_trip.right);
+    }
+
     #[test]
     fn permutatation_manual() {
         use super::Cospan;
@@ -753,4 +1385,143 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn legs_in_finset() {
+        use super::Cospan;
+        use crate::category::Composable;
+        use crate::error::Error;
+        use crate::finset::finset_morphism;
+        let left = finset_morphism(vec![0, 0, 2], 4).unwrap();
+        let right = finset_morphism(vec![1, 2], 4).unwrap();
+        let cospan =
+            Cospan::from_legs(left.clone(), right.clone(), vec!['a', 'b', 'c', 'd']).unwrap();
+        assert_eq!(cospan.left_leg(), left);
+        assert_eq!(cospan.right_leg(), right);
+        assert_eq!(cospan.domain(), vec!['a', 'a', 'c']);
+        assert_eq!(cospan.codomain(), vec!['b', 'c']);
+        assert_eq!(
+            Cospan::from_legs(left, right, vec!['a', 'b', 'c']).err(),
+            Some(Error::InterfaceLengthMismatch { left: 3, right: 4 })
+        );
+        let identity = Cospan::new(vec![0, 1], vec![0, 1], vec!['x', 'y']);
+        assert_eq!(identity.left_leg(), identity.right_leg());
+        assert_eq!(identity.left_leg(), (vec![0, 1], 0));
+    }
+
+    #[test]
+    fn isomorphic_cospans() {
+        use super::Cospan;
+        let cospan = Cospan::new(vec![0, 1, 1], vec![2, 0], vec!['a', 'b', 'c']);
+        let renumbered = Cospan::new(vec![2, 0, 0], vec![1, 2], vec!['b', 'c', 'a']);
+        assert!(cospan.is_isomorphic_to(&renumbered));
+        assert!(renumbered.is_isomorphic_to(&cospan));
+        assert!(cospan.to_compact_string() != renumbered.to_compact_string());
+
+        /*
+        the same shape but with a label or a leg changed
+        */
+        let relabelled = Cospan::new(vec![2, 0, 0], vec![1, 2], vec!['b', 'a', 'a']);
+        assert!(!cospan.is_isomorphic_to(&relabelled));
+        let glued = Cospan::new(vec![2, 0, 0], vec![0, 2], vec!['b', 'c', 'a']);
+        assert!(!cospan.is_isomorphic_to(&glued));
+        let split = Cospan::new(vec![2, 0, 1], vec![1, 2], vec!['b', 'b', 'a']);
+        assert!(!renumbered.is_isomorphic_to(&split));
+
+        /*
+        middle nodes not hit by either side only count by their labels
+        */
+        let mut with_extra = cospan.clone();
+        with_extra.add_middle('d');
+        with_extra.add_middle('e');
+        let mut other_extra = renumbered.clone();
+        other_extra.add_middle('e');
+        other_extra.add_middle('d');
+        assert!(with_extra.is_isomorphic_to(&other_extra));
+        assert!(!with_extra.is_isomorphic_to(&cospan));
+        other_extra.add_middle('d');
+        with_extra.add_middle('e');
+        assert!(!with_extra.is_isomorphic_to(&other_extra));
+    }
+
+    #[test]
+    fn corelations() {
+        use super::Cospan;
+        use crate::finset::is_mono;
+        let cospan = Cospan::new(vec![1, 3], vec![3], vec!['a', 'b', 'c', 'd', 'e']);
+        assert!(!cospan.is_jointly_epic());
+        let (corelation, inclusion) = cospan.joint_image_factorization();
+        assert!(corelation.is_jointly_epic());
+        assert!(is_mono(&inclusion));
+        assert_eq!(
+            corelation.to_compact_string(),
+            "Cospan(2→1): [0,1]→[1] / ['b','d']"
+        );
+        assert_eq!(
+            corelation.left_leg().compose(&inclusion).unwrap(),
+            cospan.left_leg()
+        );
+        assert_eq!(
+            corelation.right_leg().compose(&inclusion).unwrap(),
+            cospan.right_leg()
+        );
+        assert!(corelation.to_corelation().is_isomorphic_to(&corelation));
+
+        /*
+        composing cospans can leave middle nodes behind that neither side reaches
+        as corelations those are gone
+        */
+        let cap = Cospan::new(vec![0, 0], vec![], vec!['a']);
+        let cup = Cospan::new(vec![], vec![0, 0], vec!['a']);
+        let circle = cup.compose(&cap).unwrap();
+        assert_eq!(circle.middle().len(), 1);
+        assert!(!circle.is_jointly_epic());
+        assert!(circle
+            .to_corelation()
+            .is_isomorphic_to(&Cospan::<char>::empty()));
+        assert!(Cospan::<char>::empty().is_jointly_epic());
+    }
+
+    #[test]
+    fn hypergraph_category() {
+        use super::Cospan;
+        use crate::frobenius::HypergraphCategory;
+        assert_eq!(
+            Cospan::frobenius_laws('a', |f: &Cospan<char>, g| f.is_isomorphic_to(g)),
+            Ok(())
+        );
+        let spider = Cospan::spider('a', 3, 2).unwrap();
+        assert!(spider.is_isomorphic_to(&Cospan::new(vec![0; 3], vec![0; 2], vec!['a'])));
+        assert!(Cospan::spider('a', 0, 0)
+            .unwrap()
+            .is_isomorphic_to(&Cospan::new(vec![], vec![], vec!['a'])));
+        assert!(Cospan::spider('a', 1, 1)
+            .unwrap()
+            .is_isomorphic_to(&Cospan::identity(&vec!['a'])));
+    }
+
+    #[test]
+    fn merging_middle() {
+        use super::Cospan;
+        let mut cospan = Cospan::new(vec![0, 1, 2], vec![3, 4], vec!['a', 'b', 'a', 'b', 'c']);
+        assert!(cospan.merge_middle(&[(0, 1)]).is_err());
+        assert!(cospan.merge_middle(&[(0, 5)]).is_err());
+        assert_eq!(cospan.middle(), &['a', 'b', 'a', 'b', 'c']);
+        let quotient = cospan.merge_middle(&[(3, 1), (2, 0)]).unwrap();
+        assert_eq!(quotient, vec![0, 1, 0, 1, 2]);
+        assert_eq!(cospan.middle(), &['a', 'b', 'c']);
+        assert_eq!(cospan.left_to_middle(), &[0, 1, 0]);
+        assert_eq!(cospan.right_to_middle(), &[1, 2]);
+        cospan.assert_valid(true);
+
+        let mut nothing_merged = Cospan::new(vec![0, 1], vec![], vec!['a', 'a']);
+        assert_eq!(nothing_merged.merge_middle(&[]).unwrap(), vec![0, 1]);
+        nothing_merged.assert_valid(true);
+        assert_eq!(
+            nothing_merged.merge_middle(&[(1, 0), (0, 1)]).unwrap(),
+            vec![0, 0]
+        );
+        assert_eq!(nothing_merged.left_to_middle(), &[0, 0]);
+        nothing_merged.assert_valid(true);
+    }
 }
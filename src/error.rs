@@ -0,0 +1,100 @@
+use std::fmt::{Debug, Display};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+    /*
+    why a composition, an interpretation or the like failed
+    labels are kept as their Debug strings so that this does not depend on Lambda
+    Other is for everything that has not been given its own variant yet
+        and is what a plain String error turns into
+    */
+    InterfaceLengthMismatch {
+        left: usize,
+        right: usize,
+    },
+    InterfaceLabelMismatch {
+        index: usize,
+        left: String,
+        right: String,
+    },
+    BracketingMismatch {
+        left: String,
+        right: String,
+    },
+    NotComposable,
+    EmptyComposition,
+    EmptyLayer,
+    InLayer {
+        layer: usize,
+        cause: Box<Error>,
+    },
+    BetweenLayers {
+        before: usize,
+        cause: Box<Error>,
+    },
+    Other(String),
+}
+
+impl Error {
+    #[allow(dead_code)]
+    pub fn label_mismatch<Lambda: Debug>(index: usize, left: &Lambda, right: &Lambda) -> Self {
+        Self::InterfaceLabelMismatch {
+            index,
+            left: format!("{:?}", left),
+            right: format!("{:?}", right),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InterfaceLengthMismatch { left, right } => write!(
+                f,
+                "Mismatch in cardinalities of common interface. {} vs {}",
+                left, right
+            ),
+            Self::InterfaceLabelMismatch { index, left, right } => write!(
+                f,
+                "Mismatch in labels of common interface. At index {} there was {} vs {}",
+                index, left, right
+            ),
+            Self::BracketingMismatch { left, right } => write!(
+                f,
+                "Mismatch in bracketing of common interface. {} vs {}",
+                left, right
+            ),
+            Self::NotComposable => write!(
+                f,
+                "Not composable. No details on how domain and codomain mismatched"
+            ),
+            Self::EmptyComposition => write!(f, "There were no morphisms to compose"),
+            Self::EmptyLayer => write!(f, "An empty layer in a generic monoidal morphism"),
+            Self::InLayer { layer, cause } => write!(f, "In layer {}. {}", layer, cause),
+            Self::BetweenLayers { before, cause } => {
+                write!(f, "Between layers {} and {}. {}", before, before + 1, cause)
+            }
+            Self::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
+}
+
+impl From<Error> for String {
+    fn from(error: Error) -> Self {
+        error.to_string()
+    }
+}
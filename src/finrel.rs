@@ -0,0 +1,317 @@
+use {
+    crate::{
+        category::{Composable, Dagger, HasIdentity},
+        error::Error,
+        finset::FinSetMorphism,
+        monoidal::Monoidal,
+        symmetric_monoidal::SymmetricMonoidalDiscreteMorphism,
+    },
+    permutations::Permutation,
+};
+
+/*
+a relation between the finite sets {0..domain} and {0..codomain}
+stored as a boolean matrix with a row for each element of the domain
+the monoidal product is the disjoint union as for FinSetMorphism and Rel
+    so the objects are just how many elements there are
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FinRel {
+    domain: usize,
+    codomain: usize,
+    related: Vec<Vec<bool>>,
+}
+
+impl FinRel {
+    #[allow(dead_code)]
+    pub fn new(
+        domain: usize,
+        codomain: usize,
+        pairs: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Result<Self, Error> {
+        let mut answer = Self::empty(domain, codomain);
+        for (source, target) in pairs {
+            if source >= domain || target >= codomain {
+                return Err(format!(
+                    "({}, {}) is not in {} by {}",
+                    source, target, domain, codomain
+                )
+                .into());
+            }
+            answer.related[source][target] = true;
+        }
+        Ok(answer)
+    }
+
+    #[allow(dead_code)]
+    pub fn empty(domain: usize, codomain: usize) -> Self {
+        Self {
+            domain,
+            codomain,
+            related: vec![vec![false; codomain]; domain],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn full(domain: usize, codomain: usize) -> Self {
+        Self {
+            domain,
+            codomain,
+            related: vec![vec![true; codomain]; domain],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_function(function: &FinSetMorphism) -> Self {
+        /*
+        the graph of the function
+        */
+        let mut answer = Self::empty(function.domain(), function.codomain());
+        for (source, target) in function.0.iter().enumerate() {
+            answer.related[source][*target] = true;
+        }
+        answer
+    }
+
+    #[allow(dead_code)]
+    pub fn relates(&self, source: usize, target: usize) -> bool {
+        self.related
+            .get(source)
+            .and_then(|row| row.get(target))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    #[allow(dead_code)]
+    pub fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.related.iter().enumerate().flat_map(|(source, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, related)| **related)
+                .map(move |(target, _)| (source, target))
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn is_function(&self) -> bool {
+        self.related
+            .iter()
+            .all(|row| row.iter().filter(|related| **related).count() == 1)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_contained_in(&self, other: &Self) -> bool {
+        self.domain == other.domain
+            && self.codomain == other.codomain
+            && self
+                .pairs()
+                .all(|(source, target)| other.related[source][target])
+    }
+
+    #[allow(dead_code)]
+    pub fn cartesian_product(&self, other: &Self) -> Self {
+        /*
+        the other monoidal structure on finite relations
+        (a,b) is related to (c,d) when a is related to c and b to d
+        with the pair (a,b) numbered a*n+b where n is the size of the second set
+        this is the one used to give relations between several variables
+            while the disjoint union in Monoidal is the one that matches FinSetMorphism
+        */
+        let mut answer = Self::empty(self.domain * other.domain, self.codomain * other.codomain);
+        for (a, c) in self.pairs() {
+            for (b, d) in other.pairs() {
+                answer.related[a * other.domain + b][c * other.codomain + d] = true;
+            }
+        }
+        answer
+    }
+}
+
+impl HasIdentity<usize> for FinRel {
+    fn identity(on_this: &usize) -> Self {
+        let mut answer = Self::empty(*on_this, *on_this);
+        for idx in 0..*on_this {
+            answer.related[idx][idx] = true;
+        }
+        answer
+    }
+}
+
+impl Composable<usize> for FinRel {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        if self.composable(other).is_err() {
+            return Err(Error::InterfaceLengthMismatch {
+                left: self.codomain(),
+                right: other.domain(),
+            });
+        }
+        let mut answer = Self::empty(self.domain, other.codomain);
+        for (source, middle) in self.pairs() {
+            for (target, related) in other.related[middle].iter().enumerate() {
+                answer.related[source][target] |= *related;
+            }
+        }
+        Ok(answer)
+    }
+
+    fn domain(&self) -> usize {
+        self.domain
+    }
+
+    fn codomain(&self) -> usize {
+        self.codomain
+    }
+}
+
+impl Monoidal for FinRel {
+    fn monoidal(&mut self, other: Self) {
+        /*
+        block diagonal so nothing of self is related to anything of other
+        */
+        let new_codomain = self.codomain + other.codomain;
+        for row in self.related.iter_mut() {
+            row.resize(new_codomain, false);
+        }
+        for row in other.related {
+            let mut new_row = vec![false; self.codomain];
+            new_row.extend(row);
+            self.related.push(new_row);
+        }
+        self.domain += other.domain;
+        self.codomain = new_codomain;
+    }
+}
+
+impl Dagger<usize> for FinRel {
+    fn dagger(&self) -> Self {
+        /*
+        the converse relation
+        */
+        let mut answer = Self::empty(self.codomain, self.domain);
+        for (source, target) in self.pairs() {
+            answer.related[target][source] = true;
+        }
+        answer
+    }
+}
+
+impl SymmetricMonoidalDiscreteMorphism<usize> for FinRel {
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        /*
+        the same convention as for Rel, what was at position k is now at position p.inv()(k)
+        */
+        let p_inv = p.inv();
+        let mut answer = Self::empty(self.domain, self.codomain);
+        for (source, target) in self.pairs() {
+            if of_codomain {
+                answer.related[source][p_inv.apply(target)] = true;
+            } else {
+                answer.related[p_inv.apply(source)][target] = true;
+            }
+        }
+        *self = answer;
+    }
+
+    fn from_permutation(p: Permutation, type_: usize, _types_as_on_domain: bool) -> Self {
+        /*
+        position i of the domain goes to position p(i) of the codomain
+        the objects have no labels so it does not matter which side they were given for
+        */
+        let mut answer = Self::empty(type_, type_);
+        for idx in 0..type_ {
+            answer.related[idx][p.apply(idx)] = true;
+        }
+        answer
+    }
+}
+
+mod test {
+
+    #[test]
+    fn relations_compose() {
+        use super::FinRel;
+        use crate::category::{Composable, Dagger, HasIdentity};
+        use crate::error::Error;
+        let less_than = FinRel::new(3, 3, [(0, 1), (0, 2), (1, 2)]).unwrap();
+        let twice = less_than.compose(&less_than).unwrap();
+        assert_eq!(twice.pairs().collect::<Vec<_>>(), vec![(0, 2)]);
+        assert!(twice.is_contained_in(&less_than));
+        assert!(!less_than.is_contained_in(&twice));
+        assert_eq!(less_than.compose(&FinRel::identity(&3)).unwrap(), less_than);
+        assert_eq!(
+            less_than.compose(&FinRel::full(2, 2)).err(),
+            Some(Error::InterfaceLengthMismatch { left: 3, right: 2 })
+        );
+        assert!(FinRel::new(2, 2, [(0, 2)]).is_err());
+
+        let greater_than = less_than.dagger();
+        assert!(greater_than.relates(2, 0));
+        assert!(!greater_than.relates(0, 2));
+        assert_eq!(greater_than.dagger(), less_than);
+        assert!(FinRel::identity(&4).is_unitary().unwrap());
+        assert!(!less_than.is_unitary().unwrap());
+
+        /*
+        composing a relation with its converse
+        relates everything in the domain that goes somewhere to itself
+        */
+        let some_to_all = less_than.compose(&greater_than).unwrap();
+        assert!(some_to_all.relates(0, 0) && some_to_all.relates(0, 1));
+        assert!(!some_to_all.relates(2, 2));
+    }
+
+    #[test]
+    fn functions_and_products() {
+        use super::FinRel;
+        use crate::category::{Composable, HasIdentity};
+        use crate::finset::FinSetMorphism;
+        use crate::monoidal::Monoidal;
+        use crate::symmetric_monoidal::SymmetricMonoidalDiscreteMorphism;
+        use permutations::Permutation;
+        let f: FinSetMorphism = (vec![1, 0, 1], 0);
+        let g: FinSetMorphism = (vec![2, 2], 0);
+        let as_relation = FinRel::from_function(&f);
+        assert!(as_relation.is_function());
+        assert!(!FinRel::full(2, 2).is_function());
+        assert_eq!(
+            as_relation.compose(&FinRel::from_function(&g)).unwrap(),
+            FinRel::from_function(&f.compose(&g).unwrap())
+        );
+
+        let mut sum = as_relation.clone();
+        sum.monoidal(FinRel::full(1, 2));
+        assert_eq!((sum.domain(), sum.codomain()), (4, 4));
+        assert!(sum.relates(3, 2) && sum.relates(3, 3));
+        assert!(!sum.relates(3, 0) && !sum.relates(0, 2));
+        let mut f_sum = f.clone();
+        f_sum.monoidal(g.clone());
+        let mut relations_sum = FinRel::from_function(&f);
+        relations_sum.monoidal(FinRel::from_function(&g));
+        assert_eq!(relations_sum, FinRel::from_function(&f_sum));
+
+        let p = Permutation::try_from(vec![2, 0, 1]).unwrap();
+        let q = Permutation::try_from(vec![1, 0, 2]).unwrap();
+        let p_rel = FinRel::from_permutation(p.clone(), 3, true);
+        assert!(p_rel.relates(0, 2) && p_rel.relates(1, 0) && p_rel.relates(2, 1));
+        assert_eq!(
+            p_rel
+                .compose(&FinRel::from_permutation(q.clone(), 3, true))
+                .unwrap(),
+            FinRel::from_permutation(p.clone() * q.clone(), 3, true)
+        );
+        let mut relabelled = FinRel::identity(&3);
+        relabelled.permute_side(&p, true);
+        assert_eq!(relabelled, FinRel::from_permutation(p.inv(), 3, true));
+
+        /*
+        the cartesian product of two relations
+        */
+        let equal_parity = FinRel::new(2, 2, [(0, 0), (1, 1)]).unwrap();
+        let both = FinRel::full(1, 2).cartesian_product(&equal_parity);
+        assert_eq!((both.domain(), both.codomain()), (2, 4));
+        assert_eq!(
+            both.pairs().collect::<Vec<_>>(),
+            vec![(0, 0), (0, 2), (1, 1), (1, 3)]
+        );
+    }
+}
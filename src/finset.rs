@@ -1,8 +1,10 @@
 use {
     crate::{
         category::{Composable, HasIdentity},
-        monoidal::{Monoidal, MonoidalMorphism},
+        error::Error,
+        monoidal::Monoidal,
         symmetric_monoidal::SymmetricMonoidalDiscreteMorphism,
+        utils::compact_list,
     },
     num::Integer,
     permutations::Permutation,
@@ -32,13 +34,12 @@ impl Monoidal for FinSetMorphism {
 }
 
 impl Composable<usize> for FinSetMorphism {
-    fn compose(&self, other: &Self) -> Result<Self, String> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
         if self.composable(other).is_err() {
-            return Err(format!(
-                "Not composable. The codomain of self was {}. The domain of other was {}",
-                self.codomain(),
-                other.domain()
-            ));
+            return Err(Error::InterfaceLengthMismatch {
+                left: self.codomain(),
+                right: other.domain(),
+            });
         }
         let other_codomain = other.codomain();
         let composite: Vec<_> = (0..self.domain()).map(|s| other.0[self.0[s]]).collect();
@@ -63,7 +64,63 @@ impl Composable<usize> for FinSetMorphism {
     }
 }
 
-impl MonoidalMorphism<usize> for FinSetMorphism {}
+#[allow(dead_code)]
+pub fn finset_morphism(map: FinSetMap, codomain: usize) -> Result<FinSetMorphism, Error> {
+    /*
+    the function {0..map.len()} -> {0..codomain} sending i to map[i]
+    in the encoding of FinSetMorphism where only the elements past the image are counted
+    */
+    let past_image = match map.iter().max() {
+        Some(max_val) if *max_val >= codomain => {
+            return Err(format!("{} is not below the codomain size {}", max_val, codomain).into())
+        }
+        Some(max_val) => codomain - max_val - 1,
+        None => codomain,
+    };
+    Ok((map, past_image))
+}
+
+#[allow(dead_code)]
+pub fn finset_compact_string(f: &FinSetMorphism) -> String {
+    /*
+    one line summary like FinSet(3→4): [0,2,2]
+    FinSetMorphism is a tuple so this can not be a method like the others
+    */
+    format!(
+        "FinSet({}→{}): [{}]",
+        f.domain(),
+        f.codomain(),
+        compact_list(&f.0)
+    )
+}
+
+#[allow(dead_code)]
+pub fn is_epi(f: &FinSetMorphism) -> bool {
+    f.1 == 0 && is_surjective(&f.0)
+}
+
+#[allow(dead_code)]
+pub fn is_mono(f: &FinSetMorphism) -> bool {
+    is_injective(&f.0)
+}
+
+#[allow(dead_code)]
+pub fn epi_mono_factorization(f: &FinSetMorphism) -> (FinSetMorphism, FinSetMorphism) {
+    /*
+    f as a surjection onto its image followed by the inclusion of the image
+    the image is numbered in the order it sits in the codomain
+        so the inclusion is order preserving and that makes the factorization unique
+    */
+    let mut image = f.0.clone();
+    image.sort();
+    image.dedup();
+    let surjection =
+        f.0.iter()
+            .map(|target| image.binary_search(target).expect("in the image"))
+            .collect();
+    let past_image = f.1;
+    ((surjection, 0), (image, past_image))
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OrderPresSurj {
@@ -86,13 +143,12 @@ impl Monoidal for OrderPresSurj {
 }
 
 impl Composable<usize> for OrderPresSurj {
-    fn compose(&self, other: &Self) -> Result<Self, String> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
         if self.composable(other).is_err() {
-            return Err(format!(
-                "Not composable. The codomain of self was {}. The domain of other was {}",
-                self.codomain(),
-                other.domain()
-            ));
+            return Err(Error::InterfaceLengthMismatch {
+                left: self.codomain(),
+                right: other.domain(),
+            });
         }
         let codomain = other.codomain();
         let mut answer = Vec::with_capacity(codomain);
@@ -121,8 +177,6 @@ impl Composable<usize> for OrderPresSurj {
     }
 }
 
-impl MonoidalMorphism<usize> for OrderPresSurj {}
-
 impl OrderPresSurj {
     fn to_ordinary(&self) -> FinSetMorphism {
         let domain_size: usize = self.domain();
@@ -140,6 +194,19 @@ impl OrderPresSurj {
     pub fn preimage_cardinalities(&self) -> Vec<usize> {
         self.preimage_card_minus_1.iter().map(|z| z + 1).collect()
     }
+
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like OrderPresSurj(3→2): [0,0,1]
+        */
+        format!(
+            "OrderPresSurj({}→{}): [{}]",
+            self.domain(),
+            self.codomain(),
+            compact_list(&self.to_ordinary().0)
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -166,18 +233,22 @@ impl Monoidal for OrderPresInj {
 }
 
 impl Composable<usize> for OrderPresInj {
-    fn compose(&self, other: &Self) -> Result<Self, String> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
         if self.composable(other).is_err() {
-            return Err(format!(
-                "Not composable. The codomain of self was {}. The domain of other was {}",
-                self.codomain(),
-                other.domain()
-            ));
+            return Err(Error::InterfaceLengthMismatch {
+                left: self.codomain(),
+                right: other.domain(),
+            });
         }
         let ord_self = self.to_ordinary();
         let ord_other = other.to_ordinary();
         let composite = ord_self.compose(&ord_other)?;
-        Self::try_from(composite).map_err(|_| "???".to_string())
+        Self::try_from(composite).map_err(|e| {
+            Error::from(format!(
+                "The composite of order preserving injections was not an order preserving injection. {}",
+                e
+            ))
+        })
     }
 
     fn domain(&self) -> usize {
@@ -197,8 +268,6 @@ impl Composable<usize> for OrderPresInj {
     }
 }
 
-impl MonoidalMorphism<usize> for OrderPresInj {}
-
 impl OrderPresInj {
     fn to_ordinary(&self) -> FinSetMorphism {
         let domain_size: usize = self.domain();
@@ -220,6 +289,19 @@ impl OrderPresInj {
         (answer, codomain_minus_greatest_range)
     }
 
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like OrderPresInj(2→4): [0,2]
+        */
+        format!(
+            "OrderPresInj({}→{}): [{}]",
+            self.domain(),
+            self.codomain(),
+            compact_list(&self.to_ordinary().0)
+        )
+    }
+
     fn apply(&self, test_pt: usize) -> usize {
         self.to_ordinary().0[test_pt]
     }
@@ -231,7 +313,9 @@ impl OrderPresInj {
 
 fn is_surjective(v: &[usize]) -> bool {
     // empty set to empty set
-    let Some(max_val) = v.iter().max() else { return true };
+    let Some(max_val) = v.iter().max() else {
+        return true;
+    };
     if v.len() < max_val + 1 {
         return false;
     }
@@ -242,7 +326,9 @@ fn is_surjective(v: &[usize]) -> bool {
 
 fn is_injective(v: &[usize]) -> bool {
     // empty set to empty set
-    let Some(max_val) = v.iter().max() else { return true };
+    let Some(max_val) = v.iter().max() else {
+        return true;
+    };
     if v.len() > max_val + 1 {
         return false;
     }
@@ -391,13 +477,12 @@ impl Monoidal for Decomposition {
 }
 
 impl Composable<usize> for Decomposition {
-    fn compose(&self, other: &Self) -> Result<Self, String> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
         if self.composable(other).is_err() {
-            return Err(format!(
-                "Not composable. The codomain of self was {}. The domain of other was {}",
-                self.codomain(),
-                other.domain()
-            ));
+            return Err(Error::InterfaceLengthMismatch {
+                left: self.codomain(),
+                right: other.domain(),
+            });
         }
         let other_codomain = other.codomain();
         let ord_self = self.to_ordinary();
@@ -406,9 +491,9 @@ impl Composable<usize> for Decomposition {
 
         if let Some(max_val) = composite.0.iter().max() {
             let leftover_needed = (other_codomain - max_val - 1).max(0);
-            Self::try_from((composite.0, leftover_needed)).map_err(|_| "???".to_string())
+            Self::try_from((composite.0, leftover_needed)).map_err(not_decomposable)
         } else {
-            Self::try_from(composite).map_err(|_| "???".to_string())
+            Self::try_from(composite).map_err(not_decomposable)
         }
         //todo test
     }
@@ -422,8 +507,6 @@ impl Composable<usize> for Decomposition {
     }
 }
 
-impl MonoidalMorphism<usize> for Decomposition {}
-
 impl SymmetricMonoidalDiscreteMorphism<usize> for Decomposition {
     fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
         if !of_codomain {
@@ -475,6 +558,22 @@ impl Decomposition {
             &self.order_preserving_injection,
         )
     }
+
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like Decomposition(3→4): perm [1,0,2] ; surj [0,0,1] ; inj [0,2]
+        the three parts in the order they are applied
+        */
+        format!(
+            "Decomposition({}→{}): perm [{}] ; surj [{}] ; inj [{}]",
+            self.domain(),
+            self.codomain(),
+            compact_list((0..self.domain()).map(|z| self.permutation_part.apply(z))),
+            compact_list(&self.order_preserving_surjection.to_ordinary().0),
+            compact_list(&self.order_preserving_injection.to_ordinary().0)
+        )
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -511,6 +610,13 @@ impl TryFrom<FinSetMorphism> for Decomposition {
 }
 impl error::Error for TryFromFinSetError {}
 
+fn not_decomposable(e: TryFromFinSetError) -> Error {
+    Error::from(format!(
+        "The composite could not be split into a permutation, an order preserving surjection and an order preserving injection. {}",
+        e
+    ))
+}
+
 fn monotone_epi_mono_fact(v: FinSetMap) -> (FinSetMap, FinSetMap) {
     if v.is_empty() {
         return (vec![], vec![]);
@@ -873,4 +979,51 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn function_factorization() {
+        use super::{epi_mono_factorization, finset_morphism, is_epi, is_mono, FinSetMorphism};
+        use crate::category::{Composable, HasIdentity};
+        let f = finset_morphism(vec![4, 1, 4, 2], 6).unwrap();
+        assert_eq!(f, (vec![4, 1, 4, 2], 1));
+        assert_eq!((f.domain(), f.codomain()), (4, 6));
+        assert!(finset_morphism(vec![0, 3], 3).is_err());
+        assert_eq!(finset_morphism(vec![], 2).unwrap().codomain(), 2);
+
+        let (epi, mono) = epi_mono_factorization(&f);
+        assert_eq!(epi, (vec![2, 0, 2, 1], 0));
+        assert_eq!(mono, (vec![1, 2, 4], 1));
+        assert!(is_epi(&epi) && !is_mono(&epi));
+        assert!(is_mono(&mono) && !is_epi(&mono));
+        assert_eq!(epi.compose(&mono).unwrap(), f);
+
+        for g in [
+            FinSetMorphism::identity(&3),
+            (vec![], 2),
+            (vec![0, 0, 0], 0),
+            (vec![2, 0], 3),
+        ] {
+            let (epi, mono) = epi_mono_factorization(&g);
+            assert!(is_epi(&epi));
+            assert!(is_mono(&mono));
+            assert_eq!(epi.compose(&mono).unwrap(), g);
+        }
+    }
+
+    #[test]
+    fn compact_strings() {
+        use crate::finset::{finset_compact_string, Decomposition, OrderPresInj, OrderPresSurj};
+        let f = (vec![2, 0, 0], 1);
+        assert_eq!(finset_compact_string(&f), "FinSet(3→4): [2,0,0]");
+        let surj = OrderPresSurj::try_from((vec![0, 0, 1], 0)).unwrap();
+        assert_eq!(surj.to_compact_string(), "OrderPresSurj(3→2): [0,0,1]");
+        let inj = OrderPresInj::try_from((vec![0, 2], 1)).unwrap();
+        assert_eq!(inj.to_compact_string(), "OrderPresInj(2→4): [0,2]");
+        let decomposed = Decomposition::try_from(f).unwrap();
+        assert_eq!(
+            decomposed.to_compact_string(),
+            "Decomposition(3→4): perm [2,0,1] ; surj [0,0,1] ; inj [0,2]"
+        );
+        assert_eq!(finset_compact_string(&(vec![], 0)), "FinSet(0→0): []");
+    }
 }
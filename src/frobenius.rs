@@ -1,17 +1,21 @@
 use {
     crate::{
-        category::{ComposableMutating, HasIdentity},
+        category::{ComposableMutating, Dagger, HasIdentity},
+        error::Error,
         finset::Decomposition,
-        monoidal::{Monoidal, MonoidalMutatingMorphism},
-        symmetric_monoidal::SymmetricMonoidalMutatingMorphism,
-        utils::in_place_permute,
+        monoidal::{tensor_pair, Braided, HasWireTypes, Monoidal},
+        symmetric_monoidal::{
+            HasSymmetricBraiding, SymmetricMonoidalMorphism, SymmetricMonoidalMutatingMorphism,
+        },
+        utils::{compact_list, in_place_permute, same_labels_check},
     },
     num::Integer,
     permutations::Permutation,
     std::{convert::identity, fmt::Debug},
 };
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FrobeniusOperation<Lambda: Eq + Copy, BlackBoxLabel: Eq + Copy> {
     Unit(Lambda),
     Multiplication(Lambda),
@@ -99,6 +103,57 @@ where
     }
 }
 
+impl<Lambda, BlackBoxLabel> HasWireTypes<Lambda> for FrobeniusOperation<Lambda, BlackBoxLabel>
+where
+    Lambda: Eq + Copy,
+    BlackBoxLabel: Eq + Copy,
+{
+    fn source_types(&self) -> Vec<Lambda> {
+        FrobeniusOperation::source_types(self)
+    }
+
+    fn target_types(&self) -> Vec<Lambda> {
+        FrobeniusOperation::target_types(self)
+    }
+}
+
+impl<Lambda, BlackBoxLabel> HasIdentity<Lambda> for FrobeniusOperation<Lambda, BlackBoxLabel>
+where
+    Lambda: Eq + Copy,
+    BlackBoxLabel: Eq + Copy,
+{
+    fn identity(on_this: &Lambda) -> Self {
+        Self::Identity(*on_this)
+    }
+}
+
+impl<Lambda, BlackBoxLabel> Dagger<Vec<Lambda>> for FrobeniusOperation<Lambda, BlackBoxLabel>
+where
+    Lambda: Eq + Copy,
+    BlackBoxLabel: Eq + Copy,
+{
+    /*
+    the horizontal flip where black boxes keep their labels
+    use hflip directly when the flipped black boxes should be relabelled
+    */
+    fn dagger(&self) -> Self {
+        let mut answer = self.clone();
+        answer.hflip(identity);
+        answer
+    }
+}
+
+impl<Lambda, BlackBoxLabel> HasSymmetricBraiding<Lambda>
+    for FrobeniusOperation<Lambda, BlackBoxLabel>
+where
+    Lambda: Eq + Copy,
+    BlackBoxLabel: Eq + Copy,
+{
+    fn symmetric_braiding(z: &Lambda, w: &Lambda) -> Self {
+        Self::SymmetricBraiding(*z, *w)
+    }
+}
+
 #[derive(PartialEq, Eq, Clone)]
 struct FrobeniusBlock<Lambda: Eq + Copy, BlackBoxLabel: Eq + Copy> {
     op: FrobeniusOperation<Lambda, BlackBoxLabel>,
@@ -283,15 +338,12 @@ where
     fn append_layer(
         &mut self,
         next_layer: FrobeniusLayer<Lambda, BlackBoxLabel>,
-    ) -> Result<(), String> {
+    ) -> Result<(), Error> {
         /*
         composition with one more layer
         */
-        if let Some(v) = self.layers.pop() {
-            if v.right_type != next_layer.left_type {
-                return Err("type mismatch in frobenius morphims composition".to_string());
-            }
-            self.layers.push(v);
+        if let Some(v) = self.layers.last() {
+            same_labels_check(v.right_type.iter(), next_layer.left_type.iter())?;
         }
         self.layers.push(next_layer);
         Ok(())
@@ -312,6 +364,70 @@ where
     }
 }
 
+impl<Lambda, BlackBoxLabel> FrobeniusOperation<Lambda, BlackBoxLabel>
+where
+    Lambda: Eq + Copy + Debug,
+    BlackBoxLabel: Eq + Copy + Debug,
+{
+    fn to_compact_string(&self) -> String {
+        match self {
+            Self::Unit(z) => format!("η({:?})", z),
+            Self::Multiplication(z) => format!("μ({:?})", z),
+            Self::Comultiplication(z) => format!("δ({:?})", z),
+            Self::Counit(z) => format!("ε({:?})", z),
+            Self::Identity(z) => format!("id({:?})", z),
+            Self::SymmetricBraiding(z, w) => format!("σ({:?},{:?})", z, w),
+            Self::UnSpecifiedBox(label, srcs, tgts) => format!(
+                "{:?}([{}]→[{}])",
+                label,
+                compact_list(srcs),
+                compact_list(tgts)
+            ),
+        }
+    }
+}
+
+impl<Lambda, BlackBoxLabel> FrobeniusMorphism<Lambda, BlackBoxLabel>
+where
+    Lambda: Eq + Copy + Debug,
+    BlackBoxLabel: Eq + Copy + Debug,
+{
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like Frobenius(['a','a']→['a']): μ('a') ; id('a')
+        the blocks within a layer are separated by ⊗ and the layers by ;
+        */
+        let domain = self.layers.first().map_or(vec![], |l| l.left_type.clone());
+        let codomain = self.layers.last().map_or(vec![], |l| l.right_type.clone());
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| {
+                if layer.blocks.is_empty() {
+                    return "id([])".to_string();
+                }
+                layer
+                    .blocks
+                    .iter()
+                    .map(|block| block.op.to_compact_string())
+                    .collect::<Vec<_>>()
+                    .join(" ⊗ ")
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "Frobenius([{}]→[{}]): {}",
+            compact_list(&domain),
+            compact_list(&codomain),
+            if layers.is_empty() {
+                "id([])".to_string()
+            } else {
+                layers.join(" ; ")
+            }
+        )
+    }
+}
+
 impl<Lambda, BlackBoxLabel> HasIdentity<Vec<Lambda>> for FrobeniusMorphism<Lambda, BlackBoxLabel>
 where
     Lambda: Eq + Copy + Debug,
@@ -357,7 +473,7 @@ where
     Lambda: Eq + Copy + Debug,
     BlackBoxLabel: Eq + Copy,
 {
-    fn composable(&self, other: &Self) -> Result<(), String> {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
         if self.layers.is_empty() || other.layers.is_empty() {
             if self.layers.is_empty() && other.layers.is_empty() {
                 return Ok(());
@@ -366,39 +482,44 @@ where
                 if other_interface.is_empty() {
                     return Ok(());
                 } else {
-                    return Err("Mismatch in cardinalities of common interface".to_string());
+                    return Err(Error::InterfaceLengthMismatch {
+                        left: 0,
+                        right: other_interface.len(),
+                    });
                 }
             } else {
                 let self_interface = &self.layers.last().unwrap().right_type;
                 if self_interface.is_empty() {
                     return Ok(());
                 } else {
-                    return Err("Mismatch in cardinalities of common interface".to_string());
+                    return Err(Error::InterfaceLengthMismatch {
+                        left: self_interface.len(),
+                        right: 0,
+                    });
                 }
             }
         }
         let self_interface = &self.layers.last().unwrap().right_type;
         let other_interface = &other.layers[0].left_type;
         if self_interface.len() != other_interface.len() {
-            Err("Mismatch in cardinalities of common interface".to_string())
-        } else if self_interface != other_interface {
-            for idx in 0..self_interface.len() {
-                let w1 = self_interface[idx];
-                let w2 = other_interface[idx];
-                if w1 != w2 {
-                    return Err(format!(
-                        "Mismatch in labels of common interface. At some index there was {:?} vs {:?}",
-                        w1, w2
-                    ));
-                }
-            }
-            Err("Mismatch in labels of common interface at some unknown index.".to_string())
+            Err(Error::InterfaceLengthMismatch {
+                left: self_interface.len(),
+                right: other_interface.len(),
+            })
+        } else if let Some(idx) =
+            (0..self_interface.len()).find(|idx| self_interface[*idx] != other_interface[*idx])
+        {
+            Err(Error::label_mismatch(
+                idx,
+                &self_interface[idx],
+                &other_interface[idx],
+            ))
         } else {
             Ok(())
         }
     }
 
-    fn compose(&mut self, other: Self) -> Result<(), String> {
+    fn compose(&mut self, other: Self) -> Result<(), Error> {
         for next_layer in other.layers {
             self.append_layer(next_layer)?;
         }
@@ -420,14 +541,6 @@ where
     }
 }
 
-impl<Lambda, BlackBoxLabel> MonoidalMutatingMorphism<Vec<Lambda>>
-    for FrobeniusMorphism<Lambda, BlackBoxLabel>
-where
-    Lambda: Eq + Copy + Debug,
-    BlackBoxLabel: Eq + Copy,
-{
-}
-
 impl<Lambda, BlackBoxLabel> SymmetricMonoidalMutatingMorphism<Lambda>
     for FrobeniusMorphism<Lambda, BlackBoxLabel>
 where
@@ -620,9 +733,9 @@ pub trait Frobenius<Lambda: Eq + Copy + Debug, BlackBoxLabel: Eq + Copy>:
     fn basic_interpret<F>(
         single_step: &FrobeniusOperation<Lambda, BlackBoxLabel>,
         black_box_interpreter: &F,
-    ) -> Result<Self, String>
+    ) -> Result<Self, Error>
     where
-        F: Fn(&BlackBoxLabel, &[Lambda], &[Lambda]) -> Result<Self, String>,
+        F: Fn(&BlackBoxLabel, &[Lambda], &[Lambda]) -> Result<Self, Error>,
     {
         /*
         interpret a single frobenius operation as a Self
@@ -648,9 +761,9 @@ pub trait Frobenius<Lambda: Eq + Copy + Debug, BlackBoxLabel: Eq + Copy>:
     fn interpret<F>(
         morphism: &FrobeniusMorphism<Lambda, BlackBoxLabel>,
         black_box_interpreter: &F,
-    ) -> Result<Self, String>
+    ) -> Result<Self, Error>
     where
-        F: Fn(&BlackBoxLabel, &[Lambda], &[Lambda]) -> Result<Self, String>,
+        F: Fn(&BlackBoxLabel, &[Lambda], &[Lambda]) -> Result<Self, Error>,
     {
         /*
         interpret a complicated frobenius morphism as a Self
@@ -659,7 +772,7 @@ pub trait Frobenius<Lambda: Eq + Copy + Debug, BlackBoxLabel: Eq + Copy>:
         let mut answer = Self::identity(&morphism.domain());
         for layer in &morphism.layers {
             if layer.blocks.is_empty() {
-                return Err("somehow an empty layer in a frobenius morphism???".to_string());
+                return Err(Error::EmptyLayer);
             }
             let first = &layer.blocks[0];
             let mut cur_layer = Self::basic_interpret(&first.op, black_box_interpreter)?;
@@ -697,9 +810,9 @@ where
     fn basic_interpret<F>(
         single_step: &FrobeniusOperation<Lambda, BlackBoxLabel>,
         _black_box_interpreter: &F,
-    ) -> Result<Self, String>
+    ) -> Result<Self, Error>
     where
-        F: Fn(&BlackBoxLabel, &[Lambda], &[Lambda]) -> Result<Self, String>,
+        F: Fn(&BlackBoxLabel, &[Lambda], &[Lambda]) -> Result<Self, Error>,
     {
         /*
         ignores black_box_interpreter as if it was just the simple
@@ -711,9 +824,9 @@ where
     fn interpret<F>(
         morphism: &FrobeniusMorphism<Lambda, BlackBoxLabel>,
         _black_box_interpreter: &F,
-    ) -> Result<Self, String>
+    ) -> Result<Self, Error>
     where
-        F: Fn(&BlackBoxLabel, &[Lambda], &[Lambda]) -> Result<Self, String>,
+        F: Fn(&BlackBoxLabel, &[Lambda], &[Lambda]) -> Result<Self, Error>,
     {
         /*
         ignores black_box_interpreter as if it was just the simple
@@ -723,6 +836,102 @@ where
     }
 }
 
+pub trait HypergraphCategory<Lambda: Eq + Copy>:
+    SymmetricMonoidalMorphism<Lambda> + HasIdentity<Vec<Lambda>> + Clone
+{
+    /*
+    every basic object z is a special commutative Frobenius object
+    split : z -> z \otimes z and merge : z \otimes z -> z
+    unit : I -> z and counit : z -> I
+    unlike Frobenius this is for the immutable Composable
+        and the laws can be checked directly
+    */
+    fn split(z: Lambda) -> Self;
+    fn merge(z: Lambda) -> Self;
+    fn unit(z: Lambda) -> Self;
+    fn counit(z: Lambda) -> Self;
+
+    #[allow(dead_code)]
+    fn spider(z: Lambda, inputs: usize, outputs: usize) -> Result<Self, Error> {
+        /*
+        merge all the inputs into one and then split that into all the outputs
+        by the Frobenius laws that is every connected diagram with that many legs
+        */
+        let mut merged = if inputs == 0 {
+            Self::unit(z)
+        } else {
+            Self::identity(&vec![z])
+        };
+        for _ in 1..inputs {
+            merged = tensor_pair(merged, Self::identity(&vec![z])).compose(&Self::merge(z))?;
+        }
+        let mut split = if outputs == 0 {
+            Self::counit(z)
+        } else {
+            Self::identity(&vec![z])
+        };
+        for _ in 1..outputs {
+            split = Self::split(z).compose(&tensor_pair(split, Self::identity(&vec![z])))?;
+        }
+        merged.compose(&split)
+    }
+
+    #[allow(dead_code)]
+    fn frobenius_laws<F>(z: Lambda, same: F) -> Result<(), Error>
+    where
+        F: Fn(&Self, &Self) -> bool,
+    {
+        /*
+        same says when two morphisms should count as equal
+            so that things like cospans can be compared up to isomorphism
+        (merge \otimes id) ; merge = (id \otimes merge) ; merge and (unit \otimes id) ; merge = id = (id \otimes unit) ; merge
+        split ; (split \otimes id) = split ; (id \otimes split) and split ; (counit \otimes id) = id = split ; (id \otimes counit)
+        beta ; merge = merge and split ; beta = split
+        (split \otimes id) ; (id \otimes merge) = merge ; split = (id \otimes split) ; (merge \otimes id)
+        split ; merge = id
+        */
+        let id = Self::identity(&vec![z]);
+        let beta = Self::braiding(&vec![z], &vec![z]);
+        let (split, merge) = (Self::split(z), Self::merge(z));
+        let with_id = |f: Self| tensor_pair(f, id.clone());
+        let id_with = |f: Self| tensor_pair(id.clone(), f);
+
+        let left_assoc = with_id(merge.clone()).compose(&merge)?;
+        let right_assoc = id_with(merge.clone()).compose(&merge)?;
+        if !same(&left_assoc, &right_assoc) {
+            return Err("Merging was not associative".into());
+        }
+        let left_unit = with_id(Self::unit(z)).compose(&merge)?;
+        let right_unit = id_with(Self::unit(z)).compose(&merge)?;
+        if !same(&left_unit, &id) || !same(&right_unit, &id) {
+            return Err("Merging with the unit was not the identity".into());
+        }
+        let left_coassoc = split.compose(&with_id(split.clone()))?;
+        let right_coassoc = split.compose(&id_with(split.clone()))?;
+        if !same(&left_coassoc, &right_coassoc) {
+            return Err("Splitting was not coassociative".into());
+        }
+        let left_counit = split.compose(&with_id(Self::counit(z)))?;
+        let right_counit = split.compose(&id_with(Self::counit(z)))?;
+        if !same(&left_counit, &id) || !same(&right_counit, &id) {
+            return Err("Splitting then the counit was not the identity".into());
+        }
+        if !same(&beta.compose(&merge)?, &merge) || !same(&split.compose(&beta)?, &split) {
+            return Err("Merging or splitting was not commutative".into());
+        }
+        let frobenius_left = with_id(split.clone()).compose(&id_with(merge.clone()))?;
+        let frobenius_middle = merge.compose(&split)?;
+        let frobenius_right = id_with(split.clone()).compose(&with_id(merge.clone()))?;
+        if !same(&frobenius_left, &frobenius_middle) || !same(&frobenius_right, &frobenius_middle) {
+            return Err("The Frobenius law did not hold".into());
+        }
+        if !same(&split.compose(&merge)?, &id) {
+            return Err("Splitting then merging was not the identity".into());
+        }
+        Ok(())
+    }
+}
+
 mod test {
 
     #[test]
@@ -762,6 +971,22 @@ mod test {
         }
     }
 
+    #[test]
+    fn compact_frobenius() {
+        use super::{FrobeniusMorphism, FrobeniusOperation};
+        use crate::{category::ComposableMutating, monoidal::Monoidal};
+        let mut x: FrobeniusMorphism<char, u8> = FrobeniusOperation::Multiplication('a').into();
+        x.monoidal(FrobeniusOperation::UnSpecifiedBox(3, vec![], vec!['b']).into());
+        x.compose(FrobeniusOperation::SymmetricBraiding('a', 'b').into())
+            .unwrap();
+        assert_eq!(
+            x.to_compact_string(),
+            "Frobenius(['a','a']→['b','a']): μ('a') ⊗ 3([]→['b']) ; σ('a','b')"
+        );
+        let nothing: FrobeniusMorphism<char, u8> = FrobeniusMorphism::new();
+        assert_eq!(nothing.to_compact_string(), "Frobenius([]→[]): id([])");
+    }
+
     #[test]
     fn basic_spiders() {
         use super::{special_frobenius_morphism, FrobeniusMorphism, FrobeniusOperation};
@@ -0,0 +1,266 @@
+use {
+    crate::category::{Composable, ProductMorphism},
+    std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData},
+};
+
+#[allow(dead_code)]
+pub trait Functor<T1: Eq, M1: Composable<T1>, T2: Eq, M2: Composable<T2>> {
+    /*
+    objects to objects and morphisms to morphisms
+    preserving identities and composition is up to the implementor
+    */
+    fn on_object(&self, object: &T1) -> T2;
+    fn on_morphism(&self, morphism: &M1) -> M2;
+}
+
+impl<T1, M1, T2, M2, F, G> Functor<T1, M1, T2, M2> for (F, G)
+where
+    T1: Eq,
+    M1: Composable<T1>,
+    T2: Eq,
+    M2: Composable<T2>,
+    F: Fn(&T1) -> T2,
+    G: Fn(&M1) -> M2,
+{
+    /*
+    a pair of functions, the first on objects and the second on morphisms
+    */
+    fn on_object(&self, object: &T1) -> T2 {
+        (self.0)(object)
+    }
+
+    fn on_morphism(&self, morphism: &M1) -> M2 {
+        (self.1)(morphism)
+    }
+}
+
+/*
+the projections out of a product category onto either factor
+*/
+#[allow(dead_code)]
+pub struct ProjectFirst;
+#[allow(dead_code)]
+pub struct ProjectSecond;
+
+impl<TA, TB, A, B> Functor<(TA, TB), ProductMorphism<A, B>, TA, A> for ProjectFirst
+where
+    TA: Eq + Clone,
+    TB: Eq,
+    A: Composable<TA> + Clone,
+    B: Composable<TB>,
+{
+    fn on_object(&self, object: &(TA, TB)) -> TA {
+        object.0.clone()
+    }
+
+    fn on_morphism(&self, morphism: &ProductMorphism<A, B>) -> A {
+        morphism.0.clone()
+    }
+}
+
+impl<TA, TB, A, B> Functor<(TA, TB), ProductMorphism<A, B>, TB, B> for ProjectSecond
+where
+    TA: Eq,
+    TB: Eq + Clone,
+    A: Composable<TA>,
+    B: Composable<TB> + Clone,
+{
+    fn on_object(&self, object: &(TA, TB)) -> TB {
+        object.1.clone()
+    }
+
+    fn on_morphism(&self, morphism: &ProductMorphism<A, B>) -> B {
+        morphism.1.clone()
+    }
+}
+
+#[allow(dead_code)]
+pub struct NaturalTransformation<T1, M1, T2, M2, F, G>
+where
+    T1: Eq + Hash,
+    M1: Composable<T1>,
+    T2: Eq,
+    M2: Composable<T2>,
+    F: Functor<T1, M1, T2, M2>,
+    G: Functor<T1, M1, T2, M2>,
+{
+    /*
+    from the functor source to the functor target
+    a component source(a) -> target(a) for each object a that has been given one
+    only finitely many objects can be given components
+    so naturality is only ever checked on the morphisms between those
+    */
+    source: F,
+    target: G,
+    components: HashMap<T1, M2>,
+    phantom: PhantomData<(M1, T2)>,
+}
+
+impl<T1, M1, T2, M2, F, G> NaturalTransformation<T1, M1, T2, M2, F, G>
+where
+    T1: Eq + Hash + Clone + Debug,
+    M1: Composable<T1>,
+    T2: Eq + Debug,
+    M2: Composable<T2>,
+    F: Functor<T1, M1, T2, M2>,
+    G: Functor<T1, M1, T2, M2>,
+{
+    #[allow(dead_code)]
+    pub fn new(source: F, target: G) -> Self {
+        Self {
+            source,
+            target,
+            components: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_components<I, H>(
+        source: F,
+        target: G,
+        objects: I,
+        component_maker: H,
+    ) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = T1>,
+        H: Fn(&T1) -> M2,
+    {
+        let mut answer = Self::new(source, target);
+        for object in objects {
+            let component = component_maker(&object);
+            answer.add_component(object, component)?;
+        }
+        Ok(answer)
+    }
+
+    #[allow(dead_code)]
+    pub fn add_component(&mut self, object: T1, component: M2) -> Result<(), String> {
+        /*
+        the component at object has to go from source(object) to target(object)
+        replaces any component that was already there
+        */
+        let expected_domain = self.source.on_object(&object);
+        let expected_codomain = self.target.on_object(&object);
+        if component.domain() != expected_domain || component.codomain() != expected_codomain {
+            return Err(format!(
+                "The component at {:?} went from {:?} to {:?} instead of from {:?} to {:?}",
+                object,
+                component.domain(),
+                component.codomain(),
+                expected_domain,
+                expected_codomain
+            ));
+        }
+        self.components.insert(object, component);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn component(&self, object: &T1) -> Option<&M2> {
+        self.components.get(object)
+    }
+
+    #[allow(dead_code)]
+    pub fn check_naturality(&self, morphisms: &[M1]) -> Result<(), String>
+    where
+        M2: PartialEq,
+    {
+        /*
+        for each f : a -> b in morphisms
+            source(f) ; eta_b = eta_a ; target(f)
+        an error if that fails or if a or b does not have a component
+        */
+        for morphism in morphisms {
+            let (a, b) = (morphism.domain(), morphism.codomain());
+            let missing = |object: &T1| format!("There was no component at {:?}", object);
+            let eta_a = self.component(&a).ok_or_else(|| missing(&a))?;
+            let eta_b = self.component(&b).ok_or_else(|| missing(&b))?;
+            let source_then_eta = self.source.on_morphism(morphism).compose(eta_b)?;
+            let eta_then_target = eta_a.compose(&self.target.on_morphism(morphism))?;
+            if source_then_eta != eta_then_target {
+                return Err(format!(
+                    "The naturality square for a morphism from {:?} to {:?} did not commute",
+                    a, b
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+mod test {
+
+    #[test]
+    fn mirror_intertwiner() {
+        use super::NaturalTransformation;
+        use crate::{
+            category::{Composable, HasIdentity},
+            rigid_monoidal::CompactClosed,
+            unstable::temperley_lieb::BrauerMorphism,
+        };
+        type B = BrauerMorphism<i32>;
+        fn reversal(n: usize) -> B {
+            /*
+            send the first strand to the end then reverse the rest
+            */
+            if n <= 1 {
+                return B::identity(&n);
+            }
+            B::swap(1, n - 1)
+                .compose(&(reversal(n - 1) & B::identity(&1)))
+                .unwrap()
+        }
+        let identity_functor = (|n: &usize| *n, |f: &B| f.clone());
+        let mirror_functor = (|n: &usize| *n, |f: &B| f.mirror());
+        let mut morphisms = B::temperley_lieb_gens(4);
+        morphisms.extend(B::symmetric_alg_gens(4));
+        morphisms.push(B::swap(1, 2));
+        morphisms.push(B::unit(&2));
+        morphisms.push(B::counit(&1) & B::identity(&2));
+
+        let eta =
+            NaturalTransformation::from_components(identity_functor, mirror_functor, 0..5, |n| {
+                reversal(*n)
+            })
+            .unwrap();
+        assert_eq!(eta.check_naturality(&morphisms), Ok(()));
+        assert_eq!(eta.component(&3), Some(&reversal(3)));
+
+        let not_natural =
+            NaturalTransformation::from_components(identity_functor, mirror_functor, 0..5, |n| {
+                B::identity(n)
+            })
+            .unwrap();
+        assert!(not_natural.check_naturality(&morphisms).is_err());
+
+        let mut partial = NaturalTransformation::new(identity_functor, mirror_functor);
+        assert!(partial.add_component(2, B::identity(&3)).is_err());
+        assert!(partial.add_component(2, reversal(2)).is_ok());
+        assert!(partial.check_naturality(&morphisms).is_err());
+    }
+
+    #[test]
+    fn product_projections() {
+        use super::{Functor, ProjectFirst, ProjectSecond};
+        use crate::category::{Composable, ProductMorphism};
+        use crate::unstable::temperley_lieb::BrauerMorphism;
+        type B = BrauerMorphism<i32>;
+        let e = B::temperley_lieb_gens(3);
+        let f = ProductMorphism(e[0].clone(), e[1].clone());
+        let g = ProductMorphism(e[1].clone(), e[0].clone());
+        let fg = f.compose(&g).unwrap();
+        let first: &dyn Functor<(usize, usize), ProductMorphism<B, B>, usize, B> = &ProjectFirst;
+        let second: &dyn Functor<(usize, usize), ProductMorphism<B, B>, usize, B> = &ProjectSecond;
+        assert_eq!(first.on_object(&fg.domain()), 3);
+        assert_eq!(
+            first.on_morphism(&fg),
+            first
+                .on_morphism(&f)
+                .compose(&first.on_morphism(&g))
+                .unwrap()
+        );
+        assert_eq!(second.on_morphism(&fg), e[1].compose(&e[0]).unwrap());
+        assert_eq!(second.on_object(&(2, 5)), 5);
+    }
+}
@@ -0,0 +1,365 @@
+use {
+    crate::{
+        category::Composable,
+        error::Error,
+        open_hypergraph::{Hyperedge, HypergraphMatch, OpenHypergraph},
+    },
+    std::fmt::Debug,
+};
+
+type VertexIndex = usize;
+
+/*
+a double pushout rule L <- K -> R
+K is only vertices, given by their labels
+    with left_map saying where they are in L and right_map where they are in R
+left_map has to be injective so that deleting what is in L but not K is well defined
+    right_map does not so a rule can glue vertices together
+the boundaries of L and R play no part
+    only their vertices and hyperedges do
+*/
+#[derive(Clone)]
+pub struct DpoRule<V: Sized + Eq + Copy + Debug, E> {
+    left: OpenHypergraph<V, E>,
+    interface: Vec<V>,
+    left_map: Vec<VertexIndex>,
+    right: OpenHypergraph<V, E>,
+    right_map: Vec<VertexIndex>,
+}
+
+impl<V, E> DpoRule<V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+    E: Clone + PartialEq,
+{
+    #[allow(dead_code)]
+    pub fn new(
+        left: OpenHypergraph<V, E>,
+        interface: Vec<V>,
+        left_map: Vec<VertexIndex>,
+        right: OpenHypergraph<V, E>,
+        right_map: Vec<VertexIndex>,
+    ) -> Result<Self, Error> {
+        for (map, side) in [(&left_map, &left), (&right_map, &right)] {
+            if map.len() != interface.len() {
+                return Err(Error::InterfaceLengthMismatch {
+                    left: interface.len(),
+                    right: map.len(),
+                });
+            }
+            for (idx, (k, v)) in interface.iter().zip(map).enumerate() {
+                if side.vertices().get(*v) != Some(k) {
+                    return Err(Error::InterfaceLabelMismatch {
+                        index: idx,
+                        left: format!("{:?}", k),
+                        right: format!("{:?}", side.vertices().get(*v)),
+                    });
+                }
+            }
+        }
+        if (1..left_map.len()).any(|idx| left_map[..idx].contains(&left_map[idx])) {
+            return Err("The interface has to go into the left hand side injectively".into());
+        }
+        Ok(Self {
+            left,
+            interface,
+            left_map,
+            right,
+            right_map,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn from_open_pair(
+        left: OpenHypergraph<V, E>,
+        right: OpenHypergraph<V, E>,
+    ) -> Result<Self, Error> {
+        /*
+        the rule for an equation between two open hypergraphs of the same type
+        the interface is the domain followed by the codomain
+            and the boundaries say where those go on each side
+        */
+        let interface = [left.domain(), left.codomain()].concat();
+        let left_map = [left.left(), left.right()].concat();
+        let right_map = [right.left(), right.right()].concat();
+        Self::new(left, interface, left_map, right, right_map)
+    }
+
+    #[allow(dead_code)]
+    pub fn interface(&self) -> &[V] {
+        &self.interface
+    }
+
+    #[allow(dead_code)]
+    pub fn find_matches(&self, host: &OpenHypergraph<V, E>) -> Vec<HypergraphMatch> {
+        /*
+        every injective label preserving way of putting L into host
+        whether they can be used is up to the gluing condition
+        */
+        self.left.monomorphisms_into(host)
+    }
+
+    #[allow(dead_code)]
+    pub fn gluing_condition(
+        &self,
+        host: &OpenHypergraph<V, E>,
+        found: &HypergraphMatch,
+    ) -> Result<(), Error> {
+        /*
+        the identification condition holds because matches are injective
+        the dangling condition is that a vertex being deleted
+            is not on a hyperedge that is staying
+            and is not on the boundary of the host
+        */
+        let deleted = self.deleted_vertices(host, found);
+        for (idx, e) in host.edges().iter().enumerate() {
+            if found.edges.contains(&idx) {
+                continue;
+            }
+            if e.sources.iter().chain(&e.targets).any(|v| deleted[*v]) {
+                return Err(format!("Hyperedge {} would be left dangling", idx).into());
+            }
+        }
+        if host.left().iter().chain(host.right()).any(|v| deleted[*v]) {
+            return Err("A vertex on the boundary would be deleted".into());
+        }
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn apply(
+        &self,
+        host: &OpenHypergraph<V, E>,
+        found: &HypergraphMatch,
+    ) -> Result<OpenHypergraph<V, E>, Error> {
+        /*
+        first the pushout complement D
+            which is host without the image of L except for the image of K
+        then the pushout of D <- K -> R
+            which adds the vertices and hyperedges of R that are not from K
+            and glues the vertices of D that K sends to the same vertex of R
+        */
+        self.gluing_condition(host, found)?;
+        let deleted = self.deleted_vertices(host, found);
+
+        /*
+        renumber the vertices of host that survive into D
+        and then merge those that R glues together
+        */
+        let mut new_index: Vec<Option<VertexIndex>> = vec![None; host.vertices().len()];
+        let mut vertices = Vec::with_capacity(host.vertices().len());
+        for (idx, label) in host.vertices().iter().enumerate() {
+            if !deleted[idx] {
+                new_index[idx] = Some(vertices.len());
+                vertices.push(*label);
+            }
+        }
+        let mut glued: Vec<VertexIndex> = (0..vertices.len()).collect();
+        let mut from_right: Vec<Option<VertexIndex>> = vec![None; self.right.vertices().len()];
+        for (k, r) in self.right_map.iter().enumerate() {
+            let in_d = new_index[found.vertices[self.left_map[k]]].expect("K is not deleted");
+            match from_right[*r] {
+                None => from_right[*r] = Some(in_d),
+                Some(earlier) => {
+                    let (keep, lose) = (glued[earlier], glued[in_d]);
+                    glued
+                        .iter_mut()
+                        .filter(|g| **g == lose)
+                        .for_each(|g| *g = keep);
+                }
+            }
+        }
+        for (r, label) in self.right.vertices().iter().enumerate() {
+            if from_right[r].is_none() {
+                from_right[r] = Some(vertices.len());
+                glued.push(vertices.len());
+                vertices.push(*label);
+            }
+        }
+        /*
+        drop the vertices that were merged into others and number what is left in order
+        */
+        let mut compact = vec![None; vertices.len()];
+        let mut kept_vertices = Vec::with_capacity(vertices.len());
+        for (idx, label) in vertices.iter().enumerate() {
+            if glued[idx] == idx {
+                compact[idx] = Some(kept_vertices.len());
+                kept_vertices.push(*label);
+            }
+        }
+        let in_result = |d_vertex: VertexIndex| compact[glued[d_vertex]].expect("a representative");
+        let from_host = |v: &VertexIndex| in_result(new_index[*v].expect("not deleted"));
+        let from_rule = |v: &VertexIndex| in_result(from_right[*v].expect("every vertex of R"));
+
+        let mut edges: Vec<Hyperedge<E>> = host
+            .edges()
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !found.edges.contains(idx))
+            .map(|(_, e)| Hyperedge {
+                label: e.label.clone(),
+                sources: e.sources.iter().map(from_host).collect(),
+                targets: e.targets.iter().map(from_host).collect(),
+            })
+            .collect();
+        edges.extend(self.right.edges().iter().map(|e| Hyperedge {
+            label: e.label.clone(),
+            sources: e.sources.iter().map(from_rule).collect(),
+            targets: e.targets.iter().map(from_rule).collect(),
+        }));
+        OpenHypergraph::new(
+            kept_vertices,
+            edges,
+            host.left().iter().map(from_host).collect(),
+            host.right().iter().map(from_host).collect(),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn rewrite_once(&self, host: &OpenHypergraph<V, E>) -> Option<OpenHypergraph<V, E>> {
+        /*
+        the first match that satisfies the gluing condition
+        */
+        self.find_matches(host)
+            .iter()
+            .find_map(|found| self.apply(host, found).ok())
+    }
+
+    fn deleted_vertices(&self, host: &OpenHypergraph<V, E>, found: &HypergraphMatch) -> Vec<bool> {
+        /*
+        the vertices of the host that are in the image of L but not of K
+        indexed by the vertices of the host
+        */
+        let mut deleted = vec![false; host.vertices().len()];
+        for (l, h) in found.vertices.iter().enumerate() {
+            deleted[*h] = !self.left_map.contains(&l);
+        }
+        deleted
+    }
+}
+
+mod test {
+
+    #[test]
+    fn rewriting_edges() {
+        use super::DpoRule;
+        use crate::category::{Composable, HasIdentity};
+        use crate::frobenius::HypergraphCategory;
+        use crate::monoidal::Monoidal;
+        use crate::open_hypergraph::OpenHypergraph;
+        type H = OpenHypergraph<char, char>;
+        let f = H::from_edge('f', &['a'], &['b']);
+        let g = H::from_edge('g', &['b'], &['c']);
+        let h = H::from_edge('h', &['a'], &['c']);
+        let rule = DpoRule::from_open_pair(f.compose(&g).unwrap(), h.clone()).unwrap();
+        assert_eq!(rule.interface(), ['a', 'c']);
+
+        let e = H::from_edge('e', &['a'], &['a']);
+        let host = e.compose(&f).unwrap().compose(&g).unwrap();
+        let matches = rule.find_matches(&host);
+        assert_eq!(matches.len(), 1);
+        let rewritten = rule.apply(&host, &matches[0]).unwrap();
+        assert_eq!(rewritten.domain(), vec!['a']);
+        assert_eq!(rewritten.codomain(), vec!['c']);
+        assert_eq!(rewritten.vertices().len(), 3);
+        let labels = rewritten
+            .edges()
+            .iter()
+            .map(|e| e.label)
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!['e', 'h']);
+        assert_eq!(rewritten.edges()[0].targets, rewritten.edges()[1].sources);
+        assert_eq!(rewritten.left(), rewritten.edges()[0].sources.as_slice());
+        assert_eq!(rewritten.right(), rewritten.edges()[1].targets.as_slice());
+        assert!(rule.rewrite_once(&rewritten).is_none());
+
+        /*
+        the middle vertex is also used by k so deleting it would leave k dangling
+        */
+        let k = H::from_edge('k', &['b'], &['d']);
+        let mut g_and_k = g.clone();
+        g_and_k.monoidal(k);
+        let shared = f
+            .compose(&H::split('b'))
+            .unwrap()
+            .compose(&g_and_k)
+            .unwrap();
+        let matches = rule.find_matches(&shared);
+        assert_eq!(matches.len(), 1);
+        assert!(rule.gluing_condition(&shared, &matches[0]).is_err());
+        assert!(rule.apply(&shared, &matches[0]).is_err());
+        assert!(rule.rewrite_once(&shared).is_none());
+
+        /*
+        the middle vertex is on the boundary so can not be deleted either
+        */
+        let mut g_and_wire = g.clone();
+        g_and_wire.monoidal(H::identity(&vec!['b']));
+        let exposed = f
+            .compose(&H::split('b'))
+            .unwrap()
+            .compose(&g_and_wire)
+            .unwrap();
+        assert_eq!(rule.find_matches(&exposed).len(), 1);
+        assert!(rule.rewrite_once(&exposed).is_none());
+    }
+
+    #[test]
+    fn matching_and_gluing() {
+        use super::DpoRule;
+        use crate::category::Composable;
+        use crate::error::Error;
+        use crate::monoidal::Monoidal;
+        use crate::open_hypergraph::OpenHypergraph;
+        type H = OpenHypergraph<char, char>;
+
+        /*
+        d takes two inputs and goes away leaving them glued together
+        */
+        let d = H::from_edge('d', &['a', 'a'], &[]);
+        let one_vertex = H::new(vec!['a'], vec![], vec![], vec![]).unwrap();
+        let rule = DpoRule::new(
+            d.clone(),
+            vec!['a', 'a'],
+            vec![0, 1],
+            one_vertex.clone(),
+            vec![0, 0],
+        )
+        .unwrap();
+        let mut p_and_q = H::from_edge('p', &[], &['a']);
+        p_and_q.monoidal(H::from_edge('q', &[], &['a']));
+        let host = p_and_q.compose(&d).unwrap();
+        let rewritten = rule.rewrite_once(&host).unwrap();
+        assert_eq!(rewritten.vertices(), ['a']);
+        assert_eq!(rewritten.edges().len(), 2);
+        assert_eq!(rewritten.edges()[0].targets, rewritten.edges()[1].targets);
+
+        /*
+        the two inputs of d can go either way round
+        */
+        assert_eq!(rule.find_matches(&host).len(), 1);
+        let mut two_p = H::from_edge('p', &[], &['a']);
+        two_p.monoidal(H::from_edge('p', &[], &['a']));
+        let just_p = DpoRule::from_open_pair(
+            H::from_edge('p', &[], &['a']),
+            H::from_edge('q', &[], &['a']),
+        )
+        .unwrap();
+        assert_eq!(just_p.find_matches(&two_p).len(), 2);
+        assert_eq!(just_p.find_matches(&d).len(), 0);
+
+        assert!(matches!(
+            DpoRule::new(d.clone(), vec!['b'], vec![0], one_vertex.clone(), vec![0]),
+            Err(Error::InterfaceLabelMismatch { .. })
+        ));
+        assert!(DpoRule::new(
+            d.clone(),
+            vec!['a', 'a'],
+            vec![0, 0],
+            one_vertex.clone(),
+            vec![0, 0]
+        )
+        .is_err());
+        assert!(DpoRule::new(d, vec!['a'], vec![0], one_vertex, vec![]).is_err());
+    }
+}
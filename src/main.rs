@@ -3,15 +3,28 @@ use either::Either::{Left, Right};
 use petgraph::dot::Dot;
 use union_find::{QuickUnionUf, UnionBySize};
 
+mod capabilities;
 mod category;
 mod utils;
 use category::ComposableMutating;
 mod cospan;
+mod error;
+mod functor;
+mod hypergraph_rewriting;
+mod matrix;
 mod monoidal;
 mod named_cospan;
+mod open_hypergraph;
+mod petri_net;
+mod port_graph;
+mod prop;
+mod random_diagram;
+mod rewriting;
 mod span;
+mod stochastic;
 mod symmetric_monoidal;
 use named_cospan::NamedCospan;
+mod finrel;
 mod finset;
 #[allow(unused_imports)]
 use finset::{Decomposition, OrderPresInj, OrderPresSurj};
@@ -19,11 +32,13 @@ mod frobenius;
 use frobenius::{special_frobenius_morphism, FrobeniusMorphism, FrobeniusOperation};
 mod wiring_diagram;
 use wiring_diagram::WiringDiagram;
+mod undirected_wiring_diagram;
 
 use crate::wiring_diagram::InOut;
 
-mod linear_combination;
-mod temperley_lieb;
+mod rigid_monoidal;
+#[doc(hidden)]
+pub mod unstable;
 
 fn main() {
     let mut x = NamedCospan::<u32, &'static str, &'static str>::empty();
@@ -67,7 +82,7 @@ fn main() {
         (InOut::Out, 3),
         (InOut::Out, 4),
     ];
-    let _example: WiringDiagram<_, (), _> = WiringDiagram::new(NamedCospan::new(
+    let _example: WiringDiagram<_, (), _> = WiringDiagram::from_named_cospan(NamedCospan::new(
         vec![],
         vec![0, 1, 2, 2, 0],
         vec![true, true, false],
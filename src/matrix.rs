@@ -0,0 +1,405 @@
+use {
+    crate::{
+        category::{Composable, ComposableMutating, Dagger, HasIdentity},
+        error::Error,
+        monoidal::{GenericMonoidalMorphism, Monoidal},
+        symmetric_monoidal::SymmetricMonoidalDiscreteMorphism,
+        unstable::coefficients::Conjugate,
+    },
+    num::{One, Zero},
+    permutations::Permutation,
+    std::{fmt::Debug, ops::Mul},
+};
+
+/*
+a matrix with entries in a semiring as a morphism between dimensions
+    with a row for each basis element of the domain
+    and a column for each basis element of the codomain
+so that composing self then other is the product of the matrices in that order
+the monoidal product is the Kronecker product
+    so the dimension of a tensor product is the product of the dimensions
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MatrixMorphism<R> {
+    domain: usize,
+    codomain: usize,
+    entries: Vec<Vec<R>>,
+}
+
+impl<R> MatrixMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    #[allow(dead_code)]
+    pub fn new(domain: usize, codomain: usize, entries: Vec<Vec<R>>) -> Result<Self, Error> {
+        if entries.len() != domain {
+            return Err(Error::InterfaceLengthMismatch {
+                left: domain,
+                right: entries.len(),
+            });
+        }
+        if let Some(bad_row) = entries.iter().find(|row| row.len() != codomain) {
+            return Err(Error::InterfaceLengthMismatch {
+                left: codomain,
+                right: bad_row.len(),
+            });
+        }
+        Ok(Self {
+            domain,
+            codomain,
+            entries,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn zero(domain: usize, codomain: usize) -> Self {
+        Self {
+            domain,
+            codomain,
+            entries: vec![vec![R::zero(); codomain]; domain],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn entry(&self, row: usize, column: usize) -> &R {
+        &self.entries[row][column]
+    }
+
+    #[allow(dead_code)]
+    pub fn entries(&self) -> &[Vec<R>] {
+        &self.entries
+    }
+
+    #[allow(dead_code)]
+    pub fn map_entries<R2, F>(&self, entry_changer: F) -> MatrixMorphism<R2>
+    where
+        F: Fn(&R) -> R2,
+    {
+        MatrixMorphism {
+            domain: self.domain,
+            codomain: self.codomain,
+            entries: self
+                .entries
+                .iter()
+                .map(|row| row.iter().map(&entry_changer).collect())
+                .collect(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn swap(first: usize, second: usize) -> Self {
+        /*
+        the symmetry of the Kronecker product
+        the basis element a*second+b of first \otimes second
+            goes to b*first+a of second \otimes first
+        */
+        let shuffle = (0..first * second)
+            .map(|idx| (idx % second) * first + idx / second)
+            .collect::<Vec<_>>();
+        Self::from_permutation(
+            Permutation::try_from(shuffle).expect("a shuffle is a permutation"),
+            first * second,
+            true,
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn interpret<BoxType, Lambda, F, G>(
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+        dimension_of: &G,
+        black_box_interpreter: &F,
+    ) -> Result<Self, Error>
+    where
+        Lambda: Eq + Copy + Debug,
+        F: Fn(&BoxType) -> Result<Self, Error>,
+        G: Fn(&Lambda) -> usize,
+    {
+        /*
+        send each wire to a vector space of dimension dimension_of its label
+        and each box to a matrix with the dimensions that go with its sides
+        an error if some box was sent to one of the wrong size
+        */
+        let dimension = |types: &[Lambda]| types.iter().map(dimension_of).product::<usize>();
+        let mut answer = Self::identity(&dimension(&morphism.domain()));
+        for (idx, layer) in morphism.layers().iter().enumerate() {
+            let mut cur_layer = Self::identity(&1);
+            for block in &layer.blocks {
+                cur_layer.monoidal(black_box_interpreter(block)?);
+            }
+            let expected = (dimension(&layer.left_type), dimension(&layer.right_type));
+            for (expected, found) in [
+                (expected.0, cur_layer.domain),
+                (expected.1, cur_layer.codomain),
+            ] {
+                if expected != found {
+                    return Err(Error::InLayer {
+                        layer: idx,
+                        cause: Box::new(Error::InterfaceLengthMismatch {
+                            left: expected,
+                            right: found,
+                        }),
+                    });
+                }
+            }
+            answer = answer.compose(&cur_layer)?;
+        }
+        Ok(answer)
+    }
+}
+
+impl<R> HasIdentity<usize> for MatrixMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn identity(on_this: &usize) -> Self {
+        let mut answer = Self::zero(*on_this, *on_this);
+        for idx in 0..*on_this {
+            answer.entries[idx][idx] = R::one();
+        }
+        answer
+    }
+}
+
+impl<R> Composable<usize> for MatrixMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        if self.composable(other).is_err() {
+            return Err(Error::InterfaceLengthMismatch {
+                left: self.codomain(),
+                right: other.domain(),
+            });
+        }
+        let mut answer = Self::zero(self.domain, other.codomain);
+        for (row, self_row) in self.entries.iter().enumerate() {
+            for (middle, self_entry) in self_row.iter().enumerate() {
+                if self_entry.is_zero() {
+                    continue;
+                }
+                for (column, other_entry) in other.entries[middle].iter().enumerate() {
+                    let summand = self_entry.clone() * other_entry.clone();
+                    answer.entries[row][column] = answer.entries[row][column].clone() + summand;
+                }
+            }
+        }
+        Ok(answer)
+    }
+
+    fn domain(&self) -> usize {
+        self.domain
+    }
+
+    fn codomain(&self) -> usize {
+        self.codomain
+    }
+}
+
+impl<R> Monoidal for MatrixMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn monoidal(&mut self, other: Self) {
+        /*
+        the Kronecker product
+        the basis element (a,b) is numbered a*n+b where n is the dimension on that side of other
+        */
+        let mut answer = Self::zero(self.domain * other.domain, self.codomain * other.codomain);
+        for (row, self_row) in self.entries.iter().enumerate() {
+            for (column, self_entry) in self_row.iter().enumerate() {
+                for (other_row, other_entries) in other.entries.iter().enumerate() {
+                    for (other_column, other_entry) in other_entries.iter().enumerate() {
+                        answer.entries[row * other.domain + other_row]
+                            [column * other.codomain + other_column] =
+                            self_entry.clone() * other_entry.clone();
+                    }
+                }
+            }
+        }
+        *self = answer;
+    }
+}
+
+impl<R> Dagger<usize> for MatrixMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R> + Conjugate,
+{
+    fn dagger(&self) -> Self {
+        /*
+        the conjugate transpose
+        which is just the transpose when the entries are real
+        */
+        let mut answer = Self::zero(self.codomain, self.domain);
+        for (row, self_row) in self.entries.iter().enumerate() {
+            for (column, entry) in self_row.iter().enumerate() {
+                answer.entries[column][row] = entry.conjugate();
+            }
+        }
+        answer
+    }
+}
+
+impl<R> SymmetricMonoidalDiscreteMorphism<usize> for MatrixMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        /*
+        relabel the basis on that side
+        the same convention as for FinRel, what was at position k is now at position p.inv()(k)
+        */
+        let p_inv = p.inv();
+        let mut answer = Self::zero(self.domain, self.codomain);
+        for (row, self_row) in self.entries.iter().enumerate() {
+            for (column, entry) in self_row.iter().enumerate() {
+                if of_codomain {
+                    answer.entries[row][p_inv.apply(column)] = entry.clone();
+                } else {
+                    answer.entries[p_inv.apply(row)][column] = entry.clone();
+                }
+            }
+        }
+        *self = answer;
+    }
+
+    fn from_permutation(p: Permutation, type_: usize, _types_as_on_domain: bool) -> Self {
+        /*
+        the permutation matrix sending basis element i to p(i)
+        */
+        let mut answer = Self::zero(type_, type_);
+        for idx in 0..type_ {
+            answer.entries[idx][p.apply(idx)] = R::one();
+        }
+        answer
+    }
+}
+
+mod test {
+
+    #[test]
+    fn matrix_category() {
+        use super::MatrixMorphism;
+        use crate::category::{Composable, Dagger, HasIdentity};
+        use crate::error::Error;
+        use crate::monoidal::Monoidal;
+        use crate::symmetric_monoidal::SymmetricMonoidalDiscreteMorphism;
+        use num::Complex;
+        use permutations::Permutation;
+        type M = MatrixMorphism<i32>;
+        let a = M::new(2, 3, vec![vec![1, 2, 0], vec![0, 1, -1]]).unwrap();
+        let b = M::new(3, 1, vec![vec![1], vec![1], vec![2]]).unwrap();
+        assert_eq!(
+            a.compose(&b).unwrap(),
+            M::new(2, 1, vec![vec![3], vec![-1]]).unwrap()
+        );
+        assert_eq!(
+            b.compose(&a).err(),
+            Some(Error::InterfaceLengthMismatch { left: 1, right: 2 })
+        );
+        assert!(M::new(2, 2, vec![vec![1, 0], vec![1]]).is_err());
+        assert_eq!(M::identity(&2).compose(&a).unwrap(), a);
+        assert_eq!(a.dagger().dagger(), a);
+        assert_eq!(
+            a.compose(&b).unwrap().dagger(),
+            b.dagger().compose(&a.dagger()).unwrap()
+        );
+
+        let mut kronecker = a.clone();
+        kronecker.monoidal(b.clone());
+        assert_eq!((kronecker.domain(), kronecker.codomain()), (6, 3));
+        assert_eq!(*kronecker.entry(5, 2), -2);
+        assert_eq!(*kronecker.entry(1, 0), 1);
+        let mut unit_first = M::identity(&1);
+        unit_first.monoidal(a.clone());
+        assert_eq!(unit_first, a);
+
+        /*
+        the swap moves Kronecker products past each other
+        */
+        let mut ab = a.clone();
+        ab.monoidal(b.clone());
+        let mut ba = b.clone();
+        ba.monoidal(a.clone());
+        assert_eq!(
+            M::swap(2, 3).compose(&ba).unwrap(),
+            ab.compose(&M::swap(3, 1)).unwrap()
+        );
+        assert_eq!(
+            M::swap(2, 3).compose(&M::swap(3, 2)).unwrap(),
+            M::identity(&6)
+        );
+        assert!(M::swap(2, 3).is_unitary().unwrap());
+
+        /*
+        the dagger of a complex matrix conjugates the entries as well as transposing
+        without the conjugation this one would square to minus the identity instead
+        */
+        let i = Complex::new(0, 1);
+        let zero = Complex::new(0, 0);
+        let flip = MatrixMorphism::new(2, 2, vec![vec![zero, i], vec![i, zero]]).unwrap();
+        assert_eq!(*flip.dagger().entry(1, 0), -i);
+        assert!(flip.is_unitary().unwrap());
+
+        let p = Permutation::try_from(vec![2, 0, 1]).unwrap();
+        let q = Permutation::try_from(vec![1, 0, 2]).unwrap();
+        assert_eq!(
+            M::from_permutation(p.clone(), 3, true)
+                .compose(&M::from_permutation(q.clone(), 3, true))
+                .unwrap(),
+            M::from_permutation(p.clone() * q.clone(), 3, true)
+        );
+        let mut relabelled = M::identity(&3);
+        relabelled.permute_side(&p, true);
+        assert_eq!(relabelled, M::from_permutation(p.inv(), 3, true));
+    }
+
+    #[test]
+    fn matrix_interpretation() {
+        use super::MatrixMorphism;
+        use crate::category::{Composable, HasIdentity};
+        use crate::error::Error;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        type Op = FrobeniusOperation<char, ()>;
+        type M = MatrixMorphism<u32>;
+        /*
+        the Frobenius algebra of functions on a set of size 2 with pointwise operations
+        */
+        let to_matrix = |op: &Op| -> Result<M, Error> {
+            match op {
+                Op::Identity(_) => Ok(M::identity(&2)),
+                Op::Multiplication(_) => {
+                    M::new(4, 2, vec![vec![1, 0], vec![0, 0], vec![0, 0], vec![0, 1]])
+                }
+                Op::Comultiplication(_) => M::new(2, 4, vec![vec![1, 0, 0, 0], vec![0, 0, 0, 1]]),
+                Op::Unit(_) => M::new(1, 2, vec![vec![1, 1]]),
+                Op::Counit(_) => M::new(2, 1, vec![vec![1], vec![1]]),
+                _ => Err(Error::from("not used here")),
+            }
+        };
+        let dimension = |_: &char| 2;
+        let unit_law = monoidal!([Op::Unit('a'), id('a')]; [Op::Multiplication('a')]).unwrap();
+        assert_eq!(
+            M::interpret(&unit_law, &dimension, &to_matrix).unwrap(),
+            M::identity(&2)
+        );
+        let special = monoidal!([Op::Comultiplication('a')]; [Op::Multiplication('a')]).unwrap();
+        assert_eq!(
+            M::interpret(&special, &dimension, &to_matrix).unwrap(),
+            M::identity(&2)
+        );
+        let circle = monoidal!([Op::Unit('a')]; [Op::Counit('a')]).unwrap();
+        let scalar = M::interpret(&circle, &dimension, &to_matrix).unwrap();
+        assert_eq!((scalar.domain(), scalar.codomain()), (1, 1));
+        assert_eq!(*scalar.entry(0, 0), 2);
+
+        let wrong_size = |_: &Op| -> Result<M, Error> { Ok(M::identity(&3)) };
+        assert_eq!(
+            M::interpret(&unit_law, &dimension, &wrong_size).err(),
+            Some(Error::InLayer {
+                layer: 0,
+                cause: Box::new(Error::InterfaceLengthMismatch { left: 2, right: 9 })
+            })
+        );
+    }
+}
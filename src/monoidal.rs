@@ -1,6 +1,13 @@
 use {
-    crate::category::{Composable, ComposableMutating, HasIdentity},
-    std::fmt::Debug,
+    crate::{
+        category::{Composable, ComposableMutating, Dagger, HasIdentity, Op, ProductMorphism},
+        error::Error,
+        symmetric_monoidal::{HasSymmetricBraiding, SymmetricMonoidalMutatingMorphism},
+        utils::{compact_list, dot_escape, same_labels_check},
+    },
+    either::Either::{self, Left, Right},
+    permutations::Permutation,
+    std::{collections::HashMap, fmt::Debug, hash::Hash},
 };
 
 pub trait Monoidal {
@@ -8,9 +15,31 @@ pub trait Monoidal {
     change the morphism self to the morphism (self \otimes other)
     */
     fn monoidal(&mut self, other: Self);
+
+    #[allow(dead_code)]
+    fn monoidal_product(&self, other: &Self) -> Self
+    where
+        Self: Clone,
+    {
+        /*
+        the morphism (self \otimes other) leaving both as they were
+        */
+        let mut answer = self.clone();
+        answer.monoidal(other.clone());
+        answer
+    }
+}
+
+pub trait HasWireTypes<Lambda> {
+    /*
+    the labels of the wires going into and coming out of a black box
+    */
+    fn source_types(&self) -> Vec<Lambda>;
+    fn target_types(&self) -> Vec<Lambda>;
 }
 
 #[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenericMonoidalMorphismLayer<BoxType, Lambda: Eq + Copy> {
     /*
     a single layer for a black box filled morphism
@@ -37,6 +66,27 @@ where
     }
 }
 
+impl<BoxType, Lambda> GenericMonoidalMorphismLayer<BoxType, Lambda>
+where
+    Lambda: Eq + Copy,
+    BoxType: HasWireTypes<Lambda>,
+{
+    #[allow(dead_code)]
+    pub fn from_blocks(blocks: Vec<BoxType>) -> Self {
+        /*
+        the boxes placed side by side
+        with the interfaces read off from the boxes themselves
+        */
+        let left_type = blocks.iter().flat_map(|b| b.source_types()).collect();
+        let right_type = blocks.iter().flat_map(|b| b.target_types()).collect();
+        Self {
+            blocks,
+            left_type,
+            right_type,
+        }
+    }
+}
+
 impl<BoxType, Lambda> HasIdentity<Vec<Lambda>> for GenericMonoidalMorphismLayer<BoxType, Lambda>
 where
     Lambda: Eq + Copy,
@@ -65,6 +115,19 @@ where
 }
 
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "Vec<GenericMonoidalMorphismLayer<BoxType, Lambda>>",
+        into = "Vec<GenericMonoidalMorphismLayer<BoxType, Lambda>>",
+        bound(
+            serialize = "BoxType: Clone + serde::Serialize, Lambda: serde::Serialize",
+            deserialize = "BoxType: serde::Deserialize<'de>, \
+                Lambda: serde::Deserialize<'de> + Debug"
+        )
+    )
+)]
 pub struct GenericMonoidalMorphism<BoxType, Lambda: Eq + Copy> {
     /*
     a black box filled morphism
@@ -81,7 +144,7 @@ pub struct GenericMonoidalMorphism<BoxType, Lambda: Eq + Copy> {
 
 impl<Lambda, BoxType> GenericMonoidalMorphism<BoxType, Lambda>
 where
-    Lambda: Eq + Copy,
+    Lambda: Eq + Copy + Debug,
 {
     #[allow(dead_code)]
     pub fn new() -> Self {
@@ -94,21 +157,761 @@ where
     }
 
     #[allow(dead_code)]
-    fn append_layer(
+    pub fn layers(&self) -> &[GenericMonoidalMorphismLayer<BoxType, Lambda>] {
+        &self.layers
+    }
+
+    #[allow(dead_code)]
+    pub fn blocks_at(&self, layer: usize) -> Option<&[BoxType]> {
+        self.layers.get(layer).map(|l| &l.blocks[..])
+    }
+
+    #[allow(dead_code)]
+    pub fn interface_at(&self, layer: usize) -> Option<(&[Lambda], &[Lambda])> {
+        /*
+        the wires going into and coming out of that layer
+        */
+        self.layers
+            .get(layer)
+            .map(|l| (&l.left_type[..], &l.right_type[..]))
+    }
+
+    #[allow(dead_code)]
+    pub fn iter_boxes(&self) -> impl Iterator<Item = (usize, &BoxType)> + '_ {
+        /*
+        every box from first layer to last and left to right within a layer
+        along with which layer it is in
+        */
+        self.layers
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, l)| l.blocks.iter().map(move |b| (idx, b)))
+    }
+
+    #[allow(dead_code)]
+    pub fn append_layer(
         &mut self,
         next_layer: GenericMonoidalMorphismLayer<BoxType, Lambda>,
-    ) -> Result<(), String> {
-        if let Some(last_so_far) = self.layers.pop() {
-            if last_so_far.right_type != next_layer.left_type {
-                return Err("type mismatch in morphims composition".to_string());
-            }
-            self.layers.push(last_so_far);
+    ) -> Result<(), Error> {
+        if let Some(last_so_far) = self.layers.last() {
+            same_labels_check(last_so_far.right_type.iter(), next_layer.left_type.iter())?;
         }
         self.layers.push(next_layer);
         Ok(())
     }
 }
 
+impl<Lambda, BoxType> TryFrom<Vec<GenericMonoidalMorphismLayer<BoxType, Lambda>>>
+    for GenericMonoidalMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+{
+    type Error = Error;
+
+    fn try_from(
+        layers: Vec<GenericMonoidalMorphismLayer<BoxType, Lambda>>,
+    ) -> Result<Self, Self::Error> {
+        /*
+        stack up the layers making sure each one starts where the previous one ended
+        this is what gets used when reading one back in from serialized data
+        */
+        let mut answer = Self::new();
+        for layer in layers {
+            answer.append_layer(layer)?;
+        }
+        Ok(answer)
+    }
+}
+
+impl<Lambda, BoxType> From<GenericMonoidalMorphism<BoxType, Lambda>>
+    for Vec<GenericMonoidalMorphismLayer<BoxType, Lambda>>
+where
+    Lambda: Eq + Copy,
+{
+    fn from(morphism: GenericMonoidalMorphism<BoxType, Lambda>) -> Self {
+        morphism.layers
+    }
+}
+
+/*
+a box that is not an identity along with
+    where it starts in the wires present just before it
+    how many wires go in and the types of those coming out
+*/
+type PlacedBox<BoxType, Lambda> = (BoxType, usize, usize, Vec<Lambda>);
+
+impl<Lambda, BoxType> GenericMonoidalMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone + PartialEq + HasIdentity<Lambda> + HasWireTypes<Lambda>,
+{
+    #[allow(dead_code)]
+    pub fn normalize(&mut self) {
+        /*
+        slide every box as early as the interchange law allows
+        and drop the identity boxes along with any layers that only had those
+        afterwards morphisms that only differed by the interchange law
+            or by inserted identities are equal as GenericMonoidalMorphisms
+        the one ambiguity left is between boxes without inputs and boxes without outputs
+            that meet at the same point, those stay in the order they were built
+        */
+        let domain = self.domain();
+        let placed = self.placed_boxes();
+        if placed.is_empty() {
+            if !self.layers.is_empty() {
+                *self = Self::identity(&domain);
+            }
+            return;
+        }
+        let mut leveled = Self::earliest_levels(placed, domain.len());
+        for i in 1..leveled.len() {
+            let mut j = i;
+            while j > 0 && leveled[j - 1].0 > leveled[j].0 {
+                let (first, second) = leveled.split_at_mut(j);
+                Self::interchange(&mut first[j - 1].1, &mut second[0].1);
+                leveled.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+        let mut layers = vec![];
+        let mut frontier = domain;
+        let mut remaining = &leveled[..];
+        while let Some((level, _)) = remaining.first() {
+            let same_level = remaining.iter().take_while(|(l, _)| l == level).count();
+            let (current, rest) = remaining.split_at(same_level);
+            let layer = Self::layer_of(current.iter().map(|(_, b)| b), &frontier);
+            frontier = layer.right_type.clone();
+            layers.push(layer);
+            remaining = rest;
+        }
+        self.layers = layers;
+    }
+
+    #[allow(dead_code)]
+    pub fn compact_layers(&mut self) {
+        /*
+        merge each layer into the one before it whenever they do not interact
+        meaning every wire between them passes through an identity on at least one side
+        unlike normalize the identity boxes stay and nothing moves past a layer it can not merge with
+        so this is cheap and keeps whatever order the morphism was built in
+        */
+        let mut compacted: Vec<GenericMonoidalMorphismLayer<BoxType, Lambda>> =
+            Vec::with_capacity(self.layers.len());
+        for layer in self.layers.drain(..) {
+            let merged = compacted
+                .last()
+                .and_then(|previous| Self::merge_layers(previous, &layer));
+            match merged {
+                Some(merged) => *compacted.last_mut().expect("merged with it") = merged,
+                None => compacted.push(layer),
+            }
+        }
+        self.layers = compacted;
+    }
+
+    #[allow(dead_code)]
+    pub fn enumerate_hom(
+        generators: &[BoxType],
+        domain: &[Lambda],
+        codomain: &[Lambda],
+        max_depth: usize,
+    ) -> Vec<Self> {
+        /*
+        all morphisms from domain to codomain in the free strict monoidal category
+            on the generators that use at most max_depth generators
+        every such morphism is a sequence of single generators with identities on either side
+            so grow all those sequences one generator at a time
+        different sequences related by the interchange law are the same morphism
+            so only one of each normal form is kept
+            with the same caveat as normalize about boxes without inputs or outputs
+        nothing besides interchange is imposed so this is only finite because
+            each step has finitely many generators and places to put them
+        */
+        let mut found: Vec<Self> = vec![];
+        let mut frontier = vec![(domain.to_vec(), Self::identity(&domain.to_vec()))];
+        for depth in 0..=max_depth {
+            for (types, so_far) in &frontier {
+                if types == codomain {
+                    let mut candidate = so_far.clone();
+                    candidate.normalize();
+                    if !found.contains(&candidate) {
+                        found.push(candidate);
+                    }
+                }
+            }
+            if depth == max_depth {
+                break;
+            }
+            let mut next_frontier = vec![];
+            for (types, so_far) in &frontier {
+                for generator in generators {
+                    let sources = generator.source_types();
+                    if sources.len() > types.len() {
+                        continue;
+                    }
+                    for pos in 0..=(types.len() - sources.len()) {
+                        let after = pos + sources.len();
+                        if types[pos..after] != sources[..] {
+                            continue;
+                        }
+                        let blocks = types[..pos]
+                            .iter()
+                            .map(BoxType::identity)
+                            .chain(std::iter::once(generator.clone()))
+                            .chain(types[after..].iter().map(BoxType::identity))
+                            .collect();
+                        let mut right_type = types[..pos].to_vec();
+                        right_type.extend(generator.target_types());
+                        right_type.extend_from_slice(&types[after..]);
+                        let mut extended = so_far.clone();
+                        let appended = extended.append_layer(GenericMonoidalMorphismLayer {
+                            blocks,
+                            left_type: types.clone(),
+                            right_type: right_type.clone(),
+                        });
+                        if appended.is_ok() {
+                            next_frontier.push((right_type, extended));
+                        }
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+        found
+    }
+
+    fn is_identity_box(block: &BoxType) -> bool {
+        let source_types = block.source_types();
+        source_types.len() == 1 && *block == BoxType::identity(&source_types[0])
+    }
+
+    fn merge_layers(
+        first: &GenericMonoidalMorphismLayer<BoxType, Lambda>,
+        second: &GenericMonoidalMorphismLayer<BoxType, Lambda>,
+    ) -> Option<GenericMonoidalMorphismLayer<BoxType, Lambda>> {
+        /*
+        walk along the wires between the two layers
+        a box of first can be kept if the wires out of it go into identities of second
+        and a box of second if the wires into it come out of identities of first
+        a box without outputs in first or without inputs in second needs nothing of the other side
+        None as soon as two boxes that are not identities meet on some wire
+        */
+        let mut answer = GenericMonoidalMorphismLayer::new();
+        answer.left_type = first.left_type.clone();
+        answer.right_type = second.right_type.clone();
+        let (mut i, mut j) = (0, 0);
+        while i < first.blocks.len() || j < second.blocks.len() {
+            let first_is_identity = first.blocks.get(i).map(Self::is_identity_box);
+            let second_is_identity = second.blocks.get(j).map(Self::is_identity_box);
+            match (first_is_identity, second_is_identity) {
+                (Some(true), Some(true)) => {
+                    answer.blocks.push(first.blocks[i].clone());
+                    i += 1;
+                    j += 1;
+                }
+                (Some(false), _) | (Some(true), None) => {
+                    let block = &first.blocks[i];
+                    let outputs = block.target_types().len();
+                    if j + outputs > second.blocks.len()
+                        || !second.blocks[j..j + outputs]
+                            .iter()
+                            .all(Self::is_identity_box)
+                    {
+                        return None;
+                    }
+                    answer.blocks.push(block.clone());
+                    i += 1;
+                    j += outputs;
+                }
+                (_, Some(_)) => {
+                    let block = &second.blocks[j];
+                    let inputs = block.source_types().len();
+                    if i + inputs > first.blocks.len()
+                        || !first.blocks[i..i + inputs]
+                            .iter()
+                            .all(Self::is_identity_box)
+                    {
+                        return None;
+                    }
+                    answer.blocks.push(block.clone());
+                    i += inputs;
+                    j += 1;
+                }
+                (None, None) => unreachable!("the loop stops once both layers are used up"),
+            }
+        }
+        Some(answer)
+    }
+
+    fn placed_boxes(&self) -> Vec<PlacedBox<BoxType, Lambda>> {
+        /*
+        all the boxes read left to right then layer by layer
+        as if each was done after the previous one
+        so the ones earlier in a layer have already replaced their inputs with their outputs
+        */
+        let mut answer = vec![];
+        for layer in &self.layers {
+            let mut position = 0;
+            for block in &layer.blocks {
+                let source_types = block.source_types();
+                let target_types = block.target_types();
+                if !Self::is_identity_box(block) {
+                    answer.push((
+                        block.clone(),
+                        position,
+                        source_types.len(),
+                        target_types.clone(),
+                    ));
+                }
+                position += target_types.len();
+            }
+        }
+        answer
+    }
+
+    fn earliest_levels(
+        placed: Vec<PlacedBox<BoxType, Lambda>>,
+        domain_size: usize,
+    ) -> Vec<(usize, PlacedBox<BoxType, Lambda>)> {
+        /*
+        the earliest layer each box could be in
+        one after the latest of whatever made its input wires
+            and whatever sits in the gaps between those wires
+        a box without inputs only has to wait for what is in its gap
+        the gaps at the ends of a box's outputs keep what they had before
+            but without outputs the gaps around it merge into one that waits for it
+        */
+        let mut wire_ready = vec![0; domain_size];
+        let mut gap_ready = vec![0; domain_size + 1];
+        placed
+            .into_iter()
+            .map(|placed_box| {
+                let (_, start, inputs, outputs) = &placed_box;
+                let (start, inputs) = (*start, *inputs);
+                let waits_for = wire_ready[start..start + inputs]
+                    .iter()
+                    .chain(&gap_ready[start + 1..start + inputs.max(1)])
+                    .chain(if inputs == 0 {
+                        &gap_ready[start..=start]
+                    } else {
+                        &[]
+                    })
+                    .max()
+                    .copied()
+                    .unwrap_or(0);
+                let level = waits_for + 1;
+                let new_gaps = if outputs.is_empty() {
+                    vec![level]
+                } else {
+                    let mut new_gaps = vec![gap_ready[start]];
+                    new_gaps.extend(std::iter::repeat_n(level, outputs.len() - 1));
+                    new_gaps.push(gap_ready[start + inputs]);
+                    new_gaps
+                };
+                wire_ready.splice(
+                    start..start + inputs,
+                    std::iter::repeat_n(level, outputs.len()),
+                );
+                gap_ready.splice(start..=start + inputs, new_gaps);
+                (level, placed_box)
+            })
+            .collect()
+    }
+
+    fn interchange(
+        first: &mut PlacedBox<BoxType, Lambda>,
+        second: &mut PlacedBox<BoxType, Lambda>,
+    ) {
+        /*
+        first then second becomes second then first
+        only for boxes where second does not use anything from first
+        so second is entirely to the left or entirely to the right of the outputs of first
+        */
+        let (first_start, first_inputs, first_outputs) = (first.1, first.2, first.3.len());
+        let (second_start, second_inputs, second_outputs) = (second.1, second.2, second.3.len());
+        if second_start + second_inputs <= first_start {
+            first.1 = first_start - second_inputs + second_outputs;
+        } else {
+            assert!(
+                second_start >= first_start + first_outputs,
+                "Only independent boxes can be interchanged"
+            );
+            second.1 = second_start - first_outputs + first_inputs;
+        }
+    }
+
+    fn layer_of<'a, I>(
+        boxes: I,
+        frontier: &[Lambda],
+    ) -> GenericMonoidalMorphismLayer<BoxType, Lambda>
+    where
+        I: Iterator<Item = &'a PlacedBox<BoxType, Lambda>>,
+        BoxType: 'a,
+        Lambda: 'a,
+    {
+        /*
+        put independent boxes side by side on the wires of frontier
+        filling in the rest with identities
+        the wires are tracked as either an untouched wire of frontier
+            or one of the boxes put down so far
+        a box without inputs goes before any box without outputs at the same point
+        */
+        let mut pieces: Vec<Either<usize, &PlacedBox<BoxType, Lambda>>> =
+            (0..frontier.len()).map(Left).collect();
+        for placed_box in boxes {
+            let (_, start, inputs, _) = placed_box;
+            let mut position = 0;
+            let mut at = pieces.len();
+            for (idx, piece) in pieces.iter().enumerate() {
+                if position == *start && (*inputs == 0 || piece.is_left()) {
+                    at = idx;
+                    break;
+                }
+                position += piece.as_ref().either(|_| 1, |(_, _, _, outs)| outs.len());
+            }
+            pieces.splice(at..at + inputs, [Right(placed_box)]);
+        }
+        let mut answer = GenericMonoidalMorphismLayer::new();
+        answer.left_type = frontier.to_vec();
+        for piece in pieces {
+            match piece {
+                Left(wire) => {
+                    answer.blocks.push(BoxType::identity(&frontier[wire]));
+                    answer.right_type.push(frontier[wire]);
+                }
+                Right((block, _, _, outputs)) => {
+                    answer.blocks.push(block.clone());
+                    answer.right_type.extend(outputs);
+                }
+            }
+        }
+        answer
+    }
+}
+
+impl<Lambda, BoxType> GenericMonoidalMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: HasWireTypes<Lambda>,
+{
+    #[allow(dead_code)]
+    pub fn validate(&self) -> Result<(), Vec<Error>> {
+        /*
+        pushing layers directly or reading them back in can give
+            a layer whose boxes do not fit its left_type and right_type
+            or consecutive layers that do not fit together
+        report every such problem rather than only the first
+        */
+        let mut problems = vec![];
+        for (idx, layer) in self.layers.iter().enumerate() {
+            let sources: Vec<Lambda> = layer.blocks.iter().flat_map(|b| b.source_types()).collect();
+            let targets: Vec<Lambda> = layer.blocks.iter().flat_map(|b| b.target_types()).collect();
+            for (expected, found) in [(&layer.left_type, sources), (&layer.right_type, targets)] {
+                if let Err(cause) = same_labels_check(expected.iter(), found.iter()) {
+                    problems.push(Error::InLayer {
+                        layer: idx,
+                        cause: Box::new(cause),
+                    });
+                }
+            }
+            if let Some(next) = self.layers.get(idx + 1) {
+                if let Err(cause) =
+                    same_labels_check(layer.right_type.iter(), next.left_type.iter())
+                {
+                    problems.push(Error::BetweenLayers {
+                        before: idx,
+                        cause: Box::new(cause),
+                    });
+                }
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+/*
+the domain and codomain types of each kind of box
+    kept apart from the boxes themselves
+so morphisms can be built and checked against it before anything is interpreted
+*/
+#[derive(Clone)]
+pub struct Signature<BoxType, Lambda> {
+    types: HashMap<BoxType, (Vec<Lambda>, Vec<Lambda>)>,
+}
+
+impl<BoxType, Lambda> Default for Signature<BoxType, Lambda> {
+    fn default() -> Self {
+        Self {
+            types: HashMap::new(),
+        }
+    }
+}
+
+impl<BoxType, Lambda> Signature<BoxType, Lambda>
+where
+    BoxType: Eq + Hash + Clone + Debug,
+    Lambda: Eq + Copy + Debug,
+{
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn add_box(
+        &mut self,
+        block: BoxType,
+        domain: Vec<Lambda>,
+        codomain: Vec<Lambda>,
+    ) -> Result<(), Error> {
+        if self.types.contains_key(&block) {
+            return Err(format!("{:?} is already in the signature", block).into());
+        }
+        self.types.insert(block, (domain, codomain));
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn types_of(&self, block: &BoxType) -> Option<(&[Lambda], &[Lambda])> {
+        self.types
+            .get(block)
+            .map(|(domain, codomain)| (domain.as_slice(), codomain.as_slice()))
+    }
+
+    fn known_types_of(&self, block: &BoxType) -> Result<(&[Lambda], &[Lambda]), Error> {
+        self.types_of(block)
+            .ok_or_else(|| format!("{:?} is not in the signature", block).into())
+    }
+
+    #[allow(dead_code)]
+    pub fn build(
+        &self,
+        layers: Vec<Vec<BoxType>>,
+    ) -> Result<GenericMonoidalMorphism<BoxType, Lambda>, Error> {
+        /*
+        each inner list is one layer with its boxes read left to right
+        the interfaces of each layer come from the signature
+        so a box that is not in it or layers that do not fit together
+            are caught here instead of later
+        */
+        let mut answer = GenericMonoidalMorphism::new();
+        for (idx, blocks) in layers.into_iter().enumerate() {
+            if blocks.is_empty() {
+                return Err(Error::EmptyLayer);
+            }
+            let mut left_type = vec![];
+            let mut right_type = vec![];
+            for block in &blocks {
+                let (domain, codomain) =
+                    self.known_types_of(block).map_err(|cause| Error::InLayer {
+                        layer: idx,
+                        cause: Box::new(cause),
+                    })?;
+                left_type.extend_from_slice(domain);
+                right_type.extend_from_slice(codomain);
+            }
+            if let Some(before) = answer.layers.last() {
+                same_labels_check(before.right_type.iter(), left_type.iter()).map_err(|cause| {
+                    Error::BetweenLayers {
+                        before: idx - 1,
+                        cause: Box::new(cause),
+                    }
+                })?;
+            }
+            answer.layers.push(GenericMonoidalMorphismLayer {
+                blocks,
+                left_type,
+                right_type,
+            });
+        }
+        Ok(answer)
+    }
+
+    #[allow(dead_code)]
+    pub fn check(&self, morphism: &GenericMonoidalMorphism<BoxType, Lambda>) -> Result<(), Error> {
+        /*
+        every box is in the signature and has the types it says
+            where it is put in each layer
+        and consecutive layers fit together
+        the first problem is the one reported
+        */
+        for (idx, layer) in morphism.layers.iter().enumerate() {
+            let in_layer = |cause: Error| Error::InLayer {
+                layer: idx,
+                cause: Box::new(cause),
+            };
+            let mut sources = vec![];
+            let mut targets = vec![];
+            for block in &layer.blocks {
+                let (domain, codomain) = self.known_types_of(block).map_err(in_layer)?;
+                sources.extend_from_slice(domain);
+                targets.extend_from_slice(codomain);
+            }
+            same_labels_check(layer.left_type.iter(), sources.iter()).map_err(in_layer)?;
+            same_labels_check(layer.right_type.iter(), targets.iter()).map_err(in_layer)?;
+            if let Some(next) = morphism.layers.get(idx + 1) {
+                same_labels_check(layer.right_type.iter(), next.left_type.iter()).map_err(
+                    |cause| Error::BetweenLayers {
+                        before: idx,
+                        cause: Box::new(cause),
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    fn interpret_boxes<T, F>(
+        &self,
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+        black_box_interpreter: &F,
+        sides: impl Fn(&T) -> (Vec<Lambda>, Vec<Lambda>),
+    ) -> Result<HashMap<BoxType, T>, Error>
+    where
+        F: Fn(&BoxType) -> Result<T, Error>,
+    {
+        /*
+        interpret each distinct box of morphism once
+        and make sure what it became has the types the signature says
+        */
+        self.check(morphism)?;
+        let mut interpreted = HashMap::new();
+        for block in morphism.layers.iter().flat_map(|layer| &layer.blocks) {
+            if interpreted.contains_key(block) {
+                continue;
+            }
+            let (domain, codomain) = self.known_types_of(block)?;
+            let found = black_box_interpreter(block)?;
+            let (found_domain, found_codomain) = sides(&found);
+            same_labels_check(domain.iter(), found_domain.iter())
+                .and_then(|_| same_labels_check(codomain.iter(), found_codomain.iter()))
+                .map_err(|cause| {
+                    Error::from(format!(
+                        "{:?} was interpreted with the wrong types. {}",
+                        block, cause
+                    ))
+                })?;
+            interpreted.insert(block.clone(), found);
+        }
+        Ok(interpreted)
+    }
+}
+
+impl<Lambda, BoxType> GenericMonoidalMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Debug + HasWireTypes<Lambda>,
+{
+    #[allow(dead_code)]
+    pub fn to_dot(&self) -> String {
+        /*
+        a graphviz digraph drawn left to right
+        each box is a node inside the cluster for its layer
+        each wire is an edge labelled by its type
+        the wires on the domain and codomain are attached to small point nodes
+        identity boxes are drawn too so normalize first for a tidier picture
+        */
+        let mut dot = String::from("digraph {\n    rankdir=LR;\n");
+        let domain = self.domain();
+        let mut wire_sources: Vec<String> = Vec::with_capacity(domain.len());
+        for idx in 0..domain.len() {
+            dot.push_str(&format!("    in_{} [shape=point];\n", idx));
+            wire_sources.push(format!("in_{}", idx));
+        }
+        let mut edges = Vec::new();
+        for (layer_num, layer) in self.layers.iter().enumerate() {
+            dot.push_str(&format!(
+                "    subgraph cluster_{} {{\n        label=\"layer {}\";\n",
+                layer_num, layer_num
+            ));
+            let mut next_sources = Vec::with_capacity(layer.right_type.len());
+            let mut wire_cursor = 0;
+            for (box_num, block) in layer.blocks.iter().enumerate() {
+                let node = format!("box_{}_{}", layer_num, box_num);
+                dot.push_str(&format!(
+                    "        {} [shape=box, label=\"{}\"];\n",
+                    node,
+                    dot_escape(&format!("{:?}", block))
+                ));
+                let in_count = block.source_types().len();
+                for (source, wire_type) in wire_sources
+                    .iter()
+                    .zip(layer.left_type.iter())
+                    .skip(wire_cursor)
+                    .take(in_count)
+                {
+                    edges.push((source.clone(), node.clone(), *wire_type));
+                }
+                wire_cursor += in_count;
+                next_sources.extend(std::iter::repeat_n(node, block.target_types().len()));
+            }
+            dot.push_str("    }\n");
+            wire_sources = next_sources;
+        }
+        let codomain = self.codomain();
+        for (idx, (source, wire_type)) in wire_sources.iter().zip(codomain.iter()).enumerate() {
+            dot.push_str(&format!("    out_{} [shape=point];\n", idx));
+            edges.push((source.clone(), format!("out_{}", idx), *wire_type));
+        }
+        for (source, target, wire_type) in edges {
+            dot.push_str(&format!(
+                "    {} -> {} [label=\"{}\"];\n",
+                source,
+                target,
+                dot_escape(&format!("{:?}", wire_type))
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl<Lambda, BoxType> GenericMonoidalMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Debug,
+{
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like Monoidal(['a','a']→['a']): Mult ⊗ Id ; Mult
+        the Debug of each box, the blocks within a layer separated by ⊗ and the layers by ;
+        */
+        let domain = self.layers.first().map_or(vec![], |l| l.left_type.clone());
+        let codomain = self.layers.last().map_or(vec![], |l| l.right_type.clone());
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| {
+                if layer.blocks.is_empty() {
+                    return "id([])".to_string();
+                }
+                layer
+                    .blocks
+                    .iter()
+                    .map(|block| format!("{:?}", block))
+                    .collect::<Vec<_>>()
+                    .join(" ⊗ ")
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "Monoidal([{}]→[{}]): {}",
+            compact_list(&domain),
+            compact_list(&codomain),
+            if layers.is_empty() {
+                "id([])".to_string()
+            } else {
+                layers.join(" ; ")
+            }
+        )
+    }
+}
+
 impl<Lambda, BoxType> HasIdentity<Vec<Lambda>> for GenericMonoidalMorphism<BoxType, Lambda>
 where
     Lambda: Eq + Copy,
@@ -152,53 +955,24 @@ where
 fn layers_composable<Lambda: Eq + Copy + Debug, BoxType>(
     l: &[GenericMonoidalMorphismLayer<BoxType, Lambda>],
     r: &[GenericMonoidalMorphismLayer<BoxType, Lambda>],
-) -> Result<(), String> {
-    if l.is_empty() || r.is_empty() {
-        if l.is_empty() && r.is_empty() {
-            return Ok(());
-        } else if l.is_empty() {
-            let other_interface = &r[0].left_type;
-            if other_interface.is_empty() {
-                return Ok(());
-            } else {
-                return Err("Mismatch in cardinalities of common interface".to_string());
-            }
-        } else {
-            let self_interface = &l.last().unwrap().right_type;
-            if self_interface.is_empty() {
-                return Ok(());
-            } else {
-                return Err("Mismatch in cardinalities of common interface".to_string());
-            }
-        }
-    }
-    let lhs = &l.last().unwrap().right_type;
-    let rhs = &r[0].left_type;
-    if lhs.len() != rhs.len() {
-        return Err("Mismatch in cardinalities of common interface".to_string());
-    }
-    if lhs == rhs {
-        return Ok(());
-    }
-    if let Some((w1, w2)) = lhs.iter().zip(rhs.iter()).find(|(w1, w2)| w1 != w2) {
-        return Err(format!(
-            "Mismatch in labels of common interface. At some index there was {:?} vs {:?}",
-            w1, w2
-        ));
-    }
-
-    Err("Mismatch in labels of common interface at some unknown index.".to_string())
+) -> Result<(), Error> {
+    /*
+    an empty list of layers is the identity on the empty type
+    */
+    let lhs = l.last().map_or(&[][..], |layer| &layer.right_type[..]);
+    let rhs = r.first().map_or(&[][..], |layer| &layer.left_type[..]);
+    same_labels_check(lhs.iter(), rhs.iter())
 }
 
 impl<Lambda, BoxType> ComposableMutating<Vec<Lambda>> for GenericMonoidalMorphism<BoxType, Lambda>
 where
     Lambda: Eq + Copy + Debug,
 {
-    fn composable(&self, other: &Self) -> Result<(), String> {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
         layers_composable(&self.layers, &other.layers)
     }
 
-    fn compose(&mut self, other: Self) -> Result<(), String> {
+    fn compose(&mut self, other: Self) -> Result<(), Error> {
         for next_layer in other.layers {
             self.append_layer(next_layer)?;
         }
@@ -220,11 +994,330 @@ where
     }
 }
 
-pub trait MonoidalMorphism<T: Eq>: Monoidal + Composable<T> {}
-pub trait MonoidalMutatingMorphism<T: Eq>: Monoidal + ComposableMutating<T> {}
+impl<Lambda, BoxType> Dagger<Vec<Lambda>> for GenericMonoidalMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy,
+    BoxType: Dagger<Vec<Lambda>>,
+{
+    /*
+    the layers in the opposite order
+    each with the dagger of its boxes and its two sides swapped
+    */
+    fn dagger(&self) -> Self {
+        Self {
+            layers: self
+                .layers
+                .iter()
+                .rev()
+                .map(|layer| GenericMonoidalMorphismLayer {
+                    blocks: layer.blocks.iter().map(Dagger::dagger).collect(),
+                    left_type: layer.right_type.clone(),
+                    right_type: layer.left_type.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<Lambda, BoxType> SymmetricMonoidalMutatingMorphism<Lambda>
+    for GenericMonoidalMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone + HasIdentity<Lambda> + HasSymmetricBraiding<Lambda>,
+{
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        /*
+        the same convention as for Cospan
+        afterwards the codomain (or domain) is p.permute of what it was before
+        done by composing with layers of crossings on that side
+        */
+        if of_codomain {
+            let codomain = self.codomain();
+            assert_eq!(p.len(), codomain.len());
+            let crossings = Self::from_permutation(p.inv(), &codomain, true);
+            self.compose(crossings)
+                .expect("the crossings start where self ends");
+        } else {
+            let domain = self.domain();
+            assert_eq!(p.len(), domain.len());
+            let mut crossings = Self::from_permutation(p.clone(), &domain, false);
+            crossings
+                .compose(std::mem::replace(self, Self::new()))
+                .expect("the crossings end where self starts");
+            *self = crossings;
+        }
+    }
+
+    fn from_permutation(p: Permutation, types: &[Lambda], types_as_on_domain: bool) -> Self {
+        /*
+        the wire at position i of the domain goes to position p(i) of the codomain
+        types is the domain if types_as_on_domain and otherwise the codomain
+        matching Cospan::from_permutation
+        the layers come from odd-even transposition sort
+            each one crosses some disjoint adjacent pairs of wires
+            and there are at most types.len() of them
+        */
+        assert_eq!(p.len(), types.len());
+        let mut wires: Vec<(usize, Lambda)> = (0..p.len())
+            .map(|i| {
+                let destination = p.apply(i);
+                let wire_type = if types_as_on_domain {
+                    types[i]
+                } else {
+                    types[destination]
+                };
+                (destination, wire_type)
+            })
+            .collect();
+        let mut answer = Self::new();
+        let mut parity = 0;
+        while !wires.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+            let left_type = wires.iter().map(|(_, z)| *z).collect();
+            let mut blocks = Vec::with_capacity(wires.len());
+            let mut crossed = false;
+            let mut idx = 0;
+            while idx < wires.len() {
+                if idx % 2 == parity && idx + 1 < wires.len() && wires[idx].0 > wires[idx + 1].0 {
+                    blocks.push(BoxType::symmetric_braiding(
+                        &wires[idx].1,
+                        &wires[idx + 1].1,
+                    ));
+                    wires.swap(idx, idx + 1);
+                    crossed = true;
+                    idx += 2;
+                } else {
+                    blocks.push(BoxType::identity(&wires[idx].1));
+                    idx += 1;
+                }
+            }
+            if crossed {
+                answer.layers.push(GenericMonoidalMorphismLayer {
+                    blocks,
+                    left_type,
+                    right_type: wires.iter().map(|(_, z)| *z).collect(),
+                });
+            }
+            parity = 1 - parity;
+        }
+        if answer.layers.is_empty() {
+            return Self::identity(&types.to_vec());
+        }
+        answer
+    }
+}
+
+impl<M: Monoidal> Monoidal for Op<M> {
+    /*
+    the opposite of a monoidal category is monoidal with the same tensor product
+    */
+    fn monoidal(&mut self, other: Self) {
+        self.0.monoidal(other.0);
+    }
+}
+
+impl<A: Monoidal, B: Monoidal> Monoidal for ProductMorphism<A, B> {
+    fn monoidal(&mut self, other: Self) {
+        self.0.monoidal(other.0);
+        self.1.monoidal(other.1);
+    }
+}
+
+/*
+the extension points are Monoidal, HasIdentity, Composable and ComposableMutating
+    along with Dagger, Braided, CartesianMonoidal and the traits in symmetric_monoidal
+MonoidalMorphism, MonoidalMutatingMorphism and the two GenericMonoidalInterpretable traits
+    are sealed, they come only from the blanket impls below
+    the supertraits in sealed cannot be named outside this module
+    so no other impl can be written, even for a local type
+that leaves us free to add provided methods to them without breaking implementors
+*/
+mod sealed {
+    use {
+        crate::category::{Composable, ComposableMutating, HasIdentity},
+        crate::monoidal::Monoidal,
+        std::fmt::Debug,
+    };
+
+    pub trait MonoidalMorphism<T> {}
+    pub trait MonoidalMutatingMorphism<T> {}
+    #[allow(dead_code)]
+    pub trait GenericMonoidalInterpretableMut<Lambda> {}
+    #[allow(dead_code)]
+    pub trait GenericMonoidalInterpretable<Lambda> {}
+
+    impl<T: Eq, M: Monoidal + Composable<T>> MonoidalMorphism<T> for M {}
+    impl<T: Eq, M: Monoidal + ComposableMutating<T>> MonoidalMutatingMorphism<T> for M {}
+    impl<Lambda, M> GenericMonoidalInterpretableMut<Lambda> for M
+    where
+        Lambda: Eq + Copy + Debug,
+        M: Monoidal + ComposableMutating<Vec<Lambda>> + HasIdentity<Vec<Lambda>>,
+    {
+    }
+    impl<Lambda, M> GenericMonoidalInterpretable<Lambda> for M
+    where
+        Lambda: Eq + Copy + Debug,
+        M: Monoidal + Composable<Vec<Lambda>> + HasIdentity<Vec<Lambda>>,
+    {
+    }
+}
+
+pub trait MonoidalMorphism<T: Eq>: Monoidal + Composable<T> + sealed::MonoidalMorphism<T> {}
+pub trait MonoidalMutatingMorphism<T: Eq>:
+    Monoidal + ComposableMutating<T> + sealed::MonoidalMutatingMorphism<T>
+{
+}
+
+impl<T, M> MonoidalMorphism<T> for M
+where
+    T: Eq,
+    M: Monoidal + Composable<T>,
+{
+}
+
+impl<T, M> MonoidalMutatingMorphism<T> for M
+where
+    T: Eq,
+    M: Monoidal + ComposableMutating<T>,
+{
+}
+
+#[allow(dead_code)]
+pub trait Braided<T: Eq>: MonoidalMorphism<T> + HasIdentity<T> {
+    /*
+    a braiding beta_{a,b} : a \otimes b -> b \otimes a
+    and its inverse beta_{a,b}^{-1} : b \otimes a -> a \otimes b
+    in a symmetric category the inverse is just beta_{b,a}
+    but for braids and tangles these are the two different crossings
+    */
+    fn braiding(a: &T, b: &T) -> Self;
+    fn inverse_braiding(a: &T, b: &T) -> Self;
+
+    fn braiding_laws(a: &T, b: &T, c: &T) -> Result<(), String>
+    where
+        Self: PartialEq,
+    {
+        /*
+        beta_{a,b} ; beta_{a,b}^{-1} = id_{a \otimes b} and beta_{a,b}^{-1} ; beta_{a,b} = id_{b \otimes a}
+        beta_{a \otimes b,c} = (id_a \otimes beta_{b,c}) ; (beta_{a,c} \otimes id_b)
+        beta_{a,b \otimes c} = (beta_{a,b} \otimes id_c) ; (id_b \otimes beta_{a,c})
+        the tensor product of the objects is read off from the domain of a braiding
+        */
+        let a_b = Self::braiding(a, b).domain();
+        let b_a = Self::braiding(b, a).domain();
+        let b_c = Self::braiding(b, c).domain();
+        if Self::braiding(a, b).compose(&Self::inverse_braiding(a, b))? != Self::identity(&a_b) {
+            return Err("The braiding followed by its inverse was not the identity".to_string());
+        }
+        if Self::inverse_braiding(a, b).compose(&Self::braiding(a, b))? != Self::identity(&b_a) {
+            return Err(
+                "The inverse braiding followed by the braiding was not the identity".to_string(),
+            );
+        }
+        let first_hexagon = tensor_pair(Self::identity(a), Self::braiding(b, c))
+            .compose(&tensor_pair(Self::braiding(a, c), Self::identity(b)))?;
+        if Self::braiding(&a_b, c) != first_hexagon {
+            return Err("The hexagon for a tensor product on the left did not hold".to_string());
+        }
+        let second_hexagon = tensor_pair(Self::braiding(a, b), Self::identity(c))
+            .compose(&tensor_pair(Self::identity(b), Self::braiding(a, c)))?;
+        if Self::braiding(a, &b_c) != second_hexagon {
+            return Err("The hexagon for a tensor product on the right did not hold".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+pub trait CartesianMonoidal<T: Eq>: Braided<T> + Clone {
+    /*
+    every object a has a copy a -> a \otimes a and a delete a -> I
+    making it a commutative comonoid
+    in a cartesian category every morphism commutes with both
+    in a Markov category only delete has to
+        and the morphisms that commute with copy are the deterministic ones
+    so naturality is checked one morphism at a time rather than assumed
+    */
+    fn duplicate(object: &T) -> Self;
+    fn discard(object: &T) -> Self;
+
+    fn comonoid_laws(object: &T) -> Result<(), String>
+    where
+        Self: PartialEq,
+    {
+        /*
+        copy ; (delete \otimes id) = id = copy ; (id \otimes delete)
+        copy ; (copy \otimes id) = copy ; (id \otimes copy)
+        copy ; beta_{a,a} = copy
+        */
+        let copy = Self::duplicate(object);
+        let id = Self::identity(object);
+        let left_unit =
+            copy.compose(&tensor_pair(Self::discard(object), Self::identity(object)))?;
+        let right_unit =
+            copy.compose(&tensor_pair(Self::identity(object), Self::discard(object)))?;
+        if left_unit != id || right_unit != id {
+            return Err("Deleting one of the copies was not the identity".to_string());
+        }
+        let left_assoc = copy.compose(&tensor_pair(
+            Self::duplicate(object),
+            Self::identity(object),
+        ))?;
+        let right_assoc = copy.compose(&tensor_pair(
+            Self::identity(object),
+            Self::duplicate(object),
+        ))?;
+        if left_assoc != right_assoc {
+            return Err("Copying was not coassociative".to_string());
+        }
+        if copy.compose(&Self::braiding(object, object))? != copy {
+            return Err("Swapping the two copies changed the copy".to_string());
+        }
+        Ok(())
+    }
+
+    fn discard_naturality(&self) -> Result<(), String>
+    where
+        Self: PartialEq,
+    {
+        /*
+        f ; delete_b = delete_a
+        */
+        if self.compose(&Self::discard(&self.codomain()))? != Self::discard(&self.domain()) {
+            return Err("Deleting after the morphism was not the same as deleting".to_string());
+        }
+        Ok(())
+    }
+
+    fn duplicate_naturality(&self) -> Result<(), String>
+    where
+        Self: PartialEq,
+    {
+        /*
+        f ; copy_b = copy_a ; (f \otimes f)
+        */
+        let copy_after = self.compose(&Self::duplicate(&self.codomain()))?;
+        let copy_before =
+            Self::duplicate(&self.domain()).compose(&tensor_pair(self.clone(), self.clone()))?;
+        if copy_after != copy_before {
+            return Err(
+                "Copying after the morphism was not the same as copying before it".to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+pub fn tensor_pair<M: Monoidal>(mut first: M, second: M) -> M {
+    first.monoidal(second);
+    first
+}
 
 pub trait GenericMonoidalInterpretableMut<Lambda: Eq + Copy + Debug>:
-    Monoidal + ComposableMutating<Vec<Lambda>> + HasIdentity<Vec<Lambda>>
+    Monoidal
+    + ComposableMutating<Vec<Lambda>>
+    + HasIdentity<Vec<Lambda>>
+    + sealed::GenericMonoidalInterpretableMut<Lambda>
 {
     /*
     given a function from BoxType to the
@@ -235,14 +1328,14 @@ pub trait GenericMonoidalInterpretableMut<Lambda: Eq + Copy + Debug>:
     fn interpret<F, BoxType>(
         morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
         black_box_interpreter: &F,
-    ) -> Result<Self, String>
+    ) -> Result<Self, Error>
     where
-        F: Fn(&BoxType) -> Result<Self, String>,
+        F: Fn(&BoxType) -> Result<Self, Error>,
     {
         let mut answer = Self::identity(&morphism.domain());
         for layer in &morphism.layers {
             let Some(first) = &layer.blocks.first() else {
-                return Err("somehow an empty layer in a generica monoidal morphism???".to_string());
+                return Err(Error::EmptyLayer);
             };
             let mut cur_layer = black_box_interpreter(first)?;
             for block in &layer.blocks[1..] {
@@ -252,9 +1345,75 @@ pub trait GenericMonoidalInterpretableMut<Lambda: Eq + Copy + Debug>:
         }
         Ok(answer)
     }
+
+    fn interpret_memoized<F, BoxType>(
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+        black_box_interpreter: &F,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(&BoxType) -> Result<Self, Error>,
+        BoxType: Eq + Hash,
+        Self: Clone,
+    {
+        /*
+        same as interpret but black_box_interpreter is only called once
+            for each distinct box and the result is cloned for the repeats
+        */
+        let mut cache: HashMap<&BoxType, Self> = HashMap::new();
+        let mut answer = Self::identity(&morphism.domain());
+        for layer in &morphism.layers {
+            if layer.blocks.is_empty() {
+                return Err(Error::EmptyLayer);
+            }
+            let mut cur_layer: Option<Self> = None;
+            for block in &layer.blocks {
+                let interpreted = match cache.get(block) {
+                    Some(found) => found.clone(),
+                    None => {
+                        let found = black_box_interpreter(block)?;
+                        cache.insert(block, found.clone());
+                        found
+                    }
+                };
+                match cur_layer.as_mut() {
+                    Some(so_far) => so_far.monoidal(interpreted),
+                    None => cur_layer = Some(interpreted),
+                }
+            }
+            if let Some(cur_layer) = cur_layer {
+                answer.compose(cur_layer)?;
+            }
+        }
+        Ok(answer)
+    }
+
+    fn interpret_checked<F, BoxType>(
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+        signature: &Signature<BoxType, Lambda>,
+        black_box_interpreter: &F,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(&BoxType) -> Result<Self, Error>,
+        BoxType: Eq + Hash + Clone + Debug,
+        Self: Clone,
+    {
+        /*
+        same as interpret but first checking morphism against the signature
+            and what each box is sent to against the types the signature gives it
+        so a mismatch is found before any composing happens
+        */
+        let interpreted =
+            signature.interpret_boxes(morphism, black_box_interpreter, |found: &Self| {
+                (found.domain(), found.codomain())
+            })?;
+        Self::interpret(morphism, &|block: &BoxType| Ok(interpreted[block].clone()))
+    }
 }
 pub trait GenericMonoidalInterpretable<Lambda: Eq + Copy + Debug>:
-    Monoidal + Composable<Vec<Lambda>> + HasIdentity<Vec<Lambda>>
+    Monoidal
+    + Composable<Vec<Lambda>>
+    + HasIdentity<Vec<Lambda>>
+    + sealed::GenericMonoidalInterpretable<Lambda>
 {
     /*
     given a function from BoxType to the
@@ -268,14 +1427,14 @@ pub trait GenericMonoidalInterpretable<Lambda: Eq + Copy + Debug>:
     fn interpret<F, BoxType>(
         morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
         black_box_interpreter: &F,
-    ) -> Result<Self, String>
+    ) -> Result<Self, Error>
     where
-        F: Fn(&BoxType) -> Result<Self, String>,
+        F: Fn(&BoxType) -> Result<Self, Error>,
     {
         let mut answer = Self::identity(&morphism.domain());
         for layer in &morphism.layers {
             let Some(first) = &layer.blocks.first() else {
-                return Err("somehow an empty layer in a generica monoidal morphism???".to_string());
+                return Err(Error::EmptyLayer);
             };
             let mut cur_layer = black_box_interpreter(first)?;
             for block in &layer.blocks[1..] {
@@ -285,31 +1444,850 @@ pub trait GenericMonoidalInterpretable<Lambda: Eq + Copy + Debug>:
         }
         Ok(answer)
     }
+
+    fn interpret_memoized<F, BoxType>(
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+        black_box_interpreter: &F,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(&BoxType) -> Result<Self, Error>,
+        BoxType: Eq + Hash,
+        Self: Clone,
+    {
+        /*
+        same as interpret but black_box_interpreter is only called once
+            for each distinct box and the result is cloned for the repeats
+        */
+        let mut cache: HashMap<&BoxType, Self> = HashMap::new();
+        let mut answer = Self::identity(&morphism.domain());
+        for layer in &morphism.layers {
+            if layer.blocks.is_empty() {
+                return Err(Error::EmptyLayer);
+            }
+            let mut cur_layer: Option<Self> = None;
+            for block in &layer.blocks {
+                let interpreted = match cache.get(block) {
+                    Some(found) => found.clone(),
+                    None => {
+                        let found = black_box_interpreter(block)?;
+                        cache.insert(block, found.clone());
+                        found
+                    }
+                };
+                match cur_layer.as_mut() {
+                    Some(so_far) => so_far.monoidal(interpreted),
+                    None => cur_layer = Some(interpreted),
+                }
+            }
+            if let Some(cur_layer) = cur_layer {
+                answer = answer.compose(&cur_layer)?;
+            }
+        }
+        Ok(answer)
+    }
+
+    fn interpret_checked<F, BoxType>(
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+        signature: &Signature<BoxType, Lambda>,
+        black_box_interpreter: &F,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(&BoxType) -> Result<Self, Error>,
+        BoxType: Eq + Hash + Clone + Debug,
+        Self: Clone,
+    {
+        /*
+        same as interpret but first checking morphism against the signature
+            and what each box is sent to against the types the signature gives it
+        so a mismatch is found before any composing happens
+        */
+        let interpreted =
+            signature.interpret_boxes(morphism, black_box_interpreter, |found: &Self| {
+                (found.domain(), found.codomain())
+            })?;
+        Self::interpret(morphism, &|block: &BoxType| Ok(interpreted[block].clone()))
+    }
 }
 
-impl<Lambda, BoxType> MonoidalMutatingMorphism<Vec<Lambda>>
-    for GenericMonoidalMorphism<BoxType, Lambda>
+impl<Lambda, M> GenericMonoidalInterpretableMut<Lambda> for M
 where
     Lambda: Eq + Copy + Debug,
-    BoxType: HasIdentity<Lambda> + Clone,
+    M: Monoidal + ComposableMutating<Vec<Lambda>> + HasIdentity<Vec<Lambda>>,
 {
     /*
-    the most obvious implementation of MonoidalMutatingMorphism is GenericMonoidalMorphism itself
-    use all the structure of monoidal, compose, identity provided by concatenating blocks and layers appropriately
+    GenericMonoidalMorphism itself is the most obvious instance
     */
 }
 
-impl<Lambda, BoxType> GenericMonoidalInterpretableMut<Lambda>
-    for GenericMonoidalMorphism<BoxType, Lambda>
+impl<Lambda, M> GenericMonoidalInterpretable<Lambda> for M
 where
     Lambda: Eq + Copy + Debug,
-    BoxType: HasIdentity<Lambda> + Clone,
+    M: Monoidal + Composable<Vec<Lambda>> + HasIdentity<Vec<Lambda>>,
 {
-    /*
-    the most obvious implementation of GenericMonoidalInterpretableMut is GenericMonoidalMorphism itself
-    use the default implementation given in the trait itself
-    in Frobenius we override the default implementation with just a clone
-        because there we are only concerned with the case when black_box_interpreter
-        was just sending the black boxes with the same sort of black box
-    */
+}
+
+mod test {
+
+    #[test]
+    fn permutation_layers() {
+        use super::GenericMonoidalMorphism;
+        use crate::category::{Composable, ComposableMutating};
+        use crate::cospan::Cospan;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::symmetric_monoidal::{
+            SymmetricMonoidalMorphism, SymmetricMonoidalMutatingMorphism,
+        };
+        use crate::utils::rand_perm;
+        type Crossings = GenericMonoidalMorphism<FrobeniusOperation<char, ()>, char>;
+        for n in 1..8 {
+            let types: Vec<char> = ('a'..).take(n).collect();
+            for _ in 0..10 {
+                let p = rand_perm(n, n * n);
+                for types_as_on_domain in [true, false] {
+                    let generic =
+                        Crossings::from_permutation(p.clone(), &types, types_as_on_domain);
+                    let cospan = Cospan::from_permutation(p.clone(), &types, types_as_on_domain);
+                    assert!(generic.depth() <= n);
+                    assert_eq!(generic.domain(), cospan.domain());
+                    assert_eq!(generic.codomain(), cospan.codomain());
+                }
+                let q = rand_perm(n, n * n);
+                let mut generic = Crossings::from_permutation(q.clone(), &types, true);
+                let mut cospan = Cospan::from_permutation(q, &types, true);
+                for of_codomain in [true, false] {
+                    generic.permute_side(&p, of_codomain);
+                    cospan.permute_side(&p, of_codomain);
+                    assert_eq!(generic.domain(), cospan.domain());
+                    assert_eq!(generic.codomain(), cospan.codomain());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn dagger_reverses_layers() {
+        use super::GenericMonoidalMorphism;
+        use crate::category::{ComposableMutating, Dagger};
+        use crate::frobenius::FrobeniusOperation;
+        use crate::symmetric_monoidal::SymmetricMonoidalMutatingMorphism;
+        use crate::utils::rand_perm;
+        type Crossings = GenericMonoidalMorphism<FrobeniusOperation<char, ()>, char>;
+        let types: Vec<char> = ('a'..).take(5).collect();
+        for _ in 0..10 {
+            let f = Crossings::from_permutation(rand_perm(5, 25), &types, true);
+            let g = Crossings::from_permutation(rand_perm(5, 25), &f.codomain(), true);
+            assert_eq!(f.dagger().domain(), f.codomain());
+            assert_eq!(f.dagger().codomain(), f.domain());
+            assert!(f.dagger().dagger() == f);
+            let mut f_g = f.clone();
+            f_g.compose(g.clone()).unwrap();
+            let mut g_dag_f_dag = g.dagger();
+            g_dag_f_dag.compose(f.dagger()).unwrap();
+            assert!(f_g.dagger() == g_dag_f_dag);
+        }
+    }
+
+    #[test]
+    fn interchange_normal_form() {
+        use super::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer, Monoidal};
+        use crate::category::{ComposableMutating, HasIdentity};
+        use crate::frobenius::FrobeniusOperation;
+        type Op = FrobeniusOperation<char, u8>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let single = |op: Op, left_type: Vec<char>, right_type: Vec<char>| Generic {
+            layers: vec![GenericMonoidalMorphismLayer {
+                blocks: vec![op],
+                left_type,
+                right_type,
+            }],
+        };
+        let f = single(
+            Op::UnSpecifiedBox(0, vec!['a'], vec!['b', 'b']),
+            vec!['a'],
+            vec!['b', 'b'],
+        );
+        let g = single(Op::Multiplication('c'), vec!['c', 'c'], vec!['c']);
+        let tensored = |first: &Generic, second: &Generic| {
+            let mut answer = first.clone();
+            answer.monoidal(second.clone());
+            answer
+        };
+        let composed = |first: &Generic, second: &Generic| {
+            let mut answer = first.clone();
+            answer.compose(second.clone()).unwrap();
+            answer
+        };
+        let id = |types: &[char]| Generic::identity(&types.to_vec());
+
+        let side_by_side = tensored(&f, &g);
+        let f_first = composed(
+            &tensored(&f, &id(&['c', 'c'])),
+            &tensored(&id(&['b', 'b']), &g),
+        );
+        let g_first = composed(&tensored(&id(&['a']), &g), &tensored(&f, &id(&['c'])));
+        let padded = composed(
+            &composed(&id(&['a', 'c', 'c']), &g_first),
+            &id(&['b', 'b', 'c']),
+        );
+        assert!(f_first != g_first);
+        let mut normalized = vec![];
+        for mut morphism in [side_by_side, f_first, g_first, padded] {
+            morphism.normalize();
+            assert_eq!(morphism.depth(), 1);
+            normalized.push(morphism);
+        }
+        assert!(normalized.iter().all(|morphism| *morphism == normalized[0]));
+
+        /*
+        h needs the outputs of f so it can not slide past it
+        but the unit can slide all the way to the start
+        */
+        let h = single(Op::Multiplication('b'), vec!['b', 'b'], vec!['b']);
+        let unit = single(Op::Unit('c'), vec![], vec!['c']);
+        let mut blocked = composed(&composed(&f, &h), &tensored(&id(&['b']), &unit));
+        blocked.normalize();
+        assert_eq!(blocked.depth(), 2);
+        let mut expected = composed(&f, &tensored(&h, &unit));
+        assert!(blocked != expected);
+        expected.normalize();
+        assert!(blocked == expected);
+        assert_eq!(blocked.domain(), vec!['a']);
+        assert_eq!(blocked.codomain(), vec!['b', 'c']);
+
+        let mut only_identities = composed(&id(&['a', 'b']), &id(&['a', 'b']));
+        only_identities.normalize();
+        assert!(only_identities == id(&['a', 'b']));
+    }
+
+    #[test]
+    fn compacted_chains() {
+        use super::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer, Monoidal};
+        use crate::category::{ComposableMutating, HasIdentity};
+        use crate::frobenius::FrobeniusOperation;
+        type Op = FrobeniusOperation<char, u8>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let on_wire = |op: Op, before: usize, after: usize| {
+            /*
+            op on the wires of type 'a' with before of them to its left and after to its right
+            */
+            let mut answer = Generic::identity(&vec!['a'; before]);
+            let (source, target) = match &op {
+                Op::Multiplication(_) => (vec!['a', 'a'], vec!['a']),
+                Op::Comultiplication(_) => (vec!['a'], vec!['a', 'a']),
+                Op::Unit(_) => (vec![], vec!['a']),
+                _ => (vec!['a'], vec!['a']),
+            };
+            answer.monoidal(Generic {
+                layers: vec![GenericMonoidalMorphismLayer {
+                    blocks: vec![op],
+                    left_type: source,
+                    right_type: target,
+                }],
+            });
+            answer.monoidal(Generic::identity(&vec!['a'; after]));
+            answer
+        };
+        let chain = |pieces: Vec<Generic>| {
+            let mut answer = pieces[0].clone();
+            for piece in pieces.into_iter().skip(1) {
+                answer.compose(piece).unwrap();
+            }
+            answer
+        };
+        let mut disjoint = chain(vec![
+            on_wire(Op::Comultiplication('a'), 0, 3),
+            on_wire(Op::Multiplication('a'), 3, 0),
+            on_wire(Op::Unit('a'), 2, 2),
+        ]);
+        let (domain, codomain) = (disjoint.domain(), disjoint.codomain());
+        assert_eq!(disjoint.depth(), 3);
+        disjoint.compact_layers();
+        assert_eq!(disjoint.depth(), 1);
+        assert_eq!((disjoint.domain(), disjoint.codomain()), (domain, codomain));
+        let mut normalized = disjoint.clone();
+        normalized.normalize();
+        assert!(disjoint == normalized);
+
+        /*
+        the multiplication uses both outputs of the comultiplication
+        so only the unit off to the side can join a layer
+        */
+        let mut dependent = chain(vec![
+            on_wire(Op::Comultiplication('a'), 1, 0),
+            on_wire(Op::Multiplication('a'), 1, 0),
+            on_wire(Op::Unit('a'), 0, 2),
+        ]);
+        dependent.compact_layers();
+        assert_eq!(dependent.depth(), 2);
+        assert_eq!(dependent.domain(), vec!['a', 'a']);
+        assert_eq!(dependent.codomain(), vec!['a', 'a', 'a']);
+
+        let mut identities = chain(vec![Generic::identity(&vec!['a'; 3]); 4]);
+        identities.compact_layers();
+        assert!(identities == Generic::identity(&vec!['a'; 3]));
+    }
+
+    #[test]
+    fn interface_errors() {
+        use super::{
+            GenericMonoidalInterpretable, GenericMonoidalInterpretableMut, GenericMonoidalMorphism,
+            GenericMonoidalMorphismLayer, HasWireTypes,
+        };
+        use crate::category::{Composable, ComposableMutating, HasIdentity};
+        use crate::cospan::Cospan;
+        use crate::error::Error;
+        use crate::frobenius::FrobeniusOperation;
+        type Generic = GenericMonoidalMorphism<FrobeniusOperation<char, ()>, char>;
+        let abc = Generic::identity(&vec!['a', 'b', 'c']);
+        let mut composite = abc.clone();
+        assert_eq!(
+            composite.compose(Generic::identity(&vec!['a', 'b'])),
+            Err(Error::InterfaceLengthMismatch { left: 3, right: 2 })
+        );
+        assert_eq!(
+            abc.composable(&Generic::identity(&vec!['a', 'd', 'c'])),
+            Err(Error::InterfaceLabelMismatch {
+                index: 1,
+                left: "'b'".to_string(),
+                right: "'d'".to_string()
+            })
+        );
+        assert_eq!(
+            abc.composable(&Generic::new()).map_err(|e| e.to_string()),
+            Err("Mismatch in cardinalities of common interface. 3 vs 0".to_string())
+        );
+        let cospan_error = Cospan::identity(&vec!['a', 'b'])
+            .compose(&Cospan::identity(&vec!['a', 'c']))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            cospan_error,
+            Error::InterfaceLabelMismatch { index: 1, .. }
+        ));
+        let fail_on_boxes = |_: &FrobeniusOperation<char, ()>| -> Result<Cospan<char>, Error> {
+            Err(Error::Other("no boxes".to_string()))
+        };
+        let interpreted: Result<Cospan<char>, Error> =
+            <Cospan<char> as GenericMonoidalInterpretable<char>>::interpret(&abc, &fail_on_boxes);
+        assert_eq!(
+            interpreted.err(),
+            Some(Error::Other("no boxes".to_string()))
+        );
+
+        let as_itself = |op: &FrobeniusOperation<char, ()>| -> Result<Generic, Error> {
+            Ok(Generic {
+                layers: vec![GenericMonoidalMorphismLayer {
+                    blocks: vec![op.clone()],
+                    left_type: op.source_types(),
+                    right_type: op.target_types(),
+                }],
+            })
+        };
+        let reinterpreted =
+            <Generic as GenericMonoidalInterpretableMut<char>>::interpret(&abc, &as_itself);
+        assert_eq!(reinterpreted.map(|g| g.codomain()), Ok(vec!['a', 'b', 'c']));
+        let empty_layer = Generic {
+            layers: vec![GenericMonoidalMorphismLayer::new()],
+        };
+        assert_eq!(
+            <Generic as GenericMonoidalInterpretableMut<char>>::interpret(&empty_layer, &as_itself)
+                .err(),
+            Some(Error::EmptyLayer)
+        );
+    }
+
+    #[test]
+    fn layers_round_trip() {
+        use super::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer};
+        use crate::category::ComposableMutating;
+        use crate::error::Error;
+        use crate::frobenius::FrobeniusOperation;
+        type Generic = GenericMonoidalMorphism<FrobeniusOperation<char, ()>, char>;
+        type Layer = GenericMonoidalMorphismLayer<FrobeniusOperation<char, ()>, char>;
+        let merge = Layer {
+            blocks: vec![
+                FrobeniusOperation::Multiplication('a'),
+                FrobeniusOperation::Identity('b'),
+            ],
+            left_type: vec!['a', 'a', 'b'],
+            right_type: vec!['a', 'b'],
+        };
+        let split = Layer {
+            blocks: vec![
+                FrobeniusOperation::Identity('a'),
+                FrobeniusOperation::Comultiplication('b'),
+            ],
+            left_type: vec!['a', 'b'],
+            right_type: vec!['a', 'b', 'b'],
+        };
+        let morphism = Generic::try_from(vec![merge.clone(), split.clone()]).unwrap();
+        assert_eq!(morphism.domain(), vec!['a', 'a', 'b']);
+        assert_eq!(morphism.codomain(), vec!['a', 'b', 'b']);
+        let layers: Vec<Layer> = morphism.clone().into();
+        assert!(layers == vec![merge.clone(), split.clone()]);
+        assert!(Generic::try_from(vec![]).unwrap() == Generic::new());
+        assert_eq!(
+            Generic::try_from(vec![split.clone(), merge.clone()]).err(),
+            Some(Error::InterfaceLabelMismatch {
+                index: 1,
+                left: "'b'".to_string(),
+                right: "'a'".to_string()
+            })
+        );
+        assert_eq!(
+            Generic::try_from(vec![merge.clone(), merge]).err(),
+            Some(Error::InterfaceLengthMismatch { left: 2, right: 3 })
+        );
+
+        #[cfg(feature = "serde")]
+        {
+            let written = serde_json::to_string(&morphism).unwrap();
+            let read: Generic = serde_json::from_str(&written).unwrap();
+            assert!(read == morphism);
+            let reversed = serde_json::to_string(&vec![split.clone(), split]).unwrap();
+            let rejected: Result<Generic, _> = serde_json::from_str(&reversed);
+            assert!(rejected.is_err());
+        }
+    }
+
+    #[test]
+    fn dot_export() {
+        use super::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer};
+        use crate::frobenius::FrobeniusOperation;
+        type Op = FrobeniusOperation<char, &'static str>;
+        type Layer = GenericMonoidalMorphismLayer<Op, char>;
+        let merge = Layer {
+            blocks: vec![
+                FrobeniusOperation::Multiplication('a'),
+                FrobeniusOperation::UnSpecifiedBox("f", vec!['b'], vec!['c', 'c']),
+            ],
+            left_type: vec!['a', 'a', 'b'],
+            right_type: vec!['a', 'c', 'c'],
+        };
+        let split = Layer {
+            blocks: vec![
+                FrobeniusOperation::Counit('a'),
+                FrobeniusOperation::SymmetricBraiding('c', 'c'),
+            ],
+            left_type: vec!['a', 'c', 'c'],
+            right_type: vec!['c', 'c'],
+        };
+        let morphism = GenericMonoidalMorphism::<Op, char>::try_from(vec![merge, split]).unwrap();
+        let dot = morphism.to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("subgraph cluster_0 {"));
+        assert!(dot.contains("subgraph cluster_1 {"));
+        assert!(!dot.contains("cluster_2"));
+        assert!(dot
+            .contains(r#"box_0_1 [shape=box, label="UnSpecifiedBox(\"f\", ['b'], ['c', 'c'])"];"#));
+        assert_eq!(dot.matches(" -> ").count(), 3 + 3 + 2);
+        assert!(dot.contains("in_2 -> box_0_1 [label=\"'b'\"];"));
+        assert!(dot.contains("box_0_0 -> box_1_0 [label=\"'a'\"];"));
+        assert_eq!(dot.matches("box_0_1 -> box_1_1").count(), 2);
+        assert_eq!(dot.matches("box_1_1 -> out_").count(), 2);
+
+        let empty = GenericMonoidalMorphism::<Op, char>::new().to_dot();
+        assert_eq!(empty, "digraph {\n    rankdir=LR;\n}\n");
+    }
+
+    #[test]
+    fn monoidal_macro() {
+        use super::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer};
+        use crate::category::{ComposableMutating, HasIdentity};
+        use crate::error::Error;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        type Op = FrobeniusOperation<char, &'static str>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let f = Op::UnSpecifiedBox("f", vec!['a', 'b'], vec!['c']);
+        let built: Generic = monoidal!(
+            [f.clone(), id('b')];
+            [Op::Comultiplication('c'), Op::Identity('b'),]
+        )
+        .unwrap();
+        let expected = Generic::try_from(vec![
+            GenericMonoidalMorphismLayer {
+                blocks: vec![f.clone(), Op::identity(&'b')],
+                left_type: vec!['a', 'b', 'b'],
+                right_type: vec!['c', 'b'],
+            },
+            GenericMonoidalMorphismLayer {
+                blocks: vec![Op::Comultiplication('c'), Op::Identity('b')],
+                left_type: vec!['c', 'b'],
+                right_type: vec!['c', 'c', 'b'],
+            },
+        ])
+        .unwrap();
+        assert!(built == expected);
+        assert_eq!(built.domain(), vec!['a', 'b', 'b']);
+
+        let scalar: Generic = monoidal!([Op::Unit('a')]; [Op::Counit('a')]; [];).unwrap();
+        assert_eq!(scalar.depth(), 3);
+        assert_eq!(scalar.codomain(), vec![]);
+
+        let mismatched: Result<Generic, Error> = monoidal!([f, id('b')]; [id('b'), id('c')]);
+        assert_eq!(
+            mismatched.err(),
+            Some(Error::InterfaceLabelMismatch {
+                index: 0,
+                left: "'c'".to_string(),
+                right: "'b'".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn memoized_interpretation() {
+        use super::{
+            GenericMonoidalInterpretable, GenericMonoidalInterpretableMut, GenericMonoidalMorphism,
+        };
+        use crate::category::{Composable, HasIdentity};
+        use crate::cospan::Cospan;
+        use crate::error::Error;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        use std::cell::Cell;
+        type Op = FrobeniusOperation<char, ()>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let calls = Cell::new(0);
+        let to_cospan = |op: &Op| -> Result<Cospan<char>, Error> {
+            calls.set(calls.get() + 1);
+            match op {
+                Op::Identity(z) => Ok(Cospan::identity(&vec![*z])),
+                Op::Multiplication(z) => Ok(Cospan::new(vec![0, 0], vec![0], vec![*z])),
+                Op::Comultiplication(z) => Ok(Cospan::new(vec![0], vec![0, 0], vec![*z])),
+                _ => Err(Error::Other("not used here".to_string())),
+            }
+        };
+        let repeated = monoidal!(
+            [Op::Multiplication('a'), Op::Multiplication('a'), id('b')];
+            [Op::Comultiplication('a'), Op::Identity('a'), Op::Identity('b')];
+            [Op::Multiplication('a'), id('a'), id('b')]
+        )
+        .unwrap();
+        let plain: Cospan<char> =
+            <Cospan<char> as GenericMonoidalInterpretable<char>>::interpret(&repeated, &to_cospan)
+                .unwrap();
+        assert_eq!(calls.get(), 9);
+        calls.set(0);
+        let memoized = <Cospan<char> as GenericMonoidalInterpretable<char>>::interpret_memoized(
+            &repeated, &to_cospan,
+        )
+        .unwrap();
+        assert_eq!(calls.get(), 4);
+        assert_eq!(memoized.domain(), plain.domain());
+        assert_eq!(memoized.codomain(), plain.codomain());
+        assert_eq!(memoized.left_to_middle(), plain.left_to_middle());
+        assert_eq!(memoized.right_to_middle(), plain.right_to_middle());
+
+        calls.set(0);
+        let mut again = <Generic as GenericMonoidalInterpretableMut<char>>::interpret_memoized(
+            &repeated,
+            &|op: &Op| {
+                calls.set(calls.get() + 1);
+                monoidal!([op.clone()])
+            },
+        )
+        .unwrap();
+        assert_eq!(calls.get(), 4);
+        let mut expected = repeated.clone();
+        expected.normalize();
+        again.normalize();
+        assert!(again == expected);
+    }
+
+    #[test]
+    fn structure_accessors() {
+        use super::GenericMonoidalMorphism;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        type Op = FrobeniusOperation<char, ()>;
+        let morphism: GenericMonoidalMorphism<Op, char> = monoidal!(
+            [Op::Multiplication('a'), id('b')];
+            [Op::SymmetricBraiding('a', 'b')];
+            [id('b'), Op::Counit('a')]
+        )
+        .unwrap();
+        assert_eq!(morphism.layers().len(), 3);
+        assert_eq!(
+            morphism.blocks_at(0),
+            Some(&[Op::Multiplication('a'), Op::Identity('b')][..])
+        );
+        assert_eq!(morphism.blocks_at(3), None);
+        assert_eq!(
+            morphism.interface_at(1),
+            Some((&['a', 'b'][..], &['b', 'a'][..]))
+        );
+        assert_eq!(
+            morphism.interface_at(2).map(|(_, r)| r.to_vec()),
+            Some(vec!['b'])
+        );
+        let boxes: Vec<_> = morphism.iter_boxes().collect();
+        assert_eq!(
+            boxes,
+            vec![
+                (0, &Op::Multiplication('a')),
+                (0, &Op::Identity('b')),
+                (1, &Op::SymmetricBraiding('a', 'b')),
+                (2, &Op::Identity('b')),
+                (2, &Op::Counit('a')),
+            ]
+        );
+        let counits = morphism
+            .iter_boxes()
+            .filter(|(_, b)| matches!(b, Op::Counit(_)))
+            .count();
+        assert_eq!(counits, 1);
+    }
+
+    #[test]
+    fn validation() {
+        use super::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer};
+        use crate::error::Error;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        type Op = FrobeniusOperation<char, ()>;
+        let good: GenericMonoidalMorphism<Op, char> = monoidal!(
+            [Op::Multiplication('a'), id('b')];
+            [Op::SymmetricBraiding('a', 'b')]
+        )
+        .unwrap();
+        assert_eq!(good.validate(), Ok(()));
+        assert_eq!(
+            GenericMonoidalMorphism::<Op, char>::new().validate(),
+            Ok(())
+        );
+
+        let mut bad = good.clone();
+        bad.layers[0].right_type = vec!['a', 'c'];
+        bad.layers.push(GenericMonoidalMorphismLayer {
+            blocks: vec![Op::Counit('b')],
+            left_type: vec!['b', 'a'],
+            right_type: vec![],
+        });
+        let problems = bad.validate().err().unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                Error::InLayer {
+                    layer: 0,
+                    cause: Box::new(Error::label_mismatch(1, &'c', &'b'))
+                },
+                Error::BetweenLayers {
+                    before: 0,
+                    cause: Box::new(Error::label_mismatch(1, &'c', &'b'))
+                },
+                Error::InLayer {
+                    layer: 2,
+                    cause: Box::new(Error::InterfaceLengthMismatch { left: 2, right: 1 })
+                },
+            ]
+        );
+        assert_eq!(
+            problems[2].to_string(),
+            "In layer 2. Mismatch in cardinalities of common interface. 2 vs 1"
+        );
+    }
+
+    #[test]
+    fn hom_enumeration() {
+        use super::GenericMonoidalMorphism;
+        use crate::category::{ComposableMutating, HasIdentity};
+        use crate::frobenius::FrobeniusOperation;
+        type Op = FrobeniusOperation<char, ()>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let merge = [Op::Multiplication('a')];
+        assert_eq!(
+            Generic::enumerate_hom(&merge, &['a'; 3], &['a'], 2).len(),
+            2
+        );
+        assert_eq!(
+            Generic::enumerate_hom(&merge, &['a'; 3], &['a'], 1).len(),
+            0
+        );
+        /*
+        the two outputs come from a split of the four inputs into consecutive nonempty blocks
+            1+3 and 3+1 each bracketed 2 ways and 2+2 only 1 way up to interchange
+        */
+        let merges = Generic::enumerate_hom(&merge, &['a'; 4], &['a'; 2], 5);
+        assert_eq!(merges.len(), 5);
+        for (idx, m) in merges.iter().enumerate() {
+            assert_eq!(m.domain(), vec!['a'; 4]);
+            assert_eq!(m.codomain(), vec!['a'; 2]);
+            assert_eq!(m.validate(), Ok(()));
+            assert!(merges[idx + 1..].iter().all(|other| other != m));
+        }
+
+        let identities = Generic::enumerate_hom(&merge, &['a', 'b'], &['a', 'b'], 3);
+        assert_eq!(identities.len(), 1);
+        assert!(identities[0] == Generic::identity(&vec!['a', 'b']));
+
+        let unit_counit = [Op::Unit('a'), Op::Counit('a')];
+        let scalars = Generic::enumerate_hom(&unit_counit, &[], &[], 4);
+        /*
+        nothing, one unit;counit and then two of those
+            either side by side or one after the other
+            which normalize does not identify
+        */
+        assert_eq!(scalars.len(), 4);
+        assert!(scalars.iter().all(|s| s.validate().is_ok()));
+    }
+
+    #[test]
+    fn monoidal_product_by_reference() {
+        use super::{GenericMonoidalMorphism, Monoidal};
+        use crate::category::{ComposableMutating, HasIdentity};
+        use crate::frobenius::FrobeniusOperation;
+        type Op = FrobeniusOperation<char, ()>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let merge = crate::monoidal!([Op::Multiplication('a')]).unwrap();
+        let wire = Generic::identity(&vec!['b']);
+        let product = merge.monoidal_product(&wire);
+        let mut mutated = merge.clone();
+        mutated.monoidal(wire.clone());
+        assert!(product == mutated);
+        assert_eq!(product.domain(), vec!['a', 'a', 'b']);
+        assert_eq!(merge.domain(), vec!['a', 'a']);
+        assert_eq!(wire.domain(), vec!['b']);
+        let twice = product.monoidal_product(&product);
+        assert_eq!(twice.codomain(), vec!['a', 'b', 'a', 'b']);
+    }
+
+    #[test]
+    fn signature_checking() {
+        use super::{
+            GenericMonoidalInterpretable, GenericMonoidalInterpretableMut, GenericMonoidalMorphism,
+            GenericMonoidalMorphismLayer, Signature,
+        };
+        use crate::category::{Composable, ComposableMutating, HasIdentity};
+        use crate::cospan::Cospan;
+        use crate::error::Error;
+        let mut signature = Signature::new();
+        signature
+            .add_box("merge", vec!['a', 'a'], vec!['a'])
+            .unwrap();
+        signature
+            .add_box("split", vec!['a'], vec!['a', 'a'])
+            .unwrap();
+        signature.add_box("wire", vec!['a'], vec!['a']).unwrap();
+        assert!(signature.add_box("wire", vec!['b'], vec!['b']).is_err());
+        assert_eq!(
+            signature.types_of(&"merge"),
+            Some((&['a', 'a'][..], &['a'][..]))
+        );
+
+        let built = signature
+            .build(vec![vec!["merge", "wire"], vec!["merge"], vec!["split"]])
+            .unwrap();
+        assert_eq!(built.depth(), 3);
+        assert_eq!(built.domain(), vec!['a'; 3]);
+        assert_eq!(built.codomain(), vec!['a'; 2]);
+        assert!(signature.check(&built).is_ok());
+        assert_eq!(
+            signature.build(vec![vec!["merge"], vec!["loop"]]).err(),
+            Some(Error::InLayer {
+                layer: 1,
+                cause: Box::new(Error::from("\"loop\" is not in the signature"))
+            })
+        );
+        assert_eq!(
+            signature.build(vec![vec!["merge"], vec!["merge"]]).err(),
+            Some(Error::BetweenLayers {
+                before: 0,
+                cause: Box::new(Error::InterfaceLengthMismatch { left: 1, right: 2 })
+            })
+        );
+        assert_eq!(signature.build(vec![vec![]]).err(), Some(Error::EmptyLayer));
+
+        let mut mislabelled = GenericMonoidalMorphism::<&str, char>::new();
+        mislabelled.layers.push(GenericMonoidalMorphismLayer {
+            blocks: vec!["split"],
+            left_type: vec!['a'],
+            right_type: vec!['a'],
+        });
+        assert_eq!(
+            signature.check(&mislabelled).err(),
+            Some(Error::InLayer {
+                layer: 0,
+                cause: Box::new(Error::InterfaceLengthMismatch { left: 1, right: 2 })
+            })
+        );
+
+        let to_cospan = |block: &&str| -> Result<Cospan<char>, Error> {
+            match *block {
+                "merge" => Ok(Cospan::new(vec![0, 0], vec![0], vec!['a'])),
+                "split" => Ok(Cospan::new(vec![0], vec![0, 0], vec!['a'])),
+                "wire" => Ok(Cospan::identity(&vec!['a'])),
+                _ => Err(Error::from("not a box")),
+            }
+        };
+        let checked = <Cospan<char> as GenericMonoidalInterpretable<char>>::interpret_checked(
+            &built, &signature, &to_cospan,
+        )
+        .unwrap();
+        let plain =
+            <Cospan<char> as GenericMonoidalInterpretable<char>>::interpret(&built, &to_cospan)
+                .unwrap();
+        assert_eq!(checked.domain(), plain.domain());
+        assert_eq!(checked.codomain(), plain.codomain());
+        assert_eq!(checked.left_to_middle(), plain.left_to_middle());
+        assert_eq!(checked.right_to_middle(), plain.right_to_middle());
+
+        type Op = crate::frobenius::FrobeniusOperation<char, ()>;
+        let to_generic = |block: &&str| -> Result<GenericMonoidalMorphism<Op, char>, Error> {
+            match *block {
+                "merge" => crate::monoidal!([Op::Multiplication('a')]),
+                "split" => crate::monoidal!([Op::Comultiplication('a')]),
+                "wire" => crate::monoidal!([id('a')]),
+                _ => Err(Error::from("not a box")),
+            }
+        };
+        let as_generic = <GenericMonoidalMorphism<Op, char> as GenericMonoidalInterpretableMut<
+            char,
+        >>::interpret_checked(&built, &signature, &to_generic)
+        .unwrap();
+        assert_eq!(as_generic.domain(), vec!['a'; 3]);
+        assert_eq!(as_generic.codomain(), vec!['a'; 2]);
+
+        /*
+        sending split somewhere with the wrong codomain is caught
+            even though it is only in the last layer
+        */
+        let wrong_split = |block: &&str| -> Result<Cospan<char>, Error> {
+            match *block {
+                "split" => Ok(Cospan::identity(&vec!['a'])),
+                _ => to_cospan(block),
+            }
+        };
+        let problem = <Cospan<char> as GenericMonoidalInterpretable<char>>::interpret_checked(
+            &built,
+            &signature,
+            &wrong_split,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            problem.to_string(),
+            "\"split\" was interpreted with the wrong types. Mismatch in cardinalities of common interface. 2 vs 1"
+        );
+    }
+
+    #[test]
+    fn compact_generic() {
+        use super::GenericMonoidalMorphism;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        type Op = FrobeniusOperation<char, &'static str>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let f = Op::UnSpecifiedBox("f", vec!['a', 'b'], vec!['c']);
+        let built: Generic = monoidal!([Op::Multiplication('a'), id('b')]; [f]).unwrap();
+        assert_eq!(
+            built.to_compact_string(),
+            "Monoidal(['a','a','b']→['c']): Multiplication('a') ⊗ Identity('b') ; UnSpecifiedBox(\"f\", ['a', 'b'], ['c'])"
+        );
+        assert_eq!(
+            Generic::new().to_compact_string(),
+            "Monoidal([]→[]): id([])"
+        );
+    }
 }
@@ -1,10 +1,11 @@
 use {
     crate::{
-        category::{Composable, HasIdentity},
+        category::{Composable, ComposableMutating, HasIdentity},
         cospan::Cospan,
-        monoidal::{Monoidal, MonoidalMorphism},
+        error::Error,
+        monoidal::Monoidal,
         symmetric_monoidal::SymmetricMonoidalMorphism,
-        utils::in_place_permute,
+        utils::{compact_list, in_place_permute, necessary_permutation},
     },
     either::Either::{self, Left, Right},
     log::warn,
@@ -17,7 +18,13 @@ type LeftIndex = usize;
 type RightIndex = usize;
 type MiddleIndex = usize;
 type MiddleIndexOrLambda<Lambda> = Either<MiddleIndex, Lambda>;
+type BoundaryName<LeftPortName, RightPortName> = Either<LeftPortName, RightPortName>;
+type BoundaryNamePair<LeftPortName, RightPortName> = (
+    BoundaryName<LeftPortName, RightPortName>,
+    BoundaryName<LeftPortName, RightPortName>,
+);
 
+#[derive(Clone)]
 pub struct NamedCospan<Lambda: Sized + Eq + Copy + Debug, LeftPortName, RightPortName> {
     /*
     a cospan of finite sets
@@ -67,6 +74,11 @@ where
         &self.right_names
     }
 
+    #[allow(dead_code)]
+    pub fn cospan(&self) -> &Cospan<Lambda> {
+        &self.cospan
+    }
+
     pub fn identity<T, F>(types: &[Lambda], prenames: &[T], prename_to_name: F) -> Self
     where
         F: Fn(T) -> (LeftPortName, RightPortName),
@@ -389,6 +401,50 @@ where
         self.cospan.add_middle(new_middle);
     }
 
+    #[allow(dead_code)]
+    pub fn merge_middle(
+        &mut self,
+        pairs: &[(MiddleIndex, MiddleIndex)],
+    ) -> Result<Vec<MiddleIndex>, Error> {
+        /*
+        see merge_middle in Cospan, the names stay with their nodes
+        */
+        self.cospan.merge_middle(pairs)
+    }
+
+    #[allow(dead_code)]
+    pub fn merge_middle_by_names(
+        &mut self,
+        pairs: &[BoundaryNamePair<LeftPortName, RightPortName>],
+    ) -> Result<Vec<MiddleIndex>, Error> {
+        /*
+        merge the middle nodes that the named boundary nodes go to
+            all at once rather than one pair at a time as with connect_pair
+        an error and no change if a name is not there
+            or the middle nodes have different labels
+        */
+        let middle_of = |name: &BoundaryName<LeftPortName, RightPortName>| match name {
+            Left(name) => self
+                .left_names
+                .iter()
+                .position(|z| z == name)
+                .map(|idx| self.cospan.left_to_middle()[idx]),
+            Right(name) => self
+                .right_names
+                .iter()
+                .position(|z| z == name)
+                .map(|idx| self.cospan.right_to_middle()[idx]),
+        };
+        let mut middle_pairs = Vec::with_capacity(pairs.len());
+        for (name_1, name_2) in pairs {
+            match (middle_of(name_1), middle_of(name_2)) {
+                (Some(mid_1), Some(mid_2)) => middle_pairs.push((mid_1, mid_2)),
+                _ => return Err("No boundary node with that name to merge".into()),
+            }
+        }
+        self.cospan.merge_middle(&middle_pairs)
+    }
+
     pub fn map<F, Mu>(&self, f: F) -> NamedCospan<Mu, LeftPortName, RightPortName>
     where
         F: Fn(Lambda) -> Mu,
@@ -405,6 +461,28 @@ where
         }
     }
 
+    #[allow(dead_code)]
+    pub fn rename_ports<F, G, NewLeft, NewRight>(
+        &self,
+        f: F,
+        g: G,
+    ) -> NamedCospan<Lambda, NewLeft, NewRight>
+    where
+        F: Fn(&LeftPortName) -> NewLeft,
+        G: Fn(&RightPortName) -> NewRight,
+    {
+        /*
+        change the names on the left with f and those on the right with g
+        the cospan itself stays the same
+        as in new the new names are assumed to still be unique on each side
+        */
+        NamedCospan {
+            cospan: self.cospan.clone(),
+            left_names: self.left_names.iter().map(f).collect(),
+            right_names: self.right_names.iter().map(g).collect(),
+        }
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn to_graph<T, U, F, G>(
         &self,
@@ -502,11 +580,11 @@ where
     LeftPortName: Eq + Clone,
     RightPortName: Eq + Clone,
 {
-    fn composable(&self, other: &Self) -> Result<(), String> {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
         self.cospan.composable(&other.cospan)
     }
 
-    fn compose(&self, other: &Self) -> Result<Self, String> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
         Ok(Self {
             cospan: self.cospan.compose(&other.cospan)?,
             left_names: self.left_names.clone(),
@@ -523,13 +601,117 @@ where
     }
 }
 
-impl<Lambda, LeftPortName, RightPortName> MonoidalMorphism<Vec<Lambda>>
+impl<Lambda, LeftPortName, RightPortName> ComposableMutating<Vec<Lambda>>
     for NamedCospan<Lambda, LeftPortName, RightPortName>
 where
     Lambda: Sized + Eq + Copy + Debug,
     LeftPortName: Eq + Clone,
     RightPortName: Eq + Clone,
 {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        self.cospan.composable(&other.cospan)
+    }
+
+    fn compose(&mut self, other: Self) -> Result<(), Error> {
+        /*
+        the same pushout as in Composable
+        but keeping the left names already here and taking the right names of other
+        left alone if that fails
+        */
+        self.cospan = self.cospan.compose(&other.cospan)?;
+        self.right_names = other.right_names;
+        Ok(())
+    }
+
+    fn domain(&self) -> Vec<Lambda> {
+        self.cospan.domain()
+    }
+
+    fn codomain(&self) -> Vec<Lambda> {
+        self.cospan.codomain()
+    }
+}
+
+impl<Lambda, LeftPortName, RightPortName> NamedCospan<Lambda, LeftPortName, RightPortName>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+    LeftPortName: Eq + Clone,
+    RightPortName: Eq + Clone,
+{
+    #[allow(dead_code)]
+    pub fn to_corelation(&self) -> Self {
+        /*
+        drop the middle nodes that no named node reaches
+        the names and the boundary stay as they are
+        */
+        Self {
+            cospan: self.cospan.to_corelation(),
+            left_names: self.left_names.clone(),
+            right_names: self.right_names.clone(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_isomorphic_to(&self, other: &Self) -> bool {
+        /*
+        the same up to renumbering the middle as for Cospan
+        but the boundary nodes are matched up by their names instead of their positions
+            so reordering either side does not matter either
+        */
+        let (Ok(left_p), Ok(right_p)) = (
+            necessary_permutation(&other.left_names, &self.left_names),
+            necessary_permutation(&other.right_names, &self.right_names),
+        ) else {
+            return false;
+        };
+        let mut other = other.clone();
+        other.permute_side(&left_p, false);
+        other.permute_side(&right_p, true);
+        other.left_names == self.left_names
+            && other.right_names == self.right_names
+            && self.cospan.is_isomorphic_to(&other.cospan)
+    }
+
+    #[allow(dead_code)]
+    pub fn compose_by_names<F>(&self, other: &Self, glued: F) -> Result<Self, Error>
+    where
+        F: Fn(&RightPortName, &LeftPortName) -> bool,
+    {
+        /*
+        the pushout over the shared boundary
+        but with the nodes on the right of self and the left of other matched up by their names
+            rather than by their positions
+        glued says which right name of self goes with which left name of other
+            like equality when the names are the same type
+            or something forgetting part of the name
+        every node on either side has to go with exactly one on the other side
+        */
+        let mut positions = Vec::with_capacity(other.left_names.len());
+        for left_name in &other.left_names {
+            let mut matched = self
+                .right_names
+                .iter()
+                .enumerate()
+                .filter(|(_, right_name)| glued(right_name, left_name))
+                .map(|(idx, _)| idx);
+            match (matched.next(), matched.next()) {
+                (Some(idx), None) => positions.push(idx),
+                (None, _) => {
+                    return Err("A node on the left of the second was not matched".into());
+                }
+                (Some(_), Some(_)) => {
+                    return Err(
+                        "A node on the left of the second was matched more than once".into(),
+                    );
+                }
+            }
+        }
+        let p =
+            necessary_permutation(&positions, &(0..self.right_names.len()).collect::<Vec<_>>())?;
+        let mut other = other.clone();
+        other.permute_side(&p, false);
+        Composable::compose(self, &other)
+    }
 }
 
 impl<Lambda, LeftPortName, RightPortName> SymmetricMonoidalMorphism<Lambda>
@@ -553,6 +735,106 @@ where
     }
 }
 
+impl<Lambda, LeftPortName, RightPortName> NamedCospan<Lambda, LeftPortName, RightPortName>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+    LeftPortName: Debug,
+    RightPortName: Debug,
+{
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like NamedCospan(2→1): ["x","y"]→["z"] / [0,0]→[0] / ['a']
+        the port names and then the same as the underlying cospan
+        */
+        format!(
+            "NamedCospan({}→{}): [{}]→[{}] / [{}]→[{}] / [{}]",
+            self.left_names.len(),
+            self.right_names.len(),
+            compact_list(&self.left_names),
+            compact_list(&self.right_names),
+            compact_list(self.cospan.left_to_middle()),
+            compact_list(self.cospan.right_to_middle()),
+            compact_list(self.cospan.middle())
+        )
+    }
+}
+
+/*
+the layout that to_json writes
+bump this whenever that layout changes
+*/
+#[cfg(feature = "serde")]
+#[allow(dead_code)]
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct JsonJunction<Lambda> {
+    label: Lambda,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct JsonNamedPort<'a, PortName> {
+    name: &'a PortName,
+    junction: MiddleIndex,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct JsonNamedCospan<'a, Lambda, LeftPortName, RightPortName> {
+    schema_version: u32,
+    junctions: Vec<JsonJunction<Lambda>>,
+    left: Vec<JsonNamedPort<'a, LeftPortName>>,
+    right: Vec<JsonNamedPort<'a, RightPortName>>,
+}
+
+#[cfg(feature = "serde")]
+fn json_ports<'a, PortName>(
+    names: &'a [PortName],
+    targets: &[MiddleIndex],
+) -> Vec<JsonNamedPort<'a, PortName>> {
+    std::iter::zip(names, targets.iter().copied())
+        .map(|(name, junction)| JsonNamedPort { name, junction })
+        .collect()
+}
+
+#[cfg(feature = "serde")]
+impl<Lambda, LeftPortName, RightPortName> NamedCospan<Lambda, LeftPortName, RightPortName>
+where
+    Lambda: Sized + Eq + Copy + Debug + serde::Serialize,
+    LeftPortName: serde::Serialize,
+    RightPortName: serde::Serialize,
+{
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Result<String, Error> {
+        /*
+        an object with
+            schema_version which is JSON_SCHEMA_VERSION
+            junctions, the middle in order as objects with their label
+            left and right, the ports in order as objects with their name
+                and the 0-based index of the junction they are wired to
+        for example the cospan with one wire from "a" to "b" labelled 'x' is
+            {"schema_version":1,"junctions":[{"label":"x"}],
+             "left":[{"name":"a","junction":0}],"right":[{"name":"b","junction":0}]}
+        an error only if a label or a name fails to serialize
+        */
+        let written = JsonNamedCospan {
+            schema_version: JSON_SCHEMA_VERSION,
+            junctions: self
+                .cospan
+                .middle()
+                .iter()
+                .map(|label| JsonJunction { label: *label })
+                .collect(),
+            left: json_ports(&self.left_names, self.cospan.left_to_middle()),
+            right: json_ports(&self.right_names, self.cospan.right_to_middle()),
+        };
+        serde_json::to_string(&written).map_err(|e| Error::from(e.to_string()))
+    }
+}
+
 mod test {
     #[allow(unused_imports)]
     use crate::{
@@ -696,4 +978,187 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn composing_by_names() {
+        use super::NamedCospan;
+        use crate::category::{compose_all_mutating, ComposableMutating};
+        use either::{Left, Right};
+        /*
+        x and y come in and go out the other way around
+        then z is connected to the one that came in as x by name
+        */
+        let mut crossing = NamedCospan::new(
+            vec![0, 1],
+            vec![1, 0],
+            vec!['a', 'b'],
+            vec!["x", "y"],
+            vec!["y", "x"],
+        );
+        crossing.connect_pair(Left("x"), Right("x"));
+        let joining = NamedCospan::new(
+            vec![0, 1],
+            vec![0],
+            vec!['a', 'b'],
+            vec!["x", "y"],
+            vec!["z"],
+        );
+        assert!(Composable::compose(&crossing, &joining).is_err());
+        let composite = crossing.compose_by_names(&joining, |r, l| r == l).unwrap();
+        assert_eq!(*composite.left_names(), vec!["x", "y"]);
+        assert_eq!(*composite.right_names(), vec!["z"]);
+        assert_eq!(composite.cospan().left_to_middle(), [0, 1]);
+        assert_eq!(composite.cospan().right_to_middle(), [0]);
+        assert_eq!(Composable::domain(&composite), vec!['a', 'b']);
+
+        assert!(crossing
+            .compose_by_names(&joining, |r, l| r.len() == l.len())
+            .is_err());
+        assert!(crossing.compose_by_names(&joining, |_, _| false).is_err());
+
+        /*
+        composing in place through the generic machinery
+        */
+        let identities =
+            (0..3).map(|_| NamedCospan::identity(&['a', 'b'], &["x", "y"], |name| (name, name)));
+        let mut together = compose_all_mutating(identities).unwrap();
+        assert_eq!(ComposableMutating::codomain(&together), vec!['a', 'b']);
+        assert!(ComposableMutating::compose(&mut together, joining.clone()).is_ok());
+        assert_eq!(*together.right_names(), vec!["z"]);
+        assert!(ComposableMutating::compose(&mut together, joining).is_err());
+        assert_eq!(*together.right_names(), vec!["z"]);
+    }
+
+    #[test]
+    fn isomorphic_by_names() {
+        use super::NamedCospan;
+        let cospan = NamedCospan::new(
+            vec![0, 1],
+            vec![1, 0, 2],
+            vec!['a', 'b', 'c'],
+            vec!["x", "y"],
+            vec!["u", "v", "w"],
+        );
+        let reordered = NamedCospan::new(
+            vec![1, 2],
+            vec![0, 1, 2],
+            vec!['c', 'a', 'b'],
+            vec!["x", "y"],
+            vec!["w", "v", "u"],
+        );
+        assert!(cospan.is_isomorphic_to(&reordered));
+        let swapped_names = NamedCospan::new(
+            vec![1, 2],
+            vec![0, 1, 2],
+            vec!['c', 'a', 'b'],
+            vec!["y", "x"],
+            vec!["w", "v", "u"],
+        );
+        assert!(!cospan.is_isomorphic_to(&swapped_names));
+        let other_names = NamedCospan::new(
+            vec![1, 2],
+            vec![0, 1, 2],
+            vec!['c', 'a', 'b'],
+            vec!["x", "y"],
+            vec!["w", "v", "t"],
+        );
+        assert!(!cospan.is_isomorphic_to(&other_names));
+
+        /*
+        composing in two orders gives different middles but isomorphic cospans
+        */
+        let f = NamedCospan::new(vec![0, 0], vec![0], vec!['a'], vec!["x", "y"], vec!["m"]);
+        let g = NamedCospan::new(
+            vec![0],
+            vec![0, 1],
+            vec!['a', 'a'],
+            vec!["m"],
+            vec!["p", "q"],
+        );
+        let h = NamedCospan::new(
+            vec![0, 1],
+            vec![1, 0],
+            vec!['a', 'a'],
+            vec!["p", "q"],
+            vec!["r", "s"],
+        );
+        let first = f.compose(&g).unwrap().compose(&h).unwrap();
+        let second = f.compose(&g.compose(&h).unwrap()).unwrap();
+        assert!(first.is_isomorphic_to(&second));
+
+        /*
+        the loop left behind when composing a cup and a cap is only forgotten by the corelation
+        */
+        let cup = NamedCospan::new(vec![], vec![0, 0], vec!['a'], vec![], vec!["p", "q"]);
+        let cap = NamedCospan::new(vec![0, 0], vec![], vec!['a'], vec!["p", "q"], vec![]);
+        let circle = cup.compose(&cap).unwrap();
+        assert!(!circle.is_isomorphic_to(&NamedCospan::empty()));
+        assert!(circle
+            .to_corelation()
+            .is_isomorphic_to(&NamedCospan::empty()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_export() {
+        use super::NamedCospan;
+        let cospan = NamedCospan::new(vec![0, 1], vec![1], vec!['x', 'y'], vec!["a", "b"], vec![2]);
+        let written: serde_json::Value = serde_json::from_str(&cospan.to_json().unwrap()).unwrap();
+        assert_eq!(
+            written,
+            serde_json::json!({
+                "schema_version": 1,
+                "junctions": [{"label": "x"}, {"label": "y"}],
+                "left": [{"name": "a", "junction": 0}, {"name": "b", "junction": 1}],
+                "right": [{"name": 2, "junction": 1}]
+            })
+        );
+    }
+
+    #[test]
+    fn merging_by_names() {
+        use super::NamedCospan;
+        use either::{Left, Right};
+        let mut cospan = NamedCospan::new(
+            vec![0, 1, 2],
+            vec![3],
+            vec!['x', 'x', 'y', 'x'],
+            vec!["a", "b", "c"],
+            vec!["d"],
+        );
+        assert!(cospan
+            .merge_middle_by_names(&[(Left("a"), Left("c"))])
+            .is_err());
+        assert!(cospan
+            .merge_middle_by_names(&[(Left("a"), Left("e"))])
+            .is_err());
+        let quotient = cospan
+            .merge_middle_by_names(&[(Left("a"), Right("d")), (Left("b"), Right("d"))])
+            .unwrap();
+        assert_eq!(quotient, vec![0, 0, 1, 0]);
+        assert_eq!(cospan.cospan().middle(), &['x', 'y']);
+        assert_eq!(cospan.cospan().left_to_middle(), &[0, 0, 1]);
+        assert_eq!(cospan.cospan().right_to_middle(), &[0]);
+        assert_eq!(*cospan.left_names(), vec!["a", "b", "c"]);
+        cospan.assert_valid(true);
+    }
+
+    #[test]
+    fn renaming_and_relabelling() {
+        use super::NamedCospan;
+        let cospan = NamedCospan::new(vec![0, 1], vec![1], vec!['x', 'y'], vec!["a", "b"], vec![2]);
+        let renamed = cospan.rename_ports(|name| name.to_uppercase(), |name| name * 10);
+        assert_eq!(
+            *renamed.left_names(),
+            vec!["A".to_string(), "B".to_string()]
+        );
+        assert_eq!(*renamed.right_names(), vec![20]);
+        assert_eq!(
+            renamed.cospan().left_to_middle(),
+            cospan.cospan().left_to_middle()
+        );
+        let relabelled = renamed.map(|label| label == 'x');
+        assert_eq!(relabelled.cospan().middle(), &[true, false]);
+        assert_eq!(relabelled.right_names(), renamed.right_names());
+    }
 }
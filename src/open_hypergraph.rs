@@ -0,0 +1,654 @@
+use {
+    crate::{
+        category::{Composable, HasIdentity},
+        cospan::Cospan,
+        error::Error,
+        frobenius::HypergraphCategory,
+        monoidal::Monoidal,
+        symmetric_monoidal::SymmetricMonoidalMorphism,
+    },
+    permutations::Permutation,
+    std::fmt::Debug,
+};
+
+type VertexIndex = usize;
+type EdgeIndex = usize;
+
+/*
+a hyperedge with an ordered list of the vertices it comes out of
+    and an ordered list of the vertices it goes into
+either list can have any length and can repeat vertices
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Hyperedge<E> {
+    pub label: E,
+    pub sources: Vec<VertexIndex>,
+    pub targets: Vec<VertexIndex>,
+}
+
+/*
+where each vertex and each hyperedge of a pattern went in a host
+both are injective and keep the labels
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct HypergraphMatch {
+    pub vertices: Vec<VertexIndex>,
+    pub edges: Vec<EdgeIndex>,
+}
+
+/*
+a hypergraph with vertices labelled by V and hyperedges labelled by E
+along with two ordered lists of vertices as the boundary
+    the domain side and the codomain side
+the vertices with the two boundaries are kept as a Cospan<V>
+    whose middle is the vertices
+so composing glues the codomain side of the first to the domain side of the second
+    by the same pushout as for cospans and carries the hyperedges along
+the monoidal product is the disjoint union
+*/
+#[derive(Clone)]
+pub struct OpenHypergraph<V: Sized + Eq + Copy + Debug, E> {
+    boundary: Cospan<V>,
+    edges: Vec<Hyperedge<E>>,
+}
+
+impl<V, E> OpenHypergraph<V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+    E: Clone,
+{
+    #[allow(dead_code)]
+    pub fn new(
+        vertices: Vec<V>,
+        edges: Vec<Hyperedge<E>>,
+        left: Vec<VertexIndex>,
+        right: Vec<VertexIndex>,
+    ) -> Result<Self, Error> {
+        let vertex_count = vertices.len();
+        let in_range = left.iter().chain(&right).all(|v| *v < vertex_count)
+            && edges.iter().all(|e| {
+                e.sources
+                    .iter()
+                    .chain(&e.targets)
+                    .all(|v| *v < vertex_count)
+            });
+        if !in_range {
+            return Err(format!("A vertex was out of range for {} vertices", vertex_count).into());
+        }
+        Ok(Self {
+            boundary: Cospan::new(left, right, vertices),
+            edges,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn from_edge(label: E, source_types: &[V], target_types: &[V]) -> Self {
+        /*
+        a single hyperedge with a vertex for each of its ends
+        and those vertices in order as the boundary
+        */
+        let sources: Vec<VertexIndex> = (0..source_types.len()).collect();
+        let targets: Vec<VertexIndex> =
+            (source_types.len()..source_types.len() + target_types.len()).collect();
+        Self {
+            boundary: Cospan::new(
+                sources.clone(),
+                targets.clone(),
+                [source_types, target_types].concat(),
+            ),
+            edges: vec![Hyperedge {
+                label,
+                sources,
+                targets,
+            }],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_cospan(boundary: Cospan<V>) -> Self {
+        /*
+        just the vertices and the boundary with no hyperedges
+        */
+        Self {
+            boundary,
+            edges: vec![],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn boundary(&self) -> &Cospan<V> {
+        &self.boundary
+    }
+
+    #[allow(dead_code)]
+    pub fn vertices(&self) -> &[V] {
+        self.boundary.middle()
+    }
+
+    #[allow(dead_code)]
+    pub fn edges(&self) -> &[Hyperedge<E>] {
+        &self.edges
+    }
+
+    #[allow(dead_code)]
+    pub fn left(&self) -> &[VertexIndex] {
+        self.boundary.left_to_middle()
+    }
+
+    #[allow(dead_code)]
+    pub fn right(&self) -> &[VertexIndex] {
+        self.boundary.right_to_middle()
+    }
+
+    #[allow(dead_code)]
+    pub fn add_vertex(&mut self, label: V) -> VertexIndex {
+        self.boundary.add_middle(label)
+    }
+
+    #[allow(dead_code)]
+    pub fn add_edge(
+        &mut self,
+        label: E,
+        sources: Vec<VertexIndex>,
+        targets: Vec<VertexIndex>,
+    ) -> Result<usize, Error> {
+        let vertex_count = self.vertices().len();
+        if sources.iter().chain(&targets).any(|v| *v >= vertex_count) {
+            return Err(format!("A vertex was out of range for {} vertices", vertex_count).into());
+        }
+        self.edges.push(Hyperedge {
+            label,
+            sources,
+            targets,
+        });
+        Ok(self.edges.len() - 1)
+    }
+}
+
+impl<V, E> OpenHypergraph<V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+    E: Clone + PartialEq,
+{
+    #[allow(dead_code)]
+    pub fn monomorphisms_into(&self, host: &Self) -> Vec<HypergraphMatch> {
+        /*
+        every injective label preserving way of putting self into host
+            as plain hypergraphs so neither boundary matters
+        the hyperedges are placed first and carry their vertices along
+            and then the vertices on no hyperedge go anywhere left with the same label
+        */
+        let mut found = Vec::new();
+        let mut search = MatchSearch {
+            pattern: self,
+            host,
+            vertices: vec![None; self.vertices().len()],
+            vertex_used: vec![false; host.vertices().len()],
+            edges: Vec::with_capacity(self.edges().len()),
+            edge_used: vec![false; host.edges().len()],
+        };
+        search.place_edge(0, &mut found);
+        found
+    }
+
+    #[allow(dead_code)]
+    pub fn embeddings_into(&self, host: &Self) -> Vec<HypergraphMatch> {
+        /*
+        the monomorphisms that put self into host as a subdiagram
+        only the boundary of self can be attached to the rest of host
+            so a vertex of self that is not on its boundary
+            is on no hyperedge of host outside the image
+            and is not on the boundary of host
+        these are the matches where the boundary of self can be cut out and something
+            else of the same type put in its place
+        a cospan is the case with no hyperedges through from_cospan
+        */
+        let on_boundary = |v: &VertexIndex| self.left().contains(v) || self.right().contains(v);
+        self.monomorphisms_into(host)
+            .into_iter()
+            .filter(|found| {
+                let mut interior = vec![false; host.vertices().len()];
+                for (v, image) in found.vertices.iter().enumerate() {
+                    interior[*image] = !on_boundary(&v);
+                }
+                let outside_edges_ok = host
+                    .edges()
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| !found.edges.contains(idx))
+                    .all(|(_, e)| e.sources.iter().chain(&e.targets).all(|v| !interior[*v]));
+                let host_boundary_ok = host
+                    .left()
+                    .iter()
+                    .chain(host.right())
+                    .all(|v| !interior[*v]);
+                outside_edges_ok && host_boundary_ok
+            })
+            .collect()
+    }
+}
+
+impl<V, E> HasIdentity<Vec<V>> for OpenHypergraph<V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+{
+    fn identity(on_this: &Vec<V>) -> Self {
+        Self {
+            boundary: Cospan::identity(on_this),
+            edges: vec![],
+        }
+    }
+}
+
+impl<V, E> Composable<Vec<V>> for OpenHypergraph<V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+    E: Clone,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        let (boundary, self_vertices, other_vertices) =
+            self.boundary.compose_with_middle_maps(&other.boundary)?;
+        let moved = |e: &Hyperedge<E>, vertex_map: &[VertexIndex]| Hyperedge {
+            label: e.label.clone(),
+            sources: e.sources.iter().map(|v| vertex_map[*v]).collect(),
+            targets: e.targets.iter().map(|v| vertex_map[*v]).collect(),
+        };
+        let edges = self
+            .edges
+            .iter()
+            .map(|e| moved(e, &self_vertices))
+            .chain(other.edges.iter().map(|e| moved(e, &other_vertices)))
+            .collect();
+        Ok(Self { boundary, edges })
+    }
+
+    fn domain(&self) -> Vec<V> {
+        self.boundary.domain()
+    }
+
+    fn codomain(&self) -> Vec<V> {
+        self.boundary.codomain()
+    }
+}
+
+impl<V, E> Monoidal for OpenHypergraph<V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+{
+    fn monoidal(&mut self, other: Self) {
+        let shift = self.boundary.middle().len();
+        self.boundary.monoidal(other.boundary);
+        self.edges.extend(other.edges.into_iter().map(|mut e| {
+            for v in e.sources.iter_mut().chain(e.targets.iter_mut()) {
+                *v += shift;
+            }
+            e
+        }));
+    }
+}
+
+impl<V, E> SymmetricMonoidalMorphism<V> for OpenHypergraph<V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+    E: Clone,
+{
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        /*
+        only the order of the boundary changes
+        */
+        self.boundary.permute_side(p, of_codomain);
+    }
+
+    fn from_permutation(p: Permutation, types: &[V], types_as_on_domain: bool) -> Self {
+        Self {
+            boundary: Cospan::from_permutation(p, types, types_as_on_domain),
+            edges: vec![],
+        }
+    }
+}
+
+impl<V, E> HypergraphCategory<V> for OpenHypergraph<V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+    E: Clone,
+{
+    /*
+    the same as for cospans with no hyperedges
+    */
+    fn split(z: V) -> Self {
+        Self::from_cospan(Cospan::split(z))
+    }
+
+    fn merge(z: V) -> Self {
+        Self::from_cospan(Cospan::merge(z))
+    }
+
+    fn unit(z: V) -> Self {
+        Self::from_cospan(Cospan::unit(z))
+    }
+
+    fn counit(z: V) -> Self {
+        Self::from_cospan(Cospan::counit(z))
+    }
+}
+
+struct MatchSearch<'a, V: Sized + Eq + Copy + Debug, E> {
+    pattern: &'a OpenHypergraph<V, E>,
+    host: &'a OpenHypergraph<V, E>,
+    vertices: Vec<Option<VertexIndex>>,
+    vertex_used: Vec<bool>,
+    edges: Vec<EdgeIndex>,
+    edge_used: Vec<bool>,
+}
+
+impl<'a, V, E> MatchSearch<'a, V, E>
+where
+    V: Sized + Eq + Copy + Debug,
+    E: Clone + PartialEq,
+{
+    fn place_edge(&mut self, which: usize, found: &mut Vec<HypergraphMatch>) {
+        let Some(pattern_edge) = self.pattern.edges().get(which) else {
+            self.place_vertex(0, found);
+            return;
+        };
+        for (candidate, host_edge) in self.host.edges().iter().enumerate() {
+            if self.edge_used[candidate]
+                || host_edge.label != pattern_edge.label
+                || host_edge.sources.len() != pattern_edge.sources.len()
+                || host_edge.targets.len() != pattern_edge.targets.len()
+            {
+                continue;
+            }
+            let ends = pattern_edge
+                .sources
+                .iter()
+                .chain(&pattern_edge.targets)
+                .zip(host_edge.sources.iter().chain(&host_edge.targets));
+            let mut newly_placed = Vec::new();
+            let mut fits = true;
+            for (p, h) in ends {
+                match self.vertices[*p] {
+                    Some(already) if already == *h => {}
+                    Some(_) => fits = false,
+                    None if self.vertex_used[*h]
+                        || self.pattern.vertices()[*p] != self.host.vertices()[*h] =>
+                    {
+                        fits = false
+                    }
+                    None => {
+                        self.vertices[*p] = Some(*h);
+                        self.vertex_used[*h] = true;
+                        newly_placed.push(*p);
+                    }
+                }
+                if !fits {
+                    break;
+                }
+            }
+            if fits {
+                self.edge_used[candidate] = true;
+                self.edges.push(candidate);
+                self.place_edge(which + 1, found);
+                self.edges.pop();
+                self.edge_used[candidate] = false;
+            }
+            for p in newly_placed {
+                self.vertex_used[self.vertices[p].expect("just placed")] = false;
+                self.vertices[p] = None;
+            }
+        }
+    }
+
+    fn place_vertex(&mut self, which: usize, found: &mut Vec<HypergraphMatch>) {
+        /*
+        the vertices of the pattern that no hyperedge placed
+        */
+        if which == self.vertices.len() {
+            found.push(HypergraphMatch {
+                vertices: self.vertices.iter().map(|v| v.expect("placed")).collect(),
+                edges: self.edges.clone(),
+            });
+            return;
+        }
+        if self.vertices[which].is_some() {
+            self.place_vertex(which + 1, found);
+            return;
+        }
+        for candidate in 0..self.host.vertices().len() {
+            if self.vertex_used[candidate]
+                || self.host.vertices()[candidate] != self.pattern.vertices()[which]
+            {
+                continue;
+            }
+            self.vertices[which] = Some(candidate);
+            self.vertex_used[candidate] = true;
+            self.place_vertex(which + 1, found);
+            self.vertex_used[candidate] = false;
+            self.vertices[which] = None;
+        }
+    }
+}
+
+mod test {
+
+    #[test]
+    fn gluing_edges() {
+        use super::{Hyperedge, OpenHypergraph};
+        use crate::category::{Composable, HasIdentity};
+        type H = OpenHypergraph<char, &'static str>;
+        let f = H::from_edge("f", &['a'], &['b', 'b']);
+        let g = H::from_edge("g", &['b', 'b'], &['c']);
+        assert_eq!(f.vertices(), ['a', 'b', 'b']);
+        assert_eq!(
+            f.edges(),
+            [Hyperedge {
+                label: "f",
+                sources: vec![0],
+                targets: vec![1, 2]
+            }]
+        );
+
+        /*
+        the two ends of f are glued to the two ends of g
+        */
+        let fg = f.compose(&g).unwrap();
+        assert_eq!(fg.domain(), vec!['a']);
+        assert_eq!(fg.codomain(), vec!['c']);
+        assert_eq!(fg.vertices().len(), 4);
+        assert_eq!(fg.edges().len(), 2);
+        assert_eq!(fg.edges()[0].targets, fg.edges()[1].sources);
+        assert_eq!(fg.left(), fg.edges()[0].sources.as_slice());
+        assert_eq!(fg.right(), fg.edges()[1].targets.as_slice());
+        assert!(f.compose(&f).is_err());
+
+        /*
+        identities glue on without changing anything
+        */
+        let same = |x: &H, y: &H| {
+            x.vertices() == y.vertices()
+                && x.edges() == y.edges()
+                && x.left() == y.left()
+                && x.right() == y.right()
+        };
+        assert!(same(&H::identity(&vec!['a']).compose(&f).unwrap(), &f));
+        assert!(same(&f.compose(&H::identity(&vec!['b', 'b'])).unwrap(), &f));
+
+        /*
+        a hyperedge with no sources whose target is not on the boundary
+        stays inside when composing
+        */
+        let mut with_inside = H::identity(&vec!['a']);
+        let inside = with_inside.add_vertex('z');
+        assert_eq!(with_inside.add_edge("u", vec![], vec![inside]), Ok(0));
+        assert!(with_inside.add_edge("u", vec![], vec![7]).is_err());
+        let composed = with_inside.compose(&f).unwrap();
+        assert_eq!(composed.vertices().len(), 4);
+        assert_eq!(composed.vertices()[composed.edges()[0].targets[0]], 'z');
+        let composed = H::identity(&vec!['a']).compose(&with_inside).unwrap();
+        assert!(same(&composed, &with_inside));
+
+        assert!(H::new(
+            vec!['a'],
+            vec![Hyperedge {
+                label: "f",
+                sources: vec![1],
+                targets: vec![]
+            }],
+            vec![0],
+            vec![]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn disjoint_union_and_symmetry() {
+        use super::OpenHypergraph;
+        use crate::category::{Composable, HasIdentity};
+        use crate::monoidal::{Braided, Monoidal};
+        use crate::symmetric_monoidal::SymmetricMonoidalMorphism;
+        use permutations::Permutation;
+        type H = OpenHypergraph<char, char>;
+        let f = H::from_edge('f', &['a'], &['b']);
+        let g = H::from_edge('g', &['c', 'c'], &[]);
+        let mut both = f.clone();
+        both.monoidal(g.clone());
+        assert_eq!(both.domain(), vec!['a', 'c', 'c']);
+        assert_eq!(both.codomain(), vec!['b']);
+        assert_eq!(both.edges()[1].sources, vec![2, 3]);
+
+        /*
+        swapping twice is the identity and a swap slides a hyperedge across
+        */
+        let twice = H::braiding(&vec!['a'], &vec!['b', 'c'])
+            .compose(&H::braiding(&vec!['b', 'c'], &vec!['a']))
+            .unwrap();
+        assert_eq!(twice.left(), twice.right());
+        let mut f_then_id = f.clone();
+        f_then_id.monoidal(H::identity(&vec!['c']));
+        let slid = H::braiding(&vec!['c'], &vec!['a'])
+            .compose(&f_then_id)
+            .unwrap();
+        let mut id_then_f = H::identity(&vec!['c']);
+        id_then_f.monoidal(f.clone());
+        let slid_other_way = id_then_f
+            .compose(&H::braiding(&vec!['c'], &vec!['b']))
+            .unwrap();
+        assert_eq!(slid.domain(), slid_other_way.domain());
+        assert_eq!(slid.codomain(), slid_other_way.codomain());
+        assert_eq!(slid.edges().len(), 1);
+        assert_eq!(
+            slid.vertices()[slid.edges()[0].sources[0]],
+            slid.vertices()[slid.left()[1]]
+        );
+        assert_eq!(slid.edges()[0].sources[0], slid.left()[1]);
+        assert_eq!(slid.edges()[0].targets[0], slid.right()[0]);
+        assert_eq!(
+            slid_other_way.edges()[0].sources[0],
+            slid_other_way.left()[1]
+        );
+        assert_eq!(
+            slid_other_way.edges()[0].targets[0],
+            slid_other_way.right()[0]
+        );
+
+        let mut permuted = both.clone();
+        permuted.permute_side(&Permutation::rotation_left(3, 1), false);
+        assert_eq!(permuted.edges(), both.edges());
+        assert_eq!(permuted.vertices(), both.vertices());
+    }
+
+    #[test]
+    fn special_frobenius_vertices() {
+        use super::OpenHypergraph;
+        use crate::category::Composable;
+        use crate::frobenius::HypergraphCategory;
+        type H = OpenHypergraph<char, char>;
+        let same =
+            |f: &H, g: &H| f.edges() == g.edges() && f.boundary().is_isomorphic_to(g.boundary());
+        assert_eq!(H::frobenius_laws('a', same), Ok(()));
+
+        /*
+        feeding both ends of a hyperedge from one split
+        */
+        let f = H::from_edge('f', &['a', 'a'], &['b']);
+        let shared = H::split('a').compose(&f).unwrap();
+        assert_eq!(shared.vertices().len(), 2);
+        assert_eq!(shared.edges()[0].sources[0], shared.edges()[0].sources[1]);
+        let capped = shared.compose(&H::counit('b')).unwrap();
+        assert!(capped.right().is_empty());
+        assert_eq!(capped.edges().len(), 1);
+        assert_eq!(H::spider('b', 2, 0).unwrap().vertices(), ['b']);
+    }
+
+    #[test]
+    fn finding_subdiagrams() {
+        use super::OpenHypergraph;
+        use crate::category::{Composable, HasIdentity};
+        use crate::cospan::Cospan;
+        use crate::frobenius::HypergraphCategory;
+        use crate::monoidal::Monoidal;
+        type H = OpenHypergraph<char, char>;
+        let f = H::from_edge('f', &['a'], &['b']);
+        let g = H::from_edge('g', &['b'], &['c']);
+        let pattern = f.compose(&g).unwrap();
+
+        let e = H::from_edge('e', &['a'], &['a']);
+        let host = e.compose(&pattern).unwrap();
+        let found = pattern.monomorphisms_into(&host);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].edges, vec![1, 2]);
+        assert_eq!(host.edges()[1].label, 'f');
+        assert_eq!(pattern.embeddings_into(&host), found);
+
+        /*
+        the end of the pattern can be used by more of the host
+            but the middle can not
+        */
+        let mut g_and_wire = g.clone();
+        g_and_wire.monoidal(H::identity(&vec!['b']));
+        let middle_exposed = f
+            .compose(&H::split('b'))
+            .unwrap()
+            .compose(&g_and_wire)
+            .unwrap();
+        assert_eq!(pattern.monomorphisms_into(&middle_exposed).len(), 1);
+        assert!(pattern.embeddings_into(&middle_exposed).is_empty());
+        let mut g_and_k = g.clone();
+        g_and_k.monoidal(H::from_edge('k', &['b'], &[]));
+        let middle_used = f
+            .compose(&H::split('b'))
+            .unwrap()
+            .compose(&g_and_k)
+            .unwrap();
+        assert!(pattern.embeddings_into(&middle_used).is_empty());
+        let mut c_and_k = H::identity(&vec!['c']);
+        c_and_k.monoidal(H::from_edge('k', &['c'], &[]));
+        let end_used = pattern
+            .compose(&H::split('c'))
+            .unwrap()
+            .compose(&c_and_k)
+            .unwrap();
+        assert_eq!(pattern.embeddings_into(&end_used).len(), 1);
+
+        /*
+        every way of placing one hyperedge among several
+        and a cospan as a pattern with no hyperedges
+        */
+        let mut three = f.clone();
+        three.monoidal(f.clone());
+        three.monoidal(f.clone());
+        assert_eq!(f.monomorphisms_into(&three).len(), 3);
+        assert_eq!(f.embeddings_into(&three).len(), 3);
+        assert!(g.monomorphisms_into(&three).is_empty());
+        let merge = H::from_cospan(Cospan::merge('a'));
+        assert_eq!(merge.embeddings_into(&three).len(), 3);
+        assert_eq!(
+            H::from_cospan(Cospan::merge('z'))
+                .embeddings_into(&three)
+                .len(),
+            0
+        );
+        let two_points = H::from_cospan(Cospan::new(vec![0, 1], vec![], vec!['b', 'b']));
+        assert_eq!(two_points.embeddings_into(&three).len(), 6);
+    }
+}
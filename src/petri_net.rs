@@ -0,0 +1,562 @@
+use {
+    crate::{
+        category::{Composable, HasIdentity},
+        cospan::Cospan,
+        error::Error,
+        frobenius::HypergraphCategory,
+        monoidal::Monoidal,
+        symmetric_monoidal::SymmetricMonoidalMorphism,
+    },
+    permutations::Permutation,
+    std::{collections::VecDeque, fmt::Debug},
+};
+
+type PlaceIndex = usize;
+type TransitionIndex = usize;
+type Marking = Vec<usize>;
+
+/*
+a transition with the places it takes tokens from and the places it puts tokens in
+    each along with how many tokens go along that arc
+the same place can show up more than once on either side
+    and then the multiplicities add up
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Transition<T> {
+    pub label: T,
+    pub inputs: Vec<(PlaceIndex, usize)>,
+    pub outputs: Vec<(PlaceIndex, usize)>,
+}
+
+/*
+a Petri net with places labelled by P and transitions labelled by T
+along with two ordered lists of places as the boundary
+    the same way as for OpenHypergraph
+the places with the two boundaries are kept as a Cospan<P>
+    whose middle is the places
+so composing glues the codomain places of the first to the domain places of the second
+    and carries the transitions along
+the monoidal product is the disjoint union
+a marking is how many tokens are on each place in order
+*/
+#[derive(Clone)]
+pub struct OpenPetriNet<P: Sized + Eq + Copy + Debug, T> {
+    boundary: Cospan<P>,
+    transitions: Vec<Transition<T>>,
+}
+
+impl<P, T> OpenPetriNet<P, T>
+where
+    P: Sized + Eq + Copy + Debug,
+    T: Clone,
+{
+    #[allow(dead_code)]
+    pub fn new(
+        places: Vec<P>,
+        transitions: Vec<Transition<T>>,
+        left: Vec<PlaceIndex>,
+        right: Vec<PlaceIndex>,
+    ) -> Result<Self, Error> {
+        let place_count = places.len();
+        let in_range = left.iter().chain(&right).all(|p| *p < place_count)
+            && transitions.iter().all(|t| {
+                t.inputs
+                    .iter()
+                    .chain(&t.outputs)
+                    .all(|(p, _)| *p < place_count)
+            });
+        if !in_range {
+            return Err(format!("A place was out of range for {} places", place_count).into());
+        }
+        Ok(Self {
+            boundary: Cospan::new(left, right, places),
+            transitions,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn from_transition(label: T, inputs: &[(P, usize)], outputs: &[(P, usize)]) -> Self {
+        /*
+        a single transition with a place for each of its arcs
+        and those places in order as the boundary
+        */
+        let input_places: Vec<PlaceIndex> = (0..inputs.len()).collect();
+        let output_places: Vec<PlaceIndex> = (inputs.len()..inputs.len() + outputs.len()).collect();
+        let places = inputs.iter().chain(outputs).map(|(p, _)| *p).collect();
+        let with_counts = |places: &[PlaceIndex], arcs: &[(P, usize)]| {
+            places
+                .iter()
+                .zip(arcs)
+                .map(|(place, (_, count))| (*place, *count))
+                .collect()
+        };
+        Self {
+            transitions: vec![Transition {
+                label,
+                inputs: with_counts(&input_places, inputs),
+                outputs: with_counts(&output_places, outputs),
+            }],
+            boundary: Cospan::new(input_places, output_places, places),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_cospan(boundary: Cospan<P>) -> Self {
+        /*
+        just the places and the boundary with no transitions
+        */
+        Self {
+            boundary,
+            transitions: vec![],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn boundary(&self) -> &Cospan<P> {
+        &self.boundary
+    }
+
+    #[allow(dead_code)]
+    pub fn places(&self) -> &[P] {
+        self.boundary.middle()
+    }
+
+    #[allow(dead_code)]
+    pub fn transitions(&self) -> &[Transition<T>] {
+        &self.transitions
+    }
+
+    #[allow(dead_code)]
+    pub fn left(&self) -> &[PlaceIndex] {
+        self.boundary.left_to_middle()
+    }
+
+    #[allow(dead_code)]
+    pub fn right(&self) -> &[PlaceIndex] {
+        self.boundary.right_to_middle()
+    }
+
+    #[allow(dead_code)]
+    pub fn add_place(&mut self, label: P) -> PlaceIndex {
+        self.boundary.add_middle(label)
+    }
+
+    #[allow(dead_code)]
+    pub fn add_transition(
+        &mut self,
+        label: T,
+        inputs: Vec<(PlaceIndex, usize)>,
+        outputs: Vec<(PlaceIndex, usize)>,
+    ) -> Result<TransitionIndex, Error> {
+        let place_count = self.places().len();
+        if inputs
+            .iter()
+            .chain(&outputs)
+            .any(|(p, _)| *p >= place_count)
+        {
+            return Err(format!("A place was out of range for {} places", place_count).into());
+        }
+        self.transitions.push(Transition {
+            label,
+            inputs,
+            outputs,
+        });
+        Ok(self.transitions.len() - 1)
+    }
+}
+
+impl<P, T> OpenPetriNet<P, T>
+where
+    P: Sized + Eq + Copy + Debug,
+{
+    fn check_marking(&self, marking: &[usize]) -> Result<(), Error> {
+        if marking.len() == self.boundary.middle().len() {
+            Ok(())
+        } else {
+            Err(format!(
+                "A marking had {} places but the net has {}",
+                marking.len(),
+                self.boundary.middle().len()
+            )
+            .into())
+        }
+    }
+
+    fn transition(&self, which: TransitionIndex) -> Result<&Transition<T>, Error> {
+        self.transitions.get(which).ok_or_else(|| {
+            format!(
+                "There is no transition {} among {}",
+                which,
+                self.transitions.len()
+            )
+            .into()
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn is_enabled(&self, marking: &[usize], which: TransitionIndex) -> Result<bool, Error> {
+        /*
+        there are enough tokens on every input place
+            counting a place that shows up on several input arcs once for each
+        */
+        self.check_marking(marking)?;
+        let mut needed = vec![0; marking.len()];
+        for (place, count) in &self.transition(which)?.inputs {
+            needed[*place] += count;
+        }
+        Ok(needed.iter().zip(marking).all(|(need, have)| need <= have))
+    }
+
+    #[allow(dead_code)]
+    pub fn enabled_transitions(&self, marking: &[usize]) -> Result<Vec<TransitionIndex>, Error> {
+        let mut enabled = Vec::new();
+        for which in 0..self.transitions.len() {
+            if self.is_enabled(marking, which)? {
+                enabled.push(which);
+            }
+        }
+        Ok(enabled)
+    }
+
+    #[allow(dead_code)]
+    pub fn fire(&self, marking: &[usize], which: TransitionIndex) -> Result<Marking, Error> {
+        /*
+        take the tokens from the input places and then put them on the output places
+        */
+        if !self.is_enabled(marking, which)? {
+            return Err(format!("Transition {} is not enabled", which).into());
+        }
+        let transition = self.transition(which)?;
+        let mut fired = marking.to_vec();
+        for (place, count) in &transition.inputs {
+            fired[*place] -= count;
+        }
+        for (place, count) in &transition.outputs {
+            fired[*place] += count;
+        }
+        Ok(fired)
+    }
+
+    #[allow(dead_code)]
+    pub fn fire_sequence(
+        &self,
+        marking: &[usize],
+        sequence: &[TransitionIndex],
+    ) -> Result<Marking, Error> {
+        let mut current = marking.to_vec();
+        for which in sequence {
+            current = self.fire(&current, *which)?;
+        }
+        Ok(current)
+    }
+
+    #[allow(dead_code)]
+    pub fn reachable_markings(
+        &self,
+        initial: &[usize],
+        limit: usize,
+    ) -> Result<Vec<Marking>, Error> {
+        /*
+        every marking that some sequence of firings gets to from initial
+            in the order a breadth first search finds them starting with initial itself
+        a net can have infinitely many reachable markings
+            so this gives up once there are more than limit of them
+        */
+        self.search_markings(initial, limit, |_| false)
+            .map(|(found, _)| found)
+    }
+
+    #[allow(dead_code)]
+    pub fn can_reach(
+        &self,
+        initial: &[usize],
+        target: &[usize],
+        limit: usize,
+    ) -> Result<bool, Error> {
+        /*
+        the same search as reachable_markings but stopping as soon as target shows up
+        */
+        self.check_marking(target)?;
+        self.search_markings(initial, limit, |marking| marking == target)
+            .map(|(_, reached)| reached)
+    }
+
+    fn search_markings<F>(
+        &self,
+        initial: &[usize],
+        limit: usize,
+        stop_at: F,
+    ) -> Result<(Vec<Marking>, bool), Error>
+    where
+        F: Fn(&[usize]) -> bool,
+    {
+        self.check_marking(initial)?;
+        let mut found: Vec<Marking> = vec![initial.to_vec()];
+        let mut to_visit: VecDeque<usize> = VecDeque::from([0]);
+        while let Some(visiting) = to_visit.pop_front() {
+            if stop_at(&found[visiting]) {
+                return Ok((found, true));
+            }
+            for which in self.enabled_transitions(&found[visiting])? {
+                let next = self.fire(&found[visiting], which)?;
+                if found.contains(&next) {
+                    continue;
+                }
+                if found.len() == limit {
+                    return Err(format!("More than {} markings were reachable", limit).into());
+                }
+                found.push(next);
+                to_visit.push_back(found.len() - 1);
+            }
+        }
+        Ok((found, false))
+    }
+}
+
+impl<P, T> HasIdentity<Vec<P>> for OpenPetriNet<P, T>
+where
+    P: Sized + Eq + Copy + Debug,
+{
+    fn identity(on_this: &Vec<P>) -> Self {
+        Self {
+            boundary: Cospan::identity(on_this),
+            transitions: vec![],
+        }
+    }
+}
+
+impl<P, T> Composable<Vec<P>> for OpenPetriNet<P, T>
+where
+    P: Sized + Eq + Copy + Debug,
+    T: Clone,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        let (boundary, self_places, other_places) =
+            self.boundary.compose_with_middle_maps(&other.boundary)?;
+        let moved = |t: &Transition<T>, place_map: &[PlaceIndex]| Transition {
+            label: t.label.clone(),
+            inputs: t
+                .inputs
+                .iter()
+                .map(|(p, count)| (place_map[*p], *count))
+                .collect(),
+            outputs: t
+                .outputs
+                .iter()
+                .map(|(p, count)| (place_map[*p], *count))
+                .collect(),
+        };
+        let transitions = self
+            .transitions
+            .iter()
+            .map(|t| moved(t, &self_places))
+            .chain(other.transitions.iter().map(|t| moved(t, &other_places)))
+            .collect();
+        Ok(Self {
+            boundary,
+            transitions,
+        })
+    }
+
+    fn domain(&self) -> Vec<P> {
+        self.boundary.domain()
+    }
+
+    fn codomain(&self) -> Vec<P> {
+        self.boundary.codomain()
+    }
+}
+
+impl<P, T> Monoidal for OpenPetriNet<P, T>
+where
+    P: Sized + Eq + Copy + Debug,
+{
+    fn monoidal(&mut self, other: Self) {
+        let shift = self.boundary.middle().len();
+        self.boundary.monoidal(other.boundary);
+        self.transitions
+            .extend(other.transitions.into_iter().map(|mut t| {
+                for (p, _) in t.inputs.iter_mut().chain(t.outputs.iter_mut()) {
+                    *p += shift;
+                }
+                t
+            }));
+    }
+}
+
+impl<P, T> SymmetricMonoidalMorphism<P> for OpenPetriNet<P, T>
+where
+    P: Sized + Eq + Copy + Debug,
+    T: Clone,
+{
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        /*
+        only the order of the boundary changes
+        */
+        self.boundary.permute_side(p, of_codomain);
+    }
+
+    fn from_permutation(p: Permutation, types: &[P], types_as_on_domain: bool) -> Self {
+        Self {
+            boundary: Cospan::from_permutation(p, types, types_as_on_domain),
+            transitions: vec![],
+        }
+    }
+}
+
+impl<P, T> HypergraphCategory<P> for OpenPetriNet<P, T>
+where
+    P: Sized + Eq + Copy + Debug,
+    T: Clone,
+{
+    /*
+    the same as for cospans with no transitions
+    */
+    fn split(z: P) -> Self {
+        Self::from_cospan(Cospan::split(z))
+    }
+
+    fn merge(z: P) -> Self {
+        Self::from_cospan(Cospan::merge(z))
+    }
+
+    fn unit(z: P) -> Self {
+        Self::from_cospan(Cospan::unit(z))
+    }
+
+    fn counit(z: P) -> Self {
+        Self::from_cospan(Cospan::counit(z))
+    }
+}
+
+mod test {
+
+    #[test]
+    fn gluing_and_firing() {
+        use super::{OpenPetriNet, Transition};
+        use crate::category::{Composable, HasIdentity};
+        type N = OpenPetriNet<char, &'static str>;
+
+        /*
+        two hydrogen and one oxygen make water
+        then water is split back into its parts somewhere else
+        */
+        let burn = N::from_transition("burn", &[('h', 2), ('o', 1)], &[('w', 2)]);
+        let split = N::from_transition("split", &[('w', 1)], &[('h', 1), ('o', 1)]);
+        assert_eq!(burn.places(), ['h', 'o', 'w']);
+        assert_eq!(
+            burn.transitions(),
+            [Transition {
+                label: "burn",
+                inputs: vec![(0, 2), (1, 1)],
+                outputs: vec![(2, 2)]
+            }]
+        );
+        assert_eq!(burn.domain(), vec!['h', 'o']);
+        assert_eq!(burn.codomain(), vec!['w']);
+
+        let both = burn.compose(&split).unwrap();
+        assert_eq!(both.places().len(), 5);
+        assert_eq!(both.domain(), vec!['h', 'o']);
+        assert_eq!(both.codomain(), vec!['h', 'o']);
+        let water = both.transitions()[0].outputs[0].0;
+        assert_eq!(both.transitions()[1].inputs[0].0, water);
+        assert!(split.compose(&split).is_err());
+
+        /*
+        firing through the glued place
+        */
+        let mut marking = vec![0; 5];
+        marking[both.left()[0]] = 4;
+        marking[both.left()[1]] = 1;
+        assert_eq!(both.enabled_transitions(&marking), Ok(vec![0]));
+        assert!(both.fire(&marking, 1).is_err());
+        let fired = both.fire_sequence(&marking, &[0, 1, 1]).unwrap();
+        assert_eq!(fired[both.left()[0]], 2);
+        assert_eq!(fired[both.left()[1]], 0);
+        assert_eq!(fired[both.right()[0]], 2);
+        assert_eq!(fired[both.right()[1]], 2);
+        assert_eq!(fired[water], 0);
+        assert!(both.fire(&fired, 0).is_err());
+        assert!(both.fire(&[0, 0], 0).is_err());
+        assert!(both.fire(&marking, 2).is_err());
+
+        /*
+        identities glue on without changing anything
+        */
+        let same = |x: &N, y: &N| {
+            x.places() == y.places()
+                && x.transitions() == y.transitions()
+                && x.left() == y.left()
+                && x.right() == y.right()
+        };
+        assert!(same(
+            &N::identity(&vec!['h', 'o']).compose(&burn).unwrap(),
+            &burn
+        ));
+        assert!(same(
+            &burn.compose(&N::identity(&vec!['w'])).unwrap(),
+            &burn
+        ));
+        assert!(N::new(
+            vec!['h'],
+            vec![Transition {
+                label: "burn",
+                inputs: vec![(1, 1)],
+                outputs: vec![]
+            }],
+            vec![0],
+            vec![]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn reachability() {
+        use super::OpenPetriNet;
+        use crate::category::{Composable, HasIdentity};
+        use crate::frobenius::HypergraphCategory;
+        use crate::monoidal::Monoidal;
+        type N = OpenPetriNet<char, char>;
+
+        /*
+        a token going around a loop of two places
+            closed up by merging the end back into the start
+        */
+        let there = N::from_transition('t', &[('a', 1)], &[('b', 1)]);
+        let back = N::from_transition('u', &[('b', 1)], &[('a', 1)]);
+        let round = N::unit('a')
+            .compose(&N::split('a'))
+            .unwrap()
+            .compose(&{
+                let mut both_ways = there.compose(&back).unwrap();
+                both_ways.monoidal(N::identity(&vec!['a']));
+                both_ways
+            })
+            .unwrap()
+            .compose(&N::merge('a'))
+            .unwrap()
+            .compose(&N::counit('a'))
+            .unwrap();
+        assert!(round.domain().is_empty() && round.codomain().is_empty());
+        let start = round.transitions()[0].inputs[0].0;
+        let mut marking = vec![0; round.places().len()];
+        marking[start] = 1;
+        let reachable = round.reachable_markings(&marking, 10).unwrap();
+        assert_eq!(reachable.len(), 2);
+        assert_eq!(reachable[0], marking);
+        assert_eq!(round.can_reach(&marking, &marking, 10), Ok(true));
+        let mut two = marking.clone();
+        two[start] = 2;
+        assert_eq!(round.can_reach(&marking, &two, 10), Ok(false));
+        assert_eq!(round.reachable_markings(&two, 10).unwrap().len(), 3);
+
+        /*
+        a transition with no inputs can fire forever
+        */
+        let source = N::from_transition('s', &[], &[('a', 1)]);
+        assert_eq!(source.can_reach(&[0], &[5], 10), Ok(true));
+        assert!(source.reachable_markings(&[0], 10).is_err());
+        assert!(source.can_reach(&[0], &[0, 0], 10).is_err());
+    }
+}
@@ -0,0 +1,493 @@
+use {
+    crate::{
+        category::{ComposableMutating, HasIdentity},
+        error::Error,
+        monoidal::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer, HasWireTypes},
+        symmetric_monoidal::{HasSymmetricBraiding, SymmetricMonoidalMutatingMorphism},
+        utils::{necessary_permutation, same_labels_check},
+    },
+    either::Either::{self, Left, Right},
+    petgraph::{
+        prelude::Graph,
+        stable_graph::{DefaultIx, NodeIndex},
+    },
+    std::fmt::Debug,
+};
+
+/*
+where a wire starts
+    Left(i) is the i'th wire of the domain
+    Right((node, port)) is the port'th output of that node
+*/
+pub type PortSource = Either<usize, (usize, usize)>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PortGraph<BoxType, Lambda> {
+    /*
+    the boxes of a morphism in a symmetric monoidal category as nodes
+    with a wire into every input port of every node and every wire of the codomain
+    identities and symmetric braidings are not nodes, they only say where the wires go
+    so a wire is recorded by what is at the start of it
+    the nodes are numbered by how many boxes are between them and the domain
+        and among those by where their inputs come from
+    so morphisms built differently but equal by the interchange law
+        or by naturality of the braiding get the same PortGraph
+    */
+    domain: Vec<Lambda>,
+    codomain: Vec<Lambda>,
+    nodes: Vec<BoxType>,
+    node_inputs: Vec<Vec<PortSource>>,
+    codomain_sources: Vec<PortSource>,
+}
+
+impl<BoxType, Lambda> PortGraph<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone
+        + PartialEq
+        + HasIdentity<Lambda>
+        + HasSymmetricBraiding<Lambda>
+        + HasWireTypes<Lambda>,
+{
+    #[allow(dead_code)]
+    pub fn domain(&self) -> Vec<Lambda> {
+        self.domain.clone()
+    }
+
+    #[allow(dead_code)]
+    pub fn codomain(&self) -> Vec<Lambda> {
+        self.codomain.clone()
+    }
+
+    #[allow(dead_code)]
+    pub fn nodes(&self) -> &[BoxType] {
+        &self.nodes
+    }
+
+    #[allow(dead_code)]
+    pub fn node_inputs(&self, node: usize) -> &[PortSource] {
+        &self.node_inputs[node]
+    }
+
+    #[allow(dead_code)]
+    pub fn codomain_sources(&self) -> &[PortSource] {
+        &self.codomain_sources
+    }
+
+    #[allow(dead_code)]
+    pub fn source_type(&self, source: PortSource) -> Lambda {
+        match source {
+            Left(wire) => self.domain[wire],
+            Right((node, port)) => self.nodes[node].target_types()[port],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_parts(
+        domain: Vec<Lambda>,
+        codomain: Vec<Lambda>,
+        nodes: Vec<BoxType>,
+        node_inputs: Vec<Vec<PortSource>>,
+        codomain_sources: Vec<PortSource>,
+    ) -> Result<Self, Error> {
+        /*
+        the nodes can be in any order and get put in one where each node
+            comes after everything feeding into it
+        an error if there is a cycle, if a wire has the wrong type
+            or if some wire is not used exactly once
+        the numbering is not the one from_generic would give
+            so go through to_generic and back to compare with other PortGraphs
+        */
+        if nodes.len() != node_inputs.len() || codomain.len() != codomain_sources.len() {
+            return Err(Error::from(
+                "Every node and codomain wire needs its sources",
+            ));
+        }
+        let mut uses = vec![0; domain.len()];
+        let mut output_uses: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|block| vec![0; block.target_types().len()])
+            .collect();
+        for source in node_inputs.iter().flatten().chain(&codomain_sources) {
+            let count = match source {
+                Left(wire) => uses.get_mut(*wire),
+                Right((node, port)) => output_uses.get_mut(*node).and_then(|u| u.get_mut(*port)),
+            }
+            .ok_or_else(|| format!("The wire {:?} does not exist", source))?;
+            *count += 1;
+        }
+        if uses
+            .iter()
+            .chain(output_uses.iter().flatten())
+            .any(|count| *count != 1)
+        {
+            return Err(Error::from("Some wire is not used exactly once"));
+        }
+
+        let mut order = Vec::with_capacity(nodes.len());
+        let mut placed = vec![false; nodes.len()];
+        while order.len() < nodes.len() {
+            let ready = (0..nodes.len()).find(|node| {
+                !placed[*node]
+                    && node_inputs[*node]
+                        .iter()
+                        .all(|source| source.right().is_none_or(|(other, _)| placed[other]))
+            });
+            let Some(ready) = ready else {
+                return Err(Error::from("The nodes have a cycle"));
+            };
+            placed[ready] = true;
+            order.push(ready);
+        }
+        let mut renumbering = vec![0; nodes.len()];
+        for (new_index, node) in order.iter().enumerate() {
+            renumbering[*node] = new_index;
+        }
+        let renumber =
+            |source: &PortSource| source.map_right(|(node, port)| (renumbering[node], port));
+        let answer = Self {
+            domain,
+            codomain,
+            nodes: order.iter().map(|node| nodes[*node].clone()).collect(),
+            node_inputs: order
+                .iter()
+                .map(|node| node_inputs[*node].iter().map(renumber).collect())
+                .collect(),
+            codomain_sources: codomain_sources.iter().map(renumber).collect(),
+        };
+        for node in 0..answer.nodes.len() {
+            let found_types: Vec<Lambda> = answer.node_inputs[node]
+                .iter()
+                .map(|source| answer.source_type(*source))
+                .collect();
+            same_labels_check(answer.nodes[node].source_types().iter(), found_types.iter())?;
+        }
+        let found_types: Vec<Lambda> = answer
+            .codomain_sources
+            .iter()
+            .map(|source| answer.source_type(*source))
+            .collect();
+        same_labels_check(answer.codomain.iter(), found_types.iter())?;
+        Ok(answer)
+    }
+
+    #[allow(dead_code)]
+    pub fn from_generic(morphism: &GenericMonoidalMorphism<BoxType, Lambda>) -> Self {
+        /*
+        read off the wires by following them through the layers
+        after normalizing so that the order the nodes are found in
+            does not depend on how the morphism was built up
+        then renumber the nodes so that they are in order of depth
+            and among the same depth in order of their inputs
+        only nodes without inputs at the same depth keep the order they were found in
+        */
+        let mut normalized = morphism.clone();
+        normalized.normalize();
+        let domain = normalized.domain();
+        let mut nodes = vec![];
+        let mut node_inputs: Vec<Vec<PortSource>> = vec![];
+        let mut frontier: Vec<PortSource> = (0..domain.len()).map(Left).collect();
+        for layer in normalized.layers() {
+            let mut next_frontier = Vec::with_capacity(layer.right_type.len());
+            let mut position = 0;
+            for block in &layer.blocks {
+                let source_types = block.source_types();
+                let inputs = source_types.len();
+                if inputs == 1 && *block == BoxType::identity(&source_types[0]) {
+                    next_frontier.push(frontier[position]);
+                } else if inputs == 2
+                    && *block == BoxType::symmetric_braiding(&source_types[0], &source_types[1])
+                {
+                    next_frontier.push(frontier[position + 1]);
+                    next_frontier.push(frontier[position]);
+                } else {
+                    let node = nodes.len();
+                    let outputs = block.target_types().len();
+                    next_frontier.extend((0..outputs).map(|port| Right((node, port))));
+                    node_inputs.push(frontier[position..position + inputs].to_vec());
+                    nodes.push(block.clone());
+                }
+                position += inputs;
+            }
+            frontier = next_frontier;
+        }
+
+        let mut depths: Vec<usize> = Vec::with_capacity(nodes.len());
+        for inputs in &node_inputs {
+            let depth = inputs
+                .iter()
+                .filter_map(|source| source.right().map(|(node, _)| depths[node]))
+                .max()
+                .unwrap_or(0);
+            depths.push(depth + 1);
+        }
+        let mut renumbering = vec![0; nodes.len()];
+        let mut numbered = 0;
+        let renumber = |source: &PortSource, renumbering: &[usize]| {
+            source.map_right(|(node, port)| (renumbering[node], port))
+        };
+        for depth in 1..=depths.iter().copied().max().unwrap_or(0) {
+            let mut at_depth: Vec<(Vec<PortSource>, usize)> = (0..nodes.len())
+                .filter(|node| depths[*node] == depth)
+                .map(|node| {
+                    let inputs = node_inputs[node]
+                        .iter()
+                        .map(|source| renumber(source, &renumbering))
+                        .collect();
+                    (inputs, node)
+                })
+                .collect();
+            at_depth.sort();
+            for (_, node) in at_depth {
+                renumbering[node] = numbered;
+                numbered += 1;
+            }
+        }
+
+        let mut sorted: Vec<(usize, BoxType, Vec<PortSource>)> = nodes
+            .into_iter()
+            .zip(node_inputs)
+            .enumerate()
+            .map(|(node, (block, inputs))| {
+                let inputs = inputs
+                    .iter()
+                    .map(|source| renumber(source, &renumbering))
+                    .collect();
+                (renumbering[node], block, inputs)
+            })
+            .collect();
+        sorted.sort_by_key(|(new_index, _, _)| *new_index);
+        let (nodes, node_inputs) = sorted
+            .into_iter()
+            .map(|(_, block, inputs)| (block, inputs))
+            .unzip();
+        Self {
+            domain,
+            codomain: normalized.codomain(),
+            nodes,
+            node_inputs,
+            codomain_sources: frontier
+                .iter()
+                .map(|source| renumber(source, &renumbering))
+                .collect(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn to_generic(&self) -> Result<GenericMonoidalMorphism<BoxType, Lambda>, Error> {
+        /*
+        put down the nodes one at a time in order
+        before each one, cross the wires so that its inputs are next to each other
+            where the first of them was
+        a node without inputs goes at the end
+        and at the end cross the wires into the order of the codomain
+        an error if some wire is used twice or never comes to exist
+        */
+        let mut answer = GenericMonoidalMorphism::identity(&self.domain);
+        let mut frontier: Vec<PortSource> = (0..self.domain.len()).map(Left).collect();
+        for (node, (block, inputs)) in self.nodes.iter().zip(&self.node_inputs).enumerate() {
+            let mut positions = Vec::with_capacity(inputs.len());
+            for input in inputs {
+                let position = frontier
+                    .iter()
+                    .position(|source| source == input)
+                    .ok_or_else(|| {
+                        format!("The input {:?} of node {} was not available", input, node)
+                    })?;
+                positions.push(position);
+            }
+            let start = positions.iter().copied().min().unwrap_or(frontier.len());
+            let mut rearranged: Vec<PortSource> = frontier
+                .iter()
+                .filter(|source| !inputs.contains(source))
+                .copied()
+                .collect();
+            let before = frontier[..start]
+                .iter()
+                .filter(|source| !inputs.contains(source))
+                .count();
+            rearranged.splice(before..before, inputs.iter().copied());
+            self.cross_into(&mut answer, &frontier, &rearranged)?;
+
+            let mut layer = GenericMonoidalMorphismLayer::identity(
+                &rearranged[..before]
+                    .iter()
+                    .map(|source| self.source_type(*source))
+                    .collect(),
+            );
+            let outputs = block.target_types().len();
+            layer.blocks.push(block.clone());
+            layer.left_type.extend(block.source_types());
+            layer.right_type.extend(block.target_types());
+            for source in &rearranged[before + inputs.len()..] {
+                let wire_type = self.source_type(*source);
+                layer.blocks.push(BoxType::identity(&wire_type));
+                layer.left_type.push(wire_type);
+                layer.right_type.push(wire_type);
+            }
+            answer.append_layer(layer)?;
+
+            frontier = rearranged;
+            frontier.splice(
+                before..before + inputs.len(),
+                (0..outputs).map(|port| Right((node, port))),
+            );
+        }
+        if frontier.len() != self.codomain_sources.len() {
+            return Err(Error::from("Some outputs of nodes went nowhere"));
+        }
+        self.cross_into(&mut answer, &frontier, &self.codomain_sources)?;
+        answer.compact_layers();
+        Ok(answer)
+    }
+
+    fn cross_into(
+        &self,
+        answer: &mut GenericMonoidalMorphism<BoxType, Lambda>,
+        current: &[PortSource],
+        desired: &[PortSource],
+    ) -> Result<(), Error> {
+        /*
+        compose answer with the crossings taking the wires in current order
+        to the wires in desired order
+        */
+        if current == desired {
+            return Ok(());
+        }
+        let p = necessary_permutation(current, desired)?.inv();
+        let types: Vec<Lambda> = current
+            .iter()
+            .map(|source| self.source_type(*source))
+            .collect();
+        answer.compose(GenericMonoidalMorphism::from_permutation(p, &types, true))
+    }
+
+    #[allow(dead_code, clippy::type_complexity)]
+    pub fn to_graph(
+        &self,
+    ) -> (
+        Vec<NodeIndex<DefaultIx>>,
+        Vec<NodeIndex<DefaultIx>>,
+        Vec<NodeIndex<DefaultIx>>,
+        Graph<Option<BoxType>, (usize, usize, Lambda)>,
+    ) {
+        /*
+        make this into a petgraph graph
+        vertices for every wire of the domain, every node and every wire of the codomain
+        the boundary vertices have None and the others have their box
+        the edges go along the wires with the output port they leave from,
+            the input port they arrive at and the type of the wire
+        the ports on the boundary are always 0
+        */
+        let mut graph = Graph::new();
+        let domain_vertices: Vec<_> = self.domain.iter().map(|_| graph.add_node(None)).collect();
+        let node_vertices: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|block| graph.add_node(Some(block.clone())))
+            .collect();
+        let codomain_vertices: Vec<_> =
+            self.codomain.iter().map(|_| graph.add_node(None)).collect();
+        let start_of = |source: &PortSource| match source {
+            Left(wire) => (domain_vertices[*wire], 0),
+            Right((node, port)) => (node_vertices[*node], *port),
+        };
+        for (node, inputs) in self.node_inputs.iter().enumerate() {
+            for (input_port, source) in inputs.iter().enumerate() {
+                let (start, output_port) = start_of(source);
+                let weight = (output_port, input_port, self.source_type(*source));
+                graph.add_edge(start, node_vertices[node], weight);
+            }
+        }
+        for (wire, source) in self.codomain_sources.iter().enumerate() {
+            let (start, output_port) = start_of(source);
+            let weight = (output_port, 0, self.source_type(*source));
+            graph.add_edge(start, codomain_vertices[wire], weight);
+        }
+        (domain_vertices, node_vertices, codomain_vertices, graph)
+    }
+}
+
+mod test {
+
+    #[test]
+    fn interchange_invariance() {
+        use super::PortGraph;
+        use crate::category::{ComposableMutating, HasIdentity};
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer, Monoidal};
+        use crate::symmetric_monoidal::SymmetricMonoidalMutatingMorphism;
+        use permutations::Permutation;
+        type Op = FrobeniusOperation<char, u8>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let single = |op: Op, left_type: Vec<char>, right_type: Vec<char>| {
+            let mut answer = Generic::new();
+            answer
+                .append_layer(GenericMonoidalMorphismLayer {
+                    blocks: vec![op],
+                    left_type,
+                    right_type,
+                })
+                .unwrap();
+            answer
+        };
+        let tensored = |first: &Generic, second: &Generic| first.monoidal_product(second);
+        let composed = |first: &Generic, second: &Generic| {
+            let mut answer = first.clone();
+            answer.compose(second.clone()).unwrap();
+            answer
+        };
+        let id = |types: &[char]| Generic::identity(&types.to_vec());
+        let f = single(
+            Op::UnSpecifiedBox(0, vec!['a'], vec!['b', 'b']),
+            vec!['a'],
+            vec!['b', 'b'],
+        );
+        let g = single(Op::Multiplication('c'), vec!['c', 'c'], vec!['c']);
+        let cross = |p: Vec<usize>, types: &[char]| {
+            Generic::from_permutation(Permutation::try_from(p).unwrap(), types, true)
+        };
+
+        let f_first = composed(
+            &tensored(&f, &id(&['c', 'c'])),
+            &tensored(&id(&['b', 'b']), &g),
+        );
+        let g_first = composed(&tensored(&id(&['a']), &g), &tensored(&f, &id(&['c'])));
+        let graph = PortGraph::from_generic(&f_first);
+        assert!(graph == PortGraph::from_generic(&g_first));
+        assert_eq!(graph.nodes().len(), 2);
+        assert_eq!(graph.domain(), vec!['a', 'c', 'c']);
+        assert_eq!(graph.codomain(), vec!['b', 'b', 'c']);
+
+        /*
+        sliding g through the crossing
+        */
+        let g_then_cross = composed(
+            &tensored(&g, &id(&['a'])),
+            &composed(&cross(vec![1, 0], &['c', 'a']), &tensored(&f, &id(&['c']))),
+        );
+        let cross_then_g = composed(
+            &composed(
+                &tensored(&id(&['c', 'c']), &f),
+                &tensored(&g, &id(&['b', 'b'])),
+            ),
+            &cross(vec![2, 0, 1], &['c', 'b', 'b']),
+        );
+        assert_eq!(cross_then_g.codomain(), g_then_cross.codomain());
+        assert!(PortGraph::from_generic(&g_then_cross) == PortGraph::from_generic(&cross_then_g));
+
+        for morphism in [f_first, g_then_cross, cross_then_g] {
+            let graph = PortGraph::from_generic(&morphism);
+            let rebuilt = graph.to_generic().unwrap();
+            assert_eq!(rebuilt.domain(), morphism.domain());
+            assert_eq!(rebuilt.codomain(), morphism.codomain());
+            assert!(PortGraph::from_generic(&rebuilt) == graph);
+            let (domain, nodes, codomain, petgraph) = graph.to_graph();
+            assert_eq!(
+                petgraph.node_count(),
+                domain.len() + nodes.len() + codomain.len()
+            );
+            assert_eq!(petgraph.edge_count(), 3 + codomain.len());
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use {
+    crate::{
+        category::{Composable, ComposableMutating, HasIdentity},
+        error::Error,
+        monoidal::{GenericMonoidalMorphism, GenericMonoidalMorphismLayer, HasWireTypes, Monoidal},
+        symmetric_monoidal::{
+            HasSymmetricBraiding, SymmetricMonoidalDiscreteMorphism,
+            SymmetricMonoidalMutatingMorphism,
+        },
+    },
+    permutations::Permutation,
+    std::{collections::HashMap, fmt::Debug, hash::Hash},
+};
+
+pub trait Prop: SymmetricMonoidalDiscreteMorphism<usize> + HasIdentity<usize> {
+    /*
+    a symmetric monoidal category whose objects are the natural numbers
+        with the monoidal product being addition
+    so a morphism only needs to say how many wires come in and go out
+    */
+    fn symmetry(m: usize, n: usize) -> Self {
+        /*
+        the first m wires crossing past the last n
+        */
+        let shuffle = (0..m + n)
+            .map(|i| if i < m { n + i } else { i - m })
+            .collect::<Vec<_>>();
+        Self::from_permutation(
+            Permutation::try_from(shuffle).expect("a shuffle is a permutation"),
+            m + n,
+            true,
+        )
+    }
+}
+
+impl<M> Prop for M where M: SymmetricMonoidalDiscreteMorphism<usize> + HasIdentity<usize> {}
+
+/*
+the boxes of a free PROP
+a generator remembers how many wires it takes in and gives out
+the wires themselves all have the same type so that is ()
+*/
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PropOperation<Gen> {
+    Generator(Gen, usize, usize),
+    Identity,
+    Swap,
+}
+
+impl<Gen> HasWireTypes<()> for PropOperation<Gen> {
+    fn source_types(&self) -> Vec<()> {
+        match self {
+            Self::Generator(_, inputs, _) => vec![(); *inputs],
+            Self::Identity => vec![()],
+            Self::Swap => vec![(); 2],
+        }
+    }
+
+    fn target_types(&self) -> Vec<()> {
+        match self {
+            Self::Generator(_, _, outputs) => vec![(); *outputs],
+            Self::Identity => vec![()],
+            Self::Swap => vec![(); 2],
+        }
+    }
+}
+
+impl<Gen> HasIdentity<()> for PropOperation<Gen> {
+    fn identity(_on_this: &()) -> Self {
+        Self::Identity
+    }
+}
+
+impl<Gen> HasSymmetricBraiding<()> for PropOperation<Gen> {
+    fn symmetric_braiding(_z: &(), _w: &()) -> Self {
+        Self::Swap
+    }
+}
+
+/*
+the generators of a free PROP along with how many wires each takes in and gives out
+*/
+#[derive(Clone)]
+pub struct Signature<Gen> {
+    arities: HashMap<Gen, (usize, usize)>,
+}
+
+impl<Gen: Eq + Hash> Default for Signature<Gen> {
+    fn default() -> Self {
+        Self {
+            arities: HashMap::new(),
+        }
+    }
+}
+
+impl<Gen> Signature<Gen>
+where
+    Gen: Eq + Hash + Clone + Debug,
+{
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn add_generator(
+        &mut self,
+        generator: Gen,
+        inputs: usize,
+        outputs: usize,
+    ) -> Result<(), Error> {
+        if self.arities.contains_key(&generator) {
+            return Err(format!("{:?} is already a generator", generator).into());
+        }
+        self.arities.insert(generator, (inputs, outputs));
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn arity(&self, generator: &Gen) -> Option<(usize, usize)> {
+        self.arities.get(generator).copied()
+    }
+
+    #[allow(dead_code)]
+    pub fn generator(&self, generator: &Gen) -> Result<FreePropMorphism<Gen>, Error> {
+        /*
+        the generator as a morphism of the free PROP on this signature
+        */
+        let (inputs, outputs) = self
+            .arity(generator)
+            .ok_or_else(|| format!("{:?} is not a generator", generator))?;
+        Ok(FreePropMorphism::from_generator(
+            generator.clone(),
+            inputs,
+            outputs,
+        ))
+    }
+}
+
+/*
+a morphism in the free PROP on a signature
+built from the generators, identities and swaps with composition and monoidal product
+    with the symmetries being boxes in their own right
+*/
+#[derive(Clone, PartialEq, Eq)]
+pub struct FreePropMorphism<Gen>(GenericMonoidalMorphism<PropOperation<Gen>, ()>);
+
+impl<Gen> FreePropMorphism<Gen>
+where
+    Gen: Clone + PartialEq,
+{
+    #[allow(dead_code)]
+    pub fn from_generator(generator: Gen, inputs: usize, outputs: usize) -> Self {
+        /*
+        a single generator with the given number of wires in and out
+        for when the arities are fixed by Gen itself rather than by a Signature
+        */
+        let only_layer = GenericMonoidalMorphismLayer::from_blocks(vec![PropOperation::Generator(
+            generator, inputs, outputs,
+        )]);
+        Self(
+            GenericMonoidalMorphism::try_from(vec![only_layer])
+                .expect("a single layer always lines up with itself"),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn underlying(&self) -> &GenericMonoidalMorphism<PropOperation<Gen>, ()> {
+        &self.0
+    }
+
+    #[allow(dead_code)]
+    pub fn normalize(&mut self) {
+        /*
+        the interchange normal form of the underlying diagram
+        so that two ways of writing the same morphism compare equal
+        the symmetries are boxes so this does not know that a swap twice is the identity
+        */
+        self.0.normalize();
+    }
+
+    #[allow(dead_code)]
+    pub fn interpret<P, F>(&self, generator_interpreter: &F) -> Result<P, Error>
+    where
+        P: Prop,
+        F: Fn(&Gen) -> Result<P, Error>,
+    {
+        /*
+        the unique map of PROPs to P that sends each generator where generator_interpreter says
+        which has to be to a morphism with the same number of inputs and outputs
+        */
+        let mut answer = P::identity(&self.domain());
+        for layer in self.0.layers() {
+            let mut cur_layer = P::identity(&0);
+            for block in &layer.blocks {
+                let interpreted = match block {
+                    PropOperation::Identity => P::identity(&1),
+                    PropOperation::Swap => P::symmetry(1, 1),
+                    PropOperation::Generator(generator, inputs, outputs) => {
+                        let interpreted = generator_interpreter(generator)?;
+                        if interpreted.domain() != *inputs {
+                            return Err(Error::InterfaceLengthMismatch {
+                                left: *inputs,
+                                right: interpreted.domain(),
+                            });
+                        }
+                        if interpreted.codomain() != *outputs {
+                            return Err(Error::InterfaceLengthMismatch {
+                                left: *outputs,
+                                right: interpreted.codomain(),
+                            });
+                        }
+                        interpreted
+                    }
+                };
+                cur_layer.monoidal(interpreted);
+            }
+            answer = answer.compose(&cur_layer)?;
+        }
+        Ok(answer)
+    }
+}
+
+impl<Gen> HasIdentity<usize> for FreePropMorphism<Gen> {
+    fn identity(on_this: &usize) -> Self {
+        Self(GenericMonoidalMorphism::identity(&vec![(); *on_this]))
+    }
+}
+
+impl<Gen: Clone> Composable<usize> for FreePropMorphism<Gen> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        let mut answer = self.0.clone();
+        answer.compose(other.0.clone())?;
+        Ok(Self(answer))
+    }
+
+    fn domain(&self) -> usize {
+        self.0.domain().len()
+    }
+
+    fn codomain(&self) -> usize {
+        self.0.codomain().len()
+    }
+
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        self.0.composable(&other.0)
+    }
+}
+
+impl<Gen: Clone> Monoidal for FreePropMorphism<Gen> {
+    fn monoidal(&mut self, other: Self) {
+        self.0.monoidal(other.0);
+    }
+}
+
+impl<Gen: Clone> SymmetricMonoidalDiscreteMorphism<usize> for FreePropMorphism<Gen> {
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        SymmetricMonoidalMutatingMorphism::permute_side(&mut self.0, p, of_codomain);
+    }
+
+    fn from_permutation(p: Permutation, type_: usize, types_as_on_domain: bool) -> Self {
+        Self(SymmetricMonoidalMutatingMorphism::from_permutation(
+            p,
+            &vec![(); type_],
+            types_as_on_domain,
+        ))
+    }
+}
+
+mod test {
+
+    #[test]
+    fn brauer_is_a_prop() {
+        use super::Prop;
+        use crate::category::{Composable, HasIdentity};
+        use crate::symmetric_monoidal::SymmetricMonoidalDiscreteMorphism;
+        use crate::unstable::temperley_lieb::BrauerMorphism;
+        use permutations::Permutation;
+        type B = BrauerMorphism<i32>;
+        for (m, n) in [(0, 2), (1, 1), (2, 1), (1, 3)] {
+            assert_eq!(B::symmetry(m, n), B::swap(m, n));
+        }
+        let p = Permutation::try_from(vec![2, 0, 1]).unwrap();
+        let q = Permutation::try_from(vec![1, 0, 2]).unwrap();
+        let p_then_q = B::from_permutation(p.clone(), 3, true)
+            .compose(&B::from_permutation(q.clone(), 3, true))
+            .unwrap();
+        assert_eq!(
+            p_then_q,
+            B::from_permutation(p.clone() * q.clone(), 3, true)
+        );
+        let mut relabelled = B::identity(&3);
+        relabelled.permute_side(&p, true);
+        assert_eq!(relabelled, B::from_permutation(p.inv(), 3, true));
+    }
+
+    #[test]
+    fn free_prop() {
+        use super::{FreePropMorphism, Prop, Signature};
+        use crate::category::{Composable, HasIdentity};
+        use crate::error::Error;
+        use crate::monoidal::Monoidal;
+        use crate::rigid_monoidal::CompactClosed;
+        use crate::unstable::temperley_lieb::BrauerMorphism;
+        type B = BrauerMorphism<i32>;
+        type Free = FreePropMorphism<&'static str>;
+        let mut signature = Signature::new();
+        signature.add_generator("cup", 0, 2).unwrap();
+        signature.add_generator("cap", 2, 0).unwrap();
+        assert!(signature.add_generator("cap", 2, 1).is_err());
+        assert!(signature.generator(&"loop").is_err());
+        let cup = signature.generator(&"cup").unwrap();
+        let cap = signature.generator(&"cap").unwrap();
+        let to_brauer = |g: &&str| -> Result<B, Error> {
+            match *g {
+                "cup" => Ok(B::unit(&1)),
+                "cap" => Ok(B::counit(&1)),
+                _ => Err(Error::from("not a generator")),
+            }
+        };
+
+        let mut cup_id = cup.clone();
+        cup_id.monoidal(Free::identity(&1));
+        let mut id_cap = Free::identity(&1);
+        id_cap.monoidal(cap.clone());
+        let snake = cup_id.compose(&id_cap).unwrap();
+        assert_eq!((snake.domain(), snake.codomain()), (1, 1));
+        assert_eq!(snake.interpret(&to_brauer).unwrap(), B::identity(&1));
+
+        let circle = cup.compose(&cap).unwrap();
+        assert_eq!(
+            circle.interpret(&to_brauer).unwrap(),
+            B::unit(&1).compose(&B::counit(&1)).unwrap()
+        );
+
+        let twice_swapped = Free::symmetry(1, 1).compose(&Free::symmetry(1, 1)).unwrap();
+        assert!(twice_swapped != Free::identity(&2));
+        assert_eq!(
+            twice_swapped.interpret(&to_brauer).unwrap(),
+            B::identity(&2)
+        );
+        let crossed_cup = cup.compose(&Free::symmetry(1, 1)).unwrap();
+        assert_eq!(crossed_cup.interpret(&to_brauer).unwrap(), B::unit(&1));
+
+        let wrong_arity = |_: &&str| -> Result<B, Error> { Ok(B::identity(&2)) };
+        assert_eq!(
+            cup.interpret(&wrong_arity).err(),
+            Some(Error::InterfaceLengthMismatch { left: 0, right: 2 })
+        );
+
+        let mut left = Free::identity(&1);
+        left.monoidal(cup.clone());
+        left = left.compose(&Free::symmetry(1, 2)).unwrap();
+        let mut right = cup.clone();
+        right.monoidal(Free::identity(&1));
+        left.normalize();
+        right.normalize();
+        assert!(left != right);
+        assert_eq!(
+            left.interpret(&to_brauer).unwrap(),
+            right.interpret(&to_brauer).unwrap()
+        );
+    }
+}
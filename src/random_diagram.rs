@@ -0,0 +1,271 @@
+use {
+    crate::{
+        cospan::Cospan,
+        named_cospan::NamedCospan,
+        wiring_diagram::{InOut, WiringDiagram},
+    },
+    rand::{seq::SliceRandom, Rng},
+    std::fmt::Debug,
+};
+
+/*
+how big the random diagrams may get and which labels they use
+each count is chosen uniformly from 0 up to and including the maximum
+the middle can still end up bigger than max_middle
+    when a boundary node needs a label that no middle node has yet
+pass a seeded rng such as rand::rngs::StdRng::seed_from_u64
+    to get the same diagrams every time
+*/
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+pub struct RandomDiagramSizes<Lambda> {
+    pub labels: Vec<Lambda>,
+    pub max_middle: usize,
+    pub max_boundary: usize,
+    pub max_circles: usize,
+}
+
+impl<Lambda: Eq + Copy + Debug> RandomDiagramSizes<Lambda> {
+    #[allow(dead_code)]
+    pub fn new(labels: Vec<Lambda>) -> Self {
+        /*
+        small enough to compose quickly but big enough to have
+            shared, merged and unreached middle nodes
+        */
+        Self {
+            labels,
+            max_middle: 4,
+            max_boundary: 4,
+            max_circles: 3,
+        }
+    }
+
+    fn random_labels<R: Rng>(&self, rng: &mut R, max_count: usize) -> Vec<Lambda> {
+        let count = rng.gen_range(0..=max_count);
+        (0..count)
+            .map(|_| {
+                *self
+                    .labels
+                    .choose(rng)
+                    .expect("there should be at least one label")
+            })
+            .collect()
+    }
+
+    fn random_leg<R: Rng>(
+        &self,
+        rng: &mut R,
+        types: &[Lambda],
+        middle: &mut Vec<Lambda>,
+    ) -> Vec<usize> {
+        /*
+        send each boundary node to a middle node with the same label
+        a new middle node if there is none yet
+            or sometimes anyway while there is room
+        */
+        types
+            .iter()
+            .map(|type_| {
+                let existing: Vec<usize> = (0..middle.len())
+                    .filter(|idx| middle[*idx] == *type_)
+                    .collect();
+                let room = middle.len() < self.max_middle;
+                match existing.choose(rng) {
+                    Some(idx) if !room || rng.gen_bool(0.7) => *idx,
+                    _ => {
+                        middle.push(*type_);
+                        middle.len() - 1
+                    }
+                }
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn random_cospan_from<R: Rng>(&self, rng: &mut R, domain: &[Lambda]) -> Cospan<Lambda> {
+        /*
+        a random cospan with the given domain
+        so that it can be composed after something with that codomain
+        */
+        let mut middle = self.random_labels(rng, self.max_middle);
+        let codomain = self.random_labels(rng, self.max_boundary);
+        let left = self.random_leg(rng, domain, &mut middle);
+        let right = self.random_leg(rng, &codomain, &mut middle);
+        Cospan::new(left, right, middle)
+    }
+
+    #[allow(dead_code)]
+    pub fn random_cospan<R: Rng>(&self, rng: &mut R) -> Cospan<Lambda> {
+        let domain = self.random_labels(rng, self.max_boundary);
+        self.random_cospan_from(rng, &domain)
+    }
+
+    #[allow(dead_code)]
+    pub fn random_named_cospan<R: Rng>(&self, rng: &mut R) -> NamedCospan<Lambda, usize, usize> {
+        /*
+        a random cospan with the nodes on each side named by their position
+        */
+        let cospan = self.random_cospan(rng);
+        NamedCospan::new(
+            cospan.left_to_middle().to_vec(),
+            cospan.right_to_middle().to_vec(),
+            cospan.middle().to_vec(),
+            (0..cospan.left_to_middle().len()).collect(),
+            (0..cospan.right_to_middle().len()).collect(),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn random_wiring_diagram_from<R: Rng>(
+        &self,
+        rng: &mut R,
+        outer_ports: &[(InOut, usize, Lambda)],
+        first_circle: usize,
+    ) -> WiringDiagram<Lambda, usize, usize> {
+        /*
+        a random wiring diagram with the given nodes on the external circle
+        the internal circles are first_circle, first_circle+1 and so on
+            so that diagrams to be substituted into each other can use different names
+        the nodes of each circle are named by their position on it
+            and are In or Out at random
+        */
+        let circle_count = rng.gen_range(0..=self.max_circles);
+        let mut inner_names = Vec::new();
+        let mut inner_types = Vec::new();
+        for circle in first_circle..first_circle + circle_count {
+            for (intra, type_) in self
+                .random_labels(rng, self.max_boundary)
+                .into_iter()
+                .enumerate()
+            {
+                let io = if rng.gen_bool(0.5) {
+                    InOut::In
+                } else {
+                    InOut::Out
+                };
+                inner_names.push((io, circle, intra));
+                inner_types.push(type_);
+            }
+        }
+        let outer_types: Vec<Lambda> = outer_ports.iter().map(|(_, _, type_)| *type_).collect();
+        let mut middle = self.random_labels(rng, self.max_middle);
+        let left = self.random_leg(rng, &inner_types, &mut middle);
+        let right = self.random_leg(rng, &outer_types, &mut middle);
+        WiringDiagram::from_named_cospan(NamedCospan::new(
+            left,
+            right,
+            middle,
+            inner_names,
+            outer_ports
+                .iter()
+                .map(|(io, intra, _)| (*io, *intra))
+                .collect(),
+        ))
+    }
+
+    #[allow(dead_code)]
+    pub fn random_wiring_diagram<R: Rng>(
+        &self,
+        rng: &mut R,
+        first_circle: usize,
+    ) -> WiringDiagram<Lambda, usize, usize> {
+        let outer_ports: Vec<_> = self
+            .random_labels(rng, self.max_boundary)
+            .into_iter()
+            .enumerate()
+            .map(|(intra, type_)| (InOut::In, intra, type_))
+            .collect();
+        self.random_wiring_diagram_from(rng, &outer_ports, first_circle)
+    }
+
+    #[allow(dead_code)]
+    pub fn random_filling<R: Rng>(
+        &self,
+        rng: &mut R,
+        diagram: &WiringDiagram<Lambda, usize, usize>,
+        which_circle: usize,
+        first_circle: usize,
+    ) -> WiringDiagram<Lambda, usize, usize> {
+        /*
+        a random wiring diagram whose external circle matches the internal circle
+            which_circle of diagram so it can be substituted there
+        */
+        let cospan = diagram.named_cospan().cospan();
+        let outer_ports: Vec<_> = diagram
+            .inner_ports()
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, circle, _))| *circle == which_circle)
+            .map(|(idx, (io, _, intra))| {
+                (*io, *intra, cospan.middle()[cospan.left_to_middle()[idx]])
+            })
+            .collect();
+        self.random_wiring_diagram_from(rng, &outer_ports, first_circle)
+    }
+}
+
+mod test {
+
+    #[test]
+    fn cospan_laws() {
+        use super::RandomDiagramSizes;
+        use crate::category::{Composable, HasIdentity};
+        use crate::cospan::Cospan;
+        use rand::{rngs::StdRng, SeedableRng};
+        let sizes = RandomDiagramSizes::new(vec!['a', 'b']);
+        for seed in 0..50 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let f = sizes.random_cospan(&mut rng);
+            let g = sizes.random_cospan_from(&mut rng, &f.codomain());
+            let h = sizes.random_cospan_from(&mut rng, &g.codomain());
+            f.assert_valid(false);
+            let left_first = f.compose(&g).unwrap().compose(&h).unwrap();
+            let right_first = f.compose(&g.compose(&h).unwrap()).unwrap();
+            assert!(left_first.is_isomorphic_to(&right_first));
+            let with_identities = Cospan::identity(&f.domain())
+                .compose(&f)
+                .unwrap()
+                .compose(&Cospan::identity(&f.codomain()))
+                .unwrap();
+            assert!(with_identities.is_isomorphic_to(&f));
+        }
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut again = StdRng::seed_from_u64(7);
+        assert!(sizes
+            .random_named_cospan(&mut rng)
+            .is_isomorphic_to(&sizes.random_named_cospan(&mut again)));
+    }
+
+    #[test]
+    fn substitution_associative() {
+        use super::RandomDiagramSizes;
+        use rand::{rngs::StdRng, SeedableRng};
+        let sizes = RandomDiagramSizes::new(vec![1, 2]);
+        let mut tried = 0;
+        for seed in 0..100 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let outer = sizes.random_wiring_diagram(&mut rng, 0);
+            let Some(a) = outer.circles().first().copied() else {
+                continue;
+            };
+            let middle = sizes.random_filling(&mut rng, &outer, a, 100);
+            let Some(c) = middle.circles().first().copied() else {
+                continue;
+            };
+            let inner = sizes.random_filling(&mut rng, &middle, c, 200);
+            /*
+            fill a then fill c inside it against fill c first then fill a
+            */
+            let mut outer_then = outer.clone();
+            outer_then.operadic_substitution(a, middle.clone()).unwrap();
+            outer_then.operadic_substitution(c, inner.clone()).unwrap();
+            let mut middle_first = middle;
+            middle_first.operadic_substitution(c, inner).unwrap();
+            let mut inner_then = outer;
+            inner_then.operadic_substitution(a, middle_first).unwrap();
+            assert!(outer_then.is_isomorphic_to(&inner_then));
+            tried += 1;
+        }
+        assert!(tried > 10);
+    }
+}
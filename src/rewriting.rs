@@ -0,0 +1,639 @@
+use {
+    crate::{
+        category::{ComposableMutating, HasIdentity},
+        error::Error,
+        monoidal::{GenericMonoidalMorphism, HasWireTypes},
+        port_graph::{PortGraph, PortSource},
+        symmetric_monoidal::HasSymmetricBraiding,
+        utils::same_labels_check,
+    },
+    either::Either::{self, Left, Right},
+    std::{collections::HashMap, fmt::Debug},
+};
+
+/*
+where a wire ends
+    Left((node, port)) is the port'th input of that node
+    Right(i) is the i'th wire of the codomain
+*/
+type PortTarget = Either<(usize, usize), usize>;
+
+/*
+a directed rule saying the pattern may be replaced by the replacement
+both are kept as PortGraphs so that a rule applies wherever its pattern appears
+    up to the interchange law and naturality of the braiding
+*/
+#[derive(Clone)]
+pub struct RewriteRule<BoxType, Lambda> {
+    pattern: PortGraph<BoxType, Lambda>,
+    replacement: PortGraph<BoxType, Lambda>,
+}
+
+impl<BoxType, Lambda> RewriteRule<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone
+        + PartialEq
+        + HasIdentity<Lambda>
+        + HasSymmetricBraiding<Lambda>
+        + HasWireTypes<Lambda>,
+{
+    #[allow(dead_code)]
+    pub fn new(
+        pattern: &GenericMonoidalMorphism<BoxType, Lambda>,
+        replacement: &GenericMonoidalMorphism<BoxType, Lambda>,
+    ) -> Result<Self, Error> {
+        /*
+        the two sides need the same domain and codomain
+        the pattern needs a box in it and every one of its wires has to touch a box
+            otherwise it would match a bare wire anywhere
+        */
+        same_labels_check(pattern.domain().iter(), replacement.domain().iter())?;
+        same_labels_check(pattern.codomain().iter(), replacement.codomain().iter())?;
+        let pattern = PortGraph::from_generic(pattern);
+        if pattern.nodes().is_empty() {
+            return Err(Error::from("The pattern of a rule needs at least one box"));
+        }
+        let mut fed = vec![false; pattern.domain().len()];
+        for node in 0..pattern.nodes().len() {
+            for source in pattern.node_inputs(node) {
+                if let Left(wire) = source {
+                    fed[*wire] = true;
+                }
+            }
+        }
+        if fed.contains(&false) || pattern.codomain_sources().iter().any(Either::is_left) {
+            return Err(Error::from(
+                "The pattern of a rule can not have wires going straight through",
+            ));
+        }
+        Ok(Self {
+            pattern,
+            replacement: PortGraph::from_generic(replacement),
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn pattern(&self) -> &PortGraph<BoxType, Lambda> {
+        &self.pattern
+    }
+
+    #[allow(dead_code)]
+    pub fn replacement(&self) -> &PortGraph<BoxType, Lambda> {
+        &self.replacement
+    }
+}
+
+/*
+where the pattern of a rule was found
+    the node of the target each pattern node went to
+    and the wire of the target each pattern domain wire went to
+*/
+struct Match {
+    nodes: Vec<usize>,
+    domain_wires: Vec<PortSource>,
+}
+
+fn consumers<BoxType, Lambda>(graph: &PortGraph<BoxType, Lambda>) -> HashMap<PortSource, PortTarget>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone
+        + PartialEq
+        + HasIdentity<Lambda>
+        + HasSymmetricBraiding<Lambda>
+        + HasWireTypes<Lambda>,
+{
+    let mut answer = HashMap::new();
+    for node in 0..graph.nodes().len() {
+        for (port, source) in graph.node_inputs(node).iter().enumerate() {
+            answer.insert(*source, Left((node, port)));
+        }
+    }
+    for (wire, source) in graph.codomain_sources().iter().enumerate() {
+        answer.insert(*source, Right(wire));
+    }
+    answer
+}
+
+fn find_matches<BoxType, Lambda>(
+    pattern: &PortGraph<BoxType, Lambda>,
+    target: &PortGraph<BoxType, Lambda>,
+) -> Vec<Match>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone
+        + PartialEq
+        + HasIdentity<Lambda>
+        + HasSymmetricBraiding<Lambda>
+        + HasWireTypes<Lambda>,
+{
+    /*
+    the pattern nodes are in order of depth
+        so whatever feeds a pattern node has already been matched when we get to it
+    a node with an input from another pattern node can only go to one place
+        so only those without one need to try every node of the target
+    */
+    struct Search<'a, BoxType, Lambda> {
+        pattern: &'a PortGraph<BoxType, Lambda>,
+        target: &'a PortGraph<BoxType, Lambda>,
+        consumer: HashMap<PortSource, PortTarget>,
+        nodes: Vec<usize>,
+        domain_wires: Vec<Option<PortSource>>,
+        found: Vec<Match>,
+    }
+
+    impl<BoxType, Lambda> Search<'_, BoxType, Lambda>
+    where
+        Lambda: Eq + Copy + Debug,
+        BoxType: Clone
+            + PartialEq
+            + HasIdentity<Lambda>
+            + HasSymmetricBraiding<Lambda>
+            + HasWireTypes<Lambda>,
+    {
+        fn extend(&mut self) {
+            let next = self.nodes.len();
+            if next == self.pattern.nodes().len() {
+                if let Some(found) = self.finish() {
+                    self.found.push(found);
+                }
+                return;
+            }
+            let pattern_inputs = self.pattern.node_inputs(next);
+            let candidates: Vec<usize> = match pattern_inputs.iter().find_map(|s| s.right()) {
+                Some((earlier, port)) => {
+                    match self.consumer.get(&Right((self.nodes[earlier], port))) {
+                        Some(Left((node, _))) => vec![*node],
+                        _ => vec![],
+                    }
+                }
+                None => (0..self.target.nodes().len()).collect(),
+            };
+            for candidate in candidates {
+                if self.nodes.contains(&candidate)
+                    || self.target.nodes()[candidate] != self.pattern.nodes()[next]
+                {
+                    continue;
+                }
+                let saved = self.domain_wires.clone();
+                let mut fits = true;
+                for (pattern_source, target_source) in pattern_inputs
+                    .iter()
+                    .zip(self.target.node_inputs(candidate))
+                {
+                    fits = match pattern_source {
+                        Right((earlier, port)) => {
+                            *target_source == Right((self.nodes[*earlier], *port))
+                        }
+                        Left(wire) => match self.domain_wires[*wire] {
+                            Some(already) => already == *target_source,
+                            None if self.domain_wires.contains(&Some(*target_source)) => false,
+                            None => {
+                                self.domain_wires[*wire] = Some(*target_source);
+                                true
+                            }
+                        },
+                    };
+                    if !fits {
+                        break;
+                    }
+                }
+                if fits {
+                    self.nodes.push(candidate);
+                    self.extend();
+                    self.nodes.pop();
+                }
+                self.domain_wires = saved;
+            }
+        }
+
+        fn finish(&self) -> Option<Match> {
+            /*
+            the wires coming into the match must come from outside it
+            the wires the pattern gives out must leave it
+            and nothing leaving it can come back in
+                otherwise replacing it would make a cycle
+            */
+            let inside = |source: &PortSource| {
+                source
+                    .right()
+                    .is_some_and(|(node, _)| self.nodes.contains(&node))
+            };
+            let domain_wires: Vec<PortSource> =
+                self.domain_wires.iter().copied().collect::<Option<_>>()?;
+            if domain_wires.iter().any(inside) {
+                return None;
+            }
+            let mut outside = vec![];
+            for source in self.pattern.codomain_sources() {
+                let (node, port) = source.right()?;
+                match self.consumer.get(&Right((self.nodes[node], port)))? {
+                    Left((consumer, _)) if self.nodes.contains(consumer) => return None,
+                    Left((consumer, _)) => outside.push(*consumer),
+                    Right(_) => {}
+                }
+            }
+            let mut visited = vec![false; self.target.nodes().len()];
+            while let Some(node) = outside.pop() {
+                if self.nodes.contains(&node) {
+                    return None;
+                }
+                if visited[node] {
+                    continue;
+                }
+                visited[node] = true;
+                for port in 0..self.target.nodes()[node].target_types().len() {
+                    if let Some(Left((consumer, _))) = self.consumer.get(&Right((node, port))) {
+                        outside.push(*consumer);
+                    }
+                }
+            }
+            Some(Match {
+                nodes: self.nodes.clone(),
+                domain_wires,
+            })
+        }
+    }
+
+    let mut search = Search {
+        pattern,
+        target,
+        consumer: consumers(target),
+        nodes: Vec::with_capacity(pattern.nodes().len()),
+        domain_wires: vec![None; pattern.domain().len()],
+        found: vec![],
+    };
+    search.extend();
+    search.found
+}
+
+fn replace<BoxType, Lambda>(
+    target: &PortGraph<BoxType, Lambda>,
+    rule: &RewriteRule<BoxType, Lambda>,
+    found: &Match,
+) -> Result<GenericMonoidalMorphism<BoxType, Lambda>, Error>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone
+        + PartialEq
+        + HasIdentity<Lambda>
+        + HasSymmetricBraiding<Lambda>
+        + HasWireTypes<Lambda>,
+{
+    /*
+    the nodes of the target that were not matched keep their place in line
+        and the nodes of the replacement go after them
+    a wire that came out of the match now comes out of the replacement
+        at the same place in its codomain
+    */
+    let mut new_index = vec![None; target.nodes().len()];
+    let kept: Vec<usize> = (0..target.nodes().len())
+        .filter(|node| !found.nodes.contains(node))
+        .collect();
+    for (index, node) in kept.iter().enumerate() {
+        new_index[*node] = Some(index);
+    }
+    let offset = kept.len();
+    let mut leaving = HashMap::new();
+    for (wire, source) in rule.pattern.codomain_sources().iter().enumerate() {
+        if let Right((node, port)) = source {
+            leaving.insert((found.nodes[*node], *port), wire);
+        }
+    }
+    let renumber = |source: &PortSource| {
+        source.map_right(|(node, port)| (new_index[node].expect("not in the match"), port))
+    };
+    let from_replacement = |source: &PortSource| match source {
+        Left(wire) => renumber(&found.domain_wires[*wire]),
+        Right((node, port)) => Right((offset + node, *port)),
+    };
+    let from_target = |source: &PortSource| match source {
+        Right((node, port)) if new_index[*node].is_none() => {
+            from_replacement(&rule.replacement.codomain_sources()[leaving[&(*node, *port)]])
+        }
+        _ => renumber(source),
+    };
+
+    let mut nodes = Vec::with_capacity(offset + rule.replacement.nodes().len());
+    let mut node_inputs = Vec::with_capacity(nodes.capacity());
+    for node in kept {
+        nodes.push(target.nodes()[node].clone());
+        node_inputs.push(target.node_inputs(node).iter().map(from_target).collect());
+    }
+    for node in 0..rule.replacement.nodes().len() {
+        nodes.push(rule.replacement.nodes()[node].clone());
+        node_inputs.push(
+            rule.replacement
+                .node_inputs(node)
+                .iter()
+                .map(from_replacement)
+                .collect(),
+        );
+    }
+    let codomain_sources = target.codomain_sources().iter().map(from_target).collect();
+    PortGraph::from_parts(
+        target.domain(),
+        target.codomain(),
+        nodes,
+        node_inputs,
+        codomain_sources,
+    )?
+    .to_generic()
+}
+
+/*
+a collection of rules to apply to morphisms of a symmetric monoidal category
+    as a calculator for string diagrams
+rules are tried in the order they were added
+*/
+#[derive(Clone)]
+pub struct MonoidalRewritingSystem<BoxType, Lambda> {
+    rules: Vec<RewriteRule<BoxType, Lambda>>,
+}
+
+impl<BoxType, Lambda> Default for MonoidalRewritingSystem<BoxType, Lambda> {
+    fn default() -> Self {
+        Self { rules: vec![] }
+    }
+}
+
+impl<BoxType, Lambda> MonoidalRewritingSystem<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone
+        + PartialEq
+        + HasIdentity<Lambda>
+        + HasSymmetricBraiding<Lambda>
+        + HasWireTypes<Lambda>,
+{
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn add_rule(
+        &mut self,
+        pattern: &GenericMonoidalMorphism<BoxType, Lambda>,
+        replacement: &GenericMonoidalMorphism<BoxType, Lambda>,
+    ) -> Result<(), Error> {
+        self.rules.push(RewriteRule::new(pattern, replacement)?);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn rules(&self) -> &[RewriteRule<BoxType, Lambda>] {
+        &self.rules
+    }
+
+    #[allow(dead_code)]
+    pub fn rewrite_step(
+        &self,
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+    ) -> Result<Option<GenericMonoidalMorphism<BoxType, Lambda>>, Error> {
+        /*
+        apply the first rule that matches anywhere
+        None if no rule does
+        */
+        let target = PortGraph::from_generic(morphism);
+        for rule in &self.rules {
+            if let Some(found) = find_matches(&rule.pattern, &target).first() {
+                return replace(&target, rule, found).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    #[allow(dead_code)]
+    pub fn one_step_rewrites(
+        &self,
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+    ) -> Result<Vec<GenericMonoidalMorphism<BoxType, Lambda>>, Error> {
+        /*
+        every way of applying one rule once
+        */
+        let target = PortGraph::from_generic(morphism);
+        let mut answer = vec![];
+        for rule in &self.rules {
+            for found in find_matches(&rule.pattern, &target) {
+                answer.push(replace(&target, rule, &found)?);
+            }
+        }
+        Ok(answer)
+    }
+
+    #[allow(dead_code)]
+    pub fn rewrite(
+        &self,
+        morphism: &GenericMonoidalMorphism<BoxType, Lambda>,
+        max_steps: usize,
+    ) -> Result<(GenericMonoidalMorphism<BoxType, Lambda>, bool), Error> {
+        /*
+        keep rewriting for at most max_steps steps
+        along with whether what came out has no more rules that apply
+            which is false if we stopped because of the limit
+        */
+        let mut current = morphism.clone();
+        for _ in 0..max_steps {
+            match self.rewrite_step(&current)? {
+                Some(next) => current = next,
+                None => return Ok((current, true)),
+            }
+        }
+        let finished = self.rewrite_step(&current)?.is_none();
+        Ok((current, finished))
+    }
+
+    #[allow(dead_code)]
+    #[allow(clippy::type_complexity)]
+    pub fn confluence_counterexample(
+        &self,
+        samples: &[GenericMonoidalMorphism<BoxType, Lambda>],
+        max_steps: usize,
+    ) -> Result<
+        Option<(
+            GenericMonoidalMorphism<BoxType, Lambda>,
+            GenericMonoidalMorphism<BoxType, Lambda>,
+            GenericMonoidalMorphism<BoxType, Lambda>,
+        )>,
+        Error,
+    > {
+        /*
+        for each of the samples and the patterns of the rules
+            rewrite it one step in every possible way
+            and rewrite each of those as far as max_steps allows
+        if two of them end up different then that sample along with them
+            shows the rules are not confluent
+        the patterns of the rules are where overlapping rules most often disagree
+            but this only checks the samples it is given and them
+        */
+        let mut to_check = samples.to_vec();
+        for rule in &self.rules {
+            to_check.push(rule.pattern.to_generic()?);
+        }
+        for sample in to_check {
+            let mut first_result: Option<(GenericMonoidalMorphism<BoxType, Lambda>, _)> = None;
+            for rewritten in self.one_step_rewrites(&sample)? {
+                let (result, _) = self.rewrite(&rewritten, max_steps)?;
+                let result_graph = PortGraph::from_generic(&result);
+                match &first_result {
+                    None => first_result = Some((result, result_graph)),
+                    Some((first, first_graph)) if *first_graph != result_graph => {
+                        return Ok(Some((sample, first.clone(), result)));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+mod test {
+
+    #[test]
+    fn unit_laws_modulo_interchange() {
+        use super::MonoidalRewritingSystem;
+        use crate::category::{ComposableMutating, HasIdentity};
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        use crate::monoidal::{GenericMonoidalMorphism, Monoidal};
+        use crate::port_graph::PortGraph;
+        type Op = FrobeniusOperation<char, u8>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let unit = Op::Unit('a');
+        let mult = Op::Multiplication('a');
+        let f = Op::UnSpecifiedBox(0, vec!['a'], vec!['a']);
+        let mut system = MonoidalRewritingSystem::new();
+        system
+            .add_rule(
+                &monoidal!([unit.clone(), id('a')]; [mult.clone()]).unwrap(),
+                &Generic::identity(&vec!['a']),
+            )
+            .unwrap();
+        system
+            .add_rule(
+                &monoidal!([id('a'), unit.clone()]; [mult.clone()]).unwrap(),
+                &Generic::identity(&vec!['a']),
+            )
+            .unwrap();
+        assert!(system
+            .add_rule(
+                &Generic::identity(&vec!['a']),
+                &Generic::identity(&vec!['a'])
+            )
+            .is_err());
+        assert!(system
+            .add_rule(
+                &monoidal!([f.clone()]).unwrap(),
+                &Generic::identity(&vec!['b'])
+            )
+            .is_err());
+
+        /*
+        the unit is put down a layer before the f it ends up next to
+            and the two multiplications are far apart
+        */
+        let morphism = monoidal!(
+            [id('a'), unit.clone()];
+            [f.clone(), id('a')];
+            [mult.clone()];
+            [unit.clone(), id('a')];
+            [mult.clone()];
+            [f.clone()]
+        )
+        .unwrap();
+        let (result, finished) = system.rewrite(&morphism, 10).unwrap();
+        assert!(finished);
+        let mut expected = monoidal!([f.clone()]; [f.clone()]).unwrap();
+        assert!(PortGraph::from_generic(&result) == PortGraph::from_generic(&expected));
+        assert_eq!(system.one_step_rewrites(&morphism).unwrap().len(), 2);
+
+        let (stopped, finished) = system.rewrite(&morphism, 1).unwrap();
+        assert!(!finished);
+        assert_eq!(system.one_step_rewrites(&stopped).unwrap().len(), 1);
+
+        /*
+        the rule matches beside other wires too
+        */
+        let mut wider = morphism.clone();
+        wider.monoidal(Generic::identity(&vec!['b']));
+        expected.monoidal(Generic::identity(&vec!['b']));
+        let (result, _) = system.rewrite(&wider, 10).unwrap();
+        assert!(PortGraph::from_generic(&result) == PortGraph::from_generic(&expected));
+        assert_eq!(result.domain(), vec!['a', 'b']);
+    }
+
+    #[test]
+    fn associativity_and_confluence() {
+        use super::MonoidalRewritingSystem;
+        use crate::category::ComposableMutating;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        use crate::monoidal::GenericMonoidalMorphism;
+        use crate::port_graph::PortGraph;
+        type Op = FrobeniusOperation<char, u8>;
+        type Generic = GenericMonoidalMorphism<Op, char>;
+        let mult = Op::Multiplication('a');
+        let mut system = MonoidalRewritingSystem::new();
+        system
+            .add_rule(
+                &monoidal!([mult.clone(), id('a')]; [mult.clone()]).unwrap(),
+                &monoidal!([id('a'), mult.clone()]; [mult.clone()]).unwrap(),
+            )
+            .unwrap();
+
+        /*
+        all the ways to multiply four things go to the one bracketed to the right
+        */
+        let bracketings = Generic::enumerate_hom(std::slice::from_ref(&mult), &['a'; 4], &['a'], 3);
+        assert_eq!(bracketings.len(), 5);
+        let right_bracketed =
+            monoidal!([id('a'), id('a'), mult.clone()]; [id('a'), mult.clone()]; [mult.clone()])
+                .unwrap();
+        for bracketing in &bracketings {
+            let (result, finished) = system.rewrite(bracketing, 10).unwrap();
+            assert!(finished);
+            assert!(PortGraph::from_generic(&result) == PortGraph::from_generic(&right_bracketed));
+        }
+        assert!(system
+            .confluence_counterexample(&bracketings, 10)
+            .unwrap()
+            .is_none());
+
+        /*
+        a second rule for the same pattern that does not agree with the first
+        */
+        let flipped = Op::UnSpecifiedBox(0, vec!['a', 'a', 'a'], vec!['a']);
+        system
+            .add_rule(
+                &monoidal!([mult.clone(), id('a')]; [mult.clone()]).unwrap(),
+                &monoidal!([flipped.clone()]).unwrap(),
+            )
+            .unwrap();
+        let (sample, one_way, other_way) = system
+            .confluence_counterexample(&[], 10)
+            .unwrap()
+            .expect("the two rules disagree");
+        assert_eq!(sample.domain(), vec!['a'; 3]);
+        assert!(PortGraph::from_generic(&one_way) != PortGraph::from_generic(&other_way));
+    }
+
+    #[test]
+    fn step_limit() {
+        use super::MonoidalRewritingSystem;
+        use crate::monoidal;
+        type Op = crate::frobenius::FrobeniusOperation<char, u8>;
+        let f = Op::UnSpecifiedBox(0, vec!['a'], vec!['a']);
+        let mut system = MonoidalRewritingSystem::new();
+        system
+            .add_rule(
+                &monoidal!([f.clone()]).unwrap(),
+                &monoidal!([f.clone()]; [f.clone()]).unwrap(),
+            )
+            .unwrap();
+        let (result, finished) = system.rewrite(&monoidal!([f.clone()]).unwrap(), 4).unwrap();
+        assert!(!finished);
+        assert_eq!(result.depth(), 5);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    category::HasIdentity,
+    error::Error,
+    monoidal::{tensor_pair, Braided, MonoidalMorphism},
+};
+
+#[allow(dead_code)]
+pub trait RigidMonoidalMorphism<T: Eq>: MonoidalMorphism<T> + HasIdentity<T> + Sized {
+    /*
+    every object a has a left dual a^* and a right dual ^*a
+    left evaluation a^* \otimes a -> I and left coevaluation I -> a \otimes a^*
+    right evaluation a \otimes ^*a -> I and right coevaluation I -> ^*a \otimes a
+    the two duals do not have to be the same object
+    as in a pivotal but not symmetric setting like framed tangles
+    the monoidal unit is assumed strict so I \otimes a and a are the same object
+    */
+    fn left_dual(object: &T) -> T;
+    fn right_dual(object: &T) -> T;
+    fn left_evaluation(object: &T) -> Self;
+    fn left_coevaluation(object: &T) -> Self;
+    fn right_evaluation(object: &T) -> Self;
+    fn right_coevaluation(object: &T) -> Self;
+
+    fn left_zig_zags(object: &T) -> Result<(), String>
+    where
+        Self: PartialEq,
+    {
+        /*
+        (coev \otimes id_a) ; (id_a \otimes ev) = id_a
+        (id_{a^*} \otimes coev) ; (ev \otimes id_{a^*}) = id_{a^*}
+        */
+        let dual = Self::left_dual(object);
+        let snake = tensor_pair(Self::left_coevaluation(object), Self::identity(object)).compose(
+            &tensor_pair(Self::identity(object), Self::left_evaluation(object)),
+        )?;
+        if snake != Self::identity(object) {
+            return Err(
+                "The zig-zag on the object for its left dual was not the identity".to_string(),
+            );
+        }
+        let dual_snake =
+            tensor_pair(Self::identity(&dual), Self::left_coevaluation(object)).compose(
+                &tensor_pair(Self::left_evaluation(object), Self::identity(&dual)),
+            )?;
+        if dual_snake != Self::identity(&dual) {
+            return Err("The zig-zag on the left dual was not the identity".to_string());
+        }
+        Ok(())
+    }
+
+    fn right_zig_zags(object: &T) -> Result<(), String>
+    where
+        Self: PartialEq,
+    {
+        /*
+        (id_a \otimes coev) ; (ev \otimes id_a) = id_a
+        (coev \otimes id_{^*a}) ; (id_{^*a} \otimes ev) = id_{^*a}
+        */
+        let dual = Self::right_dual(object);
+        let snake = tensor_pair(Self::identity(object), Self::right_coevaluation(object)).compose(
+            &tensor_pair(Self::right_evaluation(object), Self::identity(object)),
+        )?;
+        if snake != Self::identity(object) {
+            return Err(
+                "The zig-zag on the object for its right dual was not the identity".to_string(),
+            );
+        }
+        let dual_snake =
+            tensor_pair(Self::right_coevaluation(object), Self::identity(&dual)).compose(
+                &tensor_pair(Self::identity(&dual), Self::right_evaluation(object)),
+            )?;
+        if dual_snake != Self::identity(&dual) {
+            return Err("The zig-zag on the right dual was not the identity".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+pub trait RibbonMonoidalMorphism<T: Eq>: RigidMonoidalMorphism<T> + Braided<T> {
+    /*
+    on top of the braiding c_{a,b} : a \otimes b -> b \otimes a
+    a twist theta_a : a -> a which is the bookkeeping for framing
+    in a symmetric setting like Brauer the twist can be the identity
+    but for framed tangles it is the full rotation of the ribbon
+    */
+    fn twist(object: &T) -> Self;
+
+    fn ribbon_relations(a: &T, b: &T) -> Result<(), String>
+    where
+        Self: PartialEq,
+    {
+        /*
+        theta_{a \otimes b} = c_{a,b} ; c_{b,a} ; (theta_a \otimes theta_b)
+        theta_I = id_I
+        theta_{a^*} is the mate of theta_a using the left duality
+            (id_{a^*} \otimes coev) ; (id_{a^*} \otimes theta_a \otimes id_{a^*}) ; (ev \otimes id_{a^*})
+        the tensor product of the objects and the unit are read off
+        from the domain of the braiding and the codomain of the evaluation
+        */
+        let a_b = Self::braiding(a, b).domain();
+        let full_twist = Self::braiding(a, b)
+            .compose(&Self::braiding(b, a))?
+            .compose(&tensor_pair(Self::twist(a), Self::twist(b)))?;
+        if Self::twist(&a_b) != full_twist {
+            return Err("The twist on a tensor product was not the full twist".to_string());
+        }
+        let unit = Self::left_evaluation(a).codomain();
+        if Self::twist(&unit) != Self::identity(&unit) {
+            return Err("The twist on the unit was not the identity".to_string());
+        }
+        for object in [a, b] {
+            let dual = Self::left_dual(object);
+            let mate = tensor_pair(Self::identity(&dual), Self::left_coevaluation(object))
+                .compose(&tensor_pair(
+                    tensor_pair(Self::identity(&dual), Self::twist(object)),
+                    Self::identity(&dual),
+                ))?
+                .compose(&tensor_pair(
+                    Self::left_evaluation(object),
+                    Self::identity(&dual),
+                ))?;
+            if Self::twist(&dual) != mate {
+                return Err("The twist on a dual was not the mate of the twist".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+pub trait CompactClosed<T: Eq>: Braided<T> + Clone {
+    /*
+    a symmetric category where every object a has a single dual a^*
+    with unit (cup) eta_a : I -> a \otimes a^* and counit (cap) epsilon_a : a^* \otimes a -> I
+    the same shapes as the left coevaluation and evaluation of a rigid category
+    the monoidal unit is assumed strict so I \otimes a and a are the same object
+    */
+    fn dual(object: &T) -> T;
+    fn unit(object: &T) -> Self;
+    fn counit(object: &T) -> Self;
+
+    fn transpose(&self) -> Result<Self, Error> {
+        /*
+        for f : a -> b the transpose f^* : b^* -> a^* is
+            (id_{b^*} \otimes eta_a) ; (id_{b^*} \otimes f \otimes id_{a^*}) ; (epsilon_b \otimes id_{a^*})
+        */
+        let a = self.domain();
+        let b = self.codomain();
+        let a_dual = Self::dual(&a);
+        let b_dual = Self::dual(&b);
+        tensor_pair(Self::identity(&b_dual), Self::unit(&a))
+            .compose(&tensor_pair(
+                tensor_pair(Self::identity(&b_dual), self.clone()),
+                Self::identity(&a_dual),
+            ))?
+            .compose(&tensor_pair(Self::counit(&b), Self::identity(&a_dual)))
+    }
+
+    fn name(&self) -> Result<Self, Error> {
+        /*
+        for f : a -> b the name I -> a^* \otimes b is
+            eta_a ; beta_{a,a^*} ; (id_{a^*} \otimes f)
+        */
+        let a = self.domain();
+        let a_dual = Self::dual(&a);
+        Self::unit(&a)
+            .compose(&Self::braiding(&a, &a_dual))?
+            .compose(&tensor_pair(Self::identity(&a_dual), self.clone()))
+    }
+
+    fn coname(&self) -> Result<Self, Error> {
+        /*
+        for f : a -> b the coname a \otimes b^* -> I is
+            (f \otimes id_{b^*}) ; beta_{b,b^*} ; epsilon_b
+        */
+        let b = self.codomain();
+        let b_dual = Self::dual(&b);
+        tensor_pair(self.clone(), Self::identity(&b_dual))
+            .compose(&Self::braiding(&b, &b_dual))?
+            .compose(&Self::counit(&b))
+    }
+}
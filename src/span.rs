@@ -1,9 +1,10 @@
 use {
     crate::{
-        category::{Composable, HasIdentity},
-        monoidal::{GenericMonoidalInterpretable, Monoidal, MonoidalMorphism},
+        category::{Composable, Dagger, HasIdentity},
+        error::Error,
+        monoidal::{CartesianMonoidal, Monoidal},
         symmetric_monoidal::SymmetricMonoidalMorphism,
-        utils::{in_place_permute, represents_id},
+        utils::{compact_list, in_place_permute, represents_id},
     },
     either::Either::{self, Left, Right},
     std::{collections::HashSet, fmt::Debug},
@@ -178,6 +179,27 @@ where
     }
 }
 
+impl<Lambda> Span<Lambda>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+{
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like Span(2→1): ['a','b']←[(0,0)]→['a']
+        the labels of the domain, the pairs in the middle and the labels of the codomain
+        */
+        format!(
+            "Span({}→{}): [{}]←[{}]→[{}]",
+            self.left.len(),
+            self.right.len(),
+            compact_list(&self.left),
+            compact_list(&self.middle),
+            compact_list(&self.right)
+        )
+    }
+}
+
 impl<Lambda> HasIdentity<Vec<Lambda>> for Span<Lambda>
 where
     Lambda: Sized + Eq + Copy + Debug,
@@ -197,11 +219,11 @@ impl<Lambda> Composable<Vec<Lambda>> for Span<Lambda>
 where
     Lambda: Sized + Eq + Copy + Debug,
 {
-    fn composable(&self, other: &Self) -> Result<(), String> {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
         crate::utils::same_labels_check(self.right.iter(), other.left.iter())
     }
 
-    fn compose(&self, other: &Self) -> Result<Self, String> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
         self.composable(other)?;
         // could shortuct if self.is_right_id or other.is_left_id, but unnecessary
         let max_middle = self.middle.len().max(other.middle.len());
@@ -220,7 +242,8 @@ where
                     return Err(format!(
                         "{}\nShould be unreachable if composability already said it was all okay.",
                         z
-                    ));
+                    )
+                    .into());
                 }
             }
         }
@@ -255,29 +278,38 @@ where
     }
 }
 
-impl<Lambda> MonoidalMorphism<Vec<Lambda>> for Span<Lambda> where Lambda: Sized + Eq + Copy + Debug {}
-
-impl<Lambda> GenericMonoidalInterpretable<Lambda> for Span<Lambda> where Lambda: Eq + Copy + Debug {}
+impl<Lambda> Dagger<Vec<Lambda>> for Span<Lambda>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+{
+    fn dagger(&self) -> Self {
+        Span::dagger(self)
+    }
+}
 
 impl<Lambda> SymmetricMonoidalMorphism<Lambda> for Span<Lambda>
 where
     Lambda: Sized + Eq + Copy + Debug,
 {
     fn permute_side(&mut self, p: &permutations::Permutation, of_codomain: bool) {
+        /*
+        the same convention as for Cospan, that side becomes p.permute of what it was
+        so what was at position k is now at position p.inv()(k)
+        */
+        let p_inv = p.inv();
         if of_codomain {
             self.is_right_id = false;
             in_place_permute(&mut self.right, p);
             self.middle.iter_mut().for_each(|(_, v2)| {
-                *v2 = p.apply(*v2);
+                *v2 = p_inv.apply(*v2);
             });
         } else {
             self.is_left_id = false;
             in_place_permute(&mut self.left, p);
             self.middle.iter_mut().for_each(|(v1, _)| {
-                *v1 = p.apply(*v1);
+                *v1 = p_inv.apply(*v1);
             });
         }
-        //todo test
     }
 
     fn from_permutation(
@@ -285,24 +317,15 @@ where
         types: &[Lambda],
         types_as_on_domain: bool,
     ) -> Self {
+        /*
+        the same convention as for Cospan
+        position i of the domain goes to position p(i) of the codomain
+        */
+        let middle = (0..types.len()).map(|idx| (idx, p.apply(idx))).collect();
         if types_as_on_domain {
-            let _answer = Self {
-                left: types.to_vec(),
-                middle: (0..types.len()).map(|idx| (idx, p.apply(idx))).collect(),
-                right: p.permute(types),
-                is_left_id: true,
-                is_right_id: false,
-            };
-            todo!("p and p inverse straighten out")
+            Self::new(types.to_vec(), p.inv().permute(types), middle)
         } else {
-            let _answer = Self {
-                left: p.permute(types),
-                middle: (0..types.len()).map(|idx| (p.apply(idx), idx)).collect(),
-                right: types.to_vec(),
-                is_left_id: false,
-                is_right_id: true,
-            };
-            todo!("p and p inverse straighten out")
+            Self::new(p.permute(types), types.to_vec(), middle)
         }
     }
 }
@@ -313,6 +336,7 @@ where the source is now always assumed to be a subset of the product
 by the leg maps being jointly injective
 */
 #[repr(transparent)]
+#[derive(Clone)]
 pub struct Rel<Lambda: Eq + Sized + Debug + Copy>(Span<Lambda>);
 
 impl<Lambda> HasIdentity<Vec<Lambda>> for Rel<Lambda>
@@ -328,7 +352,7 @@ impl<Lambda> Composable<Vec<Lambda>> for Rel<Lambda>
 where
     Lambda: Sized + Eq + Copy + Debug,
 {
-    fn compose(&self, other: &Self) -> Result<Self, String> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
         self.0.compose(&other.0).map(Self)
     }
 
@@ -340,11 +364,23 @@ where
         self.0.codomain()
     }
 
-    fn composable(&self, other: &Self) -> Result<(), String> {
+    fn composable(&self, other: &Self) -> Result<(), Error> {
         self.0.composable(&other.0)
     }
 }
 
+impl<Lambda> Dagger<Vec<Lambda>> for Rel<Lambda>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+{
+    fn dagger(&self) -> Self {
+        /*
+        the converse relation, still jointly injective
+        */
+        Self(self.0.dagger())
+    }
+}
+
 impl<Lambda> Monoidal for Rel<Lambda>
 where
     Lambda: Sized + Eq + Copy + Debug,
@@ -354,11 +390,83 @@ where
     }
 }
 
-impl<Lambda> MonoidalMorphism<Vec<Lambda>> for Rel<Lambda> where Lambda: Sized + Eq + Copy + Debug {}
+impl<Lambda> PartialEq for Rel<Lambda>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+{
+    fn eq(&self, other: &Self) -> bool {
+        /*
+        the same subset of the product
+        regardless of the order or repetition of the pairs in the underlying spans
+        */
+        self.domain() == other.domain()
+            && self.codomain() == other.codomain()
+            && self.subsumes(other)
+            && other.subsumes(self)
+    }
+}
+
+impl<Lambda> SymmetricMonoidalMorphism<Lambda> for Rel<Lambda>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+{
+    fn permute_side(&mut self, p: &permutations::Permutation, of_codomain: bool) {
+        self.0.permute_side(p, of_codomain);
+    }
+
+    fn from_permutation(
+        p: permutations::Permutation,
+        types: &[Lambda],
+        types_as_on_domain: bool,
+    ) -> Self {
+        Self(Span::from_permutation(p, types, types_as_on_domain))
+    }
+}
+
+impl<Lambda> CartesianMonoidal<Vec<Lambda>> for Rel<Lambda>
+where
+    Lambda: Sized + Eq + Copy + Debug,
+{
+    /*
+    the monoidal product is the disjoint union which is a biproduct in Rel
+    so x is related to both of its copies in a \otimes a
+    and deleting is the empty relation to the empty set
+    every relation commutes with both so this is honestly cartesian
+    */
+    fn duplicate(object: &Vec<Lambda>) -> Self {
+        let n = object.len();
+        Self::new(
+            Span::new(
+                object.clone(),
+                [object.as_slice(), object.as_slice()].concat(),
+                (0..n).flat_map(|i| [(i, i), (i, n + i)]).collect(),
+            ),
+            true,
+        )
+    }
 
-impl<Lambda> GenericMonoidalInterpretable<Lambda> for Rel<Lambda> where Lambda: Eq + Copy + Debug {}
+    fn discard(object: &Vec<Lambda>) -> Self {
+        Self::new(Span::new(object.clone(), vec![], vec![]), true)
+    }
+}
 
 impl<Lambda: Eq + Sized + Debug + Copy> Rel<Lambda> {
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like Rel(2→1): ['a','b']←[(0,0)]→['a']
+        the same as for the underlying span, the middle pairs are the related elements
+        */
+        format!(
+            "Rel({}→{}): [{}]←[{}]→[{}]",
+            self.0.left.len(),
+            self.0.right.len(),
+            compact_list(&self.0.left),
+            compact_list(&self.0.middle),
+            compact_list(&self.0.right)
+        )
+    }
+
     fn new(x: Span<Lambda>, do_check: bool) -> Self {
         /*
         given a span interpret it as a relation
@@ -536,3 +644,70 @@ impl<Lambda: Eq + Sized + Debug + Copy> Rel<Lambda> {
             && self.is_transitive()
     }
 }
+
+mod test {
+
+    #[test]
+    fn permuted_spans() {
+        use super::Span;
+        use crate::{
+            category::{Composable, HasIdentity},
+            cospan::Cospan,
+            symmetric_monoidal::SymmetricMonoidalMorphism,
+            utils::rand_perm,
+        };
+        let types: Vec<char> = ('a'..).take(6).collect();
+        for _ in 0..10 {
+            let p = rand_perm(6, 36);
+            for types_as_on_domain in [true, false] {
+                let span = Span::from_permutation(p.clone(), &types, types_as_on_domain);
+                let cospan = Cospan::from_permutation(p.clone(), &types, types_as_on_domain);
+                span.assert_valid(true);
+                assert_eq!(span.domain(), cospan.domain());
+                assert_eq!(span.codomain(), cospan.codomain());
+            }
+            let mut span = Span::identity(&types);
+            let mut cospan = Cospan::identity(&types);
+            for of_codomain in [true, false] {
+                span.permute_side(&p, of_codomain);
+                cospan.permute_side(&p, of_codomain);
+                span.assert_valid(false);
+                assert_eq!(span.domain(), cospan.domain());
+                assert_eq!(span.codomain(), cospan.codomain());
+            }
+        }
+    }
+
+    #[test]
+    fn cartesian_relations() {
+        use super::{Rel, Span};
+        use crate::monoidal::{Braided, CartesianMonoidal};
+        let a = vec!['x', 'y', 'x'];
+        let b = vec!['y'];
+        let c = vec!['x', 'x'];
+        assert_eq!(Rel::comonoid_laws(&a), Ok(()));
+        assert_eq!(Rel::braiding_laws(&a, &b, &c), Ok(()));
+        let relations = [
+            Rel::new(
+                Span::new(a.clone(), c.clone(), vec![(0, 0), (0, 1), (2, 1)]),
+                true,
+            ),
+            Rel::new(Span::new(a.clone(), c.clone(), vec![]), true),
+            Rel::new(Span::new(a.clone(), b.clone(), vec![(1, 0)]), true),
+        ];
+        for relation in relations {
+            assert_eq!(relation.discard_naturality(), Ok(()));
+            assert_eq!(relation.duplicate_naturality(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn compact_relation() {
+        use super::{Rel, Span};
+        let relation = Rel::new(Span::new(vec!['x', 'y'], vec!['x'], vec![(0, 0)]), true);
+        assert_eq!(
+            relation.to_compact_string(),
+            "Rel(2→1): ['x','y']←[(0, 0)]→['x']"
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use {
+    crate::{
+        category::{Composable, HasIdentity},
+        error::Error,
+        finset::FinSetMorphism,
+        matrix::MatrixMorphism,
+        monoidal::{Braided, CartesianMonoidal, Monoidal},
+        symmetric_monoidal::SymmetricMonoidalDiscreteMorphism,
+    },
+    num::{One, Zero},
+    permutations::Permutation,
+    std::{
+        cmp::Ordering,
+        ops::{Div, Mul},
+    },
+};
+
+/*
+a Markov kernel between finite sets
+row i is the probability distribution of where i goes
+so every entry is at least zero and each row adds up to one
+the monoidal product is the Kronecker product as for MatrixMorphism
+    which is the independent product of the kernels
+the objects are how many elements the set has
+new and from_weights check the row sums exactly
+    so the entries should be exact like ratios or floats that are sums of powers of 2
+    new_approx and from_weights_approx allow some tolerance for other floats
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StochasticMorphism<R>(MatrixMorphism<R>);
+
+impl<R> StochasticMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R> + PartialOrd,
+{
+    #[allow(dead_code)]
+    pub fn new(domain: usize, codomain: usize, entries: Vec<Vec<R>>) -> Result<Self, Error> {
+        Self::new_approx(domain, codomain, entries, R::zero())
+    }
+
+    #[allow(dead_code)]
+    pub fn new_approx(
+        domain: usize,
+        codomain: usize,
+        entries: Vec<Vec<R>>,
+        tolerance: R,
+    ) -> Result<Self, Error> {
+        /*
+        the same as new but each row only has to add up to within tolerance of one
+        for floats where 0.7 + 0.2 + 0.1 is not exactly one
+        */
+        for (row_number, row) in entries.iter().enumerate() {
+            if row.iter().any(is_not_nonnegative) {
+                return Err(format!("Row {} had a negative probability", row_number).into());
+            }
+            let total = row.iter().fold(R::zero(), |acc, entry| acc + entry.clone());
+            let close_enough = total.clone() <= R::one() + tolerance.clone()
+                && R::one() <= total + tolerance.clone();
+            if !close_enough {
+                return Err(format!("Row {} did not add up to one", row_number).into());
+            }
+        }
+        MatrixMorphism::new(domain, codomain, entries).map(Self)
+    }
+
+    #[allow(dead_code)]
+    pub fn from_weights(domain: usize, codomain: usize, weights: Vec<Vec<R>>) -> Result<Self, Error>
+    where
+        R: Div<Output = R>,
+    {
+        Self::from_weights_approx(domain, codomain, weights, R::zero())
+    }
+
+    #[allow(dead_code)]
+    pub fn from_weights_approx(
+        domain: usize,
+        codomain: usize,
+        weights: Vec<Vec<R>>,
+        tolerance: R,
+    ) -> Result<Self, Error>
+    where
+        R: Div<Output = R>,
+    {
+        /*
+        divide each row by what it adds up to
+        an error for a row that is all zero or has a negative weight
+        the result is checked as in new_approx
+            so for floats the tolerance covers the rounding in the division
+        */
+        let mut entries = Vec::with_capacity(weights.len());
+        for (row_number, row) in weights.into_iter().enumerate() {
+            let total = row.iter().fold(R::zero(), |acc, entry| acc + entry.clone());
+            if row.iter().any(is_not_nonnegative) || total.is_zero() {
+                return Err(format!("Row {} can not be made a distribution", row_number).into());
+            }
+            entries.push(row.into_iter().map(|entry| entry / total.clone()).collect());
+        }
+        Self::new_approx(domain, codomain, entries, tolerance)
+    }
+
+    #[allow(dead_code)]
+    pub fn distribution(probabilities: Vec<R>) -> Result<Self, Error> {
+        /*
+        a state, the morphism from the one element set
+        */
+        Self::new(1, probabilities.len(), vec![probabilities])
+    }
+
+    #[allow(dead_code)]
+    pub fn from_function(function: &FinSetMorphism) -> Self {
+        /*
+        the deterministic kernel sending i to function(i) with certainty
+        */
+        let mut entries = vec![vec![R::zero(); function.codomain()]; function.domain()];
+        for (source, target) in function.0.iter().enumerate() {
+            entries[source][*target] = R::one();
+        }
+        Self(
+            MatrixMorphism::new(function.domain(), function.codomain(), entries)
+                .expect("one row for each element of the domain"),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn probability(&self, source: usize, target: usize) -> &R {
+        self.0.entry(source, target)
+    }
+
+    #[allow(dead_code)]
+    pub fn matrix(&self) -> &MatrixMorphism<R> {
+        &self.0
+    }
+
+    #[allow(dead_code)]
+    pub fn is_deterministic(&self) -> bool {
+        /*
+        every row puts all its probability on one place
+        these are the ones that commute with copying
+        */
+        self.0.entries().iter().all(|row| {
+            row.iter().filter(|entry| !entry.is_zero()).count() == 1
+                && row.iter().any(|entry| entry.is_one())
+        })
+    }
+}
+
+fn is_not_nonnegative<R: Zero + PartialOrd>(entry: &R) -> bool {
+    /*
+    negative or incomparable to zero like NaN
+    */
+    !matches!(
+        entry.partial_cmp(&R::zero()),
+        Some(Ordering::Greater | Ordering::Equal)
+    )
+}
+
+impl<R> HasIdentity<usize> for StochasticMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn identity(on_this: &usize) -> Self {
+        Self(MatrixMorphism::identity(on_this))
+    }
+}
+
+impl<R> Composable<usize> for StochasticMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        self.0.compose(&other.0).map(Self)
+    }
+
+    fn domain(&self) -> usize {
+        self.0.domain()
+    }
+
+    fn codomain(&self) -> usize {
+        self.0.codomain()
+    }
+}
+
+impl<R> Monoidal for StochasticMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn monoidal(&mut self, other: Self) {
+        self.0.monoidal(other.0);
+    }
+}
+
+impl<R> SymmetricMonoidalDiscreteMorphism<usize> for StochasticMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        self.0.permute_side(p, of_codomain);
+    }
+
+    fn from_permutation(p: Permutation, type_: usize, types_as_on_domain: bool) -> Self {
+        Self(MatrixMorphism::from_permutation(
+            p,
+            type_,
+            types_as_on_domain,
+        ))
+    }
+}
+
+impl<R> Braided<usize> for StochasticMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    fn braiding(a: &usize, b: &usize) -> Self {
+        Self(MatrixMorphism::swap(*a, *b))
+    }
+
+    fn inverse_braiding(a: &usize, b: &usize) -> Self {
+        Self::braiding(b, a)
+    }
+}
+
+impl<R> CartesianMonoidal<usize> for StochasticMorphism<R>
+where
+    R: Clone + Zero + One + Mul<Output = R>,
+{
+    /*
+    copying sends i to (i,i) and deleting sends everything to the one element set
+    every kernel commutes with deleting since its rows add up to one
+        but only the deterministic ones commute with copying
+    */
+    fn duplicate(object: &usize) -> Self {
+        let mut entries = vec![vec![R::zero(); object * object]; *object];
+        for (idx, row) in entries.iter_mut().enumerate() {
+            row[idx * object + idx] = R::one();
+        }
+        Self(MatrixMorphism::new(*object, object * object, entries).expect("sizes fit"))
+    }
+
+    fn discard(object: &usize) -> Self {
+        Self(MatrixMorphism::new(*object, 1, vec![vec![R::one()]; *object]).expect("sizes fit"))
+    }
+}
+
+mod test {
+
+    #[test]
+    fn markov_kernels() {
+        use super::StochasticMorphism;
+        use crate::category::{Composable, HasIdentity};
+        use crate::monoidal::{Braided, CartesianMonoidal, Monoidal};
+        use num::rational::Ratio;
+        type S = StochasticMorphism<Ratio<i64>>;
+        let r = |n, d| Ratio::new(n, d);
+        let coin = S::distribution(vec![r(1, 2), r(1, 2)]).unwrap();
+        let noisy = S::new(
+            2,
+            3,
+            vec![
+                vec![r(3, 4), r(1, 4), r(0, 1)],
+                vec![r(0, 1), r(1, 3), r(2, 3)],
+            ],
+        )
+        .unwrap();
+        assert!(S::new(1, 2, vec![vec![r(1, 2), r(1, 3)]]).is_err());
+        assert!(S::new(1, 2, vec![vec![r(3, 2), r(-1, 2)]]).is_err());
+        assert_eq!(
+            S::from_weights(1, 3, vec![vec![r(1, 1), r(2, 1), r(1, 1)]]).unwrap(),
+            S::distribution(vec![r(1, 4), r(1, 2), r(1, 4)]).unwrap()
+        );
+        assert!(S::from_weights(1, 2, vec![vec![r(0, 1), r(0, 1)]]).is_err());
+
+        let pushed = coin.compose(&noisy).unwrap();
+        assert_eq!(
+            pushed,
+            S::distribution(vec![r(3, 8), r(7, 24), r(1, 3)]).unwrap()
+        );
+
+        /*
+        two independent coins
+        */
+        let mut two_coins = coin.clone();
+        two_coins.monoidal(coin.clone());
+        assert_eq!(two_coins, S::distribution(vec![r(1, 4); 4]).unwrap());
+
+        for n in [1, 2, 3] {
+            assert_eq!(S::comonoid_laws(&n), Ok(()));
+            assert_eq!(S::braiding_laws(&n, &2, &3), Ok(()));
+        }
+        assert_eq!(noisy.discard_naturality(), Ok(()));
+        assert!(noisy.duplicate_naturality().is_err());
+        assert!(!noisy.is_deterministic());
+
+        let function = S::from_function(&(vec![2, 0], 0));
+        assert!(function.is_deterministic());
+        assert_eq!(function.duplicate_naturality(), Ok(()));
+        assert_eq!(function.discard_naturality(), Ok(()));
+        assert_eq!(*function.probability(0, 2), r(1, 1));
+
+        /*
+        copying a coin gives perfectly correlated outcomes
+            unlike flipping two
+        */
+        let copied = coin.compose(&S::duplicate(&2)).unwrap();
+        assert_eq!(
+            copied,
+            S::distribution(vec![r(1, 2), r(0, 1), r(0, 1), r(1, 2)]).unwrap()
+        );
+        assert!(copied != two_coins);
+        assert_eq!(
+            copied
+                .compose(&{
+                    let mut first_only = S::identity(&2);
+                    first_only.monoidal(S::discard(&2));
+                    first_only
+                })
+                .unwrap(),
+            coin
+        );
+    }
+
+    #[test]
+    fn float_kernels() {
+        use super::StochasticMorphism;
+        type S = StochasticMorphism<f64>;
+        /*
+        rounding means 0.7 + 0.2 + 0.1 is not exactly 1
+        */
+        let probabilities = vec![vec![0.7, 0.2, 0.1]];
+        assert!(S::new(1, 3, probabilities.clone()).is_err());
+        let rounded = S::new_approx(1, 3, probabilities, 1e-9).unwrap();
+        assert_eq!(*rounded.probability(0, 0), 0.7);
+        assert!(S::new_approx(1, 2, vec![vec![0.5, 0.6]], 1e-9).is_err());
+        assert!(S::new_approx(1, 2, vec![vec![1.1, -0.1]], 1e-9).is_err());
+        assert!(S::new_approx(1, 2, vec![vec![f64::NAN, 1.0]], 1e-9).is_err());
+        assert!(S::new_approx(1, 1, vec![vec![f64::NAN]], f64::INFINITY).is_err());
+
+        let thirds =
+            S::from_weights_approx(2, 3, vec![vec![1.0; 3], vec![0.3, 0.3, 0.4]], 1e-9).unwrap();
+        assert!((thirds.probability(0, 1) - 1.0 / 3.0).abs() < 1e-12);
+        assert!(S::from_weights_approx(1, 2, vec![vec![2.0, -1.0]], 1e-9).is_err());
+        assert!(S::from_weights_approx(1, 2, vec![vec![f64::NAN, 1.0]], 1e-9).is_err());
+        assert!(S::from_weights_approx(1, 2, vec![vec![0.0, 0.0]], 1e-9).is_err());
+    }
+}
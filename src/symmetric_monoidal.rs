@@ -1,8 +1,20 @@
 use {
-    crate::monoidal::{MonoidalMorphism, MonoidalMutatingMorphism},
+    crate::{
+        category::{HasIdentity, Op},
+        monoidal::{Braided, MonoidalMorphism, MonoidalMutatingMorphism},
+    },
     permutations::Permutation,
 };
 
+pub trait HasSymmetricBraiding<T>: Sized {
+    /*
+    the crossing of two wires, going from z \otimes w to w \otimes z
+    so that a box type can fill in the swaps of a permutation
+    the same way HasIdentity lets it fill in the straight wires
+    */
+    fn symmetric_braiding(z: &T, w: &T) -> Self;
+}
+
 pub trait SymmetricMonoidalMorphism<T: Eq>: MonoidalMorphism<Vec<T>> {
     /*
     can pre/post compose a given morphism with a permutation (possibly panic if the permutation is not of the right cardinality)
@@ -14,6 +26,35 @@ pub trait SymmetricMonoidalMorphism<T: Eq>: MonoidalMorphism<Vec<T>> {
     fn from_permutation(p: Permutation, types: &[T], types_as_on_domain: bool) -> Self;
 }
 
+impl<Lambda, M> Braided<Vec<Lambda>> for M
+where
+    Lambda: Eq + Copy,
+    M: SymmetricMonoidalMorphism<Lambda> + HasIdentity<Vec<Lambda>>,
+{
+    /*
+    the symmetric case
+    the braiding is the permutation taking the a.len() wires of a past the b.len() wires of b
+    and its inverse is the braiding the other way around
+    */
+    fn braiding(a: &Vec<Lambda>, b: &Vec<Lambda>) -> Self {
+        let shuffle = (0..a.len() + b.len())
+            .map(|i| {
+                if i < a.len() {
+                    b.len() + i
+                } else {
+                    i - a.len()
+                }
+            })
+            .collect::<Vec<_>>();
+        let p = Permutation::try_from(shuffle).expect("a block swap is a permutation");
+        Self::from_permutation(p, &[a.as_slice(), b.as_slice()].concat(), true)
+    }
+
+    fn inverse_braiding(a: &Vec<Lambda>, b: &Vec<Lambda>) -> Self {
+        Self::braiding(b, a)
+    }
+}
+
 pub trait SymmetricMonoidalDiscreteMorphism<T: Eq>: MonoidalMorphism<T> {
     /*
     for finset they are morphisms on finite sets, but rather than specify the domain/codomain as Vec<Singleton>
@@ -32,3 +73,50 @@ pub trait SymmetricMonoidalMutatingMorphism<T: Eq>: MonoidalMutatingMorphism<Vec
     fn permute_side(&mut self, p: &Permutation, of_codomain: bool);
     fn from_permutation(p: Permutation, types: &[T], types_as_on_domain: bool) -> Self;
 }
+
+impl<T, M> SymmetricMonoidalMorphism<T> for Op<M>
+where
+    T: Eq,
+    M: SymmetricMonoidalMorphism<T>,
+{
+    /*
+    the codomain of the opposite is the domain underneath and vice versa
+    position i of the opposite's domain going to position p(i) of its codomain
+        is position p(i) of the domain underneath going to position i of its codomain
+    */
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        self.0.permute_side(p, !of_codomain);
+    }
+
+    fn from_permutation(p: Permutation, types: &[T], types_as_on_domain: bool) -> Self {
+        Self(M::from_permutation(p.inv(), types, !types_as_on_domain))
+    }
+}
+
+impl<T, M> SymmetricMonoidalDiscreteMorphism<T> for Op<M>
+where
+    T: Eq,
+    M: SymmetricMonoidalDiscreteMorphism<T>,
+{
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        self.0.permute_side(p, !of_codomain);
+    }
+
+    fn from_permutation(p: Permutation, types: T, types_as_on_domain: bool) -> Self {
+        Self(M::from_permutation(p.inv(), types, !types_as_on_domain))
+    }
+}
+
+impl<T, M> SymmetricMonoidalMutatingMorphism<T> for Op<M>
+where
+    T: Eq,
+    M: SymmetricMonoidalMutatingMorphism<T>,
+{
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        self.0.permute_side(p, !of_codomain);
+    }
+
+    fn from_permutation(p: Permutation, types: &[T], types_as_on_domain: bool) -> Self {
+        Self(M::from_permutation(p.inv(), types, !types_as_on_domain))
+    }
+}
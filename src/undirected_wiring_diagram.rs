@@ -0,0 +1,384 @@
+use {
+    crate::{
+        category::Composable,
+        error::Error,
+        finrel::FinRel,
+        named_cospan::NamedCospan,
+        wiring_diagram::{InOut, WiringDiagram},
+    },
+    either::Either,
+    std::fmt::Debug,
+};
+
+type Ports<IntraCircle, Lambda> = Vec<(IntraCircle, Lambda)>;
+
+/*
+an undirected wiring diagram with wires labelled using Lambda
+the same as a WiringDiagram except that the nodes have no InOut
+so the nodes on the internal circles are named by
+    an InterCircle for which of multiple internal circles we are on
+    an IntraCircle to label which node on that circle it is
+and the nodes on the single external circle by just an IntraCircle
+the middle of the cospan is the set of junctions that the nodes are wired to
+*/
+#[allow(dead_code, clippy::upper_case_acronyms)]
+#[repr(transparent)]
+pub struct UWD<Lambda: Eq + Copy + Debug, InterCircle: Eq + Clone, IntraCircle: Eq + Clone>(
+    NamedCospan<Lambda, (InterCircle, IntraCircle), IntraCircle>,
+);
+
+impl<Lambda, InterCircle, IntraCircle> UWD<Lambda, InterCircle, IntraCircle>
+where
+    Lambda: Eq + Copy + Debug,
+    InterCircle: Eq + Clone,
+    IntraCircle: Eq + Clone,
+{
+    #[allow(dead_code)]
+    pub fn from_named_cospan(
+        inside: NamedCospan<Lambda, (InterCircle, IntraCircle), IntraCircle>,
+    ) -> Self {
+        Self(inside)
+    }
+
+    #[allow(dead_code)]
+    pub fn new(
+        outer_ports: Ports<IntraCircle, Lambda>,
+        circles: Vec<(InterCircle, Ports<IntraCircle, Lambda>)>,
+    ) -> Result<Self, Error> {
+        /*
+        a diagram with the given nodes on the external circle
+            and the given internal circles with their nodes
+        each node is on its own junction so nothing is connected yet
+            use connect_pair to put in the wires
+        an error if a circle is repeated or a node is repeated on the same circle
+        */
+        let undirected = |ports: Ports<IntraCircle, Lambda>| {
+            ports
+                .into_iter()
+                .map(|(intra, type_)| (InOut::Undirected, intra, type_))
+                .collect()
+        };
+        let directed = WiringDiagram::new(
+            undirected(outer_ports),
+            circles
+                .into_iter()
+                .map(|(circle, ports)| (circle, undirected(ports)))
+                .collect(),
+        )?;
+        Ok(Self::from_wiring_diagram(&directed))
+    }
+
+    #[allow(dead_code)]
+    pub fn from_wiring_diagram(diagram: &WiringDiagram<Lambda, InterCircle, IntraCircle>) -> Self {
+        /*
+        forget the orientations of all the nodes
+        */
+        let cospan = diagram.named_cospan().cospan();
+        Self(NamedCospan::new(
+            cospan.left_to_middle().to_vec(),
+            cospan.right_to_middle().to_vec(),
+            cospan.middle().to_vec(),
+            diagram
+                .inner_ports()
+                .iter()
+                .map(|(_, circle, intra)| (circle.clone(), intra.clone()))
+                .collect(),
+            diagram
+                .outer_ports()
+                .iter()
+                .map(|(_, intra)| intra.clone())
+                .collect(),
+        ))
+    }
+
+    #[allow(dead_code)]
+    pub fn to_wiring_diagram(&self) -> WiringDiagram<Lambda, InterCircle, IntraCircle> {
+        /*
+        the same diagram with every node Undirected
+        */
+        let cospan = self.0.cospan();
+        WiringDiagram::from_named_cospan(NamedCospan::new(
+            cospan.left_to_middle().to_vec(),
+            cospan.right_to_middle().to_vec(),
+            cospan.middle().to_vec(),
+            self.0
+                .left_names()
+                .iter()
+                .map(|(circle, intra)| (InOut::Undirected, circle.clone(), intra.clone()))
+                .collect(),
+            self.0
+                .right_names()
+                .iter()
+                .map(|intra| (InOut::Undirected, intra.clone()))
+                .collect(),
+        ))
+    }
+
+    #[allow(dead_code)]
+    pub fn named_cospan(&self) -> &NamedCospan<Lambda, (InterCircle, IntraCircle), IntraCircle> {
+        &self.0
+    }
+
+    #[allow(dead_code)]
+    pub fn outer_ports(&self) -> &[IntraCircle] {
+        self.0.right_names()
+    }
+
+    #[allow(dead_code)]
+    pub fn inner_ports(&self) -> &[(InterCircle, IntraCircle)] {
+        self.0.left_names()
+    }
+
+    #[allow(dead_code)]
+    pub fn circles(&self) -> Vec<InterCircle> {
+        /*
+        the internal circles in the order they first have a node
+        */
+        let mut answer: Vec<InterCircle> = Vec::new();
+        for (circle, _) in self.0.left_names() {
+            if !answer.contains(circle) {
+                answer.push(circle.clone());
+            }
+        }
+        answer
+    }
+
+    #[allow(dead_code)]
+    pub fn circle_ports(&self, which_circle: &InterCircle) -> Vec<IntraCircle> {
+        self.0
+            .left_names()
+            .iter()
+            .filter(|(circle, _)| circle == which_circle)
+            .map(|(_, intra)| intra.clone())
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn connect_pair(
+        &mut self,
+        node_1: Either<(InterCircle, IntraCircle), IntraCircle>,
+        node_2: Either<(InterCircle, IntraCircle), IntraCircle>,
+    ) {
+        /*
+        put the two nodes on the same junction
+        see connect_pair in WiringDiagram for when no change is made
+        */
+        self.0.connect_pair(node_1, node_2)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_isomorphic_to(&self, other: &Self) -> bool {
+        /*
+        the same nodes on the same circles wired up the same way
+        */
+        self.0.is_isomorphic_to(&other.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn operadic_substitution(
+        &mut self,
+        which_circle: InterCircle,
+        internal_other: Self,
+    ) -> Result<(), Error>
+    where
+        InterCircle: Copy,
+        IntraCircle: Copy,
+    {
+        /*
+        replace the internal circle which_circle with the contents of internal_other
+        the node intra of that circle is glued to the node intra of the external circle
+            of internal_other
+        see operadic_substitution in WiringDiagram for the order of the nodes afterwards
+            and when it is an error
+        */
+        let mut directed = self.to_wiring_diagram();
+        directed.operadic_substitution(which_circle, internal_other.to_wiring_diagram())?;
+        *self = Self::from_wiring_diagram(&directed);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn evaluate<F, G>(&self, size_of: F, relation_of: G) -> Result<FinRel, Error>
+    where
+        F: Fn(Lambda) -> usize,
+        G: Fn(&InterCircle) -> FinRel,
+    {
+        /*
+        the relation between the nodes of the external circle
+            when each internal circle is filled in by a relation between its nodes
+        a junction labelled z takes one of size_of(z) values
+        a relation between several nodes is a FinRel from 1
+            to the tuples of values of those nodes in order
+            numbered as in FinRel::cartesian_product
+        so relation_of(c) should relate 0 to the allowed tuples on the nodes of c
+        and the answer relates 0 to the tuples on the external nodes
+            for which the junctions can be given values
+            so that the tuple on every internal circle is allowed
+        this agrees with composing the circles as decorated cospans in FinRel
+            but is found by trying every value on every junction
+            so it is only for small diagrams
+        an error if relation_of gives a FinRel of the wrong shape
+        */
+        let cospan = self.0.cospan();
+        let sizes: Vec<usize> = cospan.middle().iter().map(|z| size_of(*z)).collect();
+        let tuple_count = |junctions: &[usize]| -> usize {
+            junctions.iter().map(|junction| sizes[*junction]).product()
+        };
+        let tuple_number = |junctions: &[usize], values: &[usize]| {
+            junctions.iter().fold(0, |so_far, junction| {
+                so_far * sizes[*junction] + values[*junction]
+            })
+        };
+
+        let mut allowed = Vec::new();
+        for circle in self.circles() {
+            let junctions: Vec<usize> = self
+                .0
+                .left_names()
+                .iter()
+                .zip(cospan.left_to_middle())
+                .filter(|((which_circle, _), _)| *which_circle == circle)
+                .map(|(_, junction)| *junction)
+                .collect();
+            let relation = relation_of(&circle);
+            let expected = tuple_count(&junctions);
+            if relation.domain() != 1 || relation.codomain() != expected {
+                return Err(format!(
+                    "An internal circle needed a relation from 1 to {} but it was from {} to {}",
+                    expected,
+                    relation.domain(),
+                    relation.codomain()
+                )
+                .into());
+            }
+            allowed.push((junctions, relation));
+        }
+
+        let outer_junctions = cospan.right_to_middle();
+        let mut related = Vec::new();
+        if !sizes.contains(&0) {
+            let mut values = vec![0; sizes.len()];
+            loop {
+                if allowed.iter().all(|(junctions, relation)| {
+                    relation.relates(0, tuple_number(junctions, &values))
+                }) {
+                    related.push((0, tuple_number(outer_junctions, &values)));
+                }
+                let Some(junction) = (0..values.len()).find(|idx| values[*idx] + 1 < sizes[*idx])
+                else {
+                    break;
+                };
+                values[junction] += 1;
+                values[..junction].fill(0);
+            }
+        }
+        FinRel::new(1, tuple_count(outer_junctions), related)
+    }
+}
+
+mod test {
+
+    #[test]
+    fn building_and_substitution() {
+        use super::UWD;
+        use either::{Left, Right};
+        type U = UWD<char, char, usize>;
+        let series = |first: char, second: char| {
+            /*
+            outer node 0 to node 0 of first, node 1 of first to node 0 of second
+                and node 1 of second to outer node 1
+            */
+            let mut answer = U::new(
+                vec![(0, 'x'), (1, 'x')],
+                vec![
+                    (first, vec![(0, 'x'), (1, 'x')]),
+                    (second, vec![(0, 'x'), (1, 'x')]),
+                ],
+            )
+            .unwrap();
+            answer.connect_pair(Right(0), Left((first, 0)));
+            answer.connect_pair(Left((first, 1)), Left((second, 0)));
+            answer.connect_pair(Left((second, 1)), Right(1));
+            answer
+        };
+        let mut outer = series('a', 'b');
+        assert_eq!(outer.circles(), vec!['a', 'b']);
+        assert_eq!(outer.circle_ports(&'b'), vec![0, 1]);
+        assert_eq!(outer.outer_ports(), &[0, 1]);
+        assert_eq!(outer.named_cospan().cospan().middle().len(), 3);
+        assert!(outer.is_isomorphic_to(&U::from_wiring_diagram(&outer.to_wiring_diagram())));
+        assert!(U::new(vec![(0, 'x'), (0, 'x')], vec![]).is_err());
+
+        outer.operadic_substitution('b', series('c', 'd')).unwrap();
+        assert_eq!(outer.circles(), vec!['c', 'd', 'a']);
+        assert_eq!(outer.named_cospan().cospan().middle().len(), 4);
+        assert!(outer.operadic_substitution('a', series('c', 'e')).is_err());
+        assert!(outer
+            .operadic_substitution('a', U::new(vec![(0, 'x')], vec![]).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn relational_evaluation() {
+        use super::UWD;
+        use crate::category::Composable;
+        use crate::finrel::FinRel;
+        use either::{Left, Right};
+        type U = UWD<char, char, usize>;
+        /*
+        on 3 values 0 <= 1 <= 2 as a binary relation and as a relation from 1 to pairs
+        */
+        let less_eq = FinRel::new(3, 3, (0..3).flat_map(|a| (a..3).map(move |b| (a, b)))).unwrap();
+        let as_pairs = |binary: &FinRel| {
+            FinRel::new(1, 9, binary.pairs().map(|(a, b)| (0, a * 3 + b))).unwrap()
+        };
+        let strictly_less = FinRel::new(3, 3, [(0, 1), (1, 2), (0, 2)]).unwrap();
+        let relation_of = |circle: &char| match circle {
+            'a' => as_pairs(&less_eq),
+            _ => as_pairs(&strictly_less),
+        };
+
+        let mut series = U::new(
+            vec![(0, 'x'), (1, 'x')],
+            vec![
+                ('a', vec![(0, 'x'), (1, 'x')]),
+                ('b', vec![(0, 'x'), (1, 'x')]),
+            ],
+        )
+        .unwrap();
+        series.connect_pair(Right(0), Left(('a', 0)));
+        series.connect_pair(Left(('a', 1)), Left(('b', 0)));
+        series.connect_pair(Left(('b', 1)), Right(1));
+        let evaluated = series.evaluate(|_| 3, relation_of).unwrap();
+        assert_eq!(
+            evaluated,
+            as_pairs(&less_eq.compose(&strictly_less).unwrap())
+        );
+
+        /*
+        both ends of one circle on the same junction is the diagonal of its relation
+        and a junction on no circle relates everything to everything
+        */
+        let mut looped = U::new(
+            vec![(0, 'x'), (1, 'x')],
+            vec![('b', vec![(0, 'x'), (1, 'x')])],
+        )
+        .unwrap();
+        looped.connect_pair(Left(('b', 0)), Left(('b', 1)));
+        looped.connect_pair(Left(('b', 0)), Right(0));
+        let evaluated = looped.evaluate(|_| 3, relation_of).unwrap();
+        assert_eq!(evaluated, FinRel::empty(1, 9));
+        let evaluated = looped.evaluate(|_| 3, |_| as_pairs(&less_eq)).unwrap();
+        assert_eq!(
+            evaluated,
+            FinRel::new(
+                1,
+                9,
+                (0..3).flat_map(|a| (0..3).map(move |b| (0, a * 3 + b)))
+            )
+            .unwrap()
+        );
+
+        assert!(series.evaluate(|_| 2, relation_of).is_err());
+    }
+}
@@ -0,0 +1,20 @@
+/*
+experimental subsystems whose interfaces are still moving
+    diagram algebras, their coefficients and the linear algebra over them
+nothing in here is held to the stability of the core traits in
+    category, monoidal, symmetric_monoidal, rigid_monoidal and frobenius
+*/
+pub mod braid;
+pub mod coefficients;
+pub mod free_module;
+pub mod interner;
+pub mod linear_algebra;
+pub mod linear_combination;
+pub mod linear_relation;
+#[cfg(feature = "serde")]
+pub mod migrate;
+pub mod non_strict;
+pub mod presentation;
+pub mod signal_flow;
+pub mod temperley_lieb;
+pub mod term_map;
@@ -0,0 +1,254 @@
+use {
+    crate::{
+        category::{Composable, HasIdentity},
+        error::Error,
+        monoidal::Monoidal,
+        unstable::{
+            coefficients::Laurent, linear_combination::MaybeSendSync,
+            temperley_lieb::BrauerMorphism,
+        },
+    },
+    num::{One, Zero},
+    std::ops::{Add, AddAssign, Mul, MulAssign},
+};
+
+/*
+a braid on some number of strands as a word in the Artin generators
+(i,true) is sigma_{i+1} where strand i crosses over strand i+1
+(i,false) is its inverse
+the word is read in the same order as composition so the first letter happens first
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Braid {
+    strands: usize,
+    word: Vec<(usize, bool)>,
+}
+
+impl Braid {
+    #[allow(dead_code)]
+    pub fn new(strands: usize, word: Vec<(usize, bool)>) -> Result<Self, Error> {
+        if let Some((i, _)) = word.iter().find(|(i, _)| i + 1 >= strands) {
+            return Err(format!("There is no sigma_{} on {} strands", i + 1, strands).into());
+        }
+        Ok(Self { strands, word })
+    }
+
+    #[allow(dead_code)]
+    pub fn strands(&self) -> usize {
+        self.strands
+    }
+
+    pub fn writhe(&self) -> i64 {
+        /*
+        each positive crossing counts +1 and each negative crossing -1
+        */
+        self.word
+            .iter()
+            .map(|(_, positive)| if *positive { 1 } else { -1 })
+            .sum()
+    }
+
+    #[allow(dead_code)]
+    pub fn mirror(&self) -> Self {
+        /*
+        change every crossing
+        the closure is the mirror image of the original closure
+        */
+        Self {
+            strands: self.strands,
+            word: self
+                .word
+                .iter()
+                .map(|(i, positive)| (*i, !positive))
+                .collect(),
+        }
+    }
+
+    pub fn cable(&self, k: usize) -> Self {
+        /*
+        replace every strand by k parallel strands
+        so strand i becomes the bundle ik..ik+k-1
+        crossing bundle i over bundle i+1 takes k^2 crossings all of the same sign
+            the j'th strand from the right of bundle i moves right past all of bundle i+1
+        the parallel strands are the blackboard framing of the closure
+        */
+        let word = self
+            .word
+            .iter()
+            .flat_map(|(i, positive)| {
+                (0..k).flat_map(move |j| (0..k).map(move |l| (i * k + (k - 1) - j + l, *positive)))
+            })
+            .collect();
+        Self {
+            strands: self.strands * k,
+            word,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn underlying_permutation<T>(&self) -> BrauerMorphism<T>
+    where
+        T: Add<Output = T>
+            + Zero
+            + One
+            + Clone
+            + AddAssign
+            + Mul<Output = T>
+            + MulAssign
+            + MaybeSendSync,
+    {
+        /*
+        forget which strand went over, leaving the permutation of the strands
+        as a Brauer diagram
+        */
+        self.word
+            .iter()
+            .fold(BrauerMorphism::identity(&self.strands), |acc, (i, _)| {
+                acc * BrauerMorphism::symmetric_alg_gen(*i, self.strands)
+            })
+    }
+
+    pub fn kauffman_bracket(&self) -> BrauerMorphism<Laurent> {
+        /*
+        the image in Temperley-Lieb with coefficients in Z[A,A^{-1}]
+        sigma_{i+1} goes to A + A^{-1}e_{i+1} and its inverse to A^{-1} + Ae_{i+1}
+        the loops are still powers of delta, which is -A^2-A^{-2}
+        */
+        let a = Laurent::monomial(1, 1);
+        let a_inv = Laurent::monomial(1, -1);
+        self.word.iter().fold(
+            BrauerMorphism::identity(&self.strands),
+            |acc, (i, positive)| {
+                let (identity_coeff, cup_cap_coeff) = if *positive {
+                    (a.clone(), a_inv.clone())
+                } else {
+                    (a_inv.clone(), a.clone())
+                };
+                acc * BrauerMorphism::temperley_lieb_crossing(
+                    *i,
+                    self.strands,
+                    identity_coeff,
+                    cup_cap_coeff,
+                )
+            },
+        )
+    }
+}
+
+#[allow(dead_code)]
+pub fn colored_jones(braid: &Braid, color: usize) -> Laurent {
+    /*
+    the unnormalized colored Jones polynomial of the closure of braid, in the variable A
+    colored by the color+1 dimensional representation
+        so the unknot gives D_color(-A^2-A^{-2}) and color 1 is the Kauffman bracket version of Jones
+    cable the braid color times, put the Jones-Wenzl projector p_color on each bundle
+    take the Markov trace and correct the blackboard framing by ((-1)^color A^{color^2+2color})^{-writhe}
+    jones_wenzl only gives d p_color so the trace is divided by d once for each bundle
+    */
+    let delta = -Laurent::monomial(1, 2) - Laurent::monomial(1, -2);
+    let (scaled_projector, scale) = BrauerMorphism::<Laurent>::jones_wenzl(color);
+    let mut projectors = BrauerMorphism::identity(&0);
+    for _ in 0..braid.strands {
+        projectors.monoidal(scaled_projector.clone());
+    }
+    let closed = projectors
+        .compose(&braid.cable(color).kauffman_bracket())
+        .expect("The projectors and the cabled braid are both on strands*color strands")
+        .markov_trace(delta.clone())
+        .expect("A braid is an endomorphism");
+    let scale = scale
+        .markov_trace(delta)
+        .expect("The scale is in Hom(0,0)")
+        .pow(braid.strands as u32);
+    let bracket = closed
+        .exact_div(&scale)
+        .expect("The trace of the projectors is a multiple of the scale");
+    let framing_exponent = -((color * color + 2 * color) as i32) * braid.writhe() as i32;
+    let framing_sign = if (color as i64 * braid.writhe()) % 2 == 0 {
+        1
+    } else {
+        -1
+    };
+    bracket * Laurent::monomial(framing_sign, framing_exponent)
+}
+
+mod test {
+
+    #[test]
+    fn kinked_unknots() {
+        use super::{colored_jones, Braid};
+        use crate::unstable::coefficients::Laurent;
+        use num::One;
+        /*
+        the closures of sigma_1 and sigma_1^{-1} on 2 strands are both the unknot
+        with the framing corrected the colored Jones of the unknot is D_color(delta)
+        */
+        let delta = -Laurent::monomial(1, 2) - Laurent::monomial(1, -2);
+        let mut chebyshev = vec![Laurent::one(), delta.clone()];
+        for k in 2..4 {
+            chebyshev.push(delta.clone() * chebyshev[k - 1].clone() - chebyshev[k - 2].clone());
+        }
+        let one_strand = Braid::new(1, vec![]).unwrap();
+        let positive_kink = Braid::new(2, vec![(0, true)]).unwrap();
+        let negative_kink = positive_kink.mirror();
+        for (color, expected) in chebyshev.iter().enumerate() {
+            assert_eq!(colored_jones(&one_strand, color), *expected);
+            assert_eq!(colored_jones(&positive_kink, color), *expected);
+            assert_eq!(colored_jones(&negative_kink, color), *expected);
+        }
+        assert_eq!(chebyshev[2].to_string(), "A^4 + 1 + A^-4");
+    }
+
+    #[test]
+    fn trefoil() {
+        use super::{colored_jones, Braid};
+        use crate::unstable::coefficients::Laurent;
+        /*
+        the closure of sigma_1^3 is a trefoil
+        dividing by the unknot gives its Jones polynomial in t = A^{-4}
+            t + t^3 - t^4
+        */
+        let trefoil = Braid::new(2, vec![(0, true); 3]).unwrap();
+        let delta = -Laurent::monomial(1, 2) - Laurent::monomial(1, -2);
+        let jones = colored_jones(&trefoil, 1).exact_div(&delta).unwrap();
+        assert_eq!(
+            jones,
+            Laurent::monomial(1, -4) + Laurent::monomial(1, -12) - Laurent::monomial(1, -16)
+        );
+        /*
+        the mirror image has A and A^{-1} swapped
+        and the figure eight knot is its own mirror image
+        */
+        for color in 1..3 {
+            assert_eq!(
+                colored_jones(&trefoil.mirror(), color),
+                colored_jones(&trefoil, color).bar()
+            );
+        }
+        let figure_eight =
+            Braid::new(3, vec![(0, true), (1, false), (0, true), (1, false)]).unwrap();
+        let figure_eight_jones = colored_jones(&figure_eight, 2);
+        assert_eq!(figure_eight_jones, figure_eight_jones.bar());
+        assert_ne!(colored_jones(&trefoil, 2), colored_jones(&trefoil, 2).bar());
+    }
+
+    #[test]
+    fn cabled_braids() {
+        use super::Braid;
+        /*
+        cabling the braid and then forgetting the crossings
+        is the same as cabling the permutation
+        */
+        let braid = Braid::new(3, vec![(0, true), (1, false), (0, true)]).unwrap();
+        for k in 0..4 {
+            let cabled = braid.cable(k);
+            assert_eq!(cabled.strands(), 3 * k);
+            assert_eq!(cabled.writhe(), braid.writhe() * (k * k) as i64);
+            assert_eq!(
+                cabled.underlying_permutation::<i32>(),
+                braid.underlying_permutation::<i32>().cable(k)
+            );
+        }
+        assert!(Braid::new(2, vec![(1, true)]).is_err());
+    }
+}
@@ -0,0 +1,491 @@
+use {
+    num::{rational::Ratio, BigInt, Complex, Integer, Num, One, Zero},
+    std::{
+        collections::BTreeMap,
+        fmt::{self, Display},
+        ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+    },
+};
+
+/*
+integers mod P stored as the representative in 0..P
+division uses Fermat's little theorem so P should be prime for Div to be meaningful
+everything else works for any P > 1
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub struct Zp<const P: u64>(u64);
+
+impl<const P: u64> Zp<P> {
+    pub fn new(value: u64) -> Self {
+        Self(value % P)
+    }
+
+    #[allow(dead_code)]
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(&self, mut exponent: u64) -> Self {
+        /*
+        square and multiply
+        */
+        let mut base = *self;
+        let mut answer = Self::one();
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                answer *= base;
+            }
+            base *= base;
+            exponent /= 2;
+        }
+        answer
+    }
+
+    #[allow(dead_code)]
+    pub fn inverse(&self) -> Option<Self> {
+        /*
+        a^{P-2} is the inverse of a nonzero a when P is prime
+        */
+        if self.is_zero() {
+            return None;
+        }
+        let candidate = self.pow(P - 2);
+        (candidate * *self == Self::one()).then_some(candidate)
+    }
+}
+
+impl<const P: u64> From<i64> for Zp<P> {
+    fn from(value: i64) -> Self {
+        Self((value as i128).rem_euclid(P as i128) as u64)
+    }
+}
+
+impl<const P: u64> Display for Zp<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} mod {}", self.0, P)
+    }
+}
+
+impl<const P: u64> Add for Zp<P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 + rhs.0 as u128) % P as u128) as u64)
+    }
+}
+
+impl<const P: u64> Sub for Zp<P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl<const P: u64> Neg for Zp<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self((P - self.0) % P)
+    }
+}
+
+impl<const P: u64> Mul for Zp<P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 * rhs.0 as u128) % P as u128) as u64)
+    }
+}
+
+impl<const P: u64> Div for Zp<P> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        let inverse = rhs
+            .inverse()
+            .unwrap_or_else(|| panic!("{} is not invertible", rhs));
+        self * inverse
+    }
+}
+
+impl<const P: u64> Rem for Zp<P> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        /*
+        in a field every division is exact so the remainder is 0
+        */
+        if rhs.is_zero() {
+            panic!("Remainder by 0 mod {}", P);
+        }
+        Self::zero()
+    }
+}
+
+impl<const P: u64> AddAssign for Zp<P> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const P: u64> SubAssign for Zp<P> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const P: u64> MulAssign for Zp<P> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const P: u64> DivAssign for Zp<P> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const P: u64> Zero for Zp<P> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const P: u64> One for Zp<P> {
+    fn one() -> Self {
+        Self(1 % P)
+    }
+}
+
+impl<const P: u64> Num for Zp<P> {
+    type FromStrRadixErr = std::num::ParseIntError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        u64::from_str_radix(s, radix).map(Self::new)
+    }
+}
+
+/*
+Laurent polynomials in one variable A with integer coefficients
+stored as exponent -> coefficient with no zero coefficients
+so equal polynomials have equal representations
+*/
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Laurent(BTreeMap<i32, i64>);
+
+impl Laurent {
+    pub fn monomial(coeff: i64, exponent: i32) -> Self {
+        let mut answer = Self::zero();
+        answer.add_term(exponent, coeff);
+        answer
+    }
+
+    #[allow(dead_code)]
+    pub fn variable() -> Self {
+        Self::monomial(1, 1)
+    }
+
+    #[allow(dead_code)]
+    pub fn coeff(&self, exponent: i32) -> i64 {
+        self.0.get(&exponent).copied().unwrap_or(0)
+    }
+
+    fn add_term(&mut self, exponent: i32, coeff: i64) {
+        let new_coeff = self.coeff(exponent) + coeff;
+        if new_coeff == 0 {
+            self.0.remove(&exponent);
+        } else {
+            self.0.insert(exponent, new_coeff);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn bar(&self) -> Self {
+        /*
+        A goes to A^{-1}
+        this fixes delta = -A^2 - A^{-2} and takes the Kauffman bracket of a link to that of its mirror image
+        */
+        Self(
+            self.0
+                .iter()
+                .map(|(exponent, coeff)| (-exponent, *coeff))
+                .collect(),
+        )
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> Self {
+        /*
+        square and multiply
+        */
+        let mut base = self.clone();
+        let mut answer = Self::one();
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                answer *= base.clone();
+            }
+            base *= base.clone();
+            exponent /= 2;
+        }
+        answer
+    }
+
+    pub fn exact_div(&self, rhs: &Self) -> Option<Self> {
+        /*
+        the q with q*rhs = self if there is one with integer coefficients
+        long division from the top exponent down
+        the exponents of q can not go below the difference of the lowest exponents
+            so needing to go below that means rhs did not divide self
+        */
+        let (&rhs_top, &rhs_lead) = rhs.0.iter().next_back()?;
+        let &rhs_bottom = rhs.0.keys().next()?;
+        let Some(&self_bottom) = self.0.keys().next() else {
+            return Some(Self::zero());
+        };
+        let mut remainder = self.clone();
+        let mut quotient = Self::zero();
+        while let Some((&top, &lead)) = remainder.0.iter().next_back() {
+            let exponent = top - rhs_top;
+            if lead % rhs_lead != 0 || exponent < self_bottom - rhs_bottom {
+                return None;
+            }
+            let term = Self::monomial(lead / rhs_lead, exponent);
+            remainder -= rhs.clone() * term.clone();
+            quotient += term;
+        }
+        Some(quotient)
+    }
+}
+
+impl Display for Laurent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /*
+        highest exponent first, like 2A^3 - 1 + A^-4
+        */
+        if self.0.is_empty() {
+            return write!(f, "0");
+        }
+        for (idx, (exponent, coeff)) in self.0.iter().rev().enumerate() {
+            let magnitude = coeff.abs();
+            if idx == 0 {
+                if *coeff < 0 {
+                    write!(f, "-")?;
+                }
+            } else {
+                write!(f, " {} ", if *coeff < 0 { '-' } else { '+' })?;
+            }
+            match (*exponent, magnitude) {
+                (0, _) => write!(f, "{}", magnitude)?,
+                (1, 1) => write!(f, "A")?,
+                (1, _) => write!(f, "{}A", magnitude)?,
+                (_, 1) => write!(f, "A^{}", exponent)?,
+                _ => write!(f, "{}A^{}", magnitude, exponent)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Add for Laurent {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl Sub for Laurent {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl Neg for Laurent {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(
+            self.0
+                .into_iter()
+                .map(|(exponent, coeff)| (exponent, -coeff))
+                .collect(),
+        )
+    }
+}
+
+impl Mul for Laurent {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut answer = Self::zero();
+        for (e1, c1) in &self.0 {
+            for (e2, c2) in &rhs.0 {
+                answer.add_term(e1 + e2, c1 * c2);
+            }
+        }
+        answer
+    }
+}
+
+impl AddAssign for Laurent {
+    fn add_assign(&mut self, rhs: Self) {
+        for (exponent, coeff) in rhs.0 {
+            self.add_term(exponent, coeff);
+        }
+    }
+}
+
+impl SubAssign for Laurent {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self += -rhs;
+    }
+}
+
+impl MulAssign for Laurent {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = std::mem::take(self) * rhs;
+    }
+}
+
+impl Zero for Laurent {
+    fn zero() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl One for Laurent {
+    fn one() -> Self {
+        Self::monomial(1, 0)
+    }
+}
+
+/*
+the involution on coefficients that a dagger applies along with flipping the diagram
+complex conjugation for the complex numbers and the identity for anything real
+*/
+pub trait Conjugate {
+    fn conjugate(&self) -> Self;
+}
+
+macro_rules! real_conjugate {
+    ($($t:ty),*) => {
+        $(
+            impl Conjugate for $t {
+                fn conjugate(&self) -> Self {
+                    self.clone()
+                }
+            }
+        )*
+    };
+}
+
+real_conjugate!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, BigInt);
+
+impl<T: Clone + Integer> Conjugate for Ratio<T> {
+    fn conjugate(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<T: Clone + Num + Neg<Output = T>> Conjugate for Complex<T> {
+    fn conjugate(&self) -> Self {
+        self.conj()
+    }
+}
+
+impl<const P: u64> Conjugate for Zp<P> {
+    fn conjugate(&self) -> Self {
+        *self
+    }
+}
+
+mod test {
+
+    #[test]
+    fn arithmetic() {
+        use super::Zp;
+        use num::{Num, One, Zero};
+        type F7 = Zp<7>;
+        assert_eq!(F7::new(5) + F7::new(4), F7::new(2));
+        assert_eq!(F7::new(2) - F7::new(5), F7::new(4));
+        assert_eq!(-F7::new(3), F7::new(4));
+        assert_eq!(-F7::zero(), F7::zero());
+        assert_eq!(F7::new(3) * F7::new(5), F7::one());
+        assert_eq!(F7::new(3).inverse(), Some(F7::new(5)));
+        assert_eq!(F7::zero().inverse(), None);
+        assert_eq!(F7::new(6) / F7::new(3), F7::new(2));
+        assert_eq!(F7::from(-1), F7::new(6));
+        assert_eq!(F7::new(3).pow(6), F7::one());
+        assert_eq!(F7::from_str_radix("15", 10), Ok(F7::new(1)));
+        assert_eq!(F7::new(4).to_string(), "4 mod 7");
+        assert_eq!(Zp::<6>::new(2).inverse(), None);
+        type Big = Zp<18446744073709551557>;
+        assert_eq!(Big::from(-1) * Big::from(-1), Big::one());
+    }
+
+    #[test]
+    fn laurent_polynomials() {
+        use super::Laurent;
+        use num::{One, Zero};
+        let a = Laurent::variable();
+        let a_inv = Laurent::monomial(1, -1);
+        assert_eq!(a.clone() * a_inv.clone(), Laurent::one());
+        let delta = -a.pow(2) - a_inv.pow(2);
+        assert_eq!(delta.to_string(), "-A^2 - A^-2");
+        assert_eq!(delta.bar(), delta);
+        let squared = delta.clone() * delta.clone();
+        assert_eq!(squared.coeff(0), 2);
+        assert_eq!(squared.exact_div(&delta), Some(delta.clone()));
+        assert_eq!((squared.clone() + Laurent::one()).exact_div(&delta), None);
+        assert_eq!(
+            (a.clone() - a.clone()).exact_div(&delta),
+            Some(Laurent::zero())
+        );
+        assert_eq!(squared.exact_div(&Laurent::zero()), None);
+        assert_eq!(
+            (a.clone() * Laurent::monomial(3, 0) + a_inv).to_string(),
+            "3A + A^-1"
+        );
+    }
+
+    #[test]
+    fn characteristic_p_diagrams() {
+        use super::Zp;
+        use crate::{
+            category::Composable,
+            unstable::{linear_combination::LinearCombination, temperley_lieb::BrauerMorphism},
+        };
+        use num::{One, Zero};
+        type F7 = Zp<7>;
+        let a: LinearCombination<F7, &str> = [("x", F7::new(3))].into_iter().collect();
+        let mut vanishes = a.clone() * F7::new(7) + a * F7::zero();
+        vanishes.simplify();
+        assert!(vanishes.is_zero());
+        /*
+        e_i e_i = delta e_i so at delta = 7 which is 0 mod 7 each e_i squares to 0
+        */
+        let e_1 = BrauerMorphism::<F7>::temperley_lieb_gen(0, 3);
+        let squared = e_1.compose(&e_1).unwrap().specialize_delta(F7::new(7));
+        assert_eq!(
+            squared.to_compact_string(),
+            "TL(3→3): 0 mod 7·d^0·[0-1|2-5|3-4]"
+        );
+        assert_eq!(e_1.kernel(F7::zero()).unwrap().len(), 3);
+        assert_eq!(e_1.image(F7::zero()).unwrap().len(), 2);
+        assert_eq!(e_1.fixed_points(F7::zero()).unwrap().len(), 0);
+        assert_eq!(e_1.fixed_points(F7::one()).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,195 @@
+use {
+    crate::unstable::{linear_algebra, linear_combination::LinearCombination},
+    num::{Num, One, Zero},
+    std::{
+        collections::HashSet,
+        hash::Hash,
+        ops::{AddAssign, Mul, MulAssign},
+    },
+};
+
+/*
+a free module over R with a chosen basis indexed by Basis
+adding, scaling and zero come from the supertraits
+and every vector breaks up into finitely many basis elements with their coefficients
+so algorithms only need this rather than a particular way of storing the terms
+*/
+pub trait FreeModule<R: Clone>: Clone + Zero + Mul<R, Output = Self> {
+    type Basis: Clone + Eq + Hash;
+
+    fn basis_vector(b: Self::Basis) -> Self;
+    fn coefficient(&self, b: &Self::Basis) -> R;
+    fn basis_terms<'a>(&'a self) -> impl Iterator<Item = (&'a Self::Basis, &'a R)>
+    where
+        Self::Basis: 'a,
+        R: 'a;
+
+    fn scale(self, r: R) -> Self {
+        self * r
+    }
+
+    fn coordinates(&self, basis: &[Self::Basis]) -> Vec<R> {
+        /*
+        the coefficients of each of the given basis elements in order
+        anything not in the list is ignored
+        */
+        basis.iter().map(|b| self.coefficient(b)).collect()
+    }
+
+    fn from_coordinates(basis: &[Self::Basis], coordinates: Vec<R>) -> Self {
+        basis
+            .iter()
+            .cloned()
+            .zip(coordinates)
+            .fold(Self::zero(), |acc, (b, c)| acc + Self::basis_vector(b) * c)
+    }
+}
+
+impl<R, T> FreeModule<R> for LinearCombination<R, T>
+where
+    R: Clone + Zero + One + AddAssign + MulAssign,
+    T: Clone + Eq + Hash,
+{
+    type Basis = T;
+
+    fn basis_vector(b: T) -> Self {
+        Self::singleton(b)
+    }
+
+    fn coefficient(&self, b: &T) -> R {
+        self.get(b).cloned().unwrap_or_else(R::zero)
+    }
+
+    fn basis_terms<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a R)>
+    where
+        T: 'a,
+        R: 'a,
+    {
+        self.iter()
+    }
+}
+
+#[allow(dead_code)]
+pub fn coordinate_matrix<R, M>(vectors: &[M]) -> (Vec<M::Basis>, Vec<Vec<R>>)
+where
+    R: Clone,
+    M: FreeModule<R>,
+{
+    /*
+    all the basis elements that show up in any of the vectors in order of first appearance
+    and the matrix whose column j is the coordinates of vectors[j] in them
+    */
+    let mut seen = HashSet::new();
+    let mut basis = Vec::new();
+    for v in vectors {
+        for (b, _) in v.basis_terms() {
+            if seen.insert(b.clone()) {
+                basis.push(b.clone());
+            }
+        }
+    }
+    let columns: Vec<Vec<R>> = vectors.iter().map(|v| v.coordinates(&basis)).collect();
+    let matrix = (0..basis.len())
+        .map(|i| columns.iter().map(|col| col[i].clone()).collect())
+        .collect();
+    (basis, matrix)
+}
+
+#[allow(dead_code)]
+pub fn span<R, M>(vectors: &[M]) -> Result<Vec<M>, String>
+where
+    R: Num + Clone,
+    M: FreeModule<R>,
+{
+    /*
+    a linearly independent subset of the vectors with the same span
+    each one kept is not in the span of the ones before it
+    */
+    let (basis, matrix) = coordinate_matrix(vectors);
+    if basis.is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(linear_algebra::independent_columns(&matrix)?
+        .into_iter()
+        .map(|j| vectors[j].clone())
+        .collect())
+}
+
+#[allow(dead_code)]
+pub fn gram_schmidt<R, M, F>(vectors: &[M], inner_product: F) -> Vec<M>
+where
+    R: Num + Clone,
+    M: FreeModule<R>,
+    F: Fn(&M, &M) -> R,
+{
+    /*
+    orthogonal vectors with the same span without normalizing
+    so only field operations on R are needed
+    any vector in the span of the ones before it is dropped
+    */
+    let mut orthogonal: Vec<M> = Vec::with_capacity(vectors.len());
+    for v in vectors {
+        let mut u = v.clone();
+        for w in &orthogonal {
+            let projection = inner_product(v, w) / inner_product(w, w);
+            u = u + w.clone().scale(R::zero() - projection);
+        }
+        if !u.is_zero() {
+            orthogonal.push(u);
+        }
+    }
+    orthogonal
+}
+
+mod test {
+
+    #[test]
+    fn coordinates_and_span() {
+        use super::{coordinate_matrix, span, FreeModule};
+        use crate::unstable::linear_combination::LinearCombination;
+        use num::rational::Ratio;
+        type V = LinearCombination<Ratio<i64>, char>;
+        let r = |n| Ratio::from_integer(n);
+        let x: V = [('a', r(1)), ('b', r(2))].into_iter().collect();
+        let y: V = [('b', r(1)), ('c', r(-1))].into_iter().collect();
+        let z = x.clone() + y.clone().scale(r(3));
+        assert_eq!(z.coefficient(&'b'), r(5));
+        assert_eq!(z.coefficient(&'d'), r(0));
+        let (basis, matrix) = coordinate_matrix(&[x.clone(), y.clone(), z.clone()]);
+        assert_eq!(basis.len(), 3);
+        assert_eq!(matrix.len(), 3);
+        assert!(matrix.iter().all(|row| row.len() == 3));
+        let independent = span(&[x.clone(), y.clone(), z]).unwrap();
+        assert_eq!(independent, vec![x, y]);
+        assert!(span::<Ratio<i64>, V>(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn orthogonalizing() {
+        use super::{gram_schmidt, FreeModule};
+        use crate::unstable::linear_combination::LinearCombination;
+        use num::{rational::Ratio, One, Zero};
+        type V = LinearCombination<Ratio<i64>, usize>;
+        let r = |n| Ratio::from_integer(n);
+        let dot = |v: &V, w: &V| {
+            v.pair(w, |b1, b2| {
+                if b1 == b2 {
+                    Ratio::one()
+                } else {
+                    Ratio::zero()
+                }
+            })
+        };
+        let vectors: Vec<V> = vec![
+            [(0, r(1)), (1, r(1))].into_iter().collect(),
+            [(0, r(1)), (2, r(1))].into_iter().collect(),
+            [(1, r(-1)), (2, r(1))].into_iter().collect(),
+        ];
+        let orthogonal = gram_schmidt(&vectors, dot);
+        assert_eq!(orthogonal.len(), 2);
+        assert_eq!(dot(&orthogonal[0], &orthogonal[1]), r(0));
+        assert_eq!(orthogonal[0], vectors[0]);
+        assert_eq!(orthogonal[1].coefficient(&0), Ratio::new(1, 2));
+        assert_eq!(orthogonal[1].coefficient(&1), Ratio::new(-1, 2));
+    }
+}
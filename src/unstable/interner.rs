@@ -0,0 +1,75 @@
+use std::{collections::HashSet, hash::Hash, sync::Arc};
+
+/*
+hash-consing for basis elements
+equal values handed to intern come back as the same Arc
+so terms that show up over and over across many linear combinations are only stored once
+Arc<T> compares and hashes by the value, so it can be the Target of a LinearCombination directly
+*/
+#[derive(Debug, Default)]
+pub struct Interner<T: Eq + Hash> {
+    pool: HashSet<Arc<T>>,
+}
+
+impl<T: Eq + Hash> Interner<T> {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            pool: HashSet::new(),
+        }
+    }
+
+    pub fn intern(&mut self, value: T) -> Arc<T> {
+        /*
+        the shared copy of value, making one if this is the first time it is seen
+        */
+        if let Some(shared) = self.pool.get(&value) {
+            return shared.clone();
+        }
+        let shared = Arc::new(value);
+        self.pool.insert(shared.clone());
+        shared
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn collect_garbage(&mut self) {
+        /*
+        forget the values that nothing outside the interner is holding on to anymore
+        */
+        self.pool.retain(|shared| Arc::strong_count(shared) > 1);
+    }
+}
+
+mod test {
+
+    #[test]
+    fn sharing() {
+        use super::Interner;
+        use std::sync::Arc;
+        let mut interner = Interner::new();
+        assert!(interner.is_empty());
+        let first = interner.intern(vec![1, 2, 3]);
+        let second = interner.intern(vec![1, 2, 3]);
+        let other = interner.intern(vec![3, 2, 1]);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert_eq!(interner.len(), 2);
+        drop(other);
+        interner.collect_garbage();
+        assert_eq!(interner.len(), 1);
+        drop(first);
+        drop(second);
+        interner.collect_garbage();
+        assert!(interner.is_empty());
+    }
+}
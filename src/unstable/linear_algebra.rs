@@ -0,0 +1,374 @@
+use {
+    num::{Num, Signed},
+    std::fmt::Debug,
+};
+
+/*
+how many positive, negative and zero entries there are
+in a diagonal matrix congruent to a symmetric matrix
+by Sylvester's law of inertia this does not depend on which diagonalization was used
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub positive: usize,
+    pub negative: usize,
+    pub zero: usize,
+}
+
+impl Signature {
+    #[allow(dead_code)]
+    pub fn is_positive_semidefinite(&self) -> bool {
+        self.negative == 0
+    }
+
+    #[allow(dead_code)]
+    pub fn is_positive_definite(&self) -> bool {
+        self.negative == 0 && self.zero == 0
+    }
+}
+
+fn check_symmetric<F>(gram: &[Vec<F>]) -> Result<(), String>
+where
+    F: PartialEq + Debug,
+{
+    let n = gram.len();
+    if let Some(bad_row) = gram.iter().position(|row| row.len() != n) {
+        return Err(format!(
+            "Not a square matrix. Row {} had length {} instead of {}",
+            bad_row,
+            gram[bad_row].len(),
+            n
+        ));
+    }
+    for (i, row) in gram.iter().enumerate() {
+        for (j, entry) in row.iter().enumerate().skip(i + 1) {
+            if *entry != gram[j][i] {
+                return Err(format!(
+                    "Not a symmetric matrix. At ({},{}) there was {:?} vs {:?}",
+                    i, j, entry, gram[j][i]
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn signature<F>(gram: &[Vec<F>]) -> Result<Signature, String>
+where
+    F: Signed + Clone + Debug,
+{
+    /*
+    an LDL^T factorization of the symmetric matrix gram done with exact arithmetic
+    so F should be something like a Ratio rather than a float
+    each pivot is a diagonal entry that is nonzero
+    if all remaining diagonal entries are 0 but some off diagonal a_ij is not
+        then add row/column j to row/column i which is another congruence
+        making the new a_ii = 2 a_ij nonzero
+    the signs of the pivots give the signature
+    */
+    check_symmetric(gram)?;
+    let mut a = gram.to_vec();
+    let mut remaining: Vec<usize> = (0..a.len()).collect();
+    let mut answer = Signature {
+        positive: 0,
+        negative: 0,
+        zero: 0,
+    };
+    while !remaining.is_empty() {
+        if let Some(pivot_loc) = remaining.iter().position(|i| !a[*i][*i].is_zero()) {
+            let p = remaining.swap_remove(pivot_loc);
+            let d = a[p][p].clone();
+            if d.is_positive() {
+                answer.positive += 1;
+            } else {
+                answer.negative += 1;
+            }
+            for i in remaining.iter() {
+                let multiplier = a[*i][p].clone() / d.clone();
+                for j in remaining.iter() {
+                    a[*i][*j] = a[*i][*j].clone() - multiplier.clone() * a[p][*j].clone();
+                }
+            }
+            continue;
+        }
+        let off_diagonal = remaining
+            .iter()
+            .flat_map(|i| remaining.iter().map(move |j| (*i, *j)))
+            .find(|(i, j)| !a[*i][*j].is_zero());
+        let Some((i, j)) = off_diagonal else {
+            answer.zero += remaining.len();
+            break;
+        };
+        for k in remaining.iter() {
+            a[i][*k] = a[i][*k].clone() + a[j][*k].clone();
+        }
+        for k in remaining.iter() {
+            a[*k][i] = a[*k][i].clone() + a[*k][j].clone();
+        }
+    }
+    Ok(answer)
+}
+
+#[allow(dead_code)]
+pub fn is_positive_semidefinite<F>(gram: &[Vec<F>]) -> Result<bool, String>
+where
+    F: Signed + Clone + Debug,
+{
+    /*
+    is the symmetric matrix gram positive semidefinite
+    for a Gram matrix of a bilinear form, this is the question of whether that form is unitarizable
+    */
+    signature(gram).map(|s| s.is_positive_semidefinite())
+}
+
+fn check_rectangular<F>(matrix: &[Vec<F>]) -> Result<usize, String> {
+    /*
+    all rows the same length, which is then the number of columns
+    */
+    let columns = matrix.first().map_or(0, |row| row.len());
+    if let Some(bad_row) = matrix.iter().position(|row| row.len() != columns) {
+        return Err(format!(
+            "Not a matrix. Row {} had length {} instead of {}",
+            bad_row,
+            matrix[bad_row].len(),
+            columns
+        ));
+    }
+    Ok(columns)
+}
+
+fn row_reduce<F>(matrix: &[Vec<F>], columns: usize) -> (Vec<Vec<F>>, Vec<usize>)
+where
+    F: Num + Clone,
+{
+    /*
+    reduced row echelon form with exact arithmetic
+    and which columns have the pivots
+    */
+    let mut a = matrix.to_vec();
+    let mut pivot_columns = Vec::new();
+    let mut pivot_row = 0;
+    for col in 0..columns {
+        let Some(found) = (pivot_row..a.len()).find(|r| !a[*r][col].is_zero()) else {
+            continue;
+        };
+        a.swap(pivot_row, found);
+        let scale = a[pivot_row][col].clone();
+        for entry in a[pivot_row].iter_mut() {
+            *entry = entry.clone() / scale.clone();
+        }
+        let pivot_values = a[pivot_row].clone();
+        for (r, row) in a.iter_mut().enumerate() {
+            if r == pivot_row || row[col].is_zero() {
+                continue;
+            }
+            let multiplier = row[col].clone();
+            for (entry, pivot_value) in row.iter_mut().zip(pivot_values.iter()) {
+                *entry = entry.clone() - multiplier.clone() * pivot_value.clone();
+            }
+        }
+        pivot_columns.push(col);
+        pivot_row += 1;
+    }
+    (a, pivot_columns)
+}
+
+#[allow(dead_code)]
+pub fn kernel<F>(matrix: &[Vec<F>]) -> Result<Vec<Vec<F>>, String>
+where
+    F: Num + Clone,
+{
+    /*
+    a basis for the vectors v with matrix * v = 0
+    one for each column without a pivot
+    */
+    let columns = check_rectangular(matrix)?;
+    Ok(kernel_with_columns(matrix, columns))
+}
+
+#[allow(dead_code)]
+pub fn kernel_with_columns<F>(matrix: &[Vec<F>], columns: usize) -> Vec<Vec<F>>
+where
+    F: Num + Clone,
+{
+    /*
+    the same as kernel but saying how many columns there are
+    so that a matrix with no rows still has a kernel of the right size
+    the rows must already all have that length
+    */
+    let (reduced, pivot_columns) = row_reduce(matrix, columns);
+    let free_columns = (0..columns).filter(|c| !pivot_columns.contains(c));
+    free_columns
+        .map(|free| {
+            let mut v = vec![F::zero(); columns];
+            v[free] = F::one();
+            for (row, pivot) in pivot_columns.iter().enumerate() {
+                v[*pivot] = F::zero() - reduced[row][free].clone();
+            }
+            v
+        })
+        .collect()
+}
+
+#[allow(dead_code)]
+pub fn row_space<F>(matrix: &[Vec<F>], columns: usize) -> Vec<Vec<F>>
+where
+    F: Num + Clone,
+{
+    /*
+    the nonzero rows of the reduced row echelon form
+    two matrices have the same row space exactly when these are equal
+    the rows must already all have length columns
+    */
+    let (mut reduced, pivot_columns) = row_reduce(matrix, columns);
+    reduced.truncate(pivot_columns.len());
+    reduced
+}
+
+#[allow(dead_code)]
+pub fn independent_columns<F>(matrix: &[Vec<F>]) -> Result<Vec<usize>, String>
+where
+    F: Num + Clone,
+{
+    /*
+    the indices of the pivot columns
+    each is independent of the ones before it and together they span the image
+    */
+    let columns = check_rectangular(matrix)?;
+    let (_, pivot_columns) = row_reduce(matrix, columns);
+    Ok(pivot_columns)
+}
+
+#[allow(dead_code)]
+pub fn image<F>(matrix: &[Vec<F>]) -> Result<Vec<Vec<F>>, String>
+where
+    F: Num + Clone,
+{
+    /*
+    a basis for the span of the columns
+    given by the columns of the original matrix where the pivots are
+    */
+    Ok(independent_columns(matrix)?
+        .into_iter()
+        .map(|col| matrix.iter().map(|row| row[col].clone()).collect())
+        .collect())
+}
+
+#[allow(dead_code)]
+pub fn fixed_points<F>(matrix: &[Vec<F>]) -> Result<Vec<Vec<F>>, String>
+where
+    F: Num + Clone,
+{
+    /*
+    a basis for the vectors v with matrix * v = v
+    which is the kernel of matrix - 1
+    */
+    let columns = check_rectangular(matrix)?;
+    if columns != matrix.len() && !matrix.is_empty() {
+        return Err(format!(
+            "Not a square matrix. There were {} rows and {} columns",
+            matrix.len(),
+            columns
+        ));
+    }
+    let shifted: Vec<Vec<F>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(j, entry)| {
+                    if i == j {
+                        entry.clone() - F::one()
+                    } else {
+                        entry.clone()
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    kernel(&shifted)
+}
+
+mod test {
+
+    #[test]
+    fn small_signatures() {
+        use super::{is_positive_semidefinite, signature, Signature};
+        use num::rational::Ratio;
+        let r = |z: i64| Ratio::<i64>::from_integer(z);
+        let identity = vec![
+            vec![r(1), r(0), r(0)],
+            vec![r(0), r(1), r(0)],
+            vec![r(0), r(0), r(1)],
+        ];
+        assert_eq!(
+            signature(&identity),
+            Ok(Signature {
+                positive: 3,
+                negative: 0,
+                zero: 0
+            })
+        );
+        let hyperbolic = vec![vec![r(0), r(1)], vec![r(1), r(0)]];
+        assert_eq!(
+            signature(&hyperbolic),
+            Ok(Signature {
+                positive: 1,
+                negative: 1,
+                zero: 0
+            })
+        );
+        let rank_one = vec![vec![r(1), r(1)], vec![r(1), r(1)]];
+        assert_eq!(is_positive_semidefinite(&rank_one), Ok(true));
+        assert!(!signature(&rank_one).unwrap().is_positive_definite());
+        let not_symmetric = vec![vec![r(1), r(2)], vec![r(1), r(1)]];
+        assert!(signature(&not_symmetric).is_err());
+        let not_square = vec![vec![r(1), r(2)]];
+        assert!(signature(&not_square).is_err());
+    }
+
+    #[test]
+    fn kernel_and_image() {
+        use super::{fixed_points, image, kernel};
+        use num::rational::Ratio;
+        let r = |z: i64| Ratio::<i64>::from_integer(z);
+        let m = vec![vec![r(1), r(2), r(3)], vec![r(2), r(4), r(6)]];
+        let found_kernel = kernel(&m).unwrap();
+        assert_eq!(found_kernel.len(), 2);
+        for v in found_kernel.iter() {
+            for row in m.iter() {
+                let dot: Ratio<i64> = row.iter().zip(v).map(|(a, b)| a * b).sum();
+                assert_eq!(dot, r(0));
+            }
+        }
+        assert_eq!(image(&m), Ok(vec![vec![r(1), r(2)]]));
+        let projection = vec![vec![r(1), r(1)], vec![r(0), r(0)]];
+        assert_eq!(fixed_points(&projection), Ok(vec![vec![r(1), r(0)]]));
+        assert_eq!(kernel(&projection), Ok(vec![vec![r(-1), r(1)]]));
+        assert!(fixed_points(&m).is_err());
+        assert!(kernel(&[vec![r(1)], vec![]]).is_err());
+        let nothing: Vec<Vec<Ratio<i64>>> = vec![];
+        assert_eq!(kernel(&nothing), Ok(vec![]));
+    }
+
+    #[test]
+    fn tl_gram_matrix() {
+        use super::signature;
+        use num::rational::Ratio;
+        /*
+        the Gram matrix of the standard module of TL_4 with no through strands
+        in the basis of the 2 cup diagrams is [[d^2, d],[d, d^2]]
+        its determinant d^2(d^2-1) changes sign at d=1
+        */
+        for (d, expected_negative) in [(2, 0), (1, 0), (0, 0)] {
+            let d = Ratio::<i64>::from_integer(d);
+            let gram = vec![vec![d * d, d], vec![d, d * d]];
+            assert_eq!(signature(&gram).unwrap().negative, expected_negative);
+        }
+        let d = Ratio::<i64>::new(1, 2);
+        let gram = vec![vec![d * d, d], vec![d, d * d]];
+        let found = signature(&gram).unwrap();
+        assert_eq!((found.positive, found.negative, found.zero), (1, 1, 0));
+    }
+}
@@ -0,0 +1,1441 @@
+use {
+    crate::unstable::{interner::Interner, term_map::TermMap},
+    num::{complex::ComplexFloat, One, Zero},
+    std::{
+        cmp::Ordering,
+        fmt::{self, Debug, Display},
+        hash::Hash,
+        ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    },
+};
+
+/*
+a formal linear combination of terms from Target with coefficients drawn from Coeffs
+*/
+#[repr(transparent)]
+#[derive(PartialEq, Eq, Default, Clone)]
+pub struct LinearCombination<Coeffs: Clone, Target: Eq + Hash>(TermMap<Target, Coeffs>);
+
+/*
+with the parallel feature the products and linearly_extend split the terms of self across threads
+so the terms, coefficients and closures involved have to be Send and Sync
+without it every type qualifies and generic code can state the bound either way
+*/
+#[cfg(feature = "parallel")]
+pub trait MaybeSendSync: Send + Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> MaybeSendSync for T {}
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSendSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSendSync for T {}
+
+impl<Coeffs: Clone + Display, Target: Eq + Hash + Display> Display
+    for LinearCombination<Coeffs, Target>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_with(|t| t.to_string()))
+    }
+}
+
+impl<Coeffs: Clone + Debug, Target: Eq + Hash + Debug> Debug for LinearCombination<Coeffs, Target> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /*
+        the same as Display but with the Debug of everything
+        so test failures do not show the raw map in whatever order it happens to be in
+        */
+        write!(
+            f,
+            "LinearCombination({})",
+            self.format_terms(|t| format!("{:?}", t), |c| format!("{:?}", c))
+        )
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> FromIterator<(Target, Coeffs)>
+    for LinearCombination<Coeffs, Target>
+{
+    fn from_iter<T: IntoIterator<Item = (Target, Coeffs)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> IntoIterator for LinearCombination<Coeffs, Target> {
+    type Item = (Target, Coeffs);
+    type IntoIter = crate::unstable::term_map::IntoIter<Target, Coeffs>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, Coeffs: Clone, Target: Eq + Hash> IntoIterator for &'a LinearCombination<Coeffs, Target> {
+    type Item = (&'a Target, &'a Coeffs);
+    type IntoIter = crate::unstable::term_map::Iter<'a, Target, Coeffs>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> Add for LinearCombination<Coeffs, Target>
+where
+    Coeffs: AddAssign,
+{
+    /*
+    add two formal sums
+    */
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut new_map = self.0;
+        for (k, v) in rhs.0.into_iter() {
+            if let Some(x) = new_map.get_mut(&k) {
+                *x += v;
+            } else {
+                new_map.insert(k, v);
+            }
+        }
+        Self(new_map)
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> AddAssign for LinearCombination<Coeffs, Target>
+where
+    Coeffs: AddAssign,
+{
+    /*
+    add two formal sums
+    */
+    fn add_assign(&mut self, rhs: Self) {
+        for (k, v) in rhs.0.into_iter() {
+            if let Some(x) = self.0.get_mut(&k) {
+                *x += v;
+            } else {
+                self.0.insert(k, v);
+            }
+        }
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> Sub for LinearCombination<Coeffs, Target>
+where
+    Coeffs: SubAssign + Neg<Output = Coeffs>,
+{
+    /*
+    subtract two formal sums
+    */
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let mut new_map = self.0;
+        for (k, v) in rhs.0.into_iter() {
+            if let Some(x) = new_map.get_mut(&k) {
+                *x -= v;
+            } else {
+                new_map.insert(k, -v);
+            }
+        }
+        Self(new_map)
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> Neg for LinearCombination<Coeffs, Target>
+where
+    Coeffs: Neg<Output = Coeffs>,
+{
+    /*
+    negate a formal sum
+    */
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let mut new_map = self.0;
+        for val in new_map.values_mut() {
+            *val = -val.clone();
+        }
+        Self(new_map)
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> Mul<Coeffs> for LinearCombination<Coeffs, Target>
+where
+    Coeffs: MulAssign,
+{
+    /*
+    multiply a formal sum by a coefficient
+    */
+    type Output = Self;
+
+    fn mul(self, rhs: Coeffs) -> Self {
+        let mut new_map = self.0;
+        for val in new_map.values_mut() {
+            *val *= rhs.clone();
+        }
+        Self(new_map)
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash + Clone> Mul for LinearCombination<Coeffs, Target>
+where
+    Coeffs: AddAssign + Mul<Output = Coeffs> + Zero + MaybeSendSync,
+    Target: Mul<Output = Target> + MaybeSendSync,
+{
+    /*
+    multiply two formal sums provided the target has a multiplication operation
+    */
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.combine_by_ref(&rhs, |k1, k2| k1.clone() * k2.clone())
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> Div<Coeffs> for LinearCombination<Coeffs, Target>
+where
+    Coeffs: DivAssign,
+{
+    /*
+    divide a formal sum by a coefficient
+    only makes sense when the coefficients are something like a field
+    */
+    type Output = Self;
+
+    fn div(mut self, rhs: Coeffs) -> Self {
+        self /= rhs;
+        self
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> DivAssign<Coeffs> for LinearCombination<Coeffs, Target>
+where
+    Coeffs: DivAssign,
+{
+    /*
+    divide a formal sum by a coefficient
+    */
+    fn div_assign(&mut self, rhs: Coeffs) {
+        for val in self.0.values_mut() {
+            *val /= rhs.clone();
+        }
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> Zero for LinearCombination<Coeffs, Target>
+where
+    Coeffs: AddAssign + Zero,
+{
+    /*
+    the empty sum
+    something with all coefficients 0 also counts as zero even before simplify
+    */
+    fn zero() -> Self {
+        Self(TermMap::new())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.values().all(Zero::is_zero)
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash + Clone> One for LinearCombination<Coeffs, Target>
+where
+    Coeffs: AddAssign + Mul<Output = Coeffs> + Zero + One + MaybeSendSync,
+    Target: Mul<Output = Target> + One + MaybeSendSync,
+{
+    /*
+    the unit of the target with coefficient 1
+    */
+    fn one() -> Self {
+        Self::singleton(Target::one())
+    }
+}
+
+/*
+This would be a conflicting implementation of Mul for two LinearCombination's
+*/
+/*
+impl<Coeffs: Clone, Target: Eq + Hash + Clone> Mul for LinearCombination<Coeffs, Target>
+where
+    Coeffs: AddAssign + Mul<Output = Coeffs> + MulAssign + One,
+    Target: Mul<Output = LinearCombination<Coeffs,Target>>,
+{
+    /*
+    multiply two formal sums provided the target has a multiplication operation
+    that produces formal sums (usually singletons but does not have to be)
+    */
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut ret_val = Self(TermMap::new());
+        for (k1, c_k1) in self.0 {
+            for (k2, c_k2) in &rhs.0 {
+                ret_val += (k1.clone() * k2.clone()) * (c_k1.clone() * c_k2.clone());
+            }
+        }
+        ret_val
+    }
+}
+*/
+
+impl<Coeffs: Clone, Target: Eq + Hash> MulAssign<Coeffs> for LinearCombination<Coeffs, Target>
+where
+    Coeffs: MulAssign,
+{
+    /*
+    multiply a formal sum by a coefficient
+    */
+    fn mul_assign(&mut self, rhs: Coeffs) {
+        for val in self.0.values_mut() {
+            *val *= rhs.clone();
+        }
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> LinearCombination<Coeffs, Target> {
+    #[allow(dead_code)]
+    pub fn get(&self, target: &Target) -> Option<&Coeffs> {
+        /*
+        the coefficient of a particular term
+        a term that is present with coefficient 0 until simplify is called gives Some
+        */
+        self.0.get(target)
+    }
+
+    #[allow(dead_code)]
+    pub fn contains_term(&self, target: &Target) -> bool {
+        self.0.contains_key(target)
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        /*
+        how many terms are stored
+        including any with coefficient 0 until simplify is called
+        */
+        self.0.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        /*
+        no terms stored at all
+        stronger than is_zero which allows terms with coefficient 0
+        */
+        self.0.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Target> {
+        /*
+        the terms without their coefficients
+        */
+        self.0.keys()
+    }
+
+    #[allow(dead_code)]
+    pub fn values(&self) -> impl Iterator<Item = &Coeffs> {
+        /*
+        the coefficients without their terms
+        */
+        self.0.values()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Target, &Coeffs)> {
+        /*
+        the terms with their coefficients
+        in no particular order unless the ordered feature is on
+        */
+        self.0.iter()
+    }
+
+    #[allow(dead_code)]
+    pub fn tensor<U>(
+        &self,
+        rhs: &LinearCombination<Coeffs, U>,
+    ) -> LinearCombination<Coeffs, (Target, U)>
+    where
+        Coeffs: Mul<Output = Coeffs>,
+        Target: Clone,
+        U: Eq + Hash + Clone,
+    {
+        /*
+        the formal tensor product
+        every pair of terms with the product of their coefficients
+        the pairs are all different so nothing gets added together
+        */
+        let mut new_map = TermMap::with_capacity(self.0.len() * rhs.0.len());
+        for (k1, c_k1) in &self.0 {
+            for (k2, c_k2) in &rhs.0 {
+                new_map.insert((k1.clone(), k2.clone()), c_k1.clone() * c_k2.clone());
+            }
+        }
+        LinearCombination(new_map)
+    }
+
+    pub fn linear_combine<U, V, F>(
+        &self,
+        rhs: LinearCombination<Coeffs, U>,
+        combiner: F,
+    ) -> LinearCombination<Coeffs, V>
+    where
+        Coeffs: AddAssign + Mul<Output = Coeffs> + Zero + MaybeSendSync,
+        Target: Eq + Hash + Clone + MaybeSendSync,
+        U: Eq + Hash + Clone + MaybeSendSync,
+        V: Eq + Hash + MaybeSendSync,
+        F: Fn(Target, U) -> V + MaybeSendSync,
+    {
+        /*
+        given a linear combination of T's and a linear combination of U's
+        and an operation that acts like multiplication of T and U to produce V
+        perform the multiplication
+        */
+        self.combine_by_ref(&rhs, |k1, k2| combiner(k1.clone(), k2.clone()))
+    }
+
+    pub fn into_linear_combine<U, V, F>(
+        self,
+        rhs: LinearCombination<Coeffs, U>,
+        combiner: F,
+    ) -> LinearCombination<Coeffs, V>
+    where
+        Coeffs: AddAssign + Mul<Output = Coeffs> + Zero + MaybeSendSync,
+        Target: MaybeSendSync,
+        U: Eq + Hash + MaybeSendSync,
+        V: Eq + Hash + MaybeSendSync,
+        F: Fn(&Target, &U) -> V + MaybeSendSync,
+    {
+        /*
+        the same as linear_combine but using up both sides
+        the combiner only borrows the terms so none of them get cloned
+        and each product goes straight into the result rather than through a singleton
+        */
+        self.combine_by_ref(&rhs, combiner)
+    }
+
+    fn combine_by_ref<U, V, F>(
+        &self,
+        rhs: &LinearCombination<Coeffs, U>,
+        combiner: F,
+    ) -> LinearCombination<Coeffs, V>
+    where
+        Coeffs: AddAssign + Mul<Output = Coeffs> + Zero + MaybeSendSync,
+        Target: MaybeSendSync,
+        U: Eq + Hash + MaybeSendSync,
+        V: Eq + Hash + MaybeSendSync,
+        F: Fn(&Target, &U) -> V + MaybeSendSync,
+    {
+        /*
+        every product of a term of self with a term of rhs added up into one map
+        with the parallel feature the terms of self are split across threads
+        each thread builds up its own partial sum and those get added together at the end
+        */
+        #[cfg(not(feature = "parallel"))]
+        let new_map = {
+            let mut new_map = TermMap::with_capacity(self.0.len().max(rhs.0.len()));
+            for (k1, c_k1) in &self.0 {
+                for (k2, c_k2) in &rhs.0 {
+                    *new_map.get_or_insert_with(combiner(k1, k2), Coeffs::zero) +=
+                        c_k1.clone() * c_k2.clone();
+                }
+            }
+            new_map
+        };
+        #[cfg(feature = "parallel")]
+        let new_map = {
+            use rayon::prelude::*;
+            let lhs_terms: Vec<_> = self.0.iter().collect();
+            lhs_terms
+                .into_par_iter()
+                .fold(TermMap::new, |mut partial, (k1, c_k1)| {
+                    for (k2, c_k2) in &rhs.0 {
+                        *partial.get_or_insert_with(combiner(k1, k2), Coeffs::zero) +=
+                            c_k1.clone() * c_k2.clone();
+                    }
+                    partial
+                })
+                .reduce(TermMap::new, |mut total, partial| {
+                    for (k, v) in partial {
+                        *total.get_or_insert_with(k, Coeffs::zero) += v;
+                    }
+                    total
+                })
+        };
+        LinearCombination(new_map)
+    }
+
+    #[allow(dead_code)]
+    pub fn convolve<F>(&self, rhs: &Self, operation: F) -> Self
+    where
+        Coeffs: AddAssign + Mul<Output = Coeffs> + Zero + MaybeSendSync,
+        Target: MaybeSendSync,
+        F: Fn(&Target, &Target) -> Target + MaybeSendSync,
+    {
+        /*
+        the product in the monoid algebra where Target is a monoid under operation
+        (sum a_g g) * (sum b_h h) = sum a_g b_h operation(g,h)
+        for when the multiplication is not the Mul of Target
+        like composing permutations in the group algebra of a symmetric group
+        */
+        self.combine_by_ref(rhs, operation)
+    }
+
+    #[allow(dead_code)]
+    pub fn retain_terms<F>(&mut self, mut term_predicate: F)
+    where
+        F: FnMut(&Target, &Coeffs) -> bool,
+    {
+        /*
+        keep only the terms satisfying the predicate
+        */
+        self.0.retain(|t, c| term_predicate(t, c));
+    }
+
+    pub fn extract_terms<F>(&mut self, mut term_predicate: F) -> Self
+    where
+        F: FnMut(&Target, &Coeffs) -> bool,
+    {
+        /*
+        remove the terms satisfying the predicate and return them
+        self keeps everything else, so the two add back up to the original
+        */
+        let (extracted, kept): (TermMap<_, _>, TermMap<_, _>) = std::mem::take(&mut self.0)
+            .into_iter()
+            .partition(|(t, c)| term_predicate(t, c));
+        self.0 = kept;
+        Self(extracted)
+    }
+
+    pub fn partition_terms<F>(mut self, term_predicate: F) -> (Self, Self)
+    where
+        F: FnMut(&Target, &Coeffs) -> bool,
+    {
+        /*
+        the terms satisfying the predicate and the ones that do not
+        */
+        let satisfying = self.extract_terms(term_predicate);
+        (satisfying, self)
+    }
+
+    fn format_terms<F, G>(&self, term_formatter: F, coeff_formatter: G) -> String
+    where
+        F: Fn(&Target) -> String,
+        G: Fn(&Coeffs) -> String,
+    {
+        /*
+        coefficient·term for each term joined by +
+        sorted by the formatted term so the output does not depend on the order in the map
+        */
+        let mut terms: Vec<_> = self
+            .0
+            .iter()
+            .map(|(t, c)| (term_formatter(t), coeff_formatter(c)))
+            .collect();
+        if terms.is_empty() {
+            return "0".to_string();
+        }
+        terms.sort();
+        terms
+            .into_iter()
+            .map(|(t, c)| format!("{}·{}", c, t))
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    pub fn display_with<F>(&self, term_formatter: F) -> String
+    where
+        F: Fn(&Target) -> String,
+        Coeffs: Display,
+    {
+        /*
+        like 3·x + 2·y with each term written by term_formatter
+        for when Target has no Display or a different one is wanted
+        */
+        self.format_terms(term_formatter, |c| c.to_string())
+    }
+
+    #[allow(dead_code)]
+    pub fn intern_terms(
+        self,
+        interner: &mut Interner<Target>,
+    ) -> LinearCombination<Coeffs, std::sync::Arc<Target>> {
+        /*
+        the same combination but with each term replaced by its shared copy in interner
+        so combinations that have a lot of terms in common do not store them twice
+        */
+        LinearCombination(
+            self.0
+                .into_iter()
+                .map(|(t, c)| (interner.intern(t), c))
+                .collect(),
+        )
+    }
+
+    pub fn map_coeffs<Coeffs2: Clone, F>(
+        self,
+        coeff_changer: F,
+    ) -> LinearCombination<Coeffs2, Target>
+    where
+        F: Fn(Coeffs) -> Coeffs2,
+    {
+        /*
+        change all the coefficients by a function that may change their type
+        like including the integers into the rationals or reducing them mod p
+        should be a ring homomorphism so that this is the induced map R[Target] -> S[Target]
+        */
+        LinearCombination(
+            self.0
+                .into_iter()
+                .map(|(t, c)| (t, coeff_changer(c)))
+                .collect(),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn evaluate<M, F>(&self, f: F) -> M
+    where
+        F: Fn(&Target) -> M,
+        M: Zero + Mul<Coeffs, Output = M>,
+    {
+        /*
+        send each term to something in a module M over Coeffs
+        and sum up f(term)*coefficient
+        this is the linear map R[Target] -> M determined by where the basis goes
+        so a representation of the terms gives one of the combinations
+        */
+        self.0
+            .iter()
+            .fold(M::zero(), |acc, (t, c)| acc + f(t) * c.clone())
+    }
+}
+
+impl<Coeffs: Clone, Target: Eq + Hash> LinearCombination<Coeffs, Target>
+where
+    Coeffs: One,
+{
+    pub fn singleton(t: Target) -> Self {
+        /*
+        a single term with coefficient 1
+        */
+        Self([(t, <_>::one())].into())
+    }
+
+    pub fn change_coeffs<F>(&mut self, coeff_changer: F)
+    where
+        F: Fn(Coeffs) -> Coeffs,
+    {
+        /*
+        change all the coefficients by a function
+        should be by some endomorphism of a coefficient ring
+        so that this is the induced on endomorphism on R[Target]
+        */
+        for val in self.0.values_mut() {
+            *val = coeff_changer(val.clone());
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn try_change_coeffs<E, F>(&mut self, coeff_changer: F) -> Result<(), E>
+    where
+        F: Fn(Coeffs) -> Result<Coeffs, E>,
+    {
+        /*
+        same as change_coeffs but for functions that can fail
+        like exact division or narrowing to a smaller integer type
+        the first error is given back and then self is left as it was
+        */
+        let changed = self
+            .0
+            .values()
+            .map(|c| coeff_changer(c.clone()))
+            .collect::<Result<Vec<_>, E>>()?;
+        for (val, new_val) in self.0.values_mut().zip(changed) {
+            *val = new_val;
+        }
+        Ok(())
+    }
+
+    pub fn all_terms_satisfy<F>(&self, term_predicate: F) -> bool
+    where
+        F: Fn(&Target) -> bool,
+    {
+        /*
+        do all the terms without their coefficients
+        satisfy some predicate
+        */
+        self.0.keys().all(term_predicate)
+    }
+}
+
+impl<Coeffs: Clone + Zero, Target: Eq + Hash> LinearCombination<Coeffs, Target> {
+    pub fn simplify(&mut self) {
+        /*
+        get rid of all the terms that have 0 coefficient
+        */
+        self.0.retain(|_, v| !v.is_zero());
+    }
+
+    #[allow(dead_code)]
+    pub fn pair<U, F>(&self, rhs: &LinearCombination<Coeffs, U>, form: F) -> Coeffs
+    where
+        Coeffs: Mul<Output = Coeffs>,
+        U: Eq + Hash,
+        F: Fn(&Target, &U) -> Coeffs,
+    {
+        /*
+        the bilinear pairing of sum c_i b_i and sum d_j b'_j
+        given by sum c_i d_j form(b_i, b'_j)
+        computed directly without building up the product combination
+        */
+        let mut answer = Coeffs::zero();
+        for (k1, c_k1) in &self.0 {
+            for (k2, c_k2) in &rhs.0 {
+                answer = answer + c_k1.clone() * c_k2.clone() * form(k1, k2);
+            }
+        }
+        answer
+    }
+
+    #[allow(dead_code)]
+    pub fn support(&self) -> impl Iterator<Item = &Target> {
+        /*
+        the terms with nonzero coefficient
+        */
+        self.0.iter().filter(|(_, c)| !c.is_zero()).map(|(t, _)| t)
+    }
+
+    #[allow(dead_code)]
+    pub fn sort_terms<F>(&self, order: F) -> Vec<(&Target, &Coeffs)>
+    where
+        F: Fn(&Target, &Target) -> Ordering,
+    {
+        /*
+        the terms with nonzero coefficients from largest to smallest according to order
+        so the first is the leading term
+        */
+        let mut terms: Vec<_> = self.0.iter().filter(|(_, c)| !c.is_zero()).collect();
+        terms.sort_by(|(t1, _), (t2, _)| order(t2, t1));
+        terms
+    }
+
+    #[allow(dead_code)]
+    pub fn leading_term<F>(&self, order: F) -> Option<(&Target, &Coeffs)>
+    where
+        F: Fn(&Target, &Target) -> Ordering,
+    {
+        /*
+        the largest term with a nonzero coefficient according to order
+        None for the zero combination
+        */
+        self.0
+            .iter()
+            .filter(|(_, c)| !c.is_zero())
+            .max_by(|(t1, _), (t2, _)| order(t1, t2))
+    }
+
+    #[allow(dead_code)]
+    pub fn normalize(&mut self, by_term: &Target) -> Result<(), String>
+    where
+        Coeffs: DivAssign,
+    {
+        /*
+        divide everything by the coefficient of by_term
+        so that by_term has coefficient 1 afterwards
+        fails if by_term is not there or has coefficient 0
+        */
+        let Some(scale) = self.0.get(by_term).cloned() else {
+            return Err("The term to normalize by was not present".to_string());
+        };
+        if scale.is_zero() {
+            return Err("The term to normalize by had coefficient 0".to_string());
+        }
+        *self /= scale;
+        Ok(())
+    }
+}
+
+impl<Coeffs: Clone + Zero, Target: Clone + Eq + Hash> LinearCombination<Coeffs, Target> {
+    pub fn inj_linearly_extend<Target2: Eq + Hash, F>(
+        &self,
+        injection: F,
+    ) -> LinearCombination<Coeffs, Target2>
+    where
+        F: Fn(Target) -> Target2,
+    {
+        /*
+        do an injective map T1->T2 to induce a map
+        R[T1] -> R[T2]
+        */
+        self.try_inj_linearly_extend(injection)
+            .unwrap_or_else(|_| panic!("The function called injection should have been injective"))
+    }
+
+    pub fn try_inj_linearly_extend<Target2: Eq + Hash, F>(
+        &self,
+        injection: F,
+    ) -> Result<LinearCombination<Coeffs, Target2>, (Target, Target)>
+    where
+        F: Fn(Target) -> Target2,
+    {
+        /*
+        same as inj_linearly_extend but when the map is not injective on the terms present
+        give back two terms that got sent to the same place instead of panicking
+        */
+        let mut new_map: TermMap<Target2, Coeffs> = TermMap::with_capacity(self.0.len());
+        for (k, v) in self.0.iter() {
+            let new_key = injection(k.clone());
+            if new_map.contains_key(&new_key) {
+                let earlier = self
+                    .0
+                    .keys()
+                    .find(|other| *other != k && injection((*other).clone()) == new_key)
+                    .expect("Something was already sent to this term");
+                return Err((earlier.clone(), k.clone()));
+            }
+            new_map.insert(new_key, v.clone());
+        }
+        Ok(LinearCombination(new_map))
+    }
+
+    pub fn linearly_extend<Target2, F>(&self, f: F) -> LinearCombination<Coeffs, Target2>
+    where
+        F: Fn(Target) -> Target2 + MaybeSendSync,
+        Coeffs: Add<Output = Coeffs> + MaybeSendSync,
+        Target: MaybeSendSync,
+        Target2: Eq + Hash + MaybeSendSync,
+    {
+        /*
+        do a map T1->T2 (but this time not necessarily injective) to induce a map
+        R[T1] -> R[T2]
+        with the parallel feature the terms are mapped across threads and the partial sums added at the end
+        */
+        fn add_term<K: Eq + Hash, C: Clone + Add<Output = C>>(map: &mut TermMap<K, C>, k: K, v: C) {
+            if let Some(x) = map.get_mut(&k) {
+                *x = x.clone() + v;
+            } else {
+                map.insert(k, v);
+            }
+        }
+        #[cfg(not(feature = "parallel"))]
+        let new_map = {
+            let mut new_map = TermMap::with_capacity(self.0.len());
+            for (k, v) in self.0.iter() {
+                add_term(&mut new_map, f(k.clone()), v.clone());
+            }
+            new_map
+        };
+        #[cfg(feature = "parallel")]
+        let new_map = {
+            use rayon::prelude::*;
+            let terms: Vec<_> = self.0.iter().collect();
+            terms
+                .into_par_iter()
+                .fold(TermMap::new, |mut partial, (k, v)| {
+                    add_term(&mut partial, f(k.clone()), v.clone());
+                    partial
+                })
+                .reduce(TermMap::new, |mut total, partial| {
+                    for (k, v) in partial {
+                        add_term(&mut total, k, v);
+                    }
+                    total
+                })
+        };
+        LinearCombination(new_map)
+    }
+
+    pub fn antilinearly_extend<Target2: Eq + Hash, F, G>(
+        &self,
+        f: F,
+        conj: G,
+    ) -> LinearCombination<Coeffs, Target2>
+    where
+        F: Fn(Target) -> Target2,
+        G: Fn(Coeffs) -> Coeffs,
+        Coeffs: Add<Output = Coeffs>,
+    {
+        /*
+        do a map T1->T2 and conjugate every coefficient at the same time
+        so sum c_i t_i goes to sum conj(c_i) f(t_i)
+        for dagger like operations where both happen together
+        */
+        let mut new_map: TermMap<Target2, Coeffs> = TermMap::with_capacity(self.0.len());
+        for (k, v) in self.0.iter() {
+            let new_key = f(k.clone());
+            let new_val = conj(v.clone());
+            if let Some(x) = new_map.get_mut(&new_key) {
+                *x = x.clone() + new_val;
+            } else {
+                new_map.insert(new_key, new_val);
+            }
+        }
+        LinearCombination(new_map)
+    }
+}
+
+impl<Coeffs: ComplexFloat, Target: Eq + Hash> LinearCombination<Coeffs, Target> {
+    #[allow(dead_code)]
+    pub fn approx_eq(&self, other: &Self, tolerance: Coeffs::Real) -> bool {
+        /*
+        every term has coefficients within tolerance of each other
+        a term missing from one side counts as having coefficient 0 there
+        for floating point coefficients where rounding makes == too strict
+        */
+        let close = |a: Coeffs, b: Coeffs| (a - b).abs() <= tolerance;
+        self.0
+            .iter()
+            .all(|(t, c)| close(*c, other.0.get(t).copied().unwrap_or_else(Coeffs::zero)))
+            && other
+                .0
+                .iter()
+                .all(|(t, c)| self.0.contains_key(t) || close(*c, Coeffs::zero()))
+    }
+}
+
+impl<Coeffs: Clone, Target: Clone + Eq + Hash + Ord> LinearCombination<Coeffs, Target> {
+    #[allow(dead_code)]
+    pub fn to_sorted_vec(&self) -> Vec<(Target, Coeffs)> {
+        /*
+        all the (term, coefficient) sorted by term
+        the same combination always gives the same vector whatever order the map is in
+        zero coefficients are kept, so two combinations are equal exactly when these are
+        */
+        let mut terms: Vec<_> = self.0.iter().map(|(t, c)| (t.clone(), c.clone())).collect();
+        terms.sort_by(|(t1, _), (t2, _)| t1.cmp(t2));
+        terms
+    }
+}
+
+impl<Coeffs: Clone + Hash, Target: Eq + Hash + Ord> Hash for LinearCombination<Coeffs, Target> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        /*
+        hash the terms in sorted order so that equal combinations hash the same
+        */
+        let mut terms: Vec<_> = self.0.iter().collect();
+        terms.sort_by_key(|(t, _)| *t);
+        terms.hash(state);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Coeffs: Clone, Target: Eq + Hash> serde::Serialize for LinearCombination<Coeffs, Target>
+where
+    Coeffs: serde::Serialize,
+    Target: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /*
+        written out as a list of (term, coefficient)
+        rather than a map so that the terms do not have to be usable
+        as map keys in formats like JSON
+        */
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Coeffs: Clone, Target: Eq + Hash> serde::Deserialize<'de>
+    for LinearCombination<Coeffs, Target>
+where
+    Coeffs: serde::Deserialize<'de>,
+    Target: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /*
+        a term listed twice would otherwise silently keep only its last coefficient
+        */
+        let terms = Vec::<(Target, Coeffs)>::deserialize(deserializer)?;
+        let listed = terms.len();
+        let ret_val: Self = terms.into_iter().collect();
+        if ret_val.len() != listed {
+            return Err(serde::de::Error::custom(format!(
+                "{} terms were listed but only {} were distinct, each term can only be listed once",
+                listed,
+                ret_val.len()
+            )));
+        }
+        Ok(ret_val)
+    }
+}
+
+mod test {
+
+    #[test]
+    fn adding() {
+        use super::LinearCombination;
+        let one_a = LinearCombination::singleton("a".to_string());
+        let two_b = LinearCombination::singleton("b".to_string()) * 2;
+        let one_a_plus_two_b = one_a.clone() + two_b.clone();
+        let two_b_plus_one_a = two_b + one_a;
+        assert_eq!(one_a_plus_two_b, two_b_plus_one_a);
+        let mut zeroed = one_a_plus_two_b - two_b_plus_one_a;
+        zeroed.simplify();
+        assert!(zeroed.0.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn repeated_terms() {
+        use super::LinearCombination;
+        let read: LinearCombination<i32, char> =
+            serde_json::from_str(r#"[["a", 2], ["b", 3]]"#).unwrap();
+        assert_eq!(read, [('a', 2), ('b', 3)].into_iter().collect());
+        let repeated: Result<LinearCombination<i32, char>, _> =
+            serde_json::from_str(r#"[["a", 2], ["b", 3], ["a", 5]]"#);
+        assert!(repeated.is_err());
+    }
+
+    #[test]
+    fn iterating() {
+        use super::LinearCombination;
+        let combination: LinearCombination<i32, _> =
+            [("a", 3), ("b", -4), ("c", 0)].into_iter().collect();
+        let squared_norm: i32 = combination.values().map(|c| c * c).sum();
+        assert_eq!(squared_norm, 25);
+        let mut terms: Vec<_> = combination.keys().cloned().collect();
+        terms.sort();
+        assert_eq!(terms, vec!["a", "b", "c"]);
+        let mut borrowed = 0;
+        for (term, coeff) in &combination {
+            assert_eq!(combination.get(term), Some(coeff));
+            borrowed += 1;
+        }
+        assert_eq!(borrowed, combination.iter().count());
+        let mut owned: Vec<_> = combination.clone().into_iter().collect();
+        owned.sort();
+        assert_eq!(owned, vec![("a", 3), ("b", -4), ("c", 0)]);
+        let rebuilt: LinearCombination<i32, _> = combination.clone().into_iter().collect();
+        assert_eq!(rebuilt, combination);
+    }
+
+    #[test]
+    fn zero_and_one() {
+        use super::LinearCombination;
+        use num::{One, Zero};
+        /*
+        the monoid ring of the positive integers under multiplication
+        */
+        let x: LinearCombination<i32, u32> = [(2, 3), (5, -1)].into_iter().collect();
+        assert_eq!(x.clone() + LinearCombination::zero(), x);
+        assert_eq!(x.clone() * LinearCombination::one(), x);
+        assert_eq!(LinearCombination::one() * x.clone(), x);
+        assert!(!x.is_zero());
+        assert!((x.clone() - x.clone()).is_zero());
+        assert_eq!(LinearCombination::<i32, u32>::one().get(&1), Some(&1));
+        /*
+        used as the coefficients of another linear combination
+        */
+        let y: LinearCombination<LinearCombination<i32, u32>, &str> =
+            [("a", x.clone()), ("b", LinearCombination::zero())]
+                .into_iter()
+                .collect();
+        let doubled = y.clone() + y;
+        assert_eq!(doubled.get(&"a"), Some(&(x.clone() + x)));
+        assert!(doubled.get(&"b").unwrap().is_zero());
+    }
+
+    #[test]
+    fn tensoring() {
+        use super::LinearCombination;
+        let x: LinearCombination<i32, &str> = [("a", 2), ("b", 3)].into_iter().collect();
+        let y: LinearCombination<i32, u32> = [(1, 5), (2, -1), (3, 1)].into_iter().collect();
+        let x_y = x.tensor(&y);
+        assert_eq!(x_y.keys().count(), 6);
+        assert_eq!(x_y.get(&("a", 1)), Some(&10));
+        assert_eq!(x_y.get(&("b", 2)), Some(&-3));
+        /*
+        linear_combine is the tensor product followed by the combiner
+        */
+        let fused = x.linear_combine(y, |t, u| t.len() as u32 + u);
+        let via_tensor = x_y.linearly_extend(|(t, u)| t.len() as u32 + u);
+        assert_eq!(fused, via_tensor);
+    }
+
+    #[test]
+    fn dividing() {
+        use super::LinearCombination;
+        use num::rational::Ratio;
+        let r = |n: i64, d: i64| Ratio::new(n, d);
+        let x: LinearCombination<Ratio<i64>, &str> =
+            [("e", r(3, 1)), ("id", r(1, 2))].into_iter().collect();
+        let halved = x.clone() / r(2, 1);
+        assert_eq!(halved.get(&"e"), Some(&r(3, 2)));
+        assert_eq!(halved.clone() * r(2, 1), x);
+        let mut normalized = x.clone();
+        assert_eq!(normalized.normalize(&"e"), Ok(()));
+        assert_eq!(normalized.get(&"e"), Some(&r(1, 1)));
+        assert_eq!(normalized.get(&"id"), Some(&r(1, 6)));
+        let mut with_zero = x.clone() + [("z", r(0, 1))].into_iter().collect();
+        assert!(with_zero.normalize(&"z").is_err());
+        assert!(with_zero.normalize(&"missing").is_err());
+        assert_eq!(with_zero.get(&"e"), Some(&r(3, 1)));
+    }
+
+    #[test]
+    fn term_orders() {
+        use super::LinearCombination;
+        let x: LinearCombination<i32, &str> = [("bb", 2), ("a", -1), ("ccc", 0), ("dd", 5)]
+            .into_iter()
+            .collect();
+        let by_name = |s: &&str, t: &&str| s.cmp(t);
+        assert_eq!(x.leading_term(by_name), Some((&"dd", &5)));
+        assert_eq!(
+            x.sort_terms(by_name),
+            vec![(&"dd", &5), (&"bb", &2), (&"a", &-1)]
+        );
+        let by_length_then_name = |s: &&str, t: &&str| s.len().cmp(&t.len()).then(s.cmp(t));
+        assert_eq!(x.leading_term(by_length_then_name), Some((&"dd", &5)));
+        let reversed = |s: &&str, t: &&str| t.cmp(s);
+        assert_eq!(x.leading_term(reversed), Some((&"a", &-1)));
+        let zero: LinearCombination<i32, &str> = [("a", 0)].into_iter().collect();
+        assert_eq!(zero.leading_term(by_name), None);
+        assert!(zero.sort_terms(by_name).is_empty());
+    }
+
+    #[test]
+    fn lookup() {
+        use super::LinearCombination;
+        let a = "a".to_string();
+        let b = "b".to_string();
+        let mut combination = LinearCombination::singleton(a.clone()) * 3
+            + LinearCombination::singleton(b.clone()) * 2
+            - LinearCombination::singleton(b.clone()) * 2;
+        assert_eq!(combination.get(&a), Some(&3));
+        assert_eq!(combination.get(&b), Some(&0));
+        assert!(combination.contains_term(&b));
+        assert!(!combination.contains_term(&"c".to_string()));
+        combination.simplify();
+        assert_eq!(combination.get(&b), None);
+        assert!(!combination.contains_term(&b));
+    }
+
+    #[test]
+    fn term_order() {
+        use super::LinearCombination;
+        let terms = ["d", "a", "c", "b", "e"];
+        let combination: LinearCombination<i32, _> = terms.iter().map(|t| (*t, 1)).collect();
+        let mut doubled = combination.clone() + combination.clone();
+        doubled.simplify();
+        assert_eq!(doubled.keys().count(), terms.len());
+        if cfg!(feature = "ordered") {
+            assert_eq!(combination.keys().cloned().collect::<Vec<_>>(), terms);
+            assert_eq!(doubled.keys().cloned().collect::<Vec<_>>(), terms);
+        }
+        let reversed: LinearCombination<i32, _> = terms.iter().rev().map(|t| (*t, 1)).collect();
+        assert_eq!(reversed, combination);
+    }
+
+    #[test]
+    fn filtering_terms() {
+        use super::LinearCombination;
+        let combination: LinearCombination<i32, _> = [("a", 1), ("bb", 2), ("ccc", 3), ("dd", 0)]
+            .into_iter()
+            .collect();
+        let mut short = combination.clone();
+        short.retain_terms(|t, _| t.len() < 3);
+        assert_eq!(short.keys().count(), 3);
+        assert!(!short.contains_term(&"ccc"));
+        let mut rest = combination.clone();
+        let doubled_letters = rest.extract_terms(|t, _| t.len() == 2);
+        assert_eq!(doubled_letters.get(&"bb"), Some(&2));
+        assert_eq!(doubled_letters.get(&"dd"), Some(&0));
+        assert_eq!(rest.keys().count(), 2);
+        assert_eq!(doubled_letters + rest, combination.clone());
+        let (odd, even) = combination.clone().partition_terms(|_, c| c % 2 == 1);
+        assert_eq!(odd.keys().count(), 2);
+        assert_eq!(even.keys().count(), 2);
+        assert_eq!(odd + even, combination);
+    }
+
+    #[test]
+    fn mapping_coeffs() {
+        use super::LinearCombination;
+        use num::rational::Ratio;
+        let combination: LinearCombination<i32, _> = [("a", 3), ("b", -2)].into_iter().collect();
+        let halves = combination.clone().map_coeffs(|c| Ratio::new(c, 2));
+        assert_eq!(halves.get(&"a"), Some(&Ratio::new(3, 2)));
+        assert_eq!(halves.get(&"b"), Some(&Ratio::from_integer(-1)));
+        let floats = combination.map_coeffs(f64::from);
+        assert_eq!(floats.get(&"b"), Some(&-2.0));
+    }
+
+    #[test]
+    fn fallible_relabeling() {
+        use super::LinearCombination;
+        let combination: LinearCombination<i32, _> = [(1, 5), (2, 6), (4, 7)].into_iter().collect();
+        let shifted = combination.try_inj_linearly_extend(|t| t + 10).unwrap();
+        assert_eq!(shifted.get(&14), Some(&7));
+        assert_eq!(shifted, combination.inj_linearly_extend(|t| t + 10));
+        let collision = combination.try_inj_linearly_extend(|t| t % 3);
+        let Err((first, second)) = collision else {
+            panic!("1 and 4 are both 1 mod 3");
+        };
+        assert_eq!((first.min(second), first.max(second)), (1, 4));
+        let on_present_terms = combination.try_inj_linearly_extend(|t| t % 4);
+        assert!(on_present_terms.is_ok());
+    }
+
+    #[test]
+    fn displaying() {
+        use super::LinearCombination;
+        let combination: LinearCombination<i32, _> =
+            [("y", 2), ("x", 3), ("z", -1)].into_iter().collect();
+        assert_eq!(combination.to_string(), "3·x + 2·y + -1·z");
+        assert_eq!(
+            format!("{:?}", combination),
+            "LinearCombination(3·\"x\" + 2·\"y\" + -1·\"z\")"
+        );
+        assert_eq!(
+            combination.display_with(|t| t.to_uppercase()),
+            "3·X + 2·Y + -1·Z"
+        );
+        let pairs: LinearCombination<i32, (u8, u8)> =
+            [((1, 0), 1), ((0, 1), 4)].into_iter().collect();
+        assert_eq!(
+            pairs.display_with(|(a, b)| format!("e{}{}", a, b)),
+            "4·e01 + 1·e10"
+        );
+        assert_eq!(LinearCombination::<i32, &str>::default().to_string(), "0");
+    }
+
+    #[test]
+    fn sorted_export() {
+        use super::LinearCombination;
+        use std::collections::HashSet;
+        let forwards: LinearCombination<i32, _> =
+            [("b", 2), ("a", 1), ("c", 0)].into_iter().collect();
+        let backwards: LinearCombination<i32, _> =
+            [("c", 0), ("a", 1), ("b", 2)].into_iter().collect();
+        assert_eq!(forwards.to_sorted_vec(), vec![("a", 1), ("b", 2), ("c", 0)]);
+        assert_eq!(forwards.to_sorted_vec(), backwards.to_sorted_vec());
+        let mut seen = HashSet::new();
+        seen.insert(forwards.clone());
+        assert!(seen.contains(&backwards));
+        let mut simplified = forwards;
+        simplified.simplify();
+        assert_eq!(simplified.to_sorted_vec(), vec![("a", 1), ("b", 2)]);
+        assert!(!seen.contains(&simplified));
+    }
+
+    #[test]
+    fn support_size() {
+        use super::LinearCombination;
+        use num::Zero;
+        let mut combination: LinearCombination<i32, _> =
+            [("a", 1), ("b", 0), ("c", -3)].into_iter().collect();
+        assert_eq!(combination.len(), 3);
+        assert!(!combination.is_empty());
+        let mut support: Vec<_> = combination.support().cloned().collect();
+        support.sort();
+        assert_eq!(support, vec!["a", "c"]);
+        combination.simplify();
+        assert_eq!(combination.len(), 2);
+        let cancelled = combination.clone() - combination;
+        assert!(cancelled.is_zero());
+        assert!(!cancelled.is_empty());
+        assert_eq!(cancelled.support().count(), 0);
+        assert!(LinearCombination::<i32, &str>::zero().is_empty());
+    }
+
+    #[test]
+    fn many_term_products() {
+        /*
+        enough terms that with the parallel feature the work really is split up
+        */
+        use super::LinearCombination;
+        let lhs: LinearCombination<i64, i64> = (0..200).map(|k| (k, k % 7 - 3)).collect();
+        let rhs: LinearCombination<i64, i64> = (0..150).map(|k| (k * 3, k % 5 + 1)).collect();
+        let mut summed = LinearCombination::default();
+        let mut multiplied = LinearCombination::default();
+        for (k1, c_k1) in lhs.iter() {
+            for (k2, c_k2) in rhs.iter() {
+                summed += LinearCombination::singleton(k1 + k2) * (c_k1 * c_k2);
+                multiplied += LinearCombination::singleton(k1 * k2) * (c_k1 * c_k2);
+            }
+        }
+        assert_eq!(lhs.linear_combine(rhs.clone(), |a, b| a + b), summed);
+        assert_eq!(lhs.clone() * rhs, multiplied);
+        let mut reduced = LinearCombination::default();
+        for (k, c_k) in lhs.iter() {
+            reduced += LinearCombination::singleton(k % 11) * *c_k;
+        }
+        assert_eq!(lhs.linearly_extend(|k| k % 11), reduced);
+    }
+
+    #[test]
+    fn combining_by_reference() {
+        use super::LinearCombination;
+        let lhs: LinearCombination<i32, String> = [("a".to_string(), 2), ("b".to_string(), 3)]
+            .into_iter()
+            .collect();
+        let rhs: LinearCombination<i32, String> = [("c".to_string(), 5), ("".to_string(), -1)]
+            .into_iter()
+            .collect();
+        let expected = lhs.linear_combine(rhs.clone(), |x, y| x + &y);
+        let observed = lhs.into_linear_combine(rhs, |x, y| format!("{}{}", x, y));
+        assert_eq!(observed, expected);
+        assert_eq!(observed.get(&"bc".to_string()), Some(&15));
+        let collapsing: LinearCombination<i32, usize> = [(0, 1), (1, 1)].into_iter().collect();
+        let summed = collapsing
+            .clone()
+            .into_linear_combine(collapsing.clone(), |x, y| x + y);
+        assert_eq!(
+            summed,
+            collapsing.linear_combine(collapsing.clone(), |x, y| x + y)
+        );
+        assert_eq!(summed.get(&1), Some(&2));
+    }
+
+    #[test]
+    fn antilinear_extension() {
+        use super::LinearCombination;
+        use num::complex::Complex;
+        let combination: LinearCombination<Complex<i32>, i32> = [
+            (1, Complex::new(1, 2)),
+            (2, Complex::new(0, -1)),
+            (3, Complex::new(4, 0)),
+        ]
+        .into_iter()
+        .collect();
+        let extended = combination.antilinearly_extend(|k| k % 2, |c| c.conj());
+        assert_eq!(extended.get(&1), Some(&Complex::new(5, -2)));
+        assert_eq!(extended.get(&0), Some(&Complex::new(0, 1)));
+        let mut two_steps = combination.linearly_extend(|k| k % 2);
+        two_steps.change_coeffs(|c| c.conj());
+        assert_eq!(extended, two_steps);
+    }
+
+    #[test]
+    fn evaluating() {
+        use super::LinearCombination;
+        use num::complex::Complex;
+        /*
+        1 + 2x^2 - x^3 as powers of x with their coefficients
+        */
+        let polynomial: LinearCombination<f64, i32> =
+            [(0, 1.0), (2, 2.0), (3, -1.0)].into_iter().collect();
+        assert_eq!(polynomial.evaluate(|k| 2.0_f64.powi(*k)), 1.0);
+        let at_i: Complex<f64> = polynomial.evaluate(|k| Complex::i().powi(*k));
+        assert_eq!(at_i, Complex::new(-1.0, 1.0));
+        assert_eq!(
+            LinearCombination::<f64, i32>::default().evaluate(|_| 7.0),
+            0.0
+        );
+        /*
+        a change of basis is an evaluation into another linear combination
+        */
+        let words: LinearCombination<i32, &str> = [("ab", 2), ("b", 3)].into_iter().collect();
+        let letters: LinearCombination<i32, char> =
+            words.evaluate(|w| w.chars().map(|l| (l, 1)).collect());
+        assert_eq!(letters.get(&'a'), Some(&2));
+        assert_eq!(letters.get(&'b'), Some(&5));
+    }
+
+    #[test]
+    fn pairing() {
+        use super::LinearCombination;
+        let v: LinearCombination<i32, char> = [('a', 2), ('b', -1)].into_iter().collect();
+        let w: LinearCombination<i32, char> = [('a', 3), ('c', 5)].into_iter().collect();
+        let dot = |x: &char, y: &char| if x == y { 1 } else { 0 };
+        assert_eq!(v.pair(&w, dot), 6);
+        assert_eq!(v.pair(&v, dot), 5);
+        /*
+        a form between different bases
+        'a' paired with x^k gives k and 'b' paired with anything gives 1
+        so 2*2*1 + 2*1*3 - 1*2 - 1*1
+        */
+        let powers: LinearCombination<i32, u32> = [(1, 2), (3, 1)].into_iter().collect();
+        let pairing = v.pair(&powers, |x, k| if *x == 'a' { *k as i32 } else { 1 });
+        assert_eq!(pairing, 7);
+        assert_eq!(LinearCombination::<i32, char>::default().pair(&w, dot), 0);
+    }
+
+    #[test]
+    fn group_algebra() {
+        use super::LinearCombination;
+        type Perm = [usize; 3];
+        let then = |g: &Perm, h: &Perm| -> Perm { [h[g[0]], h[g[1]], h[g[2]]] };
+        let all: Vec<Perm> = vec![
+            [0, 1, 2],
+            [1, 0, 2],
+            [0, 2, 1],
+            [2, 1, 0],
+            [1, 2, 0],
+            [2, 0, 1],
+        ];
+        /*
+        the symmetrizer is 6 times an idempotent
+        */
+        let symmetrizer: LinearCombination<i32, Perm> = all.iter().map(|g| (*g, 1)).collect();
+        assert_eq!(
+            symmetrizer.convolve(&symmetrizer, then),
+            symmetrizer.clone() * 6
+        );
+        let s_1: LinearCombination<i32, Perm> = [([1, 0, 2], 1)].into_iter().collect();
+        let s_2: LinearCombination<i32, Perm> = [([0, 2, 1], 1)].into_iter().collect();
+        assert_ne!(s_1.convolve(&s_2, then), s_2.convolve(&s_1, then));
+        let one: LinearCombination<i32, Perm> = [([0, 1, 2], 1)].into_iter().collect();
+        assert_eq!(s_1.convolve(&one, then), s_1);
+        assert_eq!(s_1.convolve(&s_1, then), one);
+    }
+
+    #[test]
+    fn approximately_equal() {
+        use super::LinearCombination;
+        use num::complex::Complex;
+        let a: LinearCombination<f64, &str> = [("x", 0.1 + 0.2), ("y", 1.0)].into_iter().collect();
+        let b: LinearCombination<f64, &str> =
+            [("x", 0.3), ("y", 1.0), ("z", 1e-12)].into_iter().collect();
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, 1e-9));
+        assert!(b.approx_eq(&a, 1e-9));
+        assert!(!a.approx_eq(&b, 1e-15));
+        let c: LinearCombination<f64, &str> = [("x", 0.3)].into_iter().collect();
+        assert!(!a.approx_eq(&c, 1e-9));
+        let rotated: LinearCombination<Complex<f64>, &str> =
+            [("x", Complex::from_polar(1.0, std::f64::consts::FRAC_PI_2))]
+                .into_iter()
+                .collect();
+        let i: LinearCombination<Complex<f64>, &str> = [("x", Complex::i())].into_iter().collect();
+        assert!(rotated.approx_eq(&i, 1e-12));
+    }
+
+    #[test]
+    fn interning_terms() {
+        use super::LinearCombination;
+        use crate::unstable::interner::Interner;
+        use std::sync::Arc;
+        let mut interner = Interner::new();
+        let a: LinearCombination<i32, String> = [("long".to_string(), 1), ("x".to_string(), 2)]
+            .into_iter()
+            .collect();
+        let b: LinearCombination<i32, String> = [("long".to_string(), 5), ("y".to_string(), 2)]
+            .into_iter()
+            .collect();
+        let a_shared = a.clone().intern_terms(&mut interner);
+        let b_shared = b.intern_terms(&mut interner);
+        assert_eq!(interner.len(), 3);
+        let long = |c: &LinearCombination<i32, Arc<String>>| {
+            c.keys().find(|t| t.as_str() == "long").unwrap().clone()
+        };
+        assert!(Arc::ptr_eq(&long(&a_shared), &long(&b_shared)));
+        assert_eq!(a_shared.get(&Arc::new("x".to_string())), Some(&2));
+        assert_eq!(a_shared.inj_linearly_extend(|t| (*t).clone()), a);
+    }
+
+    #[test]
+    fn fallible_coeffs() {
+        use super::LinearCombination;
+        let mut combination: LinearCombination<i64, char> =
+            [('a', 6), ('b', -4), ('c', 10)].into_iter().collect();
+        let halve = |c: i64| if c % 2 == 0 { Ok(c / 2) } else { Err(c) };
+        assert_eq!(combination.try_change_coeffs(halve), Ok(()));
+        let halved: LinearCombination<i64, char> =
+            [('a', 3), ('b', -2), ('c', 5)].into_iter().collect();
+        assert_eq!(combination, halved);
+        assert!(combination.try_change_coeffs(halve).is_err());
+        assert_eq!(combination, halved);
+        let narrow = |c: i64| i8::try_from(c * 100).map(i64::from);
+        assert!(combination.try_change_coeffs(narrow).is_err());
+        assert_eq!(combination, halved);
+    }
+}
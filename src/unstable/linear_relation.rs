@@ -0,0 +1,477 @@
+use {
+    crate::{
+        category::{Composable, Dagger, HasIdentity},
+        error::Error,
+        frobenius::FrobeniusOperation,
+        matrix::MatrixMorphism,
+        monoidal::Monoidal,
+        symmetric_monoidal::SymmetricMonoidalDiscreteMorphism,
+        unstable::linear_algebra::{kernel_with_columns, row_space},
+    },
+    num::Num,
+    permutations::Permutation,
+};
+
+/*
+a linear relation from F^domain to F^codomain
+    which is a subspace of F^domain \oplus F^codomain
+kept as a basis of rows (x,y) with x the first domain entries
+    and the rest for the codomain
+the basis is the reduced row echelon form of the subspace
+    so two relations are equal exactly when the subspaces are
+the monoidal product is the direct sum
+    so the objects are dimensions and n wires carry n numbers as in graphical linear algebra
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LinearRelation<F> {
+    domain: usize,
+    codomain: usize,
+    basis: Vec<Vec<F>>,
+}
+
+impl<F> LinearRelation<F>
+where
+    F: Num + Clone,
+{
+    #[allow(dead_code)]
+    pub fn new(domain: usize, codomain: usize, spanning: Vec<Vec<F>>) -> Result<Self, Error> {
+        /*
+        the span of the given vectors each with domain+codomain entries
+        */
+        if let Some(bad) = spanning.iter().find(|v| v.len() != domain + codomain) {
+            return Err(Error::InterfaceLengthMismatch {
+                left: domain + codomain,
+                right: bad.len(),
+            });
+        }
+        Ok(Self {
+            domain,
+            codomain,
+            basis: row_space(&spanning, domain + codomain),
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn from_matrix(matrix: &MatrixMorphism<F>) -> Self {
+        /*
+        the graph of x -> x*matrix
+        so composing matrices goes to composing their graphs
+        */
+        let (domain, codomain) = (matrix.domain(), matrix.codomain());
+        let spanning = (0..domain)
+            .map(|row| {
+                let mut v = vec![F::zero(); domain];
+                v[row] = F::one();
+                v.extend(matrix.entries()[row].iter().cloned());
+                v
+            })
+            .collect::<Vec<_>>();
+        Self::new(domain, codomain, spanning).expect("lengths fit")
+    }
+
+    #[allow(dead_code)]
+    pub fn basis(&self) -> &[Vec<F>] {
+        &self.basis
+    }
+
+    #[allow(dead_code)]
+    pub fn dimension(&self) -> usize {
+        self.basis.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn relates(&self, source: &[F], target: &[F]) -> bool {
+        /*
+        whether (source, target) is in the subspace
+        which is when adding it does not make the span any bigger
+        */
+        if source.len() != self.domain || target.len() != self.codomain {
+            return false;
+        }
+        let mut spanning = self.basis.clone();
+        spanning.push(source.iter().chain(target).cloned().collect());
+        row_space(&spanning, self.domain + self.codomain).len() == self.basis.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_contained_in(&self, other: &Self) -> bool {
+        self.domain == other.domain
+            && self.codomain == other.codomain
+            && self
+                .basis
+                .iter()
+                .all(|v| other.relates(&v[..self.domain], &v[self.domain..]))
+    }
+
+    #[allow(dead_code)]
+    pub fn scalar(factor: F) -> Self {
+        Self::new(1, 1, vec![vec![F::one(), factor]]).expect("lengths fit")
+    }
+
+    #[allow(dead_code)]
+    pub fn copy() -> Self {
+        /*
+        x goes to (x,x)
+        */
+        Self::new(1, 2, vec![vec![F::one(), F::one(), F::one()]]).expect("lengths fit")
+    }
+
+    #[allow(dead_code)]
+    pub fn discard() -> Self {
+        /*
+        x goes to the only element of F^0 whatever it is
+        */
+        Self::new(1, 0, vec![vec![F::one()]]).expect("lengths fit")
+    }
+
+    #[allow(dead_code)]
+    pub fn add() -> Self {
+        /*
+        (x,y) goes to x+y
+        */
+        Self::new(
+            2,
+            1,
+            vec![
+                vec![F::one(), F::zero(), F::one()],
+                vec![F::zero(), F::one(), F::one()],
+            ],
+        )
+        .expect("lengths fit")
+    }
+
+    #[allow(dead_code)]
+    pub fn zero() -> Self {
+        /*
+        the only element of F^0 goes to 0
+        */
+        Self::new(0, 1, vec![]).expect("lengths fit")
+    }
+
+    #[allow(dead_code)]
+    pub fn copying_frobenius<Lambda, BlackBoxLabel>(
+        op: &FrobeniusOperation<Lambda, BlackBoxLabel>,
+    ) -> Result<Self, Error>
+    where
+        Lambda: Eq + Copy,
+        BlackBoxLabel: Eq + Copy,
+    {
+        /*
+        the Frobenius algebra where every wire carries one number
+        and a spider says all the numbers on its legs are equal
+        */
+        match op {
+            FrobeniusOperation::Comultiplication(_) => Ok(Self::copy()),
+            FrobeniusOperation::Counit(_) => Ok(Self::discard()),
+            FrobeniusOperation::Multiplication(_) => Ok(Self::copy().dagger()),
+            FrobeniusOperation::Unit(_) => Ok(Self::discard().dagger()),
+            FrobeniusOperation::Identity(_) => Ok(Self::identity(&1)),
+            FrobeniusOperation::SymmetricBraiding(_, _) => Ok(Self::swap()),
+            FrobeniusOperation::UnSpecifiedBox(..) => Err(Error::from(
+                "Only the Frobenius generators have a meaning here",
+            )),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn adding_frobenius<Lambda, BlackBoxLabel>(
+        op: &FrobeniusOperation<Lambda, BlackBoxLabel>,
+    ) -> Result<Self, Error>
+    where
+        Lambda: Eq + Copy,
+        BlackBoxLabel: Eq + Copy,
+    {
+        /*
+        the other Frobenius algebra where every wire carries one number
+        and a spider says the numbers coming in add up to the ones going out
+        */
+        match op {
+            FrobeniusOperation::Multiplication(_) => Ok(Self::add()),
+            FrobeniusOperation::Unit(_) => Ok(Self::zero()),
+            FrobeniusOperation::Comultiplication(_) => Ok(Self::add().dagger()),
+            FrobeniusOperation::Counit(_) => Ok(Self::zero().dagger()),
+            FrobeniusOperation::Identity(_) => Ok(Self::identity(&1)),
+            FrobeniusOperation::SymmetricBraiding(_, _) => Ok(Self::swap()),
+            FrobeniusOperation::UnSpecifiedBox(..) => Err(Error::from(
+                "Only the Frobenius generators have a meaning here",
+            )),
+        }
+    }
+
+    fn swap() -> Self {
+        Self::from_permutation(
+            Permutation::try_from(vec![1, 0]).expect("a permutation"),
+            2,
+            true,
+        )
+    }
+}
+
+impl<F> HasIdentity<usize> for LinearRelation<F>
+where
+    F: Num + Clone,
+{
+    fn identity(on_this: &usize) -> Self {
+        Self::from_matrix(&MatrixMorphism::identity(on_this))
+    }
+}
+
+impl<F> Composable<usize> for LinearRelation<F>
+where
+    F: Num + Clone,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        /*
+        (x,z) is related when there is a y with (x,y) in self and (y,z) in other
+        combinations of the basis of self and of other whose y parts agree
+            are the kernel of putting the y parts of self next to minus those of other
+        */
+        if self.composable(other).is_err() {
+            return Err(Error::InterfaceLengthMismatch {
+                left: self.codomain(),
+                right: other.domain(),
+            });
+        }
+        let middle = self.codomain;
+        let columns = self.basis.len() + other.basis.len();
+        let agreement: Vec<Vec<F>> = (0..middle)
+            .map(|k| {
+                self.basis
+                    .iter()
+                    .map(|v| v[self.domain + k].clone())
+                    .chain(other.basis.iter().map(|v| F::zero() - v[k].clone()))
+                    .collect()
+            })
+            .collect();
+        let spanning = kernel_with_columns(&agreement, columns)
+            .into_iter()
+            .map(|coefficients| {
+                let mut combined = vec![F::zero(); self.domain + other.codomain];
+                for (coefficient, v) in coefficients.iter().zip(&self.basis) {
+                    for (entry, x) in combined.iter_mut().zip(&v[..self.domain]) {
+                        *entry = entry.clone() + coefficient.clone() * x.clone();
+                    }
+                }
+                for (coefficient, v) in coefficients[self.basis.len()..].iter().zip(&other.basis) {
+                    for (entry, z) in combined[self.domain..].iter_mut().zip(&v[middle..]) {
+                        *entry = entry.clone() + coefficient.clone() * z.clone();
+                    }
+                }
+                combined
+            })
+            .collect();
+        Self::new(self.domain, other.codomain, spanning)
+    }
+
+    fn domain(&self) -> usize {
+        self.domain
+    }
+
+    fn codomain(&self) -> usize {
+        self.codomain
+    }
+}
+
+impl<F> Monoidal for LinearRelation<F>
+where
+    F: Num + Clone,
+{
+    fn monoidal(&mut self, other: Self) {
+        /*
+        the direct sum
+        (x,y) becomes (x,0,y,0) and (x',y') becomes (0,x',0,y')
+        */
+        let (domain, codomain) = (self.domain + other.domain, self.codomain + other.codomain);
+        let mut spanning = Vec::with_capacity(self.basis.len() + other.basis.len());
+        for v in &self.basis {
+            let mut padded = v[..self.domain].to_vec();
+            padded.extend(vec![F::zero(); other.domain]);
+            padded.extend(v[self.domain..].iter().cloned());
+            padded.extend(vec![F::zero(); other.codomain]);
+            spanning.push(padded);
+        }
+        for v in &other.basis {
+            let mut padded = vec![F::zero(); self.domain];
+            padded.extend(v[..other.domain].iter().cloned());
+            padded.extend(vec![F::zero(); self.codomain]);
+            padded.extend(v[other.domain..].iter().cloned());
+            spanning.push(padded);
+        }
+        *self = Self::new(domain, codomain, spanning).expect("lengths fit");
+    }
+}
+
+impl<F> Dagger<usize> for LinearRelation<F>
+where
+    F: Num + Clone,
+{
+    fn dagger(&self) -> Self {
+        /*
+        the converse relation with the two sides swapped
+        */
+        let spanning = self
+            .basis
+            .iter()
+            .map(|v| {
+                v[self.domain..]
+                    .iter()
+                    .chain(&v[..self.domain])
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+        Self::new(self.codomain, self.domain, spanning).expect("lengths fit")
+    }
+}
+
+impl<F> SymmetricMonoidalDiscreteMorphism<usize> for LinearRelation<F>
+where
+    F: Num + Clone,
+{
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        /*
+        the same convention as for MatrixMorphism, what was at position k is now at position p.inv()(k)
+        */
+        let p_inv = p.inv();
+        let (offset, size) = if of_codomain {
+            (self.domain, self.codomain)
+        } else {
+            (0, self.domain)
+        };
+        let spanning = self
+            .basis
+            .iter()
+            .map(|v| {
+                let mut moved = v.clone();
+                for k in 0..size {
+                    moved[offset + p_inv.apply(k)] = v[offset + k].clone();
+                }
+                moved
+            })
+            .collect();
+        *self = Self::new(self.domain, self.codomain, spanning).expect("lengths fit");
+    }
+
+    fn from_permutation(p: Permutation, type_: usize, types_as_on_domain: bool) -> Self {
+        Self::from_matrix(&MatrixMorphism::from_permutation(
+            p,
+            type_,
+            types_as_on_domain,
+        ))
+    }
+}
+
+mod test {
+
+    #[test]
+    fn graphical_linear_algebra() {
+        use super::LinearRelation;
+        use crate::category::{Composable, Dagger, HasIdentity};
+        use crate::matrix::MatrixMorphism;
+        use crate::monoidal::Monoidal;
+        use num::rational::Ratio;
+        type Q = Ratio<i64>;
+        type L = LinearRelation<Q>;
+        let q = |n| Ratio::from_integer(n);
+        let tensor = |first: &L, second: &L| {
+            let mut answer = first.clone();
+            answer.monoidal(second.clone());
+            answer
+        };
+        let id = L::identity(&1);
+
+        /*
+        copying then adding is multiplying by 2
+        */
+        assert_eq!(L::copy().compose(&L::add()).unwrap(), L::scalar(q(2)));
+        assert!(L::scalar(q(3)).relates(&[q(2)], &[q(6)]));
+        assert!(!L::scalar(q(3)).relates(&[q(2)], &[q(5)]));
+
+        /*
+        the two Frobenius algebras are both special
+        and adding then copying is the bialgebra law
+        */
+        assert_eq!(L::copy().compose(&L::copy().dagger()).unwrap(), id);
+        assert_eq!(L::add().dagger().compose(&L::add()).unwrap(), id);
+        let swap_middle = tensor(&tensor(&id, &L::swap()), &id);
+        let bialgebra = tensor(&L::copy(), &L::copy())
+            .compose(&swap_middle)
+            .unwrap()
+            .compose(&tensor(&L::add(), &L::add()))
+            .unwrap();
+        assert_eq!(L::add().compose(&L::copy()).unwrap(), bialgebra);
+        assert_eq!(
+            L::copy()
+                .compose(&tensor(&id, &L::copy()))
+                .unwrap()
+                .dagger()
+                .dimension(),
+            1
+        );
+
+        /*
+        a relation that is not a function
+        the converse of scaling by 0 relates 0 to everything
+        */
+        let all_from_zero = L::scalar(q(0)).dagger();
+        assert!(all_from_zero.relates(&[q(0)], &[q(5)]));
+        assert!(!all_from_zero.relates(&[q(1)], &[q(5)]));
+        assert_eq!(
+            L::scalar(q(0)).compose(&all_from_zero).unwrap(),
+            L::discard().compose(&L::discard().dagger()).unwrap()
+        );
+        assert!(
+            L::scalar(q(2)).is_contained_in(&L::discard().compose(&L::discard().dagger()).unwrap())
+        );
+        assert_eq!(
+            L::scalar(q(2)).compose(&L::scalar(q(2)).dagger()).unwrap(),
+            id
+        );
+
+        /*
+        matrices go in by their graphs
+        */
+        let a = MatrixMorphism::new(2, 1, vec![vec![q(1)], vec![q(2)]]).unwrap();
+        let b = MatrixMorphism::new(1, 2, vec![vec![q(3), q(-1)]]).unwrap();
+        assert_eq!(
+            L::from_matrix(&a.compose(&b).unwrap()),
+            L::from_matrix(&a).compose(&L::from_matrix(&b)).unwrap()
+        );
+        assert!(L::from_matrix(&b).compose(&L::scalar(q(1))).is_err());
+        let direct_sum = tensor(&L::from_matrix(&a), &L::from_matrix(&b));
+        assert!(direct_sum.relates(&[q(1), q(1), q(2)], &[q(3), q(6), q(-2)]));
+    }
+
+    #[test]
+    fn frobenius_generators() {
+        use super::LinearRelation;
+        use crate::category::{Composable, HasIdentity};
+        use crate::frobenius::FrobeniusOperation;
+        use num::rational::Ratio;
+        type L = LinearRelation<Ratio<i64>>;
+        type Op = FrobeniusOperation<(), ()>;
+        for interpret in [
+            L::copying_frobenius::<(), ()>,
+            L::adding_frobenius::<(), ()>,
+        ] {
+            let mult = interpret(&Op::Multiplication(())).unwrap();
+            let comult = interpret(&Op::Comultiplication(())).unwrap();
+            let unit = interpret(&Op::Unit(())).unwrap();
+            let counit = interpret(&Op::Counit(())).unwrap();
+            let id = interpret(&Op::Identity(())).unwrap();
+            assert_eq!(id, L::identity(&1));
+            assert_eq!(comult.compose(&mult).unwrap(), id);
+            let mut unit_then_id = unit.clone();
+            crate::monoidal::Monoidal::monoidal(&mut unit_then_id, id.clone());
+            assert_eq!(unit_then_id.compose(&mult).unwrap(), id);
+            let mut id_then_counit = id.clone();
+            crate::monoidal::Monoidal::monoidal(&mut id_then_counit, counit.clone());
+            assert_eq!(comult.compose(&id_then_counit).unwrap(), id);
+            assert!(interpret(&Op::UnSpecifiedBox((), vec![], vec![])).is_err());
+        }
+        assert!(
+            L::copying_frobenius(&Op::Multiplication(()))
+                != L::adding_frobenius(&Op::Multiplication(()))
+        );
+    }
+}
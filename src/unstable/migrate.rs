@@ -0,0 +1,132 @@
+use {
+    crate::unstable::temperley_lieb::BrauerMorphism,
+    num::{One, Zero},
+    serde::{Deserialize, Serialize},
+    std::ops::Add,
+};
+
+/*
+the layout version that archive writes
+bump this and add a variant to ArchivedBrauerMorphism
+whenever the serialized form of BrauerMorphism changes
+*/
+#[allow(dead_code)]
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/*
+a BrauerMorphism together with the version of the layout it was written with
+so a stored result can still be read after the layout changes
+an older variant holds a copy of the old layout rather than BrauerMorphism itself
+and migrate converts it step by step up to the current one
+Legacy is a bare BrauerMorphism with no version around it
+which is what was written before there were archives at all
+its layout is the same as V1 so it is read as BrauerMorphism directly
+a version this crate does not know about is an error when reading
+*/
+#[allow(dead_code)]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "schema_version", content = "morphism")]
+pub enum ArchivedBrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone,
+{
+    #[serde(rename = "1")]
+    V1(BrauerMorphism<T>),
+    #[serde(untagged)]
+    Legacy(BrauerMorphism<T>),
+}
+
+impl<T> ArchivedBrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone,
+{
+    #[allow(dead_code)]
+    pub fn archive(morphism: BrauerMorphism<T>) -> Self {
+        /*
+        always written with the current layout
+        */
+        Self::V1(morphism)
+    }
+
+    #[allow(dead_code)]
+    pub fn schema_version(&self) -> u32 {
+        match self {
+            Self::Legacy(_) => 0,
+            Self::V1(_) => 1,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn migrate(self) -> Result<BrauerMorphism<T>, String> {
+        /*
+        bring it up to the current layout
+        the invariants were already checked when the BrauerMorphism was read in
+        */
+        match self {
+            Self::Legacy(current) | Self::V1(current) => Ok(current),
+        }
+    }
+}
+
+mod test {
+
+    #[test]
+    fn archived_fixtures() {
+        use super::ArchivedBrauerMorphism;
+        use crate::unstable::temperley_lieb::BrauerMorphism;
+        let e_2: ArchivedBrauerMorphism<i32> =
+            serde_json::from_str(include_str!("../../fixtures/migrate/brauer_v1_e2.json")).unwrap();
+        assert_eq!(e_2.schema_version(), 1);
+        assert_eq!(e_2.migrate(), Ok(BrauerMorphism::temperley_lieb_gen(1, 3)));
+        let swap_plus_id: ArchivedBrauerMorphism<i32> =
+            serde_json::from_str(include_str!("../../fixtures/migrate/brauer_v1_sum.json"))
+                .unwrap();
+        assert_eq!(
+            swap_plus_id.migrate().map(|m| m.to_compact_string()),
+            Ok("Br(2→2): 3·d^0·[0-2|1-3] + 1·d^0·[0-3|1-2]".to_string())
+        );
+    }
+
+    #[test]
+    fn legacy_fixtures() {
+        use super::ArchivedBrauerMorphism;
+        use crate::unstable::temperley_lieb::BrauerMorphism;
+        let e_1: ArchivedBrauerMorphism<i32> =
+            serde_json::from_str(include_str!("../../fixtures/migrate/brauer_legacy_e1.json"))
+                .unwrap();
+        assert_eq!(e_1.schema_version(), 0);
+        assert_eq!(e_1.migrate(), Ok(BrauerMorphism::temperley_lieb_gen(0, 2)));
+        let crossing_tl: Result<ArchivedBrauerMorphism<i32>, _> = serde_json::from_str(
+            include_str!("../../fixtures/migrate/brauer_legacy_bad_tl.json"),
+        );
+        assert!(crossing_tl.is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        use super::{ArchivedBrauerMorphism, CURRENT_SCHEMA_VERSION};
+        use crate::unstable::temperley_lieb::BrauerMorphism;
+        let original = BrauerMorphism::<i32>::temperley_lieb_gen(0, 4)
+            * BrauerMorphism::temperley_lieb_gen(0, 4);
+        let archived = ArchivedBrauerMorphism::archive(original.clone());
+        assert_eq!(archived.schema_version(), CURRENT_SCHEMA_VERSION);
+        let written = serde_json::to_string(&archived).unwrap();
+        let read: ArchivedBrauerMorphism<i32> = serde_json::from_str(&written).unwrap();
+        assert_eq!(read.migrate(), Ok(original));
+    }
+
+    #[test]
+    fn bad_archives() {
+        use super::ArchivedBrauerMorphism;
+        let from_the_future: Result<ArchivedBrauerMorphism<i32>, _> =
+            serde_json::from_str(include_str!("../../fixtures/migrate/brauer_v999.json"));
+        assert!(from_the_future.is_err());
+        let crossing_tl: Result<ArchivedBrauerMorphism<i32>, _> =
+            serde_json::from_str(include_str!("../../fixtures/migrate/brauer_v1_bad_tl.json"));
+        assert!(crossing_tl.is_err());
+        let not_matching: Result<ArchivedBrauerMorphism<i32>, _> = serde_json::from_str(
+            include_str!("../../fixtures/migrate/brauer_v1_bad_matching.json"),
+        );
+        assert!(not_matching.is_err());
+    }
+}
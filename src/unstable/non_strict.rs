@@ -0,0 +1,379 @@
+use {
+    crate::{
+        category::{ComposableMutating, HasIdentity},
+        error::Error,
+        monoidal::{GenericMonoidalMorphism, Monoidal},
+        utils::same_labels_check,
+    },
+    std::fmt::Debug,
+};
+
+/*
+an object of a monoidal category that is not assumed strict
+so a tensor product remembers how it was bracketed and where the units are
+flatten forgets that and gives the object of the strictification
+*/
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ObjectTree<Lambda> {
+    Unit,
+    Leaf(Lambda),
+    Tensor(Box<ObjectTree<Lambda>>, Box<ObjectTree<Lambda>>),
+}
+
+impl<Lambda: Copy> ObjectTree<Lambda> {
+    #[allow(dead_code)]
+    pub fn tensor(self, other: Self) -> Self {
+        Self::Tensor(Box::new(self), Box::new(other))
+    }
+
+    #[allow(dead_code)]
+    pub fn flatten(&self) -> Vec<Lambda> {
+        let mut answer = vec![];
+        self.flatten_into(&mut answer);
+        answer
+    }
+
+    fn flatten_into(&self, answer: &mut Vec<Lambda>) {
+        match self {
+            Self::Unit => {}
+            Self::Leaf(z) => answer.push(*z),
+            Self::Tensor(left, right) => {
+                left.flatten_into(answer);
+                right.flatten_into(answer);
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn left_bracketed(types: &[Lambda]) -> Self {
+        /*
+        ((a \otimes b) \otimes c) and so on
+        the unit for no types at all
+        */
+        types
+            .iter()
+            .map(|z| Self::Leaf(*z))
+            .reduce(Self::tensor)
+            .unwrap_or(Self::Unit)
+    }
+
+    #[allow(dead_code)]
+    pub fn right_bracketed(types: &[Lambda]) -> Self {
+        /*
+        (a \otimes (b \otimes c)) and so on
+        the unit for no types at all
+        */
+        types
+            .iter()
+            .rev()
+            .map(|z| Self::Leaf(*z))
+            .reduce(|acc, z| z.tensor(acc))
+            .unwrap_or(Self::Unit)
+    }
+}
+
+/*
+a morphism between bracketed objects
+by the coherence theorem it is determined by a morphism of the strictification
+    along with the bracketings of its domain and codomain
+so the associators, unitors and any composite of them all have an identity underneath
+what is not strict is composition which needs the bracketings to agree exactly
+    instead of only agreeing after flattening
+    so one has to rebracket explicitly with the structural isomorphisms below
+*/
+#[derive(Clone, PartialEq, Eq)]
+pub struct NonStrictMorphism<BoxType, Lambda: Eq + Copy> {
+    source: ObjectTree<Lambda>,
+    target: ObjectTree<Lambda>,
+    underlying: GenericMonoidalMorphism<BoxType, Lambda>,
+}
+
+impl<BoxType, Lambda> NonStrictMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone + HasIdentity<Lambda>,
+{
+    #[allow(dead_code)]
+    pub fn from_strict(
+        underlying: GenericMonoidalMorphism<BoxType, Lambda>,
+        source: ObjectTree<Lambda>,
+        target: ObjectTree<Lambda>,
+    ) -> Result<Self, Error> {
+        /*
+        the bracketings have to flatten to the domain and codomain of underlying
+        */
+        same_labels_check(source.flatten().iter(), underlying.domain().iter())?;
+        same_labels_check(target.flatten().iter(), underlying.codomain().iter())?;
+        Ok(Self {
+            source,
+            target,
+            underlying,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn source(&self) -> &ObjectTree<Lambda> {
+        &self.source
+    }
+
+    #[allow(dead_code)]
+    pub fn target(&self) -> &ObjectTree<Lambda> {
+        &self.target
+    }
+
+    #[allow(dead_code)]
+    pub fn underlying(&self) -> &GenericMonoidalMorphism<BoxType, Lambda> {
+        &self.underlying
+    }
+
+    #[allow(dead_code)]
+    pub fn into_underlying(self) -> GenericMonoidalMorphism<BoxType, Lambda> {
+        self.underlying
+    }
+
+    fn structural(source: ObjectTree<Lambda>, target: ObjectTree<Lambda>) -> Self {
+        /*
+        only for source and target that are already known to flatten the same way
+        */
+        Self {
+            underlying: GenericMonoidalMorphism::identity(&source.flatten()),
+            source,
+            target,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn associator(a: ObjectTree<Lambda>, b: ObjectTree<Lambda>, c: ObjectTree<Lambda>) -> Self {
+        /*
+        (a \otimes b) \otimes c -> a \otimes (b \otimes c)
+        */
+        Self::structural(
+            a.clone().tensor(b.clone()).tensor(c.clone()),
+            a.tensor(b.tensor(c)),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn associator_inverse(
+        a: ObjectTree<Lambda>,
+        b: ObjectTree<Lambda>,
+        c: ObjectTree<Lambda>,
+    ) -> Self {
+        /*
+        a \otimes (b \otimes c) -> (a \otimes b) \otimes c
+        */
+        Self::structural(
+            a.clone().tensor(b.clone().tensor(c.clone())),
+            a.tensor(b).tensor(c),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn left_unitor(a: ObjectTree<Lambda>) -> Self {
+        /*
+        I \otimes a -> a
+        */
+        Self::structural(ObjectTree::Unit.tensor(a.clone()), a)
+    }
+
+    #[allow(dead_code)]
+    pub fn left_unitor_inverse(a: ObjectTree<Lambda>) -> Self {
+        Self::structural(a.clone(), ObjectTree::Unit.tensor(a))
+    }
+
+    #[allow(dead_code)]
+    pub fn right_unitor(a: ObjectTree<Lambda>) -> Self {
+        /*
+        a \otimes I -> a
+        */
+        Self::structural(a.clone().tensor(ObjectTree::Unit), a)
+    }
+
+    #[allow(dead_code)]
+    pub fn right_unitor_inverse(a: ObjectTree<Lambda>) -> Self {
+        Self::structural(a.clone(), a.tensor(ObjectTree::Unit))
+    }
+
+    #[allow(dead_code)]
+    pub fn rebracket(from: ObjectTree<Lambda>, to: ObjectTree<Lambda>) -> Result<Self, Error> {
+        /*
+        the unique composite of associators, unitors and their inverses
+            going between two bracketings of the same list of types
+        */
+        same_labels_check(from.flatten().iter(), to.flatten().iter())?;
+        Ok(Self::structural(from, to))
+    }
+
+    #[allow(dead_code)]
+    pub fn rebracket_source(&mut self, new_source: ObjectTree<Lambda>) -> Result<(), Error> {
+        /*
+        precompose with the rebracketing from new_source to the current source
+        */
+        let mut answer = Self::rebracket(new_source, self.source.clone())?;
+        answer.compose(self.clone())?;
+        *self = answer;
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn rebracket_target(&mut self, new_target: ObjectTree<Lambda>) -> Result<(), Error> {
+        /*
+        postcompose with the rebracketing from the current target to new_target
+        */
+        let rebracketing = Self::rebracket(self.target.clone(), new_target)?;
+        self.compose(rebracketing)
+    }
+}
+
+impl<BoxType, Lambda> HasIdentity<ObjectTree<Lambda>> for NonStrictMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone + HasIdentity<Lambda>,
+{
+    fn identity(on_this: &ObjectTree<Lambda>) -> Self {
+        Self::structural(on_this.clone(), on_this.clone())
+    }
+}
+
+impl<BoxType, Lambda> ComposableMutating<ObjectTree<Lambda>> for NonStrictMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+{
+    fn composable(&self, other: &Self) -> Result<(), Error> {
+        if self.target != other.source {
+            return Err(Error::BracketingMismatch {
+                left: format!("{:?}", self.target),
+                right: format!("{:?}", other.source),
+            });
+        }
+        self.underlying.composable(&other.underlying)
+    }
+
+    fn compose(&mut self, other: Self) -> Result<(), Error> {
+        self.composable(&other)?;
+        self.underlying.compose(other.underlying)?;
+        self.target = other.target;
+        Ok(())
+    }
+
+    fn domain(&self) -> ObjectTree<Lambda> {
+        self.source.clone()
+    }
+
+    fn codomain(&self) -> ObjectTree<Lambda> {
+        self.target.clone()
+    }
+}
+
+impl<BoxType, Lambda> Monoidal for NonStrictMorphism<BoxType, Lambda>
+where
+    Lambda: Eq + Copy + Debug,
+    BoxType: Clone + HasIdentity<Lambda>,
+{
+    fn monoidal(&mut self, other: Self) {
+        self.source = self.source.clone().tensor(other.source);
+        self.target = self.target.clone().tensor(other.target);
+        self.underlying.monoidal(other.underlying);
+    }
+}
+
+mod test {
+
+    #[test]
+    fn bracketings() {
+        use super::ObjectTree;
+        let left = ObjectTree::left_bracketed(&['a', 'b', 'c']);
+        let right = ObjectTree::right_bracketed(&['a', 'b', 'c']);
+        let (a, b, c) = (
+            ObjectTree::Leaf('a'),
+            ObjectTree::Leaf('b'),
+            ObjectTree::Leaf('c'),
+        );
+        assert_eq!(left, a.clone().tensor(b.clone()).tensor(c.clone()));
+        assert_eq!(right, a.tensor(b.tensor(c)));
+        assert_ne!(left, right);
+        assert_eq!(left.flatten(), right.flatten());
+        assert_eq!(ObjectTree::<char>::left_bracketed(&[]), ObjectTree::Unit);
+        assert_eq!(
+            ObjectTree::Unit.tensor(ObjectTree::Leaf('a')).flatten(),
+            vec!['a']
+        );
+    }
+
+    #[test]
+    fn rebracket_before_composing() {
+        use super::{NonStrictMorphism, ObjectTree};
+        use crate::category::{ComposableMutating, HasIdentity};
+        use crate::error::Error;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal;
+        use crate::monoidal::{GenericMonoidalMorphism, Monoidal};
+        type Op = FrobeniusOperation<char, ()>;
+        type NonStrict = NonStrictMorphism<Op, char>;
+        let leaf = ObjectTree::Leaf;
+        let normal = |f: &NonStrict| {
+            let mut underlying = f.underlying().clone();
+            underlying.normalize();
+            (f.domain(), f.codomain(), underlying)
+        };
+        let merge: GenericMonoidalMorphism<Op, char> =
+            monoidal!([Op::Multiplication('a')]).unwrap();
+        let merge_twice: GenericMonoidalMorphism<Op, char> = monoidal!(
+            [Op::Multiplication('a'), id('a')];
+            [Op::Multiplication('a')]
+        )
+        .unwrap();
+
+        /*
+        m : a \otimes a -> a and then m \otimes 1 : (a \otimes a) \otimes a -> a \otimes a
+        */
+        let m =
+            NonStrict::from_strict(merge.clone(), leaf('a').tensor(leaf('a')), leaf('a')).unwrap();
+        let mut m_then_m = m.clone();
+        m_then_m.monoidal(NonStrict::identity(&leaf('a')));
+        assert_eq!(
+            m_then_m.domain(),
+            ObjectTree::left_bracketed(&['a', 'a', 'a'])
+        );
+        m_then_m.compose(m.clone()).unwrap();
+        assert!(*m_then_m.underlying() == merge_twice);
+
+        /*
+        starting from a \otimes (a \otimes a) the associator has to come first
+        */
+        let right = ObjectTree::right_bracketed(&['a', 'a', 'a']);
+        let mut from_right = NonStrict::identity(&right);
+        assert!(matches!(
+            from_right.composable(&m_then_m),
+            Err(Error::BracketingMismatch { .. })
+        ));
+        let alpha_inv = NonStrict::associator_inverse(leaf('a'), leaf('a'), leaf('a'));
+        from_right.compose(alpha_inv).unwrap();
+        from_right.compose(m_then_m.clone()).unwrap();
+        assert_eq!(from_right.domain(), right);
+        assert_eq!(from_right.codomain(), leaf('a'));
+        let mut rebracketed = m_then_m.clone();
+        rebracketed.rebracket_source(right.clone()).unwrap();
+        assert!(normal(&rebracketed) == normal(&from_right));
+
+        /*
+        the triangle identity holds on the nose for the underlying morphisms
+        */
+        let (x, y) = (leaf('a'), leaf('b'));
+        let mut alpha_lambda = NonStrict::associator(x.clone(), ObjectTree::Unit, y.clone());
+        let mut one_lambda = NonStrict::identity(&x);
+        one_lambda.monoidal(NonStrict::left_unitor(y.clone()));
+        alpha_lambda.compose(one_lambda).unwrap();
+        let mut rho_one = NonStrict::right_unitor(x.clone());
+        rho_one.monoidal(NonStrict::identity(&y));
+        assert!(normal(&alpha_lambda) == normal(&rho_one));
+
+        let mut unit_padded = m.clone();
+        unit_padded
+            .rebracket_target(leaf('a').tensor(ObjectTree::Unit))
+            .unwrap();
+        assert!(normal(&unit_padded).2 == normal(&m).2);
+        assert!(unit_padded.rebracket_target(leaf('b')).is_err());
+        assert!(NonStrict::from_strict(merge, leaf('a'), leaf('a')).is_err());
+    }
+}
@@ -0,0 +1,433 @@
+use {
+    crate::category::{Composable, HasIdentity},
+    std::{cmp::Ordering, collections::HashMap, fmt::Debug, hash::Hash},
+};
+
+/*
+a category given by generating morphisms and relations between words in them
+a word is a list of generators read in diagrammatic order so [f,g] is f;g
+the empty word stands for the identity on whichever object is needed
+*/
+#[derive(Clone)]
+pub struct Presentation<Obj, Gen> {
+    generators: HashMap<Gen, (Obj, Obj)>,
+    relations: Vec<(Vec<Gen>, Vec<Gen>)>,
+}
+
+/*
+the oriented relations that come out of completing a presentation
+every rule rewrites its left side to something smaller in the shortlex order
+if is_complete then the rules are confluent and normal forms are unique
+otherwise completion was cut off and they are only a sound way to rewrite
+*/
+#[derive(Clone)]
+pub struct RewritingSystem<Obj, Gen> {
+    generators: HashMap<Gen, (Obj, Obj)>,
+    rules: Vec<(Vec<Gen>, Vec<Gen>)>,
+    is_complete: bool,
+}
+
+fn shortlex<Gen: Ord>(w1: &[Gen], w2: &[Gen]) -> Ordering {
+    w1.len().cmp(&w2.len()).then_with(|| w1.cmp(w2))
+}
+
+fn find_subword<Gen: Eq>(word: &[Gen], pattern: &[Gen]) -> Option<usize> {
+    if pattern.is_empty() || pattern.len() > word.len() {
+        return None;
+    }
+    word.windows(pattern.len())
+        .position(|window| window == pattern)
+}
+
+impl<Obj, Gen> Presentation<Obj, Gen>
+where
+    Obj: Eq + Clone + Debug,
+    Gen: Eq + Hash + Ord + Clone + Debug,
+{
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            generators: HashMap::new(),
+            relations: vec![],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn add_generator(&mut self, name: Gen, domain: Obj, codomain: Obj) -> Result<(), String> {
+        if self.generators.contains_key(&name) {
+            return Err(format!("{:?} was already a generator", name));
+        }
+        self.generators.insert(name, (domain, codomain));
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn add_relation(&mut self, lhs: Vec<Gen>, rhs: Vec<Gen>) -> Result<(), String> {
+        /*
+        both sides have to be composable words with the same domain and codomain
+        an empty side is the identity so the other side then has to be an endomorphism
+        */
+        let compatible = match (self.word_type(&lhs)?, self.word_type(&rhs)?) {
+            (Some(lhs_type), Some(rhs_type)) => lhs_type == rhs_type,
+            (Some((a, b)), None) | (None, Some((a, b))) => a == b,
+            (None, None) => true,
+        };
+        if !compatible {
+            return Err(format!(
+                "The two sides of {:?} = {:?} are not between the same objects",
+                lhs, rhs
+            ));
+        }
+        self.relations.push((lhs, rhs));
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn word_type(&self, word: &[Gen]) -> Result<Option<(Obj, Obj)>, String> {
+        /*
+        the domain and codomain of a word
+        None for the empty word and an error if it is not composable
+        */
+        let mut answer: Option<(Obj, Obj)> = None;
+        for generator in word {
+            let (domain, codomain) = self
+                .generators
+                .get(generator)
+                .ok_or_else(|| format!("{:?} is not a generator", generator))?;
+            answer = match answer {
+                None => Some((domain.clone(), codomain.clone())),
+                Some((start, end)) if end == *domain => Some((start, codomain.clone())),
+                Some((_, end)) => {
+                    return Err(format!(
+                        "{:?} can not come after something ending at {:?}",
+                        generator, end
+                    ))
+                }
+            };
+        }
+        Ok(answer)
+    }
+
+    #[allow(dead_code)]
+    pub fn complete(&self, max_rules: usize) -> RewritingSystem<Obj, Gen> {
+        /*
+        Knuth-Bendix completion with the shortlex order from the order on Gen
+        orient every relation from larger to smaller
+        retire any rule whose left side contains the new left side back into the equations
+        then resolve the critical pairs from overlapping left sides until there are none left
+        gives up with is_complete false once there are more than max_rules rules
+        because completion need not terminate for an arbitrary presentation
+        */
+        let mut system = RewritingSystem {
+            generators: self.generators.clone(),
+            rules: vec![],
+            is_complete: false,
+        };
+        let mut pending = self.relations.clone();
+        loop {
+            while let Some((lhs, rhs)) = pending.pop() {
+                let lhs = system.normal_form(&lhs);
+                let rhs = system.normal_form(&rhs);
+                let (bigger, smaller) = match shortlex(&lhs, &rhs) {
+                    Ordering::Equal => continue,
+                    Ordering::Greater => (lhs, rhs),
+                    Ordering::Less => (rhs, lhs),
+                };
+                let (retired, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut system.rules)
+                    .into_iter()
+                    .partition(|(l, _)| find_subword(l, &bigger).is_some());
+                system.rules = kept;
+                pending.extend(retired);
+                system.rules.push((bigger, smaller));
+                let reduced_rhs: Vec<_> = system
+                    .rules
+                    .iter()
+                    .map(|(_, r)| system.normal_form(r))
+                    .collect();
+                for ((_, r), reduced) in system.rules.iter_mut().zip(reduced_rhs) {
+                    *r = reduced;
+                }
+                if system.rules.len() > max_rules {
+                    return system;
+                }
+            }
+            pending = system
+                .critical_pairs()
+                .into_iter()
+                .filter(|(w1, w2)| system.normal_form(w1) != system.normal_form(w2))
+                .collect();
+            if pending.is_empty() {
+                system.is_complete = true;
+                return system;
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn interpret<M, F>(
+        &self,
+        word: &[Gen],
+        empty_on: &Obj,
+        generator_interpreter: &F,
+    ) -> Result<M, String>
+    where
+        M: Composable<Obj> + HasIdentity<Obj>,
+        F: Fn(&Gen) -> Result<M, String>,
+    {
+        /*
+        the composite of the generators in order
+        the empty word becomes the identity on empty_on
+        */
+        let Some((first, rest)) = word.split_first() else {
+            return Ok(M::identity(empty_on));
+        };
+        let mut answer = generator_interpreter(first)?;
+        for generator in rest {
+            answer = answer.compose(&generator_interpreter(generator)?)?;
+        }
+        Ok(answer)
+    }
+
+    #[allow(dead_code)]
+    pub fn check_relations<M, F>(&self, generator_interpreter: &F) -> Result<(), String>
+    where
+        M: Composable<Obj> + HasIdentity<Obj> + PartialEq,
+        F: Fn(&Gen) -> Result<M, String>,
+    {
+        /*
+        does every relation hold once the generators are sent to actual morphisms
+        */
+        for (lhs, rhs) in &self.relations {
+            let endpoints = match self.word_type(lhs)? {
+                Some(endpoints) => Some(endpoints),
+                None => self.word_type(rhs)?,
+            };
+            let Some((domain, _)) = endpoints else {
+                continue;
+            };
+            let lhs_morphism = self.interpret(lhs, &domain, generator_interpreter)?;
+            let rhs_morphism = self.interpret(rhs, &domain, generator_interpreter)?;
+            if lhs_morphism != rhs_morphism {
+                return Err(format!("The relation {:?} = {:?} did not hold", lhs, rhs));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Obj, Gen> Default for Presentation<Obj, Gen>
+where
+    Obj: Eq + Clone + Debug,
+    Gen: Eq + Hash + Ord + Clone + Debug,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Obj, Gen> RewritingSystem<Obj, Gen>
+where
+    Obj: Eq + Clone + Debug,
+    Gen: Eq + Hash + Ord + Clone + Debug,
+{
+    #[allow(dead_code)]
+    pub fn is_complete(&self) -> bool {
+        self.is_complete
+    }
+
+    #[allow(dead_code)]
+    pub fn rules(&self) -> &[(Vec<Gen>, Vec<Gen>)] {
+        &self.rules
+    }
+
+    pub fn normal_form(&self, word: &[Gen]) -> Vec<Gen> {
+        /*
+        keep rewriting the first left side found until none are left
+        this stops because every step makes the word smaller in the shortlex order
+        */
+        let mut current = word.to_vec();
+        'rewriting: loop {
+            for (lhs, rhs) in &self.rules {
+                if let Some(position) = find_subword(&current, lhs) {
+                    current.splice(position..position + lhs.len(), rhs.iter().cloned());
+                    continue 'rewriting;
+                }
+            }
+            return current;
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn equal_words(&self, w1: &[Gen], w2: &[Gen]) -> Result<bool, String> {
+        /*
+        equal normal forms always means equal morphisms
+        different normal forms only mean different morphisms when completion finished
+        */
+        if self.normal_form(w1) == self.normal_form(w2) {
+            Ok(true)
+        } else if self.is_complete {
+            Ok(false)
+        } else {
+            Err(format!(
+                "Could not decide whether {:?} and {:?} are equal because completion was cut off",
+                w1, w2
+            ))
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn irreducible_words(&self, max_length: usize) -> Vec<Vec<Gen>> {
+        /*
+        all composable words up to max_length that no rule applies to
+        when complete these are exactly one representative of each morphism
+        a prefix of an irreducible word is irreducible
+            so they are built up one generator at a time
+            and only the rules that end at the new generator need to be checked
+        */
+        let mut alphabet: Vec<&Gen> = self.generators.keys().collect();
+        alphabet.sort();
+        let mut answer = vec![vec![]];
+        let mut frontier: Vec<Vec<Gen>> = vec![vec![]];
+        for _ in 0..max_length {
+            let mut next_frontier = vec![];
+            for word in &frontier {
+                let end = word.last().map(|last| &self.generators[last].1);
+                for generator in &alphabet {
+                    if end.is_some_and(|end| *end != self.generators[*generator].0) {
+                        continue;
+                    }
+                    let mut extended = word.clone();
+                    extended.push((*generator).clone());
+                    if self.rules.iter().all(|(lhs, _)| !extended.ends_with(lhs)) {
+                        next_frontier.push(extended);
+                    }
+                }
+            }
+            answer.extend(next_frontier.iter().cloned());
+            frontier = next_frontier;
+        }
+        answer
+    }
+
+    fn critical_pairs(&self) -> Vec<(Vec<Gen>, Vec<Gen>)> {
+        /*
+        when a suffix of one left side is a prefix of another
+        the overlapped word can be rewritten in two ways
+        */
+        let mut answer = vec![];
+        for (l1, r1) in &self.rules {
+            for (l2, r2) in &self.rules {
+                for overlap in 1..l1.len().min(l2.len()) {
+                    if l1[l1.len() - overlap..] != l2[..overlap] {
+                        continue;
+                    }
+                    let first_way = [r1.as_slice(), &l2[overlap..]].concat();
+                    let second_way = [&l1[..l1.len() - overlap], r2.as_slice()].concat();
+                    answer.push((first_way, second_way));
+                }
+            }
+        }
+        answer
+    }
+}
+
+mod test {
+
+    #[allow(dead_code)]
+    fn symmetric_group(n: usize) -> super::Presentation<(), usize> {
+        /*
+        s_0 ... s_{n-2} with the Coxeter relations
+        */
+        let mut presentation = super::Presentation::new();
+        for i in 0..n - 1 {
+            presentation.add_generator(i, (), ()).unwrap();
+            presentation.add_relation(vec![i, i], vec![]).unwrap();
+        }
+        for i in 0..n - 1 {
+            for j in i + 1..n - 1 {
+                if j == i + 1 {
+                    presentation
+                        .add_relation(vec![i, j, i], vec![j, i, j])
+                        .unwrap();
+                } else {
+                    presentation.add_relation(vec![j, i], vec![i, j]).unwrap();
+                }
+            }
+        }
+        presentation
+    }
+
+    #[test]
+    fn coxeter_completion() {
+        for (n, factorial) in [(2, 2), (3, 6), (4, 24)] {
+            let system = symmetric_group(n).complete(100);
+            assert!(system.is_complete());
+            let longest = n * (n - 1) / 2;
+            assert_eq!(system.irreducible_words(longest + 1).len(), factorial);
+        }
+        let system = symmetric_group(4).complete(100);
+        assert_eq!(system.equal_words(&[0, 1, 0], &[1, 0, 1]), Ok(true));
+        assert_eq!(
+            system.equal_words(&[0, 2, 1, 2], &[2, 0, 1, 2, 1, 1]),
+            Ok(true)
+        );
+        assert_eq!(system.equal_words(&[0, 1], &[1, 0]), Ok(false));
+        assert!(system.normal_form(&[2, 1, 0, 0, 1, 2]).is_empty());
+    }
+
+    #[test]
+    fn typed_words() {
+        use super::Presentation;
+        let mut presentation = Presentation::new();
+        presentation.add_generator('f', 'a', 'b').unwrap();
+        presentation.add_generator('g', 'b', 'a').unwrap();
+        presentation.add_generator('h', 'b', 'b').unwrap();
+        assert!(presentation.add_generator('f', 'a', 'a').is_err());
+        assert_eq!(
+            presentation.word_type(&['f', 'h', 'g']),
+            Ok(Some(('a', 'a')))
+        );
+        assert_eq!(presentation.word_type(&[]), Ok(None));
+        assert!(presentation.word_type(&['f', 'f']).is_err());
+        assert!(presentation.word_type(&['k']).is_err());
+        assert!(presentation.add_relation(vec!['f'], vec!['h']).is_err());
+        assert!(presentation.add_relation(vec!['f', 'g'], vec![]).is_ok());
+        assert!(presentation.add_relation(vec!['h', 'h'], vec!['h']).is_ok());
+        let system = presentation.complete(10);
+        assert!(system.is_complete());
+        assert_eq!(
+            system.normal_form(&['f', 'g', 'f', 'h', 'h']),
+            vec!['f', 'h']
+        );
+        let words = system.irreducible_words(3);
+        assert!(words.contains(&vec!['g', 'f', 'h']));
+        assert!(!words.contains(&vec!['f', 'f']));
+        assert!(!words.contains(&vec!['h', 'h', 'g']));
+    }
+
+    #[test]
+    fn cut_off_completion() {
+        use super::Presentation;
+        /*
+        the Baumslag-Solitar style relation b a = a b b together with c a = a c
+        keeps producing the new rules a c^n b b -> c^n b a for n = 1, 2, ...
+            so any limit is hit
+        the limit is well above the 2 rules the relations start out as
+            so it is completion that goes past it
+        */
+        let mut presentation = Presentation::new();
+        presentation.add_generator('a', (), ()).unwrap();
+        presentation.add_generator('b', (), ()).unwrap();
+        presentation.add_generator('c', (), ()).unwrap();
+        presentation
+            .add_relation(vec!['b', 'a'], vec!['a', 'b', 'b'])
+            .unwrap();
+        presentation
+            .add_relation(vec!['c', 'a'], vec!['a', 'c'])
+            .unwrap();
+        let system = presentation.complete(10);
+        assert!(!system.is_complete());
+        assert!(system.rules().len() > 10);
+        assert!(system.equal_words(&['b', 'a'], &['c']).is_err());
+    }
+}
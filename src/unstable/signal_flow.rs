@@ -0,0 +1,344 @@
+use {
+    crate::{
+        category::{Composable, Dagger, HasIdentity},
+        error::Error,
+        matrix::MatrixMorphism,
+        monoidal::Monoidal,
+        prop::FreePropMorphism,
+        symmetric_monoidal::SymmetricMonoidalDiscreteMorphism,
+        unstable::linear_relation::LinearRelation,
+    },
+    num::Num,
+    permutations::Permutation,
+};
+
+/*
+the generators of the signal flow calculus
+    where every wire carries a single signal
+Copy and Discard send a signal to two places or to none
+Add and Zero sum two signals or make the constant zero one
+Gain multiplies by a constant and Delay holds a signal back for one tick
+the Co versions are the mirror images of the first four
+    so that wires can be bent back round into feedback loops
+*/
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SignalFlowGenerator<F> {
+    Copy,
+    Discard,
+    Add,
+    Zero,
+    Gain(F),
+    Delay,
+    CoCopy,
+    CoDiscard,
+    CoAdd,
+    CoZero,
+}
+
+impl<F> SignalFlowGenerator<F> {
+    #[allow(dead_code)]
+    pub fn arity(&self) -> (usize, usize) {
+        match self {
+            Self::Copy => (1, 2),
+            Self::Discard => (1, 0),
+            Self::Add => (2, 1),
+            Self::Zero => (0, 1),
+            Self::Gain(_) | Self::Delay => (1, 1),
+            Self::CoCopy => (2, 1),
+            Self::CoDiscard => (0, 1),
+            Self::CoAdd => (1, 2),
+            Self::CoZero => (1, 0),
+        }
+    }
+}
+
+/*
+a signal flow graph as a morphism of the free PROP on the generators above
+so composing is plugging the outputs of one into the inputs of the next
+    and the monoidal product is putting two side by side
+*/
+#[derive(Clone, PartialEq, Eq)]
+pub struct SignalFlowGraph<F>(FreePropMorphism<SignalFlowGenerator<F>>);
+
+impl<F> SignalFlowGraph<F>
+where
+    F: Clone + PartialEq,
+{
+    #[allow(dead_code)]
+    pub fn generator(generator: SignalFlowGenerator<F>) -> Self {
+        let (inputs, outputs) = generator.arity();
+        Self(FreePropMorphism::from_generator(generator, inputs, outputs))
+    }
+
+    #[allow(dead_code)]
+    pub fn underlying(&self) -> &FreePropMorphism<SignalFlowGenerator<F>> {
+        &self.0
+    }
+
+    #[allow(dead_code)]
+    pub fn feedback(&self, looped: usize) -> Result<Self, Error> {
+        /*
+        feed the last looped outputs back round into the last looped inputs
+            the first of those outputs into the first of those inputs and so on
+        one wire at a time starting with the last
+            a cup made of CoDiscard then Copy starts the loop
+            and a cap made of CoCopy then Discard closes it
+        */
+        let (domain, codomain) = (self.domain(), self.codomain());
+        if looped > domain || looped > codomain {
+            return Err(format!(
+                "Can not feed back {} wires from {} outputs into {} inputs",
+                looped, codomain, domain
+            )
+            .into());
+        }
+        let cup = Self::generator(SignalFlowGenerator::CoDiscard)
+            .compose(&Self::generator(SignalFlowGenerator::Copy))?;
+        let cap = Self::generator(SignalFlowGenerator::CoCopy)
+            .compose(&Self::generator(SignalFlowGenerator::Discard))?;
+        let mut answer = self.clone();
+        for _ in 0..looped {
+            let (domain, codomain) = (answer.domain(), answer.codomain());
+            let mut open_loop = Self::identity(&(domain - 1));
+            open_loop.monoidal(cup.clone());
+            let mut through = answer;
+            through.monoidal(Self::identity(&1));
+            let mut close_loop = Self::identity(&(codomain - 1));
+            close_loop.monoidal(cap.clone());
+            answer = open_loop.compose(&through)?.compose(&close_loop)?;
+        }
+        Ok(answer)
+    }
+}
+
+impl<F> SignalFlowGraph<F>
+where
+    F: Num + Clone,
+{
+    #[allow(dead_code)]
+    pub fn to_linear_relation(&self, delay: F) -> Result<LinearRelation<F>, Error> {
+        /*
+        the linear relation between the signals on the inputs and on the outputs
+        Delay becomes multiplying by the given delay
+            when F is a field of rational functions that should be the indeterminate
+            and for plain numbers it gives the transfer function at that point
+        a feedback loop can make this a relation that is not a function
+        */
+        self.0.interpret(&|generator: &SignalFlowGenerator<F>| {
+            Ok(match generator {
+                SignalFlowGenerator::Copy => LinearRelation::copy(),
+                SignalFlowGenerator::Discard => LinearRelation::discard(),
+                SignalFlowGenerator::Add => LinearRelation::add(),
+                SignalFlowGenerator::Zero => LinearRelation::zero(),
+                SignalFlowGenerator::Gain(factor) => LinearRelation::scalar(factor.clone()),
+                SignalFlowGenerator::Delay => LinearRelation::scalar(delay.clone()),
+                SignalFlowGenerator::CoCopy => LinearRelation::copy().dagger(),
+                SignalFlowGenerator::CoDiscard => LinearRelation::discard().dagger(),
+                SignalFlowGenerator::CoAdd => LinearRelation::add().dagger(),
+                SignalFlowGenerator::CoZero => LinearRelation::zero().dagger(),
+            })
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn to_matrix(&self, delay: F) -> Result<MatrixMorphism<F>, Error> {
+        /*
+        the matrix when the linear relation is the graph of a linear map
+        that is when the reduced basis starts with the identity on the inputs
+            and then row k of the matrix is where the k'th input goes
+        */
+        let relation = self.to_linear_relation(delay)?;
+        let (domain, codomain) = (relation.domain(), relation.codomain());
+        let is_graph = relation.dimension() == domain
+            && relation.basis().iter().enumerate().all(|(row, v)| {
+                v[..domain]
+                    .iter()
+                    .enumerate()
+                    .all(|(column, x)| *x == if row == column { F::one() } else { F::zero() })
+            });
+        if !is_graph {
+            return Err(Error::from(
+                "The signal flow graph does not give a function of its inputs",
+            ));
+        }
+        MatrixMorphism::new(
+            domain,
+            codomain,
+            relation
+                .basis()
+                .iter()
+                .map(|v| v[domain..].to_vec())
+                .collect(),
+        )
+    }
+}
+
+impl<F> HasIdentity<usize> for SignalFlowGraph<F> {
+    fn identity(on_this: &usize) -> Self {
+        Self(FreePropMorphism::identity(on_this))
+    }
+}
+
+impl<F: Clone> Composable<usize> for SignalFlowGraph<F> {
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        self.0.compose(&other.0).map(Self)
+    }
+
+    fn domain(&self) -> usize {
+        self.0.domain()
+    }
+
+    fn codomain(&self) -> usize {
+        self.0.codomain()
+    }
+}
+
+impl<F: Clone> Monoidal for SignalFlowGraph<F> {
+    fn monoidal(&mut self, other: Self) {
+        self.0.monoidal(other.0);
+    }
+}
+
+impl<F: Clone> SymmetricMonoidalDiscreteMorphism<usize> for SignalFlowGraph<F> {
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        self.0.permute_side(p, of_codomain);
+    }
+
+    fn from_permutation(p: Permutation, type_: usize, types_as_on_domain: bool) -> Self {
+        Self(FreePropMorphism::from_permutation(
+            p,
+            type_,
+            types_as_on_domain,
+        ))
+    }
+}
+
+mod test {
+
+    #[test]
+    fn gains_and_sums() {
+        use super::{SignalFlowGenerator, SignalFlowGraph};
+        use crate::category::{Composable, HasIdentity};
+        use crate::matrix::MatrixMorphism;
+        use crate::monoidal::Monoidal;
+        use crate::prop::Prop;
+        use crate::unstable::linear_relation::LinearRelation;
+        use num::rational::Ratio;
+        type Q = Ratio<i64>;
+        type S = SignalFlowGraph<Q>;
+        let q = |n| Ratio::from_integer(n);
+        let gain = |n| S::generator(SignalFlowGenerator::Gain(q(n)));
+
+        /*
+        copying then adding is a gain of 2
+        */
+        let doubled = S::generator(SignalFlowGenerator::Copy)
+            .compose(&S::generator(SignalFlowGenerator::Add))
+            .unwrap();
+        assert_eq!(
+            doubled.to_linear_relation(q(0)).unwrap(),
+            LinearRelation::scalar(q(2))
+        );
+        let six = gain(2).compose(&gain(3)).unwrap();
+        assert_eq!(
+            six.to_matrix(q(0)).unwrap(),
+            MatrixMorphism::new(1, 1, vec![vec![q(6)]]).unwrap()
+        );
+        assert!(gain(2).compose(&S::identity(&2)).is_err());
+
+        /*
+        side by side gains are a diagonal matrix
+            and swapping them first moves the entries across
+        */
+        let mut both = gain(2);
+        both.monoidal(gain(5));
+        assert_eq!(
+            both.to_matrix(q(0)).unwrap().entries(),
+            [vec![q(2), q(0)], vec![q(0), q(5)]]
+        );
+        let swapped = S::symmetry(1, 1).compose(&both).unwrap();
+        assert_eq!(
+            swapped.to_matrix(q(0)).unwrap().entries(),
+            [vec![q(0), q(5)], vec![q(2), q(0)]]
+        );
+
+        /*
+        a delay is whatever it is evaluated at
+        and the mirror image of copying is not a function
+        */
+        let delayed = S::generator(SignalFlowGenerator::Delay)
+            .compose(&gain(3))
+            .unwrap();
+        assert_eq!(delayed.to_matrix(q(4)).unwrap().entries(), [vec![q(12)]]);
+        let co_copy = S::generator(SignalFlowGenerator::CoCopy);
+        assert!(co_copy.to_matrix(q(0)).is_err());
+        assert!(co_copy
+            .to_linear_relation(q(0))
+            .unwrap()
+            .relates(&[q(3), q(3)], &[q(3)]));
+    }
+
+    #[test]
+    fn feedback_loops() {
+        use super::{SignalFlowGenerator, SignalFlowGraph};
+        use crate::category::{Composable, HasIdentity};
+        use crate::monoidal::Monoidal;
+        use num::rational::Ratio;
+        type Q = Ratio<i64>;
+        type S = SignalFlowGraph<Q>;
+        let q = |n| Ratio::from_integer(n);
+
+        /*
+        y = x + a z y so that y = x / (1 - a z)
+        the sum is copied out and also delayed, scaled by a and fed back in
+        */
+        let loop_body = |a: i64| {
+            let mut back = S::identity(&1);
+            back.monoidal(
+                S::generator(SignalFlowGenerator::Delay)
+                    .compose(&S::generator(SignalFlowGenerator::Gain(q(a))))
+                    .unwrap(),
+            );
+            S::generator(SignalFlowGenerator::Add)
+                .compose(&S::generator(SignalFlowGenerator::Copy))
+                .unwrap()
+                .compose(&back)
+                .unwrap()
+        };
+        let closed = loop_body(1).feedback(1).unwrap();
+        assert_eq!((closed.domain(), closed.codomain()), (1, 1));
+        assert_eq!(
+            closed.to_matrix(Ratio::new(1, 2)).unwrap().entries(),
+            [vec![q(2)]]
+        );
+        assert_eq!(
+            loop_body(3)
+                .feedback(1)
+                .unwrap()
+                .to_matrix(q(1))
+                .unwrap()
+                .entries(),
+            [vec![Ratio::new(-1, 2)]]
+        );
+
+        /*
+        with a z = 1 the only input that works is 0 and then any output does
+        */
+        let stuck = closed.to_linear_relation(q(1)).unwrap();
+        assert!(closed.to_matrix(q(1)).is_err());
+        assert!(stuck.relates(&[q(0)], &[q(7)]));
+        assert!(!stuck.relates(&[q(1)], &[q(7)]));
+
+        /*
+        feeding back nothing changes nothing and too much is refused
+        */
+        assert!(loop_body(1).feedback(0).unwrap() == loop_body(1));
+        assert!(loop_body(1).feedback(3).is_err());
+        let two_loops = {
+            let mut both = loop_body(1);
+            both.monoidal(S::identity(&1));
+            both
+        };
+        assert_eq!(two_loops.feedback(2).unwrap().domain(), 1);
+    }
+}
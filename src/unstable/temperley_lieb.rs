@@ -0,0 +1,2729 @@
+use {
+    crate::{
+        category::{Composable, Dagger, HasIdentity},
+        error::Error,
+        matrix::MatrixMorphism,
+        monoidal::{Braided, Monoidal},
+        rigid_monoidal::{CompactClosed, RibbonMonoidalMorphism, RigidMonoidalMorphism},
+        symmetric_monoidal::SymmetricMonoidalDiscreteMorphism,
+        unstable::{
+            coefficients::Conjugate,
+            interner::Interner,
+            linear_algebra,
+            linear_combination::{LinearCombination, MaybeSendSync},
+        },
+    },
+    itertools::Itertools,
+    num::{complex::ComplexFloat, Num, One, Zero},
+    permutations::Permutation,
+    petgraph::{
+        algo::{connected_components, has_path_connecting, DfsSpace},
+        Graph, Undirected,
+    },
+    std::{
+        collections::HashSet,
+        fmt::{Debug, Display},
+        hash::Hash,
+        ops::{Add, AddAssign, BitAnd, Mul, MulAssign, Neg},
+    },
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pair(pub usize, pub usize);
+
+impl Pair {
+    pub fn iter(&self) -> impl Iterator<Item = usize> {
+        [self.0, self.1].into_iter()
+    }
+
+    pub fn map(&self, f: impl Fn(usize) -> usize) -> Self {
+        Self(f(self.0), f(self.1))
+    }
+
+    pub fn all(&self, f: impl Fn(usize) -> bool) -> bool {
+        f(self.0) && f(self.1)
+    }
+
+    pub fn any(&self, f: impl Fn(usize) -> bool) -> bool {
+        f(self.0) || f(self.1)
+    }
+
+    fn flip_upside_down(&self, source: usize, target: usize) -> Self {
+        self.map(|v| if v < source { v + target } else { v - source })
+    }
+
+    fn mirror(&self, source: usize, target: usize) -> Self {
+        self.map(|v| {
+            if v < source {
+                source - 1 - v
+            } else {
+                2 * source + target - 1 - v
+            }
+        })
+    }
+
+    pub fn sort(&self) -> Self {
+        Self::sorted(self.0, self.1)
+    }
+
+    pub fn sorted(x: usize, y: usize) -> Self {
+        if x < y {
+            Self(x, y)
+        } else {
+            Self(y, x)
+        }
+    }
+
+    pub fn contains(&self, x: usize) -> bool {
+        (x < self.0 && x > self.1) || (x < self.1 && x > self.0)
+    }
+}
+
+impl From<(usize, usize)> for Pair {
+    fn from(value: (usize, usize)) -> Self {
+        Self(value.0, value.1)
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Vec<Pair>", into = "Vec<Pair>")
+)]
+pub struct PerfectMatching {
+    /*
+    if gather all the entries in all the pairs, this gives 0..2n-1
+    with n being the length of pairs
+    each entry of pairs then says those two numbers are matched
+    */
+    pairs: Vec<Pair>,
+}
+
+impl FromIterator<Pair> for PerfectMatching {
+    /*
+    build a PerfectMatching from something that iterates to yield Pair
+    makes sure that this iterator gives all the numbers 0..2n-1
+    */
+    fn from_iter<T: IntoIterator<Item = Pair>>(pair_prime: T) -> Self {
+        let pairs: Vec<Pair> = pair_prime.into_iter().collect();
+        Self::try_from(pairs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl TryFrom<Vec<Pair>> for PerfectMatching {
+    type Error = String;
+
+    fn try_from(pairs: Vec<Pair>) -> Result<Self, Self::Error> {
+        /*
+        same as from_iter but reporting the failure instead of panicking
+        this is what gets used when reading one back in from serialized data
+        */
+        let max_expected = pairs.len() * 2;
+        if let Some(bad_pair) = pairs.iter().find(|p| !p.all(|x| x < max_expected)) {
+            return Err(format!(
+                "{:?} used a number that was not below {}",
+                bad_pair, max_expected
+            ));
+        }
+        let seen: HashSet<_> = pairs.iter().flat_map(|x| x.iter()).collect();
+        if seen.len() != max_expected {
+            return Err(format!(
+                "Some number in 0..{} was matched more than once",
+                max_expected
+            ));
+        }
+        let mut ret_val = Self { pairs };
+
+        ret_val.canonicalize();
+        Ok(ret_val)
+    }
+}
+
+impl From<PerfectMatching> for Vec<Pair> {
+    fn from(value: PerfectMatching) -> Self {
+        value.pairs
+    }
+}
+
+impl PerfectMatching {
+    #[allow(dead_code)]
+    fn new(pair_prime: &[Pair]) -> Self {
+        Self::from_iter(pair_prime.iter().cloned())
+    }
+
+    pub fn shift_index(&self, threshold: usize, shift_amount: usize) -> Self {
+        /*
+        the result is usually not a perfect matching of 0..2n-1 on its own
+        it is an intermediate step in building up a larger matching
+        so this does not go through from_iter and its checks
+        the shift is strictly increasing so the pairs stay in canonical order
+        */
+        Self {
+            pairs: self
+                .pairs
+                .iter()
+                .map(|p| p.map(|v| if v >= threshold { v + shift_amount } else { v }))
+                .collect(),
+        }
+    }
+
+    fn canonicalize(&mut self) {
+        /*
+        each matched pair is given as the smaller number first
+        */
+        for p in self.pairs.iter_mut() {
+            *p = p.sort();
+        }
+
+        self.pairs.sort();
+    }
+
+    fn flip_upside_down(&self, source: usize, target: usize) -> Self {
+        /*
+        interpret the numbers 0..source as on the one layer
+        then continuing the numbering on the other layer
+        so this is a perfect matching as in a BrauerDiagram
+        with specified source and target, then what is the perfect matching from
+        flipping the diagram and still using the same convention for how the nodes are numbered
+        */
+        self.pairs
+            .iter()
+            .map(|x| x.flip_upside_down(source, target))
+            .collect()
+    }
+
+    fn cable(&self, source: usize, target: usize, k: usize) -> Self {
+        /*
+        replace each dot by k dots and each line by k parallel lines
+        the copies of the dot v are v*k..v*k+k-1 with the same convention for which side they are on
+        going around the boundary as in crossing_pairs
+            the target side is traversed backwards
+        copy r of one end of a band (in boundary order) meets copy k-1-r of the other end
+        so caps and cups become nested and through lines stay parallel
+        */
+        let boundary_position = |v: usize| {
+            if v < source {
+                v
+            } else {
+                source + target - 1 - (v - source)
+            }
+        };
+        let copy_of = |v: usize, r: usize| {
+            let boundary_r = if v < source { r } else { k - 1 - r };
+            v * k + boundary_r
+        };
+        self.pairs
+            .iter()
+            .flat_map(|p| {
+                let Pair(x, y) = if boundary_position(p.0) < boundary_position(p.1) {
+                    *p
+                } else {
+                    Pair(p.1, p.0)
+                };
+                (0..k).map(move |r| Pair(copy_of(x, r), copy_of(y, k - 1 - r)))
+            })
+            .collect()
+    }
+
+    fn mirror(&self, source: usize, target: usize) -> Self {
+        /*
+        same numbering convention as flip_upside_down
+        but now reflecting the diagram left to right
+        so the i'th dot on the source side goes to the source-1-i'th
+        and similarly on the target side
+        */
+        self.pairs
+            .iter()
+            .map(|x| x.mirror(source, target))
+            .collect()
+    }
+
+    fn non_crossing(&self, source: usize, _target: usize) -> bool {
+        /*
+        when interpreting this as a BrauerDiagram with specified domain/codomain (sum of both=2n)
+        is it actually temperley lieb with no crossings
+        */
+        let source_lines = self.pairs.iter().filter(|p| p.all(|x| x < source)).cloned();
+        // source_lines are the lines connecting two points both on source side
+        let source_crossing_tests = source_lines.clone().combinations(2);
+        for cur_item in source_crossing_tests {
+            let first_block = cur_item[0];
+            let second_block = cur_item[1];
+            let a = first_block.contains(second_block.0);
+            let b = first_block.contains(second_block.1);
+            if a != b {
+                // a pair of lines that both connected source dots, crossed
+                return false;
+            }
+        }
+        // no crossing lines can use these indices because they are blocked by a line connecting
+        //      two source points
+        let mut no_through_lines_idx: HashSet<_> = source_lines
+            .flat_map(|Pair(x, y)| (1 + x.min(y))..x.max(y))
+            .collect();
+
+        // the lines connecting two points both on target side
+        let target_lines = self
+            .pairs
+            .iter()
+            .filter(|p| p.all(|x| x >= source))
+            .cloned();
+        let target_crossing_tests = target_lines.clone().combinations(2);
+        for cur_item in target_crossing_tests {
+            let first_block = cur_item[0];
+            let second_block = cur_item[1];
+            let a = first_block.contains(second_block.0);
+            let b = first_block.contains(second_block.1);
+            if a != b {
+                // a pair of lines that both connected target dots, crossed
+                return false;
+            }
+        }
+
+        // no crossing lines can use these indices because they are blocked by a line connecting
+        // two target points
+
+        no_through_lines_idx.extend(target_lines.flat_map(|Pair(x, y)| (1 + x.min(y))..x.max(y)));
+
+        // now check that those crossing lines don't use those indices that were stated to be forbidden
+        let through_lines = self
+            .pairs
+            .iter()
+            .filter(|Pair(z, w)| (*z < source && *w >= source) || (*w < source && *z >= source))
+            .map(|p| p.sort());
+
+        if through_lines
+            .clone()
+            .any(|p| p.any(|x| no_through_lines_idx.contains(&x)))
+        {
+            return false;
+        }
+
+        // the induced map from the through_lines is monotonically increasing
+        through_lines.map(|Pair(_, w)| w).is_sorted()
+    }
+
+    fn crossing_pairs(&self, source: usize, target: usize) -> Vec<(Pair, Pair)> {
+        /*
+        when interpreting this as a BrauerDiagram with specified domain/codomain (sum of both=2n)
+        which pairs of lines must cross
+        go around the boundary of the diagram, first along the source dots
+            and then back along the target dots in the opposite direction
+        two lines must cross when exactly one endpoint of one lies strictly between the endpoints of the other
+        drawing every line straight realizes exactly one crossing for each such pair
+            so the length of this is the minimal number of crossings
+        */
+        let around_boundary = |v: usize| {
+            if v < source {
+                v
+            } else {
+                source + target - 1 - (v - source)
+            }
+        };
+        self.pairs
+            .iter()
+            .tuple_combinations()
+            .filter(|(p, q)| {
+                let p_around = p.map(around_boundary);
+                let q_around = q.map(around_boundary);
+                p_around.contains(q_around.0) != p_around.contains(q_around.1)
+            })
+            .map(|(p, q)| (*p, *q))
+            .collect()
+    }
+
+    fn crossing_number(&self, source: usize, target: usize) -> usize {
+        /*
+        the minimal number of crossings when interpreting this as a BrauerDiagram
+        zero exactly when non_crossing
+        */
+        self.crossing_pairs(source, target).len()
+    }
+}
+
+/*
+a single Brauer Diagram (and an accompanying power of delta)
+- a domain size
+- a codomain size
+- some number of circles contributing delta^k
+- a perfect matching on domain+codomain
+*/
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct ExtendedPerfectMatching((usize, usize, usize, PerfectMatching));
+
+impl Mul for ExtendedPerfectMatching {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        &self * &rhs
+    }
+}
+
+impl Mul for &ExtendedPerfectMatching {
+    /*
+    concatenate the two diagrams
+    removing any circles, and adding them to the combined power of delta
+    only needs to read the two diagrams so it works on references
+    */
+    type Output = ExtendedPerfectMatching;
+
+    fn mul(self, rhs: Self) -> ExtendedPerfectMatching {
+        let (self_dom, self_cod, self_delta_pow, self_diagram) = &self.0;
+        let (self_dom, self_cod, self_delta_pow) = (*self_dom, *self_cod, *self_delta_pow);
+        let (rhs_dom, rhs_cod, rhs_delta_pow, rhs_diagram) = &rhs.0;
+        let (rhs_dom, rhs_cod, rhs_delta_pow) = (*rhs_dom, *rhs_cod, *rhs_delta_pow);
+        assert_eq!(rhs_dom, self_cod);
+        let mut g = Graph::<(), (), Undirected>::new_undirected();
+        let mut node_idcs = vec![None; self_dom + self_cod + rhs_cod];
+        for &Pair(p, q) in &self_diagram.pairs {
+            let p_loc = g.add_node(());
+            node_idcs[p] = Some(p_loc);
+            let q_loc = g.add_node(());
+            node_idcs[q] = Some(q_loc);
+            g.add_edge(p_loc, q_loc, ());
+        }
+        for (idx, cur_item) in node_idcs.iter().enumerate().take(self_dom + self_cod) {
+            assert!(
+                cur_item.is_some(),
+                "index for {idx} unset. These were the ones in self_diagram {:?}",
+                self_diagram.pairs
+            );
+        }
+        for &Pair(p, q) in &rhs_diagram.pairs {
+            let p_loc = if p >= rhs_dom {
+                let p_loc_temp = g.add_node(());
+                node_idcs[p + self_dom] = Some(p_loc_temp);
+                p_loc_temp
+            } else {
+                node_idcs[p + self_dom].unwrap()
+            };
+            let q_loc = if q >= rhs_dom {
+                let q_loc_temp = g.add_node(());
+                node_idcs[q + self_dom] = Some(q_loc_temp);
+                q_loc_temp
+            } else {
+                node_idcs[q + self_dom].unwrap()
+            };
+            g.add_edge(p_loc, q_loc, ());
+        }
+        for (idx, cur_item) in node_idcs.iter().enumerate() {
+            assert!(
+                cur_item.is_some(),
+                "index for {idx} unset. These were the ones in rhs {:?}",
+                rhs_diagram.pairs
+            );
+        }
+        let endpoints = self_dom + rhs_cod;
+        let mut endpoints_done = HashSet::<usize>::with_capacity(endpoints);
+        let mut workspace = DfsSpace::new(&g);
+        let mut final_matching = Vec::with_capacity(endpoints / 2);
+        for i in 0..endpoints {
+            if endpoints_done.contains(&i) {
+                continue;
+            }
+            let i_loc = node_idcs[if i < self_dom { i } else { i + self_cod }].unwrap();
+            for j in (i + 1)..endpoints {
+                let j_loc = node_idcs[if j < self_dom { j } else { j + self_cod }].unwrap();
+                let ij_conn = has_path_connecting(&g, i_loc, j_loc, Some(&mut workspace));
+                if ij_conn {
+                    final_matching.push(Pair(i, j));
+                    endpoints_done.insert(i);
+                    endpoints_done.insert(j);
+                    break;
+                }
+            }
+        }
+        let new_delta_power =
+            connected_components(&g) + self_delta_pow + rhs_delta_pow - (endpoints / 2);
+        ExtendedPerfectMatching((
+            self_dom,
+            rhs_cod,
+            new_delta_power,
+            PerfectMatching::from_iter(final_matching),
+        ))
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedBrauerMorphism<T>")
+)]
+pub struct BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone,
+{
+    /*
+    a linear combination of (usize,PerfectMatching)
+        where a term (k,match) means \delta^k*(match interpreted with source and target)
+    the source and target are common for all terms
+    is_def_tl means all the terms are non-crossing
+    */
+    diagram: LinearCombination<T, (usize, PerfectMatching)>,
+    source: usize,
+    target: usize,
+    is_def_tl: bool,
+}
+
+/*
+the same fields as BrauerMorphism as they were read in
+before check_valid has confirmed the invariants that construction would have maintained
+*/
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedBrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone,
+{
+    diagram: LinearCombination<T, (usize, PerfectMatching)>,
+    source: usize,
+    target: usize,
+    is_def_tl: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<UncheckedBrauerMorphism<T>> for BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone,
+{
+    type Error = String;
+
+    fn try_from(unchecked: UncheckedBrauerMorphism<T>) -> Result<Self, Self::Error> {
+        let ret_val = Self {
+            diagram: unchecked.diagram,
+            source: unchecked.source,
+            target: unchecked.target,
+            is_def_tl: unchecked.is_def_tl,
+        };
+        ret_val.check_valid()?;
+        Ok(ret_val)
+    }
+}
+
+impl<T> PartialEq for BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone + Eq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.diagram == other.diagram && self.source == other.source && self.target == other.target
+    }
+}
+
+impl<T> Debug for BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BrauerMorphism")
+            .field("diagram", &self.diagram)
+            .field("source", &self.source)
+            .field("target", &self.target)
+            .field("is_def_tl", &self.is_def_tl)
+            .finish()
+    }
+}
+
+impl<T> HasIdentity<usize> for BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone,
+{
+    fn identity(on_this: &usize) -> Self {
+        let matching: PerfectMatching = (0..*on_this).map(|x| Pair(x, x + on_this)).collect();
+        Self {
+            diagram: LinearCombination::singleton((0, matching)),
+            source: *on_this,
+            target: *on_this,
+            is_def_tl: true,
+        }
+    }
+}
+
+impl<T> Composable<usize> for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    fn compose(&self, other: &Self) -> Result<Self, Error> {
+        /*
+        put the domain and codomain information into each term to get ExtendedPerfectMatching
+        the multiplication implementation on ExtendedPerfectMatching
+        which describes the composition of single terms
+        so that induces the implementation of multiplication on LinearCombination<T,ExtendedPerfectMatching>
+        then put that information back into a BrauerMorphism<T>
+        */
+        self.composable(other)?;
+        let extended_diagram_self = self.diagram.inj_linearly_extend(|(delta_pow, diagram)| {
+            ExtendedPerfectMatching((self.domain(), self.codomain(), delta_pow, diagram))
+        });
+        let extended_diagram_other = other.diagram.inj_linearly_extend(|(delta_pow, diagram)| {
+            ExtendedPerfectMatching((other.domain(), other.codomain(), delta_pow, diagram))
+        });
+        let extended_diagram_product =
+            extended_diagram_self.into_linear_combine(extended_diagram_other, |a, b| a * b);
+        let diagram = extended_diagram_product
+            .into_iter()
+            .map(
+                |(ExtendedPerfectMatching((_, _, delta_pow, matching)), coeff)| {
+                    ((delta_pow, matching), coeff)
+                },
+            )
+            .collect::<LinearCombination<_, _>>();
+        Ok(Self {
+            diagram,
+            source: self.domain(),
+            target: other.codomain(),
+            is_def_tl: self.is_def_tl && other.is_def_tl,
+        })
+    }
+
+    fn domain(&self) -> usize {
+        self.source
+    }
+
+    fn codomain(&self) -> usize {
+        self.target
+    }
+}
+
+impl<T> Monoidal for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    fn monoidal(&mut self, other: Self) {
+        let old_domain = self.domain();
+        let old_codomain = self.codomain();
+        let other_domain = other.domain();
+        self.source += other_domain;
+        self.target += other.codomain();
+        let new_domain = self.domain();
+        self.is_def_tl &= other.is_def_tl;
+        self.diagram = std::mem::replace(&mut self.diagram, LinearCombination::zero())
+            .into_linear_combine(
+                other.diagram,
+                |(delta_pow1, matching_1), (delta_pow2, matching2)| {
+                    let mut new_matching = matching_1.shift_index(old_domain, other_domain);
+                    let mut other_shifted = matching2.shift_index(0, old_domain);
+                    other_shifted = other_shifted.shift_index(new_domain, old_codomain);
+                    new_matching.pairs.extend(other_shifted.pairs);
+                    new_matching.canonicalize();
+                    (delta_pow1 + delta_pow2, new_matching)
+                },
+            );
+    }
+}
+
+impl<T> BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone,
+{
+    #[allow(dead_code)]
+    pub fn check_valid(&self) -> Result<(), String> {
+        /*
+        the invariants that are maintained by construction
+        but need to be checked when a morphism comes from somewhere else like a file
+        every term has to match up all source+target dots
+        and if it claims to be Temperley-Lieb then there can be no crossings
+        */
+        let expected = self.source + self.target;
+        for (_, matching) in self.diagram.keys() {
+            if matching.pairs.len() * 2 != expected {
+                return Err(format!(
+                    "A term had {} dots but expected {} from the source and target",
+                    matching.pairs.len() * 2,
+                    expected
+                ));
+            }
+            if self.is_def_tl && !matching.non_crossing(self.source, self.target) {
+                return Err("A term had a crossing but claimed to be Temperley-Lieb".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    fn nested_caps(n: usize, on_source: bool) -> Self {
+        /*
+        the 2n dots on one side matched up i with 2n-1-i
+        so they are nested and there are no crossings
+        nothing on the other side
+        */
+        Self {
+            diagram: LinearCombination::singleton((
+                0,
+                (0..n).map(|i| Pair(i, 2 * n - 1 - i)).collect(),
+            )),
+            source: if on_source { 2 * n } else { 0 },
+            target: if on_source { 0 } else { 2 * n },
+            is_def_tl: true,
+        }
+    }
+}
+
+impl<T> RigidMonoidalMorphism<usize> for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    /*
+    n is self dual on both sides
+    evaluations are nested caps and coevaluations are nested cups
+    */
+    fn left_dual(object: &usize) -> usize {
+        *object
+    }
+
+    fn right_dual(object: &usize) -> usize {
+        *object
+    }
+
+    fn left_evaluation(object: &usize) -> Self {
+        Self::nested_caps(*object, true)
+    }
+
+    fn left_coevaluation(object: &usize) -> Self {
+        Self::nested_caps(*object, false)
+    }
+
+    fn right_evaluation(object: &usize) -> Self {
+        Self::nested_caps(*object, true)
+    }
+
+    fn right_coevaluation(object: &usize) -> Self {
+        Self::nested_caps(*object, false)
+    }
+}
+
+impl<T> Dagger<usize> for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync
+        + Conjugate,
+{
+    /*
+    flip every diagram upside down and conjugate its coefficient
+    */
+    fn dagger(&self) -> Self {
+        self.dagger_with(|z| z.conjugate())
+    }
+}
+
+impl<T> CompactClosed<usize> for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    /*
+    the same self duality as in the rigid structure
+    cups and caps are nested so transposing is rotating the diagram by a half turn
+    */
+    fn dual(object: &usize) -> usize {
+        *object
+    }
+
+    fn unit(object: &usize) -> Self {
+        Self::nested_caps(*object, false)
+    }
+
+    fn counit(object: &usize) -> Self {
+        Self::nested_caps(*object, true)
+    }
+}
+
+impl<T> Braided<usize> for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    /*
+    Brauer is symmetric so the braiding is the swap
+    and its inverse is the swap the other way around
+    */
+    fn braiding(a: &usize, b: &usize) -> Self {
+        Self::swap(*a, *b)
+    }
+
+    fn inverse_braiding(a: &usize, b: &usize) -> Self {
+        Self::swap(*b, *a)
+    }
+}
+
+impl<T> SymmetricMonoidalDiscreteMorphism<usize> for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    /*
+    position i of the domain goes to position p(i) of the codomain
+    with only one type of strand it does not matter which side types_as_on_domain picks
+    */
+    fn permute_side(&mut self, p: &Permutation, of_codomain: bool) {
+        if of_codomain {
+            assert_eq!(p.len(), self.target);
+            *self = self
+                .compose(&Self::from_permutation(p.inv(), self.target, true))
+                .expect("the crossings start where self ends");
+        } else {
+            assert_eq!(p.len(), self.source);
+            *self = Self::from_permutation(p.clone(), self.source, false)
+                .compose(self)
+                .expect("the crossings end where self starts");
+        }
+    }
+
+    fn from_permutation(p: Permutation, type_: usize, _types_as_on_domain: bool) -> Self {
+        assert_eq!(p.len(), type_);
+        Self {
+            diagram: LinearCombination::singleton((
+                0,
+                (0..type_).map(|i| Pair(i, type_ + p.apply(i))).collect(),
+            )),
+            source: type_,
+            target: type_,
+            is_def_tl: (0..type_).all(|i| p.apply(i) == i),
+        }
+    }
+}
+
+impl<T> RibbonMonoidalMorphism<usize> for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    /*
+    the twist is trivial
+    */
+    fn twist(object: &usize) -> Self {
+        Self::identity(object)
+    }
+}
+
+impl<T> Mul for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    /*
+    self * rhs is the composition self;rhs
+    so e(1,5) * e(3,5) can be written instead of e(1,5).compose(&e(3,5))
+    PANICS : if the codomain of self is not the domain of rhs
+        because Mul has nowhere to put an error
+    use compose instead whenever the sizes are not known to match
+        it gives back the same mismatch as an Err
+    */
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        match self.compose(&rhs) {
+            Ok(composite) => composite,
+            Err(e) => panic!("Could not compose Brauer morphisms: {}", e),
+        }
+    }
+}
+
+impl<T> BitAnd for BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    /*
+    self & rhs is the monoidal product of self and rhs
+    this binds less tightly than * so e(1,5) * e(3,5) & id(2) means (e(1,5);e(3,5)) \otimes id(2)
+    */
+    type Output = Self;
+
+    fn bitand(mut self, rhs: Self) -> Self {
+        self.monoidal(rhs);
+        self
+    }
+}
+
+impl<T> BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    #[allow(dead_code)]
+    pub fn temperley_lieb_gens(n: usize) -> Vec<Self> {
+        /*
+        give the Temperley-Lieb e_1 \cdots e_{n-1}
+        as elements of Hom_{Brauer}(n,n)
+        */
+        (0..n - 1).map(|i| Self::temperley_lieb_gen(i, n)).collect()
+    }
+
+    pub fn temperley_lieb_gen(i: usize, n: usize) -> Self {
+        /*
+        give the Temperley-Lieb e_{i+1} which caps off i and i+1 on both sides
+        as an element of Hom_{Brauer}(n,n)
+        */
+        assert!(
+            i + 1 < n,
+            "There is no e_{} in Hom_{{Brauer}}({n},{n})",
+            i + 1
+        );
+        Self {
+            diagram: LinearCombination::singleton((
+                0,
+                (0..n)
+                    .map(|j| {
+                        (if j == i {
+                            (i, i + 1)
+                        } else if j == i + 1 {
+                            (i + n, i + 1 + n)
+                        } else {
+                            (j, j + n)
+                        })
+                        .into()
+                    })
+                    .collect(),
+            )),
+            source: n,
+            target: n,
+            is_def_tl: true,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn symmetric_alg_gens(n: usize) -> Vec<Self> {
+        /*
+        give the symmetric algebra s_1 \cdots s_{n-1}
+        where s_i permutes i and i+1 in 1..n (interpreted in domain and codomain appropriately)
+        matches the rest with their shift in the codomain
+        as elements of Hom_{Brauer}(n,n)
+        */
+        (0..(n - 1))
+            .map(|i| Self::symmetric_alg_gen(i, n))
+            .collect()
+    }
+
+    pub fn symmetric_alg_gen(i: usize, n: usize) -> Self {
+        /*
+        give the symmetric algebra s_{i+1} which permutes i and i+1
+        as an element of Hom_{Brauer}(n,n)
+        */
+        assert!(
+            i + 1 < n,
+            "There is no s_{} in Hom_{{Brauer}}({n},{n})",
+            i + 1
+        );
+        Self {
+            diagram: LinearCombination::singleton((
+                0,
+                (0..n)
+                    .map(|j| {
+                        (if j == i {
+                            (i, i + n + 1)
+                        } else if j == i + 1 {
+                            (i + 1, i + n)
+                        } else {
+                            (j, j + n)
+                        })
+                        .into()
+                    })
+                    .collect(),
+            )),
+            source: n,
+            target: n,
+            is_def_tl: false,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn swap(m: usize, n: usize) -> Self {
+        /*
+        the symmetric braiding which takes the first m strands past the last n strands
+        as an element of Hom_{Brauer}(m+n,n+m)
+        the i'th of the first m goes to n+i in the codomain
+        the i'th of the last n goes to i in the codomain
+        */
+        let source = m + n;
+        Self {
+            diagram: LinearCombination::singleton((
+                0,
+                (0..source)
+                    .map(|i| {
+                        if i < m {
+                            Pair(i, source + n + i)
+                        } else {
+                            Pair(i, source + i - m)
+                        }
+                    })
+                    .collect(),
+            )),
+            source,
+            target: source,
+            is_def_tl: m == 0 || n == 0,
+        }
+    }
+
+    pub fn delta_polynomial(coeffs: &[T]) -> Self {
+        /*
+        The morphisms in Hom_{Brauer}(0,0) are in the polynomial ring T[delta]
+        Give such an element upon specifying the coefficients of such a polynomial
+        */
+        let zeroth_coeff = coeffs.first().cloned().unwrap_or_else(T::zero);
+        let empty_matching = PerfectMatching { pairs: vec![] };
+        let mut diagram = LinearCombination::singleton((0, empty_matching));
+        diagram *= zeroth_coeff;
+        for (idx, cur_coeff) in coeffs.iter().enumerate().skip(1) {
+            let empty_matching = PerfectMatching { pairs: vec![] };
+            let mut cur_diagram = LinearCombination::singleton((idx, empty_matching));
+            cur_diagram *= cur_coeff.clone();
+            diagram += cur_diagram;
+        }
+        Self {
+            diagram,
+            source: 0,
+            target: 0,
+            is_def_tl: true,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn dagger_with<F>(&self, num_dagger: F) -> Self
+    where
+        F: Fn(T) -> T,
+    {
+        /*
+        for each term, flip the diagram upside down and change the coefficient to it's daggger
+        as specified by the num_dagger function
+        */
+        let diagram = self.diagram.antilinearly_extend(
+            |(d, m)| (d, m.flip_upside_down(self.source, self.target)),
+            num_dagger,
+        );
+        Self {
+            diagram,
+            source: self.target,
+            target: self.source,
+            is_def_tl: self.is_def_tl,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn intern_terms(
+        &self,
+        interner: &mut Interner<PerfectMatching>,
+    ) -> LinearCombination<T, (usize, std::sync::Arc<PerfectMatching>)> {
+        /*
+        the terms with each diagram replaced by its shared copy in interner
+        for keeping many morphisms around whose terms use a lot of the same diagrams
+        */
+        self.diagram
+            .iter()
+            .map(|((delta_pow, matching), coeff)| {
+                (
+                    (*delta_pow, interner.intern(matching.clone())),
+                    coeff.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn map_coeffs<T2, F>(&self, coeff_changer: F) -> BrauerMorphism<T2>
+    where
+        T2: Add<Output = T2> + Zero + One + Clone,
+        F: Fn(T) -> T2,
+    {
+        /*
+        the same diagrams with the coefficients sent through a ring homomorphism T -> T2
+        */
+        BrauerMorphism {
+            diagram: self.diagram.clone().map_coeffs(coeff_changer),
+            source: self.source,
+            target: self.target,
+            is_def_tl: self.is_def_tl,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn cable(&self, k: usize) -> Self {
+        /*
+        replace every strand by k parallel strands
+        so Hom(n,m) goes to Hom(nk,mk)
+        each closed loop becomes k loops so delta^d becomes delta^{kd}
+        crossings of two strands become k^2 crossings
+        and non-crossing diagrams stay non-crossing
+        when k is 0 all the terms become the same empty diagram and get added together
+        */
+        let diagram = self
+            .diagram
+            .linearly_extend(|(d, m)| (d * k, m.cable(self.source, self.target, k)));
+        Self {
+            diagram,
+            source: self.source * k,
+            target: self.target * k,
+            is_def_tl: self.is_def_tl || k == 0,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn mirror(&self) -> Self {
+        /*
+        for each term, reflect the diagram left to right
+        the source and target stay the same
+        and reflecting does not introduce any crossings
+        */
+        let diagram = self
+            .diagram
+            .inj_linearly_extend(|(d, m)| (d, m.mirror(self.source, self.target)));
+        Self {
+            diagram,
+            source: self.source,
+            target: self.target,
+            is_def_tl: self.is_def_tl,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn set_is_tl(&mut self) {
+        /*
+        if not sure that it is definitely a Temperley-Lieb morphism,
+        then check the diagrams in all the terms
+        if already sure, then don't need to check
+        */
+        if self.is_def_tl {
+            return;
+        }
+        self.is_def_tl = self
+            .diagram
+            .all_terms_satisfy(|(_, p)| p.non_crossing(self.source, self.target));
+    }
+
+    #[allow(dead_code)]
+    pub fn crossing_pairs(&self) -> Vec<Vec<(Pair, Pair)>> {
+        /*
+        for each term, which pairs of lines of that diagram have to cross
+        useful for seeing why set_is_tl did not conclude this was Temperley-Lieb
+        */
+        self.diagram
+            .keys()
+            .map(|(_, p)| p.crossing_pairs(self.source, self.target))
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn crossing_number(&self) -> usize {
+        /*
+        the largest minimal number of crossings among the diagrams in all the terms
+        this is 0 exactly when it is a Temperley-Lieb morphism
+        */
+        self.diagram
+            .keys()
+            .map(|(_, p)| p.crossing_number(self.source, self.target))
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[allow(dead_code)]
+    pub fn split_non_crossing(&self) -> (Self, Self) {
+        /*
+        the Temperley-Lieb part with only planar diagrams and the rest with at least one crossing
+        they sum back to self
+        */
+        let (planar, crossing) = self
+            .diagram
+            .clone()
+            .partition_terms(|(_, p), _| p.non_crossing(self.source, self.target));
+        (
+            Self {
+                diagram: planar,
+                source: self.source,
+                target: self.target,
+                is_def_tl: true,
+            },
+            Self {
+                diagram: crossing,
+                source: self.source,
+                target: self.target,
+                is_def_tl: false,
+            },
+        )
+    }
+}
+
+impl<T> BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync,
+{
+    #[allow(dead_code)]
+    pub fn temperley_lieb_crossing(
+        i: usize,
+        n: usize,
+        identity_coeff: T,
+        cup_cap_coeff: T,
+    ) -> Self {
+        /*
+        identity_coeff*1 + cup_cap_coeff*e_{i+1} as an element of Hom_{Brauer}(n,n)
+        with A and A^{-1} this is the Kauffman bracket of strand i crossing over strand i+1
+        and with A^{-1} and A it is the opposite crossing
+        */
+        let mut diagram = Self::identity(&n).diagram * identity_coeff;
+        diagram += Self::temperley_lieb_gen(i, n).diagram * cup_cap_coeff;
+        Self {
+            diagram,
+            source: n,
+            target: n,
+            is_def_tl: true,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn markov_trace(&self, delta: T) -> Result<T, String> {
+        /*
+        close up each diagram by joining i in the source to i in the target around the right
+        so the closure of a Temperley-Lieb diagram stays planar
+        every closed loop is a factor of delta, on top of the power of delta already in the term
+        on Hom(0,0) this evaluates the polynomial in delta
+        */
+        if self.source != self.target {
+            return Err(format!(
+                "Only endomorphisms have a trace. This was {} -> {}",
+                self.source, self.target
+            ));
+        }
+        let n = self.source;
+        let mut answer = T::zero();
+        for ((delta_pow, matching), coeff) in self.diagram.iter() {
+            let mut g = Graph::<(), (), Undirected>::new_undirected();
+            let nodes: Vec<_> = (0..2 * n).map(|_| g.add_node(())).collect();
+            for &Pair(p, q) in &matching.pairs {
+                g.add_edge(nodes[p], nodes[q], ());
+            }
+            for idx in 0..n {
+                g.add_edge(nodes[idx], nodes[idx + n], ());
+            }
+            let loops = connected_components(&g) + delta_pow;
+            answer += (0..loops).fold(coeff.clone(), |acc, _| acc * delta.clone());
+        }
+        Ok(answer)
+    }
+}
+
+impl<T> BrauerMorphism<T>
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync
+        + Neg<Output = T>,
+{
+    #[allow(dead_code)]
+    pub fn jones_wenzl(n: usize) -> (Self, Self) {
+        /*
+        the Jones-Wenzl projector p_n in Temperley-Lieb(n) has p_0 and p_1 the identity and
+            p_{k+1} = p_k \otimes 1 - (D_{k-1}/D_k) (p_k \otimes 1) e_k (p_k \otimes 1)
+        with D_0 = 1, D_1 = delta and D_{k+1} = delta D_k - D_{k-1}
+        that divides by polynomials in delta so instead this gives (d_n p_n, d_n)
+            with d_n in Hom(0,0) given by d_0 = d_1 = 1 and d_{k+1} = D_k d_k^2
+        multiplying the recursion through by D_k d_k^2 leaves only polynomials in delta
+            d_{k+1} p_{k+1} = D_k d_k (d_k p_k \otimes 1) - D_{k-1} (d_k p_k \otimes 1) e_k (d_k p_k \otimes 1)
+        */
+        let mut chebyshev = vec![vec![T::one()], vec![T::zero(), T::one()]];
+        while chebyshev.len() < n {
+            let k = chebyshev.len();
+            let mut next = vec![T::zero()];
+            next.extend(chebyshev[k - 1].iter().cloned());
+            for (idx, coeff) in chebyshev[k - 2].iter().enumerate() {
+                next[idx] = next[idx].clone() + (-coeff.clone());
+            }
+            chebyshev.push(next);
+        }
+        let mut scaled = Self::identity(&n.min(1));
+        let mut scale = Self::identity(&0);
+        for k in 1..n {
+            let mut widened = scaled;
+            widened.monoidal(Self::identity(&1));
+            let sandwich = widened
+                .compose(&Self::temperley_lieb_gen(k - 1, k + 1))
+                .and_then(|x| x.compose(&widened))
+                .expect("Everything here is in Hom(k+1,k+1)");
+            let d_k = Self::delta_polynomial(&chebyshev[k]);
+            let kept = d_k.clone() & scale.clone() & widened;
+            let removed = Self::delta_polynomial(&chebyshev[k - 1]) & sandwich;
+            let mut diagram = kept.diagram + (-removed.diagram);
+            diagram.simplify();
+            scaled = Self {
+                diagram,
+                source: k + 1,
+                target: k + 1,
+                is_def_tl: true,
+            };
+            scale = d_k & scale.clone() & scale;
+        }
+        (scaled, scale)
+    }
+}
+
+impl<T> BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + ComplexFloat,
+{
+    #[allow(dead_code)]
+    pub fn approx_eq(&self, other: &Self, tolerance: T::Real) -> bool {
+        /*
+        same source and target and the coefficients of each diagram are within tolerance
+        the powers of delta are compared exactly so specialize_delta first to compare numerically
+        */
+        self.source == other.source
+            && self.target == other.target
+            && self.diagram.approx_eq(&other.diagram, tolerance)
+    }
+}
+
+impl<T> BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone + Mul<Output = T>,
+{
+    #[allow(dead_code)]
+    pub fn to_matrix(&self, dimension: usize) -> MatrixMorphism<T> {
+        /*
+        each strand carries a vector space of that dimension with its standard basis
+        so n strands give the Kronecker product with the first strand most significant
+        a cup or cap pairs up equal basis elements and a through strand is the identity
+        so the entry for a choice of basis element at every endpoint
+            is whether every pair of the matching chose the same one
+        and delta is the dimension because that is what a closed loop gives
+        */
+        let delta = (0..dimension).fold(T::zero(), |acc, _| acc + T::one());
+        let rows = dimension.pow(self.source as u32);
+        let columns = dimension.pow(self.target as u32);
+        let mut entries = vec![vec![T::zero(); columns]; rows];
+        for ((power, matching), coeff) in self.diagram.iter() {
+            let scale = (0..*power).fold(coeff.clone(), |acc, _| acc * delta.clone());
+            for choice in 0..dimension.pow(matching.pairs.len() as u32) {
+                let mut labels = vec![0; self.source + self.target];
+                let mut remaining = choice;
+                for pair in &matching.pairs {
+                    labels[pair.0] = remaining % dimension;
+                    labels[pair.1] = remaining % dimension;
+                    remaining /= dimension;
+                }
+                let as_index =
+                    |labels: &[usize]| labels.iter().fold(0, |acc, label| acc * dimension + label);
+                let row = as_index(&labels[..self.source]);
+                let column = as_index(&labels[self.source..]);
+                entries[row][column] = entries[row][column].clone() + scale.clone();
+            }
+        }
+        MatrixMorphism::new(rows, columns, entries).expect("the sizes were chosen to fit")
+    }
+}
+
+impl<T> BrauerMorphism<T>
+where
+    T: Add<Output = T> + Zero + One + Clone + Display,
+{
+    #[allow(dead_code)]
+    pub fn to_compact_string(&self) -> String {
+        /*
+        one line summary like TL(4→4): 2·d^1·[0-1|2-3|4-5|6-7] + 1·d^0·[0-4|1-5|2-6|3-7]
+        TL or Br depending on whether it is known to be Temperley-Lieb
+        then each term as coefficient, power of delta and the matched pairs
+        the terms are sorted so the same morphism always gives the same string
+        */
+        let kind = if self.is_def_tl { "TL" } else { "Br" };
+        let mut terms: Vec<_> = self.diagram.iter().collect();
+        terms.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0).then_with(|| a.1.pairs.cmp(&b.1.pairs)));
+        let terms = terms
+            .into_iter()
+            .map(|((delta_pow, matching), coeff)| {
+                let pairs = matching
+                    .pairs
+                    .iter()
+                    .map(|Pair(x, y)| format!("{}-{}", x, y))
+                    .collect::<Vec<_>>()
+                    .join("|");
+                format!("{}·d^{}·[{}]", coeff, delta_pow, pairs)
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "{}({}→{}): {}",
+            kind,
+            self.source,
+            self.target,
+            if terms.is_empty() {
+                "0".to_string()
+            } else {
+                terms.join(" + ")
+            }
+        )
+    }
+}
+
+fn matchings_of(points: &[usize]) -> Vec<Vec<Pair>> {
+    /*
+    all the ways to pair up points
+    the first one is paired with each of the others in turn
+    */
+    let Some((first, rest)) = points.split_first() else {
+        return vec![vec![]];
+    };
+    (0..rest.len())
+        .flat_map(|i| {
+            let others: Vec<usize> = rest
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, v)| *v)
+                .collect();
+            matchings_of(&others).into_iter().map(move |mut m| {
+                m.push(Pair(*first, rest[i]));
+                m
+            })
+        })
+        .collect()
+}
+
+fn all_diagrams(source: usize, target: usize, non_crossing: bool) -> Vec<PerfectMatching> {
+    /*
+    the basis of Hom_{Brauer}(source,target) when delta is specialized
+    or of the Temperley-Lieb part if non_crossing
+    */
+    let points: Vec<usize> = (0..source + target).collect();
+    matchings_of(&points)
+        .into_iter()
+        .map(PerfectMatching::from_iter)
+        .filter(|m| !non_crossing || m.non_crossing(source, target))
+        .collect()
+}
+
+impl<T> BrauerMorphism<T>
+where
+    T: Num + Clone + AddAssign + MulAssign + MaybeSendSync,
+{
+    #[allow(dead_code)]
+    pub fn specialize_delta(&self, delta: T) -> Self {
+        /*
+        replace delta by the value delta
+        so afterwards every term has delta^0
+        */
+        let diagram = self
+            .diagram
+            .iter()
+            .map(|((power, matching), coeff)| {
+                let scale = (0..*power).fold(T::one(), |acc, _| acc * delta.clone());
+                LinearCombination::singleton((0, matching.clone())) * (coeff.clone() * scale)
+            })
+            .fold(LinearCombination::zero(), |acc, term| acc + term);
+        Self {
+            diagram,
+            source: self.source,
+            target: self.target,
+            is_def_tl: self.is_def_tl,
+        }
+    }
+
+    fn action_matrix(&self, delta: T) -> Result<(Vec<PerfectMatching>, Vec<Vec<T>>), String> {
+        /*
+        the matrix of x -> x;self on Hom(n,n) with delta specialized
+        in the basis of diagrams, only the non-crossing ones if self is known to be Temperley-Lieb
+        entry (i,j) is the coefficient of basis i in basis j ; self
+        */
+        if self.source != self.target {
+            return Err(format!(
+                "Only endomorphisms act on their own Hom space. This was {} -> {}",
+                self.source, self.target
+            ));
+        }
+        let basis = all_diagrams(self.source, self.target, self.is_def_tl);
+        let mut matrix = vec![vec![T::zero(); basis.len()]; basis.len()];
+        for (j, basis_j) in basis.iter().enumerate() {
+            let basis_morphism = Self {
+                diagram: LinearCombination::singleton((0, basis_j.clone())),
+                source: self.source,
+                target: self.target,
+                is_def_tl: self.is_def_tl,
+            };
+            let moved = basis_morphism
+                .compose(self)?
+                .specialize_delta(delta.clone());
+            for (i, basis_i) in basis.iter().enumerate() {
+                if let Some(coeff) = moved.diagram.get(&(0, basis_i.clone())) {
+                    matrix[i][j] = coeff.clone();
+                }
+            }
+        }
+        Ok((basis, matrix))
+    }
+
+    fn with_coordinates(&self, basis: &[PerfectMatching], coordinates: Vec<T>) -> Self {
+        /*
+        the combination of the basis diagrams with these coefficients
+        in the same Hom space as self
+        */
+        let diagram = basis
+            .iter()
+            .zip(coordinates)
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .map(|(matching, coeff)| LinearCombination::singleton((0, matching.clone())) * coeff)
+            .fold(LinearCombination::zero(), |acc, term| acc + term);
+        Self {
+            diagram,
+            source: self.source,
+            target: self.target,
+            is_def_tl: self.is_def_tl,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn kernel(&self, delta: T) -> Result<Vec<Self>, String> {
+        /*
+        a basis for the x in Hom(n,n) with x;self = 0 once delta is specialized
+        x ranges over Temperley-Lieb if self is known to be Temperley-Lieb
+        */
+        let (basis, matrix) = self.action_matrix(delta)?;
+        Ok(linear_algebra::kernel(&matrix)?
+            .into_iter()
+            .map(|v| self.with_coordinates(&basis, v))
+            .collect())
+    }
+
+    #[allow(dead_code)]
+    pub fn image(&self, delta: T) -> Result<Vec<Self>, String> {
+        /*
+        a basis for the x;self with x in Hom(n,n) once delta is specialized
+        */
+        let (basis, matrix) = self.action_matrix(delta)?;
+        Ok(linear_algebra::image(&matrix)?
+            .into_iter()
+            .map(|v| self.with_coordinates(&basis, v))
+            .collect())
+    }
+
+    #[allow(dead_code)]
+    pub fn fixed_points(&self, delta: T) -> Result<Vec<Self>, String> {
+        /*
+        a basis for the x in Hom(n,n) with x;self = x once delta is specialized
+        for an idempotent this is the same space as the image
+        */
+        let (basis, matrix) = self.action_matrix(delta)?;
+        Ok(linear_algebra::fixed_points(&matrix)?
+            .into_iter()
+            .map(|v| self.with_coordinates(&basis, v))
+            .collect())
+    }
+}
+
+fn simplify<T>(me: &mut BrauerMorphism<T>)
+where
+    T: Add<Output = T>
+        + Zero
+        + One
+        + Clone
+        + AddAssign
+        + Mul<Output = T>
+        + MulAssign
+        + MaybeSendSync
+        + Eq,
+{
+    /*
+    get rid of all the terms with zero coefficient
+    */
+    me.diagram.simplify();
+}
+
+mod test {
+    use std::ops::{AddAssign, MulAssign};
+
+    use super::BrauerMorphism;
+    use crate::{error::Error, unstable::linear_combination::MaybeSendSync};
+    use either::Either;
+    use num::{One, Zero};
+
+    #[allow(dead_code)]
+    fn test_helper<T: Eq + AddAssign + MulAssign + Clone + One + Zero + MaybeSendSync>(
+        e_i: &[BrauerMorphism<T>],
+        s_i: &[BrauerMorphism<T>],
+        prod_these: &[Either<usize, usize>],
+        delta_poly_coeffs: &[T],
+    ) -> Result<BrauerMorphism<T>, Error> {
+        fn get_generator<T: Clone>(l_gens: &[T], r_gens: &[T], which: Either<usize, usize>) -> T {
+            use crate::utils::EitherExt;
+            which.join(|n| l_gens[n].clone(), |n| r_gens[n].clone())
+        }
+        use super::simplify;
+        use crate::{category::Composable, monoidal::Monoidal};
+        assert!(!prod_these.is_empty());
+        let prod_these_0 = get_generator(e_i, s_i, prod_these[0]);
+        let mut delta_poly = BrauerMorphism::delta_polynomial(delta_poly_coeffs);
+        simplify(&mut delta_poly);
+        if prod_these.len() == 1 {
+            let mut full_prod = prod_these_0;
+            full_prod.monoidal(delta_poly);
+            return Ok(full_prod);
+        }
+        let prod_these_1 = get_generator(e_i, s_i, prod_these[1]);
+        let mut full_prod = prod_these_0.compose(&prod_these_1);
+        for cur_idx in prod_these.iter().skip(2) {
+            let cur = get_generator(e_i, s_i, *cur_idx);
+            full_prod = full_prod.and_then(|z| z.compose(&cur));
+        }
+        match full_prod {
+            Ok(mut t) => {
+                t.monoidal(delta_poly);
+                Ok(t)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    #[test]
+    fn t_l_relations() {
+        use crate::{
+            category::{Composable, Dagger},
+            utils::test_asserter,
+        };
+        use either::Either::Left;
+        use num::Complex;
+        let e_i = BrauerMorphism::<Complex<i32>>::temperley_lieb_gens(5);
+        let delta_coeffs: [Complex<i32>; 2] = [<_>::zero(), <_>::one()];
+        for idx in 0..e_i.len() {
+            assert!(e_i[idx].is_def_tl);
+            let e_i_dag = e_i[idx].dagger();
+            assert!(
+                &e_i[idx] == &e_i_dag,
+                "{:?} vs {:?} when checking self adjointness of e_i",
+                e_i[idx],
+                e_i_dag
+            );
+            let e_ie_i = e_i[idx].compose(&e_i[idx]);
+            let deltae_i = test_helper(&e_i, &[], &[Left(idx)], &delta_coeffs);
+            test_asserter(
+                e_ie_i,
+                deltae_i,
+                |j, k| j.is_def_tl && k.is_def_tl,
+                "e_i e_i = delta e_i",
+            );
+            if idx < e_i.len() - 1 {
+                let prod_iji = e_i[idx]
+                    .compose(&e_i[idx + 1])
+                    .and_then(|z| z.compose(&e_i[idx]));
+                test_asserter(
+                    prod_iji,
+                    Ok(e_i[idx].clone()),
+                    |j, k| j.is_def_tl && k.is_def_tl,
+                    "e_i e_(i+1) e_i = e_i",
+                );
+            }
+            if idx > 1 {
+                let prod_iji = e_i[idx]
+                    .compose(&e_i[idx - 1])
+                    .and_then(|z| z.compose(&e_i[idx]));
+                test_asserter(
+                    prod_iji,
+                    Ok(e_i[idx].clone()),
+                    |j, k| j.is_def_tl && k.is_def_tl,
+                    "e_i e_(i-1) e_i = e_i",
+                );
+            }
+            for jdx in idx + 2..e_i.len() {
+                let prod_ij = e_i[idx].compose(&e_i[jdx]);
+                let prod_ji = e_i[jdx].compose(&e_i[idx]);
+                test_asserter(
+                    prod_ij,
+                    prod_ji,
+                    |j, k| j.is_def_tl && k.is_def_tl,
+                    "e_i e_j = e_j e_i",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn shifted_matchings() {
+        use super::{BrauerMorphism, Pair, PerfectMatching};
+        use crate::{category::Composable, monoidal::Monoidal};
+        /*
+        a shifted matching is only a step towards a bigger one
+            so it is not checked to be a perfect matching on its own
+        checking it made every monoidal product of two nonempty diagrams panic
+        */
+        let cup = PerfectMatching::new(&[Pair(0, 1)]);
+        assert_eq!(cup.shift_index(1, 2).pairs, vec![Pair(0, 3)]);
+        assert_eq!(cup.shift_index(0, 4).pairs, vec![Pair(4, 5)]);
+        let e_2 = BrauerMorphism::<i32>::temperley_lieb_gens(2);
+        let e_4 = BrauerMorphism::<i32>::temperley_lieb_gens(4);
+        let mut both = e_2[0].clone();
+        both.monoidal(e_2[0].clone());
+        assert_eq!((both.domain(), both.codomain()), (4, 4));
+        assert_eq!(both, e_4[0].compose(&e_4[2]).unwrap());
+    }
+
+    #[test]
+    fn operator_syntax() {
+        use super::BrauerMorphism;
+        use crate::{
+            category::{Composable, HasIdentity},
+            compose,
+            monoidal::Monoidal,
+            tensor,
+        };
+        let e = |i| BrauerMorphism::<i32>::temperley_lieb_gen(i, 5);
+        let id = |n| BrauerMorphism::<i32>::identity(&n);
+        let observed = (e(1) * e(3)) & id(2);
+        let mut expected = e(1).compose(&e(3)).unwrap();
+        expected.monoidal(id(2));
+        assert_eq!(observed, expected);
+        assert_eq!(observed.domain(), 7);
+        assert!(observed.is_def_tl);
+
+        assert_eq!(compose!(e(1), e(2), e(1)), Ok(e(1)));
+        assert!(compose!(e(1), id(2)).is_err());
+        assert_eq!(tensor!(e(1), id(2)), e(1) & id(2));
+        assert_eq!(tensor!(id(3)), id(3));
+    }
+
+    #[test]
+    fn mirror_relations() {
+        use super::{BrauerMorphism, Pair};
+        use crate::{category::Composable, unstable::linear_combination::LinearCombination};
+        let n = 5;
+        let e_i = BrauerMorphism::<i32>::temperley_lieb_gens(n);
+        let s_i = BrauerMorphism::<i32>::symmetric_alg_gens(n);
+        for i in 0..(n - 1) {
+            assert_eq!(e_i[i].mirror(), e_i[n - 2 - i]);
+            assert_eq!(s_i[i].mirror(), s_i[n - 2 - i]);
+            assert_eq!(e_i[i].mirror().mirror(), e_i[i]);
+        }
+        let prod = e_i[0].compose(&s_i[2]).unwrap();
+        let mirrored_prod = e_i[0].mirror().compose(&s_i[2].mirror()).unwrap();
+        assert_eq!(prod.mirror(), mirrored_prod);
+
+        /*
+        3 -> 1 with a cap on the first two source dots and the last one going through
+        the mirror image has the cap on the last two and the first one going through
+        */
+        let cap_left = BrauerMorphism::<i32> {
+            diagram: LinearCombination::singleton((
+                0,
+                vec![Pair(0, 1), Pair(2, 3)].into_iter().collect(),
+            )),
+            source: 3,
+            target: 1,
+            is_def_tl: true,
+        };
+        let cap_right = BrauerMorphism::<i32> {
+            diagram: LinearCombination::singleton((
+                0,
+                vec![Pair(1, 2), Pair(0, 3)].into_iter().collect(),
+            )),
+            source: 3,
+            target: 1,
+            is_def_tl: true,
+        };
+        assert_eq!(cap_left.mirror(), cap_right);
+        assert_eq!(cap_left.mirror().domain(), 3);
+        assert_eq!(cap_left.mirror().codomain(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Could not compose Brauer morphisms")]
+    fn operator_mismatch() {
+        use super::BrauerMorphism;
+        use crate::category::HasIdentity;
+        let _ = BrauerMorphism::<i32>::temperley_lieb_gen(1, 5) * BrauerMorphism::identity(&3);
+    }
+
+    #[test]
+    fn matching_validation() {
+        use super::{Pair, PerfectMatching};
+        let good = PerfectMatching::try_from(vec![Pair(3, 0), Pair(1, 2)]);
+        assert_eq!(
+            good.map(Vec::<Pair>::from),
+            Ok(vec![Pair(0, 3), Pair(1, 2)])
+        );
+        let too_big = PerfectMatching::try_from(vec![Pair(0, 4), Pair(1, 2)]);
+        assert!(too_big.is_err());
+        let repeated = PerfectMatching::try_from(vec![Pair(0, 1), Pair(1, 2)]);
+        assert!(repeated.is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn forged_serialization() {
+        use super::BrauerMorphism;
+        /*
+        reading back goes through check_valid
+        so fields that do not agree with the diagrams are rejected instead of panicking later
+        */
+        let e_1 = BrauerMorphism::<i32>::temperley_lieb_gen(0, 2);
+        let written = serde_json::to_string(&e_1).unwrap();
+        assert_eq!(
+            written,
+            r#"{"diagram":[[[0,[[0,1],[2,3]]],1]],"source":2,"target":2,"is_def_tl":true}"#
+        );
+        let read: BrauerMorphism<i32> = serde_json::from_str(&written).unwrap();
+        assert_eq!(read, e_1);
+        for forged in [
+            r#"{"diagram":[[[0,[[0,3],[1,2]]],1]],"source":2,"target":2,"is_def_tl":true}"#,
+            r#"{"diagram":[[[0,[[0,1],[2,3]]],1]],"source":1,"target":1,"is_def_tl":true}"#,
+            r#"{"diagram":[[[0,[[0,1],[2,3]]],1]],"source":2,"target":1,"is_def_tl":true}"#,
+            r#"{"diagram":[[[0,[[0,1]]],1]],"source":2,"target":2,"is_def_tl":true}"#,
+        ] {
+            let rejected: Result<BrauerMorphism<i32>, _> = serde_json::from_str(forged);
+            assert!(rejected.is_err(), "{} was accepted", forged);
+        }
+    }
+
+    #[test]
+    fn swap_relations() {
+        use super::BrauerMorphism;
+        use crate::category::{Composable, HasIdentity};
+        type B = BrauerMorphism<i32>;
+        assert_eq!(B::swap(1, 1), B::symmetric_alg_gen(0, 2));
+        assert_eq!(B::swap(3, 0), B::identity(&3));
+        assert_eq!(B::swap(0, 2), B::identity(&2));
+        for (m, n) in [(1, 2), (2, 3), (3, 1)] {
+            assert_eq!(B::swap(m, n) * B::swap(n, m), B::identity(&(m + n)));
+            assert!(!B::swap(m, n).is_def_tl);
+        }
+        /*
+        moving one strand past two is the same as moving it past each in turn
+        */
+        let one_past_two = B::symmetric_alg_gen(0, 3) * B::symmetric_alg_gen(1, 3);
+        assert_eq!(B::swap(1, 2), one_past_two);
+        /*
+        naturality with respect to a cup/cap on the first two strands
+        */
+        let e_then_swap = (B::temperley_lieb_gen(0, 2) & B::identity(&1)) * B::swap(2, 1);
+        let swap_then_e = B::swap(2, 1) * (B::identity(&1) & B::temperley_lieb_gen(0, 2));
+        assert_eq!(e_then_swap, swap_then_e);
+        assert_eq!(B::swap(2, 1).compose(&B::swap(1, 2)), Ok(B::identity(&3)));
+    }
+
+    #[test]
+    fn validity() {
+        use super::{BrauerMorphism, Pair};
+        use crate::unstable::linear_combination::LinearCombination;
+        assert_eq!(
+            BrauerMorphism::<i32>::temperley_lieb_gen(1, 4).check_valid(),
+            Ok(())
+        );
+        assert_eq!(BrauerMorphism::<i32>::swap(2, 3).check_valid(), Ok(()));
+        let mut bad = BrauerMorphism::<i32>::symmetric_alg_gen(0, 2);
+        bad.is_def_tl = true;
+        assert!(bad.check_valid().is_err());
+        let wrong_size = BrauerMorphism::<i32> {
+            diagram: LinearCombination::singleton((0, vec![Pair(0, 1)].into_iter().collect())),
+            source: 2,
+            target: 2,
+            is_def_tl: true,
+        };
+        assert!(wrong_size.check_valid().is_err());
+    }
+
+    #[test]
+    fn duals() {
+        use super::{simplify, BrauerMorphism};
+        use crate::{
+            category::{Composable, HasIdentity},
+            rigid_monoidal::RigidMonoidalMorphism,
+        };
+        type B = BrauerMorphism<i32>;
+        for n in 0..5 {
+            assert_eq!(B::left_zig_zags(&n), Ok(()));
+            assert_eq!(B::right_zig_zags(&n), Ok(()));
+            let cap = B::left_evaluation(&n);
+            assert_eq!((cap.domain(), cap.codomain()), (2 * n, 0));
+            assert!(cap.is_def_tl);
+        }
+        /*
+        the cup followed by the cap is a circle for each strand
+        */
+        let circles = B::left_coevaluation(&3)
+            .compose(&B::left_evaluation(&3))
+            .unwrap();
+        let mut three_circles = B::delta_polynomial(&[0, 0, 0, 1]);
+        simplify(&mut three_circles);
+        assert_eq!(circles, three_circles);
+        /*
+        the cup of 1 is e_1 after tensoring with the cap
+        */
+        assert_eq!(
+            B::left_evaluation(&1) * B::left_coevaluation(&1),
+            B::temperley_lieb_gen(0, 2)
+        );
+        assert_eq!(B::identity(&0), B::left_evaluation(&0));
+    }
+
+    #[test]
+    fn big_coefficients() {
+        use super::BrauerMorphism;
+        use crate::category::Composable;
+        use either::Either::Left;
+        use num::{BigInt, BigRational};
+        let e_i = BrauerMorphism::<BigRational>::temperley_lieb_gens(4);
+        let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+        let delta_coeffs = [BigRational::zero(), BigRational::one()];
+        let e_ie_i = e_i[1].compose(&e_i[1]);
+        let deltae_i = test_helper(&e_i, &[], &[Left(1)], &delta_coeffs);
+        assert_eq!(e_ie_i, deltae_i);
+        let huge = BigInt::from(u64::MAX) * BigInt::from(u64::MAX);
+        let scaled = test_helper(
+            &e_i,
+            &[],
+            &[Left(2)],
+            &[BigRational::from_integer(huge.clone()) * half.clone()],
+        )
+        .unwrap();
+        let squared = scaled.compose(&scaled).unwrap();
+        let expected = test_helper(
+            &e_i,
+            &[],
+            &[Left(2)],
+            &[
+                BigRational::zero(),
+                BigRational::from_integer(huge.clone() * huge) * half.clone() * half,
+            ],
+        )
+        .unwrap();
+        assert_eq!(squared, expected);
+    }
+
+    #[test]
+    fn ribbon() {
+        use super::BrauerMorphism;
+        use crate::rigid_monoidal::RibbonMonoidalMorphism;
+        type B = BrauerMorphism<i32>;
+        for a in 0..4 {
+            for b in 0..4 {
+                assert_eq!(B::ribbon_relations(&a, &b), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn braided() {
+        use super::BrauerMorphism;
+        use crate::monoidal::Braided;
+        type B = BrauerMorphism<i32>;
+        for a in 0..3 {
+            for b in 0..3 {
+                for c in 0..3 {
+                    assert_eq!(B::braiding_laws(&a, &b, &c), Ok(()));
+                }
+            }
+        }
+        assert_eq!(B::inverse_braiding(&1, &1), B::symmetric_alg_gen(0, 2));
+    }
+
+    #[test]
+    fn unitary() {
+        use super::BrauerMorphism;
+        use crate::category::{Composable, Dagger, HasIdentity};
+        use num::Complex;
+        type B = BrauerMorphism<Complex<i32>>;
+        let e_i = B::temperley_lieb_gens(4);
+        let s_i = B::symmetric_alg_gens(4);
+        for idx in 0..3 {
+            assert_eq!(s_i[idx].is_unitary(), Ok(true));
+            assert_eq!(e_i[idx].is_unitary(), Ok(false));
+            let prod = e_i[idx].compose(&s_i[2 - idx]).unwrap();
+            assert_eq!(
+                prod.dagger(),
+                s_i[2 - idx].dagger().compose(&e_i[idx].dagger()).unwrap()
+            );
+        }
+        let rotated = B::identity(&2).map_coeffs(|z| z * Complex::i());
+        assert_eq!(
+            rotated.dagger(),
+            B::identity(&2).map_coeffs(|z| z * -Complex::i())
+        );
+        assert_eq!(rotated.is_unitary(), Ok(true));
+        assert_eq!(B::swap(1, 2).is_unitary(), Ok(true));
+        assert!(B::temperley_lieb_gen(0, 2).dagger().check_valid().is_ok());
+    }
+
+    #[test]
+    fn compact_closed() {
+        use super::BrauerMorphism;
+        use crate::{
+            category::{Composable, HasIdentity},
+            rigid_monoidal::CompactClosed,
+        };
+        type B = BrauerMorphism<i32>;
+        let s_i = B::symmetric_alg_gens(4);
+        let e_i = B::temperley_lieb_gens(4);
+        for i in 0..3 {
+            assert_eq!(s_i[i].transpose().unwrap(), s_i[2 - i]);
+            assert_eq!(e_i[i].transpose().unwrap(), e_i[2 - i]);
+            assert_eq!(e_i[i].transpose().unwrap().transpose().unwrap(), e_i[i]);
+            let prod = e_i[i].compose(&s_i[(i + 1) % 3]).unwrap();
+            assert_eq!(
+                prod.transpose().unwrap(),
+                s_i[(i + 1) % 3]
+                    .transpose()
+                    .unwrap()
+                    .compose(&e_i[i].transpose().unwrap())
+                    .unwrap()
+            );
+        }
+        for n in 0..3 {
+            assert_eq!(B::identity(&n).transpose().unwrap(), B::identity(&n));
+            assert_eq!(B::identity(&n).name().unwrap(), B::unit(&n));
+            assert_eq!(B::identity(&n).coname().unwrap(), B::counit(&n));
+        }
+        assert_eq!(B::counit(&1).transpose().unwrap(), B::unit(&1));
+        assert_eq!(B::swap(1, 2).transpose().unwrap(), B::swap(1, 2));
+    }
+
+    #[test]
+    fn cabling() {
+        use super::{simplify, BrauerMorphism};
+        use crate::{
+            category::{Composable, HasIdentity},
+            monoidal::Monoidal,
+            rigid_monoidal::RigidMonoidalMorphism,
+        };
+        type B = BrauerMorphism<i32>;
+        let e_i = B::temperley_lieb_gens(3);
+        let s_i = B::symmetric_alg_gens(3);
+        for k in 1..4 {
+            assert_eq!(B::identity(&3).cable(k), B::identity(&(3 * k)));
+            assert_eq!(B::swap(1, 2).cable(k), B::swap(k, 2 * k));
+            for e in e_i.iter() {
+                assert!(e.cable(k).is_def_tl);
+                assert_eq!(e.cable(k).check_valid(), Ok(()));
+            }
+            /*
+            cabling is a monoidal functor
+            */
+            let prod = e_i[0].compose(&s_i[1]).unwrap();
+            assert_eq!(
+                prod.cable(k),
+                e_i[0].cable(k).compose(&s_i[1].cable(k)).unwrap()
+            );
+            assert_eq!(
+                (e_i[1].clone() & s_i[0].clone()).cable(k),
+                e_i[1].cable(k) & s_i[0].cable(k)
+            );
+            assert_eq!(B::left_coevaluation(&1).cable(k), B::left_coevaluation(&k));
+        }
+        /*
+        e_1 e_1 = delta e_1 so the 2-cable has delta^2
+        */
+        let cabled_e = e_i[0].cable(2);
+        let mut squared = cabled_e.clone();
+        squared.monoidal(B::delta_polynomial(&[0, 0, 1]));
+        simplify(&mut squared);
+        assert_eq!(cabled_e.compose(&cabled_e), Ok(squared));
+        let mut everything_gone = (e_i[0].clone() * e_i[0].clone()).cable(0);
+        simplify(&mut everything_gone);
+        assert_eq!(everything_gone.domain(), 0);
+        assert!(everything_gone.is_def_tl);
+    }
+
+    #[test]
+    fn idempotent_decomposition() {
+        use super::{all_diagrams, simplify, BrauerMorphism};
+        use crate::{
+            category::{Composable, HasIdentity},
+            rigid_monoidal::RigidMonoidalMorphism,
+        };
+        use num::rational::Ratio;
+        type B = BrauerMorphism<Ratio<i64>>;
+        assert_eq!(all_diagrams(3, 3, true).len(), 5);
+        assert_eq!(all_diagrams(3, 3, false).len(), 15);
+        assert_eq!(all_diagrams(2, 0, false).len(), 1);
+        let two = Ratio::from_integer(2);
+        /*
+        p = e_1/delta is idempotent once delta is 2
+        */
+        let mut p = B::temperley_lieb_gen(0, 3);
+        p.diagram /= two;
+        let mut p_squared = p.compose(&p).unwrap().specialize_delta(two);
+        simplify(&mut p_squared);
+        assert_eq!(p_squared, p);
+        let image = p.image(two).unwrap();
+        let fixed = p.fixed_points(two).unwrap();
+        let kernel = p.kernel(two).unwrap();
+        assert_eq!(image.len(), 2);
+        assert_eq!(fixed.len(), image.len());
+        assert_eq!(kernel.len() + image.len(), 5);
+        for x in fixed.iter() {
+            let mut moved = x.compose(&p).unwrap().specialize_delta(two);
+            simplify(&mut moved);
+            assert_eq!(&moved, x);
+        }
+        for x in kernel.iter() {
+            let mut moved = x.compose(&p).unwrap().specialize_delta(two);
+            simplify(&mut moved);
+            assert_eq!(moved.diagram.keys().count(), 0);
+        }
+        /*
+        the identity fixes everything in the Brauer algebra and kills nothing
+        */
+        let mut id = B::identity(&3);
+        id.is_def_tl = false;
+        assert_eq!(id.fixed_points(two).unwrap().len(), 15);
+        assert!(id.kernel(two).unwrap().is_empty());
+        assert!(B::left_evaluation(&1).kernel(two).is_err());
+    }
+
+    #[test]
+    fn compact_strings() {
+        use super::{simplify, BrauerMorphism};
+        use crate::category::HasIdentity;
+        let e_1 = BrauerMorphism::<i32>::temperley_lieb_gen(0, 2);
+        assert_eq!(e_1.to_compact_string(), "TL(2→2): 1·d^0·[0-1|2-3]");
+        let s_1 = BrauerMorphism::<i32>::symmetric_alg_gen(0, 2);
+        let e_1_squared = e_1.clone() * e_1.clone();
+        assert_eq!(e_1_squared.to_compact_string(), "TL(2→2): 1·d^1·[0-1|2-3]");
+        let mut sum = BrauerMorphism::<i32>::identity(&2);
+        sum.diagram += e_1.diagram * 2 + s_1.diagram;
+        sum.is_def_tl = false;
+        assert_eq!(
+            sum.to_compact_string(),
+            "Br(2→2): 2·d^0·[0-1|2-3] + 1·d^0·[0-2|1-3] + 1·d^0·[0-3|1-2]"
+        );
+        let mut empty = BrauerMorphism::<i32>::delta_polynomial(&[0]);
+        assert_eq!(empty.to_compact_string(), "TL(0→0): 0·d^0·[]");
+        simplify(&mut empty);
+        assert_eq!(empty.to_compact_string(), "TL(0→0): 0");
+    }
+
+    #[test]
+    fn jones_wenzl_projectors() {
+        use super::{simplify, BrauerMorphism};
+        use crate::{
+            category::{Composable, HasIdentity},
+            rigid_monoidal::RigidMonoidalMorphism,
+        };
+        type B = BrauerMorphism<i64>;
+        let chebyshev_at_3 = [1, 3, 8, 21, 55];
+        for (n, trace_ratio) in chebyshev_at_3.into_iter().enumerate() {
+            /*
+            d_n p_n squares to d_n (d_n p_n)
+            and is killed by every e_i on both sides
+            */
+            let (scaled, scale) = B::jones_wenzl(n);
+            assert!(scaled.is_def_tl);
+            let mut squared = scaled.compose(&scaled).unwrap();
+            simplify(&mut squared);
+            let mut expected = scale.clone() & scaled.clone();
+            simplify(&mut expected);
+            assert_eq!(squared, expected);
+            for e in (0..n.saturating_sub(1)).map(|i| B::temperley_lieb_gen(i, n)) {
+                let mut killed = scaled.compose(&e).unwrap();
+                simplify(&mut killed);
+                assert!(killed.diagram.is_empty());
+                let mut killed = e.compose(&scaled).unwrap();
+                simplify(&mut killed);
+                assert!(killed.diagram.is_empty());
+            }
+            /*
+            the trace of p_n is D_n(delta)
+            */
+            assert_eq!(
+                scaled.markov_trace(3),
+                Ok(scale.markov_trace(3).unwrap() * trace_ratio)
+            );
+        }
+        assert_eq!(B::identity(&2).markov_trace(5), Ok(25));
+        assert_eq!(B::swap(1, 2).markov_trace(5), Ok(5));
+        assert!(B::left_evaluation(&1).markov_trace(5).is_err());
+    }
+
+    #[test]
+    fn wiki_example() {
+        use super::{simplify, BrauerMorphism};
+        use crate::{category::Composable, monoidal::Monoidal};
+        use num::Complex;
+        let e_i = BrauerMorphism::<Complex<i32>>::temperley_lieb_gens(5);
+        let zero_complex = Complex::<i32>::zero();
+        let one_complex = Complex::<i32>::one();
+        let prod_1432 = e_i[0]
+            .compose(&e_i[3])
+            .and_then(|z| z.compose(&e_i[2]))
+            .and_then(|z| z.compose(&e_i[1]));
+        let prod_243 = e_i[1].compose(&e_i[3]).and_then(|z| z.compose(&e_i[2]));
+        let prod_143243 = e_i[0]
+            .compose(&e_i[3])
+            .and_then(|z| z.compose(&e_i[2]))
+            .and_then(|z| z.compose(&e_i[1]))
+            .and_then(|z| z.compose(&e_i[3]))
+            .and_then(|z| z.compose(&e_i[2]));
+        let observed = prod_1432.and_then(|z| match prod_243 {
+            Ok(real_prod_243) => z.compose(&real_prod_243),
+            Err(e) => Err(e),
+        });
+        let mut expected =
+            BrauerMorphism::<Complex<i32>>::delta_polynomial(&[zero_complex, one_complex]);
+        simplify(&mut expected);
+
+        match (observed, prod_143243) {
+            (Ok(real_obs), Ok(exp_wo_delta)) => {
+                assert!(real_obs.is_def_tl);
+                expected.monoidal(exp_wo_delta);
+                assert!(expected.is_def_tl);
+                assert!(PartialEq::eq(&real_obs, &expected));
+            }
+            _ => {
+                panic!("Error in composition when checking (e_1 e_4 e_3 e_2) (e_2 e_4 e_3) = delta e_1 e_4 e_3 e_2 e_4 e_3")
+            }
+        }
+    }
+
+    #[test]
+    fn compose_all_words() {
+        use super::BrauerMorphism;
+        use crate::category::{compose_all, Composable};
+        use num::Complex;
+        /*
+        compose_all on a word in the generators is the same as chaining compose by hand
+        and at the first pair that does not compose it stops without looking further
+        */
+        let e_i = BrauerMorphism::<Complex<i32>>::temperley_lieb_gens(5);
+        let by_hand = e_i[0]
+            .compose(&e_i[3])
+            .and_then(|z| z.compose(&e_i[2]))
+            .and_then(|z| z.compose(&e_i[1]))
+            .and_then(|z| z.compose(&e_i[3]))
+            .and_then(|z| z.compose(&e_i[2]))
+            .unwrap();
+        let word = [0, 3, 2, 1, 3, 2];
+        let all_at_once = compose_all(word.iter().map(|idx| e_i[*idx].clone())).unwrap();
+        assert!(PartialEq::eq(&all_at_once, &by_hand));
+
+        let e_i_small = BrauerMorphism::<Complex<i32>>::temperley_lieb_gens(3);
+        let mut seen = 0;
+        let mismatched = [
+            e_i[0].clone(),
+            e_i[1].clone(),
+            e_i_small[0].clone(),
+            e_i[2].clone(),
+            e_i_small[1].clone(),
+        ];
+        let observed = compose_all(mismatched.iter().cloned().inspect(|_| seen += 1));
+        let expected = e_i[0]
+            .compose(&e_i[1])
+            .and_then(|z| z.compose(&e_i_small[0]));
+        assert!(expected.is_err());
+        assert_eq!(observed.err(), expected.err());
+        assert_eq!(seen, 3);
+    }
+
+    #[test]
+    fn sym_relations() {
+        use super::BrauerMorphism;
+        use crate::{
+            category::{Composable, Dagger, HasIdentity},
+            utils::test_asserter,
+        };
+        use either::Either::Right;
+        use num::Complex;
+        let n = 7;
+        let s_i = BrauerMorphism::<Complex<i32>>::symmetric_alg_gens(n);
+        let one_poly_coeffs = [Complex::<i32>::one()];
+        let identity = BrauerMorphism::<Complex<i32>>::identity(&n);
+        for idx in 0..n - 1 {
+            assert!(!s_i[idx].is_def_tl);
+            let s_i_dag = s_i[idx].dagger();
+            assert!(
+                PartialEq::eq(&s_i[idx], &s_i_dag),
+                "{:?} vs {:?} when checking self adjointness of s_i",
+                s_i[idx],
+                s_i_dag
+            );
+            let s_is_i = s_i[idx].compose(&s_i[idx]);
+            test_asserter(
+                s_is_i,
+                Ok(identity.clone()),
+                |j, k| !j.is_def_tl && k.is_def_tl,
+                "s_i s_i = 1",
+            );
+            if idx < n - 2 {
+                let s_is_js_i = test_helper(
+                    &[],
+                    &s_i,
+                    &[Right(idx), Right(idx + 1), Right(idx)],
+                    &one_poly_coeffs,
+                );
+                let s_js_is_j = test_helper(
+                    &[],
+                    &s_i,
+                    &[Right(idx + 1), Right(idx), Right(idx + 1)],
+                    &one_poly_coeffs,
+                );
+                test_asserter(
+                    s_is_js_i,
+                    s_js_is_j,
+                    |j, k| !j.is_def_tl && !k.is_def_tl,
+                    "s_i s_(i+1) s_i = s_(i+1) s_i s_(i+1)",
+                );
+            }
+            if idx > 1 {
+                let s_is_js_i = test_helper(
+                    &[],
+                    &s_i,
+                    &[Right(idx), Right(idx - 1), Right(idx)],
+                    &one_poly_coeffs,
+                );
+                let s_js_is_j = test_helper(
+                    &[],
+                    &s_i,
+                    &[Right(idx - 1), Right(idx), Right(idx - 1)],
+                    &one_poly_coeffs,
+                );
+                test_asserter(
+                    s_is_js_i,
+                    s_js_is_j,
+                    |j, k| !j.is_def_tl && !k.is_def_tl,
+                    "s_i s_(i-1) s_i = s_(i-1) s_i s_(i-1)",
+                );
+            }
+            for jdx in idx + 2..s_i.len() {
+                let prod_ij = s_i[idx].compose(&s_i[jdx]);
+                let prod_ji = s_i[jdx].compose(&s_i[idx]);
+                test_asserter(
+                    prod_ij,
+                    prod_ji,
+                    |j, k| !j.is_def_tl && !k.is_def_tl,
+                    "s_i s_j = s_j s_i",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn crossing_numbers() {
+        use super::{BrauerMorphism, Pair};
+        use crate::category::{Composable, HasIdentity};
+        let n = 5;
+        let e_i = BrauerMorphism::<i32>::temperley_lieb_gens(n);
+        let s_i = BrauerMorphism::<i32>::symmetric_alg_gens(n);
+        assert_eq!(BrauerMorphism::<i32>::identity(&n).crossing_number(), 0);
+        for idx in 0..n - 1 {
+            assert_eq!(e_i[idx].crossing_number(), 0);
+            assert_eq!(s_i[idx].crossing_number(), 1);
+            assert_eq!(
+                s_i[idx].crossing_pairs(),
+                vec![vec![(Pair(idx, idx + n + 1), Pair(idx + 1, idx + n))]]
+            );
+            let mut e_is_i = e_i[idx].compose(&s_i[idx]).unwrap();
+            assert_eq!(e_is_i.crossing_number(), 0);
+            e_is_i.set_is_tl();
+            assert!(e_is_i.is_def_tl);
+        }
+        let longest_3 = s_i[0]
+            .compose(&s_i[1])
+            .and_then(|z| z.compose(&s_i[0]))
+            .unwrap();
+        assert_eq!(longest_3.crossing_number(), 3);
+        let mut with_cap = e_i[2].compose(&s_i[0]).unwrap();
+        assert_eq!(with_cap.crossing_number(), 1);
+        with_cap.set_is_tl();
+        assert!(!with_cap.is_def_tl);
+    }
+
+    #[test]
+    fn base_change() {
+        use super::BrauerMorphism;
+        use crate::{category::Composable, unstable::coefficients::Zp};
+        use num::Zero;
+        let e_i = BrauerMorphism::<i32>::temperley_lieb_gens(3);
+        let over_z = e_i[0].compose(&e_i[1]).unwrap().compose(&e_i[0]).unwrap();
+        let reduced = over_z.map_coeffs(|c| Zp::<3>::from(c as i64));
+        let e_i_mod_3 = BrauerMorphism::<Zp<3>>::temperley_lieb_gens(3);
+        assert_eq!(reduced, e_i_mod_3[0]);
+        /*
+        e_1 e_1 = delta e_1 so at delta = 3 it vanishes mod 3
+        */
+        let squared = e_i[0].compose(&e_i[0]).unwrap().specialize_delta(3);
+        assert!(!squared.diagram.is_zero());
+        assert!(squared
+            .map_coeffs(|c| Zp::<3>::from(c as i64))
+            .diagram
+            .is_zero());
+    }
+
+    #[test]
+    fn float_idempotents() {
+        use super::BrauerMorphism;
+        use crate::category::Composable;
+        /*
+        with delta = sqrt 2, e/delta is idempotent
+        but the floating point coefficients only agree up to rounding
+        */
+        let delta = 2.0_f64.sqrt();
+        let mut p = BrauerMorphism::<f64>::temperley_lieb_gen(0, 3);
+        p.diagram *= 1.0 / delta;
+        let p_squared = p.compose(&p).unwrap().specialize_delta(delta);
+        assert!(p_squared.approx_eq(&p, 1e-12));
+        assert!(!p_squared.approx_eq(&(p.clone() * p.clone()), 1e-12));
+        let mut bigger = p.clone();
+        bigger.diagram *= 1.01;
+        assert!(!p_squared.approx_eq(&bigger, 1e-12));
+        assert!(!p.approx_eq(&BrauerMorphism::temperley_lieb_gen(0, 2), 1e-12));
+    }
+
+    #[test]
+    fn shared_diagrams() {
+        use super::BrauerMorphism;
+        use crate::{category::Composable, unstable::interner::Interner};
+        let e_i = BrauerMorphism::<i32>::temperley_lieb_gens(4);
+        let mut interner = Interner::new();
+        let words = [
+            e_i[0].compose(&e_i[1]).unwrap(),
+            e_i[0].compose(&e_i[1]).unwrap().compose(&e_i[0]).unwrap(),
+            e_i[0].clone(),
+            e_i[2].compose(&e_i[0]).unwrap(),
+        ];
+        let shared: Vec<_> = words
+            .iter()
+            .map(|w| w.intern_terms(&mut interner))
+            .collect();
+        /*
+        e_1 e_2 e_1 = e_1 so only three different diagrams
+        */
+        assert_eq!(interner.len(), 3);
+        assert_eq!(shared[1].keys().next(), shared[2].keys().next());
+        assert_eq!(
+            shared[1].inj_linearly_extend(|(d, m)| (d, (*m).clone())),
+            e_i[0].diagram
+        );
+    }
+
+    #[test]
+    fn planar_split() {
+        use super::BrauerMorphism;
+        let n = 3;
+        let e_i = BrauerMorphism::<i32>::temperley_lieb_gens(n);
+        let s_i = BrauerMorphism::<i32>::symmetric_alg_gens(n);
+        let mixed = BrauerMorphism {
+            diagram: e_i[0].diagram.clone() + s_i[1].diagram.clone() + e_i[1].diagram.clone(),
+            source: n,
+            target: n,
+            is_def_tl: false,
+        };
+        let (planar, crossing) = mixed.split_non_crossing();
+        assert!(planar.is_def_tl);
+        assert_eq!(planar.crossing_number(), 0);
+        assert_eq!(
+            planar.diagram,
+            e_i[0].diagram.clone() + e_i[1].diagram.clone()
+        );
+        assert!(!crossing.is_def_tl);
+        assert_eq!(crossing, s_i[1]);
+        let (all_planar, nothing) = e_i[0].split_non_crossing();
+        assert_eq!(all_planar, e_i[0]);
+        assert_eq!(nothing.diagram.keys().count(), 0);
+    }
+
+    #[test]
+    fn tangle_relations() {
+        use super::BrauerMorphism;
+        use crate::{category::Composable, error::Error, utils::test_asserter};
+        use either::Either::{Left, Right};
+        use num::Complex;
+        let n = 7;
+        let s_i = BrauerMorphism::<Complex<i32>>::symmetric_alg_gens(n);
+        let e_i = BrauerMorphism::<Complex<i32>>::temperley_lieb_gens(n);
+        let one_poly_coeffs = [Complex::<i32>::one()];
+        for idx in 0..n - 1 {
+            let e_is_i = e_i[idx].compose(&s_i[idx]);
+            let s_ie_i: Result<BrauerMorphism<Complex<i32>>, Error> = s_i[idx].compose(&e_i[idx]);
+            test_asserter(
+                e_is_i,
+                Ok(e_i[idx].clone()),
+                |j, k| !j.is_def_tl && k.is_def_tl,
+                "e_i s_i = e_i",
+            );
+            test_asserter(
+                s_ie_i,
+                Ok(e_i[idx].clone()),
+                |j, k| !j.is_def_tl && k.is_def_tl,
+                "s_i e_i = e_i",
+            );
+            if idx < n - 2 {
+                let s_is_je_i = test_helper(
+                    &e_i,
+                    &s_i,
+                    &[Right(idx), Right(idx + 1), Left(idx)],
+                    &one_poly_coeffs,
+                );
+                let e_je_i = test_helper(&e_i, &s_i, &[Left(idx + 1), Left(idx)], &one_poly_coeffs);
+                test_asserter(
+                    s_is_je_i,
+                    e_je_i,
+                    |j, k| !j.is_def_tl && k.is_def_tl,
+                    "s_i s_(i+1) e_i = e_(i+1) e_i",
+                );
+                let e_is_je_i = test_helper(
+                    &e_i,
+                    &s_i,
+                    &[Left(idx), Right(idx + 1), Left(idx)],
+                    &one_poly_coeffs,
+                );
+                test_asserter(
+                    e_is_je_i,
+                    Ok(e_i[idx].clone()),
+                    |j, k| !j.is_def_tl && k.is_def_tl,
+                    "e_i s_(i+1) e_i = e_i",
+                );
+            }
+            if idx > 1 {
+                let s_is_je_i = test_helper(
+                    &e_i,
+                    &s_i,
+                    &[Right(idx), Right(idx - 1), Left(idx)],
+                    &one_poly_coeffs,
+                );
+                let e_je_i = test_helper(&e_i, &s_i, &[Left(idx - 1), Left(idx)], &one_poly_coeffs);
+                test_asserter(
+                    s_is_je_i,
+                    e_je_i,
+                    |j, k| !j.is_def_tl && k.is_def_tl,
+                    "s_i s_(i-1) e_i = e_(i-1) e_i",
+                );
+                let e_is_je_i = test_helper(
+                    &e_i,
+                    &s_i,
+                    &[Left(idx), Right(idx - 1), Left(idx)],
+                    &one_poly_coeffs,
+                );
+                test_asserter(
+                    e_is_je_i,
+                    Ok(e_i[idx].clone()),
+                    |j, k| !j.is_def_tl && k.is_def_tl,
+                    "e_i s_(i-1) e_i = e_i",
+                );
+            }
+            for jdx in idx + 2..s_i.len() {
+                let prod_ij = s_i[idx].compose(&e_i[jdx]);
+                let prod_ji = e_i[jdx].compose(&s_i[idx]);
+                test_asserter(
+                    prod_ij,
+                    prod_ji,
+                    |j, k| !j.is_def_tl && !k.is_def_tl,
+                    "s_i e_j = e_j s_i",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn presented_relations() {
+        use super::BrauerMorphism;
+        use crate::{
+            category::{Composable, HasIdentity},
+            rigid_monoidal::CompactClosed,
+            unstable::presentation::Presentation,
+        };
+        type B = BrauerMorphism<i64>;
+        let n = 4;
+        /*
+        the symmetric group and the Temperley-Lieb algebra on n strands as data
+        with d standing for the closed loop tensored with the identity
+        */
+        let mut symmetric = Presentation::new();
+        let mut temperley_lieb = Presentation::new();
+        temperley_lieb.add_generator(('d', 0), n, n).unwrap();
+        for i in 0..n - 1 {
+            symmetric.add_generator(('s', i), n, n).unwrap();
+            temperley_lieb.add_generator(('e', i), n, n).unwrap();
+        }
+        for i in 0..n - 1 {
+            let (s_i, e_i) = (('s', i), ('e', i));
+            symmetric.add_relation(vec![s_i, s_i], vec![]).unwrap();
+            temperley_lieb
+                .add_relation(vec![e_i, e_i], vec![('d', 0), e_i])
+                .unwrap();
+            temperley_lieb
+                .add_relation(vec![e_i, ('d', 0)], vec![('d', 0), e_i])
+                .unwrap();
+            for j in i + 1..n - 1 {
+                let (s_j, e_j) = (('s', j), ('e', j));
+                if j == i + 1 {
+                    symmetric
+                        .add_relation(vec![s_i, s_j, s_i], vec![s_j, s_i, s_j])
+                        .unwrap();
+                    temperley_lieb
+                        .add_relation(vec![e_i, e_j, e_i], vec![e_i])
+                        .unwrap();
+                    temperley_lieb
+                        .add_relation(vec![e_j, e_i, e_j], vec![e_j])
+                        .unwrap();
+                } else {
+                    symmetric
+                        .add_relation(vec![s_j, s_i], vec![s_i, s_j])
+                        .unwrap();
+                    temperley_lieb
+                        .add_relation(vec![e_j, e_i], vec![e_i, e_j])
+                        .unwrap();
+                }
+            }
+        }
+        let loop_on_nothing = B::unit(&1).compose(&B::counit(&1)).unwrap();
+        let interpreter = |generator: &(char, usize)| match generator {
+            ('s', i) => Ok(B::symmetric_alg_gen(*i, n)),
+            ('e', i) => Ok(B::temperley_lieb_gen(*i, n)),
+            ('d', _) => Ok(B::identity(&n) & loop_on_nothing.clone()),
+            _ => Err(format!("{:?} is not a generator", generator)),
+        };
+        assert_eq!(symmetric.check_relations(&interpreter), Ok(()));
+        assert_eq!(temperley_lieb.check_relations(&interpreter), Ok(()));
+
+        let symmetric_system = symmetric.complete(100);
+        assert!(symmetric_system.is_complete());
+        assert_eq!(symmetric_system.irreducible_words(7).len(), 24);
+        let temperley_lieb_system = temperley_lieb.complete(100);
+        assert!(temperley_lieb_system.is_complete());
+        let loopless = temperley_lieb_system
+            .irreducible_words(7)
+            .into_iter()
+            .filter(|word| !word.contains(&('d', 0)))
+            .count();
+        assert_eq!(loopless, 14);
+
+        let words = [
+            vec![('s', 0), ('s', 1), ('s', 0), ('s', 2), ('s', 1), ('s', 0)],
+            vec![('s', 2), ('s', 0), ('s', 2), ('s', 1)],
+            vec![('e', 0), ('e', 1), ('e', 2), ('e', 1), ('e', 0), ('e', 0)],
+            vec![('e', 2), ('e', 0), ('e', 2), ('e', 1), ('e', 2)],
+        ];
+        for word in words {
+            let (presentation, system) = if word[0].0 == 's' {
+                (&symmetric, &symmetric_system)
+            } else {
+                (&temperley_lieb, &temperley_lieb_system)
+            };
+            let normal_form = system.normal_form(&word);
+            assert!(normal_form.len() <= word.len());
+            assert_eq!(
+                presentation.interpret::<B, _>(&word, &n, &interpreter),
+                presentation.interpret::<B, _>(&normal_form, &n, &interpreter),
+                "{:?} and its normal form {:?} were different diagrams",
+                word,
+                normal_form
+            );
+        }
+    }
+
+    #[test]
+    fn matrices_of_diagrams() {
+        use super::BrauerMorphism;
+        use crate::category::{Composable, HasIdentity};
+        use crate::matrix::MatrixMorphism;
+        use crate::monoidal::Monoidal;
+        use crate::rigid_monoidal::CompactClosed;
+        type B = BrauerMorphism<i64>;
+        type M = MatrixMorphism<i64>;
+        assert_eq!(B::identity(&2).to_matrix(3), M::identity(&9));
+        assert_eq!(B::swap(1, 2).to_matrix(2), M::swap(2, 4));
+        let cup = B::unit(&1).to_matrix(2);
+        assert_eq!(cup, M::new(1, 4, vec![vec![1, 0, 0, 1]]).unwrap());
+        assert_eq!(
+            B::unit(&1).compose(&B::counit(&1)).unwrap().to_matrix(3),
+            M::new(1, 1, vec![vec![3]]).unwrap()
+        );
+
+        /*
+        taking matrices respects composition, monoidal products and delta
+        */
+        let e_i = B::temperley_lieb_gens(3);
+        let s_i = B::symmetric_alg_gens(3);
+        for dimension in [1, 2, 3] {
+            for (first, second) in [(&e_i[0], &e_i[1]), (&e_i[1], &s_i[0]), (&e_i[0], &e_i[0])] {
+                assert_eq!(
+                    first.compose(second).unwrap().to_matrix(dimension),
+                    first
+                        .to_matrix(dimension)
+                        .compose(&second.to_matrix(dimension))
+                        .unwrap()
+                );
+                let mut tensored = first.clone();
+                tensored.monoidal(second.clone());
+                let mut tensored_matrices = first.to_matrix(dimension);
+                tensored_matrices.monoidal(second.to_matrix(dimension));
+                assert_eq!(tensored.to_matrix(dimension), tensored_matrices);
+            }
+        }
+        let squared = e_i[0].compose(&e_i[0]).unwrap().to_matrix(2);
+        let doubled = e_i[0].to_matrix(2).map_entries(|entry| entry * 2);
+        assert_eq!(squared, doubled);
+    }
+}
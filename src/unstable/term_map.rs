@@ -0,0 +1,327 @@
+use {
+    smallvec::SmallVec,
+    std::{
+        fmt::{self, Debug},
+        hash::Hash,
+    },
+};
+
+/*
+with the ordered feature the terms are kept in the order they were first inserted
+so iteration and serialization are the same from run to run
+otherwise the order is whatever the HashMap gives once there are enough terms to need one
+equality does not depend on the order in either case
+*/
+#[cfg(feature = "ordered")]
+use indexmap::{
+    map::{IntoIter as LargeIntoIter, Iter as LargeIter, IterMut as LargeIterMut},
+    IndexMap as LargeMap,
+};
+#[cfg(not(feature = "ordered"))]
+use std::collections::{
+    hash_map::{IntoIter as LargeIntoIter, Iter as LargeIter, IterMut as LargeIterMut},
+    HashMap as LargeMap,
+};
+
+/*
+how many terms live inline before the SmallVec itself spills to the heap
+and how many are kept in the linear scan representation before switching to a map
+*/
+const INLINE_TERMS: usize = 4;
+const SMALL_LIMIT: usize = 8;
+
+/*
+the storage behind a LinearCombination
+almost every morphism is a single diagram or a handful of them
+so those are kept as a short list of pairs and looked up by comparing keys
+no hashing and for up to INLINE_TERMS of them no allocation either
+past SMALL_LIMIT terms it becomes a map and stays one
+*/
+#[derive(Clone)]
+pub enum TermMap<K: Eq + Hash, V> {
+    Small(SmallVec<[(K, V); INLINE_TERMS]>),
+    Large(LargeMap<K, V>),
+}
+
+impl<K: Eq + Hash, V> TermMap<K, V> {
+    pub fn new() -> Self {
+        Self::Small(SmallVec::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= SMALL_LIMIT {
+            Self::Small(SmallVec::with_capacity(capacity))
+        } else {
+            Self::Large(LargeMap::with_capacity(capacity))
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Small(terms) => terms.len(),
+            Self::Large(terms) => terms.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        match self {
+            Self::Small(terms) => terms.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            Self::Large(terms) => terms.get(key),
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        match self {
+            Self::Small(terms) => terms.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v),
+            Self::Large(terms) => terms.get_mut(key),
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.get_mut(&key) {
+            return Some(std::mem::replace(old, value));
+        }
+        self.grow_if_full();
+        match self {
+            Self::Small(terms) => {
+                terms.push((key, value));
+                None
+            }
+            Self::Large(terms) => terms.insert(key, value),
+        }
+    }
+
+    /*
+    the or_insert_with of the map entry api
+    */
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: K, default: F) -> &mut V {
+        if !self.contains_key(&key) {
+            self.grow_if_full();
+        }
+        match self {
+            Self::Small(terms) => {
+                let idx = match terms.iter().position(|(k, _)| *k == key) {
+                    Some(idx) => idx,
+                    None => {
+                        terms.push((key, default()));
+                        terms.len() - 1
+                    }
+                };
+                &mut terms[idx].1
+            }
+            Self::Large(terms) => terms.entry(key).or_insert_with(default),
+        }
+    }
+
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut keep: F) {
+        match self {
+            Self::Small(terms) => terms.retain(|(k, v)| keep(k, v)),
+            Self::Large(terms) => terms.retain(|k, v| keep(k, v)),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        match self {
+            Self::Small(terms) => Iter::Small(terms.iter()),
+            Self::Large(terms) => Iter::Large(terms.iter()),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        match self {
+            Self::Small(terms) => IterMut::Small(terms.iter_mut()),
+            Self::Large(terms) => IterMut::Large(terms.iter_mut()),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.iter_mut().map(|(_, v)| v)
+    }
+
+    fn grow_if_full(&mut self) {
+        if let Self::Small(terms) = self {
+            if terms.len() >= SMALL_LIMIT {
+                let mut large = LargeMap::with_capacity(2 * SMALL_LIMIT);
+                large.extend(terms.drain(..));
+                *self = Self::Large(large);
+            }
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> Default for TermMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq> PartialEq for TermMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Eq + Hash, V: Eq> Eq for TermMap<K, V> {}
+
+impl<K: Eq + Hash + Debug, V: Debug> Debug for TermMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Eq + Hash, V> Extend<(K, V)> for TermMap<K, V> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> FromIterator<(K, V)> for TermMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut ret_val = Self::new();
+        ret_val.extend(iter);
+        ret_val
+    }
+}
+
+impl<K: Eq + Hash, V, const N: usize> From<[(K, V); N]> for TermMap<K, V> {
+    fn from(terms: [(K, V); N]) -> Self {
+        terms.into_iter().collect()
+    }
+}
+
+pub enum Iter<'a, K, V> {
+    Small(std::slice::Iter<'a, (K, V)>),
+    Large(LargeIter<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Small(terms) => terms.next().map(|(k, v)| (k, v)),
+            Self::Large(terms) => terms.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Small(terms) => terms.size_hint(),
+            Self::Large(terms) => terms.size_hint(),
+        }
+    }
+}
+
+pub enum IterMut<'a, K, V> {
+    Small(std::slice::IterMut<'a, (K, V)>),
+    Large(LargeIterMut<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Small(terms) => terms.next().map(|(k, v)| (&*k, v)),
+            Self::Large(terms) => terms.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Small(terms) => terms.size_hint(),
+            Self::Large(terms) => terms.size_hint(),
+        }
+    }
+}
+
+pub enum IntoIter<K, V> {
+    Small(smallvec::IntoIter<[(K, V); INLINE_TERMS]>),
+    Large(LargeIntoIter<K, V>),
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Small(terms) => terms.next(),
+            Self::Large(terms) => terms.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Small(terms) => terms.size_hint(),
+            Self::Large(terms) => terms.size_hint(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> IntoIterator for TermMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Self::Small(terms) => IntoIter::Small(terms.into_iter()),
+            Self::Large(terms) => IntoIter::Large(terms.into_iter()),
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash, V> IntoIterator for &'a TermMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+mod test {
+
+    #[test]
+    fn promotion() {
+        use super::{TermMap, SMALL_LIMIT};
+
+        let mut terms = TermMap::new();
+        for i in 0..SMALL_LIMIT {
+            assert_eq!(terms.insert(i, i), None);
+        }
+        assert!(matches!(terms, TermMap::Small(_)));
+        assert_eq!(terms.insert(0, 10), Some(0));
+        assert!(matches!(terms, TermMap::Small(_)));
+
+        *terms.get_or_insert_with(SMALL_LIMIT, || 0) += 5;
+        assert!(matches!(terms, TermMap::Large(_)));
+        assert_eq!(terms.len(), SMALL_LIMIT + 1);
+        assert_eq!(terms.get(&0), Some(&10));
+        assert_eq!(terms.get(&SMALL_LIMIT), Some(&5));
+
+        let small: TermMap<_, _> = terms.iter().map(|(k, v)| (*k, *v)).take(3).collect();
+        let mut rebuilt = small.clone();
+        rebuilt.extend(terms.iter().map(|(k, v)| (*k, *v)).skip(3));
+        assert_eq!(rebuilt, terms);
+        rebuilt.retain(|k, _| small.contains_key(k));
+        assert_eq!(rebuilt, small);
+    }
+}
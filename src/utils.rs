@@ -1,4 +1,5 @@
 use {
+    crate::error::Error,
     either::Either::{self, Left, Right},
     permutations::Permutation,
     rand::{distributions::Uniform, prelude::Distribution},
@@ -35,6 +36,7 @@ pub fn represents_id(it: impl Iterator<Item = usize>) -> bool {
     it.enumerate().all(|(l, r)| l == r)
 }
 
+#[allow(dead_code)]
 pub fn remove_multiple<T>(me: &mut Vec<T>, mut to_remove: Vec<usize>) {
     to_remove.sort_unstable();
     // iterate in reverse not to change the indices
@@ -45,6 +47,35 @@ pub fn remove_multiple<T>(me: &mut Vec<T>, mut to_remove: Vec<usize>) {
     }
 }
 
+pub fn compact_list<T: Debug>(items: impl IntoIterator<Item = T>) -> String {
+    /*
+    the Debug of each item separated by commas with no spaces
+    for building up one line summaries
+    */
+    items
+        .into_iter()
+        .map(|item| format!("{:?}", item))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn dot_escape(raw: &str) -> String {
+    /*
+    so raw can go between the double quotes of a graphviz label
+    */
+    raw.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn xml_escape(raw: &str) -> String {
+    /*
+    so raw can go in the text or an attribute of an SVG element
+    */
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub fn necessary_permutation<T: Eq>(side_1: &[T], side_2: &[T]) -> Result<Permutation, String> {
     let n1 = side_1.len();
     let n2 = side_2.len();
@@ -136,17 +167,17 @@ pub fn same_labels_check<
 >(
     l: L,
     r: R,
-) -> Result<(), String> {
+) -> Result<(), Error> {
     if l.len() != r.len() {
-        return Err("Mismatch in cardinalities of common interface".to_string());
+        return Err(Error::InterfaceLengthMismatch {
+            left: l.len(),
+            right: r.len(),
+        });
     }
-    let Some((w1, w2)) = l.zip(r).find(|(a, b)| a != b) else {
+    let Some((index, (w1, w2))) = l.zip(r).enumerate().find(|(_, (a, b))| a != b) else {
         return Ok(());
     };
-    Err(format!(
-        "Mismatch in labels of common interface. At some index there was {:?} vs {:?}",
-        w1, w2
-    ))
+    Err(Error::label_mismatch(index, &w1, &w2))
 }
 
 #[allow(dead_code)]
@@ -188,6 +219,71 @@ macro_rules! assert_ok {
     };
 }
 
+#[macro_export]
+macro_rules! compose {
+    /*
+    compose!(f, g, h) is f;g;h for morphisms implementing Composable
+    as a Result with the first error encountered
+    */
+    ( $x:expr $(, $rest:expr)* $(,)? ) => {{
+        let answer: std::result::Result<_, $crate::error::Error> = std::result::Result::Ok(std::clone::Clone::clone(&$x));
+        $(
+            let answer = answer.and_then(|z| $crate::category::Composable::compose(&z, &$rest));
+        )*
+        answer
+    }};
+}
+
+#[macro_export]
+macro_rules! tensor {
+    /*
+    tensor!(f, g, h) is f \otimes g \otimes h for morphisms implementing Monoidal
+    */
+    ( $x:expr $(, $rest:expr)* $(,)? ) => {{
+        #[allow(unused_mut)]
+        let mut answer = std::clone::Clone::clone(&$x);
+        $(
+            $crate::monoidal::Monoidal::monoidal(&mut answer, std::clone::Clone::clone(&$rest));
+        )*
+        answer
+    }};
+}
+
+#[macro_export]
+macro_rules! monoidal {
+    /*
+    monoidal!([f, id(a)]; [g]) is (f \otimes 1_a);g as a GenericMonoidalMorphism
+    each bracketed list is one layer with its boxes read left to right
+    id(a) is the identity box on a single wire of type a and anything else is a box
+    the interfaces of each layer come from HasWireTypes of its boxes
+    consecutive layers are checked against each other as in append_layer
+    so this is a Result with the first mismatch encountered
+    */
+    (@blocks [$($done:expr),*]) => {
+        std::vec![$($done),*]
+    };
+    (@blocks [$($done:expr),*] id($wire:expr) $(, $($rest:tt)*)?) => {
+        $crate::monoidal!(
+            @blocks [$($done,)* $crate::category::HasIdentity::identity(&$wire)] $($($rest)*)?
+        )
+    };
+    (@blocks [$($done:expr),*] $block:expr $(, $($rest:tt)*)?) => {
+        $crate::monoidal!(@blocks [$($done,)* $block] $($($rest)*)?)
+    };
+    ( $( [ $($layer:tt)* ] );+ $(;)? ) => {{
+        let layers = std::vec![
+            $(
+                $crate::monoidal::GenericMonoidalMorphismLayer::from_blocks(
+                    $crate::monoidal!(@blocks [] $($layer)*)
+                )
+            ),+
+        ];
+        <$crate::monoidal::GenericMonoidalMorphism<_, _> as std::convert::TryFrom<_>>::try_from(
+            layers,
+        )
+    }};
+}
+
 mod test {
 
     #[test]
@@ -1,16 +1,19 @@
 use {
     crate::{
         category::Composable,
-        monoidal::Monoidal,
+        error::Error,
+        frobenius::{FrobeniusOperation, HypergraphCategory},
+        monoidal::{tensor_pair, GenericMonoidalMorphism, GenericMonoidalMorphismLayer, Monoidal},
         named_cospan::NamedCospan,
         symmetric_monoidal::SymmetricMonoidalMorphism,
-        utils::{necessary_permutation, remove_multiple},
+        utils::{dot_escape, necessary_permutation, xml_escape},
     },
-    either::Either,
-    std::fmt::Debug,
+    either::Either::{self, Left, Right},
+    std::{f64::consts::PI, fmt::Debug},
 };
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(dead_code)]
 pub enum InOut {
     In,
@@ -31,6 +34,29 @@ impl InOut {
 
 type Doubled<T> = (T, T);
 type DoubledEither<T, U> = Either<Doubled<T>, Doubled<U>>;
+type Ports<IntraCircle, Lambda> = Vec<(InOut, IntraCircle, Lambda)>;
+type NodeName<InterCircle, IntraCircle> =
+    Either<(InOut, InterCircle, IntraCircle), (InOut, IntraCircle)>;
+
+/*
+what can be wrong with a wire of a directed wiring diagram
+the counts are how many nodes are the cause
+*/
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[allow(dead_code)]
+pub enum DirectionProblem {
+    Undriven,
+    DrivenMoreThanOnce(usize),
+    Unread,
+    Undirected(usize),
+}
+
+#[derive(Clone, Default)]
+struct WireEnds {
+    drivers: usize,
+    readers: usize,
+    undirected: usize,
+}
 
 /*
 a wiring diagram with wires labelled using Lambda
@@ -43,6 +69,7 @@ B describes a set of nodes on the single external circle
     an InOut for orientation
     an IntraCircle to label which node on that circle it is
 */
+#[derive(Clone)]
 #[allow(dead_code)]
 #[repr(transparent)]
 pub struct WiringDiagram<
@@ -57,12 +84,279 @@ where
     InterCircle: Eq + Clone,
     IntraCircle: Eq + Clone,
 {
-    pub fn new(
+    pub fn from_named_cospan(
         inside: NamedCospan<Lambda, (InOut, InterCircle, IntraCircle), (InOut, IntraCircle)>,
     ) -> Self {
         Self(inside)
     }
 
+    #[allow(dead_code)]
+    pub fn new(
+        outer_ports: Ports<IntraCircle, Lambda>,
+        circles: Vec<(InterCircle, Ports<IntraCircle, Lambda>)>,
+    ) -> Result<Self, Error> {
+        /*
+        a wiring diagram with the given nodes on the external circle
+            and the given internal circles with their nodes
+        each node is on its own wire so nothing is connected yet
+            use connect_pair to put in the wires
+        an error if a circle is repeated or a node is repeated on the same circle
+        */
+        let mut inside = NamedCospan::empty();
+        for (idx, (circle, ports)) in circles.iter().enumerate() {
+            if circles[..idx].iter().any(|(earlier, _)| earlier == circle) {
+                return Err("An internal circle was given more than once".into());
+            }
+            for (port_idx, (io, intra, type_)) in ports.iter().enumerate() {
+                if ports[..port_idx]
+                    .iter()
+                    .any(|(_, earlier, _)| earlier == intra)
+                {
+                    return Err("A node was repeated on an internal circle".into());
+                }
+                inside.add_boundary_node_unknown_target(
+                    *type_,
+                    Left((*io, circle.clone(), intra.clone())),
+                );
+            }
+        }
+        for (port_idx, (io, intra, type_)) in outer_ports.iter().enumerate() {
+            if outer_ports[..port_idx]
+                .iter()
+                .any(|(_, earlier, _)| earlier == intra)
+            {
+                return Err("A node was repeated on the external circle".into());
+            }
+            inside.add_boundary_node_unknown_target(*type_, Right((*io, intra.clone())));
+        }
+        Ok(Self(inside))
+    }
+
+    #[allow(dead_code)]
+    pub fn named_cospan(
+        &self,
+    ) -> &NamedCospan<Lambda, (InOut, InterCircle, IntraCircle), (InOut, IntraCircle)> {
+        &self.0
+    }
+
+    #[allow(dead_code)]
+    pub fn outer_ports(&self) -> &[(InOut, IntraCircle)] {
+        self.0.right_names()
+    }
+
+    #[allow(dead_code)]
+    pub fn inner_ports(&self) -> &[(InOut, InterCircle, IntraCircle)] {
+        self.0.left_names()
+    }
+
+    #[allow(dead_code)]
+    pub fn circles(&self) -> Vec<InterCircle> {
+        /*
+        the internal circles in the order they first have a node
+        */
+        let mut answer: Vec<InterCircle> = Vec::new();
+        for (_, circle, _) in self.0.left_names() {
+            if !answer.contains(circle) {
+                answer.push(circle.clone());
+            }
+        }
+        answer
+    }
+
+    #[allow(dead_code)]
+    pub fn circle_ports(&self, which_circle: &InterCircle) -> Vec<(InOut, IntraCircle)> {
+        self.0
+            .left_names()
+            .iter()
+            .filter(|(_, circle, _)| circle == which_circle)
+            .map(|(io, _, intra)| (*io, intra.clone()))
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_isomorphic_to(&self, other: &Self) -> bool {
+        /*
+        the same nodes on the same circles wired up the same way
+        */
+        self.0.is_isomorphic_to(&other.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn check_orientations(&self, require_driven: bool) -> Result<(), Error> {
+        /*
+        a weaker check_directions that only looks at the drivers
+        no wire may be driven twice
+        if require_driven then every wire that drives something must also be driven
+            otherwise inputs that are left dangling are allowed
+        wires that drive nothing and Undirected nodes are fine
+        */
+        let problems = self.direction_problems();
+        let unread = |wire: &usize| problems.contains(&(*wire, DirectionProblem::Unread));
+        Self::directions_error(
+            problems
+                .iter()
+                .filter(|(wire, problem)| match problem {
+                    DirectionProblem::DrivenMoreThanOnce(_) => true,
+                    DirectionProblem::Undriven => require_driven && !unread(wire),
+                    DirectionProblem::Unread | DirectionProblem::Undirected(_) => false,
+                })
+                .copied()
+                .collect(),
+        )
+    }
+
+    fn wire_ends(&self) -> Vec<WireEnds> {
+        /*
+        how many nodes drive each wire, are driven by it or are Undirected on it
+        following the conventions in direction_problems
+        */
+        let cospan = self.0.cospan();
+        let mut ends = vec![WireEnds::default(); cospan.middle().len()];
+        let inner = self
+            .0
+            .left_names()
+            .iter()
+            .map(|(io, _, _)| *io)
+            .zip(cospan.left_to_middle());
+        let outer = self
+            .0
+            .right_names()
+            .iter()
+            .map(|(io, _)| io.flipped())
+            .zip(cospan.right_to_middle());
+        for (io, wire) in inner.chain(outer) {
+            match io {
+                InOut::Out => ends[*wire].drivers += 1,
+                InOut::In => ends[*wire].readers += 1,
+                InOut::Undirected => ends[*wire].undirected += 1,
+            }
+        }
+        ends
+    }
+
+    #[allow(dead_code)]
+    pub fn direction_problems(&self) -> Vec<(usize, DirectionProblem)> {
+        /*
+        everything that stops this from being a directed wiring diagram
+        a wire is driven by the In nodes of the external circle
+            and the Out nodes of the internal circles
+        and it drives the Out nodes of the external circle
+            and the In nodes of the internal circles
+        Undirected nodes are neither
+        every wire should be driven exactly once and drive at least one node
+        each offending wire is listed with each thing wrong with it
+            in order of the wires
+        check_directions and check_orientations are both read off from this
+        */
+        let mut problems = Vec::new();
+        for (wire, ends) in self.wire_ends().iter().enumerate() {
+            if ends.undirected > 0 {
+                problems.push((wire, DirectionProblem::Undirected(ends.undirected)));
+            }
+            match ends.drivers {
+                0 => problems.push((wire, DirectionProblem::Undriven)),
+                1 => {}
+                more => problems.push((wire, DirectionProblem::DrivenMoreThanOnce(more))),
+            }
+            if ends.readers == 0 {
+                problems.push((wire, DirectionProblem::Unread));
+            }
+        }
+        problems
+    }
+
+    #[allow(dead_code)]
+    pub fn check_directions(&self) -> Result<(), Error> {
+        /*
+        an error listing all of direction_problems if there are any
+        */
+        Self::directions_error(self.direction_problems())
+    }
+
+    fn directions_error(problems: Vec<(usize, DirectionProblem)>) -> Result<(), Error> {
+        if problems.is_empty() {
+            return Ok(());
+        }
+        let listed: Vec<String> = problems
+            .iter()
+            .map(|(wire, problem)| match problem {
+                DirectionProblem::Undriven => format!("wire {} is not driven", wire),
+                DirectionProblem::DrivenMoreThanOnce(count) => {
+                    format!("wire {} is driven by {} nodes", wire, count)
+                }
+                DirectionProblem::Unread => format!("wire {} drives nothing", wire),
+                DirectionProblem::Undirected(count) => {
+                    format!("wire {} has {} Undirected nodes", wire, count)
+                }
+            })
+            .collect();
+        Err(format!("Badly directed wires: {}", listed.join(", ")).into())
+    }
+
+    #[allow(dead_code)]
+    pub fn connect_pair_directed(
+        &mut self,
+        node_1: Either<(InOut, InterCircle, IntraCircle), (InOut, IntraCircle)>,
+        node_2: Either<(InOut, InterCircle, IntraCircle), (InOut, IntraCircle)>,
+    ) -> Result<(), Error> {
+        /*
+        connect_pair but an error and no change
+            if either node does not exist or is Undirected
+            if their wires have different labels
+            or if the joined wire would be driven more than once
+        so a diagram built up only this way never has a wire driven twice
+        */
+        let cospan = self.0.cospan();
+        let find_wire = |node: &Either<(InOut, InterCircle, IntraCircle), (InOut, IntraCircle)>| {
+            let (io, found) = match node {
+                Left(name) => (
+                    name.0,
+                    self.0
+                        .left_names()
+                        .iter()
+                        .position(|z| z == name)
+                        .map(|idx| cospan.left_to_middle()[idx]),
+                ),
+                Right(name) => (
+                    name.0,
+                    self.0
+                        .right_names()
+                        .iter()
+                        .position(|z| z == name)
+                        .map(|idx| cospan.right_to_middle()[idx]),
+                ),
+            };
+            match (io, found) {
+                (_, None) => Err(Error::from("No node with that name to connect")),
+                (InOut::Undirected, _) => Err(Error::from(
+                    "Undirected nodes can not be in a directed wiring diagram",
+                )),
+                (_, Some(wire)) => Ok(wire),
+            }
+        };
+        let wire_1 = find_wire(&node_1)?;
+        let wire_2 = find_wire(&node_2)?;
+        if cospan.middle()[wire_1] != cospan.middle()[wire_2] {
+            return Err(format!(
+                "Can not connect a wire labelled {:?} to one labelled {:?}",
+                cospan.middle()[wire_1],
+                cospan.middle()[wire_2]
+            )
+            .into());
+        }
+        if wire_1 != wire_2 {
+            let ends = self.wire_ends();
+            let drivers = ends[wire_1].drivers + ends[wire_2].drivers;
+            if drivers > 1 {
+                return Err(
+                    format!("Connecting would leave a wire driven by {} nodes", drivers).into(),
+                );
+            }
+        }
+        self.0.connect_pair(node_1, node_2);
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn change_boundary_node_name(
         &mut self,
@@ -110,6 +404,130 @@ where
         self.0.connect_pair(node_1, node_2)
     }
 
+    #[allow(dead_code)]
+    pub fn merge_junctions(
+        &mut self,
+        pairs: &[Doubled<NodeName<InterCircle, IntraCircle>>],
+    ) -> Result<Vec<usize>, Error> {
+        /*
+        put all the named nodes of each pair on the same wire at once
+            so a node connected to two others ends up with both
+        the wires are then numbered in the order of their first old wire
+            and the answer says which new wire each old one became
+        an error and no change if a node is missing or the wires have different labels
+        */
+        self.0.merge_middle_by_names(pairs)
+    }
+
+    #[allow(dead_code)]
+    pub fn connect(
+        &mut self,
+        outer_port_a: (InOut, IntraCircle),
+        outer_port_b: (InOut, IntraCircle),
+    ) -> Result<(), Error> {
+        /*
+        the trace, feed one node of the external circle back into another
+        their wires are joined and both nodes are taken off the external circle
+            with the rest of the external circle staying in order
+        a directed diagram can only feed an Out back into an In
+            and Undirected nodes only to each other
+        if nothing else was on either wire it is left as a closed loop
+            which simplify removes
+        an error and no change if either node is missing, they are the same node,
+            their orientations do not fit or their wires have different labels
+        */
+        let right_names = self.0.right_names();
+        let (Some(idx_a), Some(idx_b)) = (
+            right_names.iter().position(|z| *z == outer_port_a),
+            right_names.iter().position(|z| *z == outer_port_b),
+        ) else {
+            return Err("No node with that name on the external circle".into());
+        };
+        if idx_a == idx_b {
+            return Err("Can not connect a node to itself".into());
+        }
+        match (outer_port_a.0, outer_port_b.0) {
+            (InOut::In, InOut::Out) | (InOut::Out, InOut::In) => {}
+            (InOut::Undirected, InOut::Undirected) => {}
+            _ => {
+                return Err(format!(
+                    "Can not connect an {:?} node to an {:?} node",
+                    outer_port_a.0, outer_port_b.0
+                )
+                .into())
+            }
+        }
+        self.0
+            .merge_middle_by_names(&[(Right(outer_port_a), Right(outer_port_b))])?;
+        let cospan = self.0.cospan();
+        let kept = |idx: &usize| *idx != idx_a && *idx != idx_b;
+        self.0 = NamedCospan::new(
+            cospan.left_to_middle().to_vec(),
+            (0..cospan.right_to_middle().len())
+                .filter(kept)
+                .map(|idx| cospan.right_to_middle()[idx])
+                .collect(),
+            cospan.middle().to_vec(),
+            self.0.left_names().clone(),
+            (0..self.0.right_names().len())
+                .filter(kept)
+                .map(|idx| self.0.right_names()[idx].clone())
+                .collect(),
+        );
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn simplify(&mut self) -> usize {
+        /*
+        shrink the diagram and say how many wires fewer there are
+        a junction of degree 2 is an internal circle with just two nodes
+            one In and one Out or both Undirected
+            whose wires have the same label
+        it is taken to be a straight-through wire
+            so it is removed and its two wires become one
+        a circle of type x to x that is meant as a genuine box
+            should not be left in a diagram that gets simplified
+        then drop the wires that no node is on
+            these are the closed loops left when operadic_substitution glues
+            a wire of the filling back onto itself
+            and the wires left behind by delete_boundary_node
+        the nodes that stay can be reordered as in delete_boundary_node
+        */
+        let before = self.0.cospan().middle().len();
+        while let Some((node_a, node_b)) = self.circles().into_iter().find_map(|circle| {
+            let mut nodes = self
+                .0
+                .left_names()
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, which, _))| *which == circle);
+            let (Some((idx_a, name_a)), Some((idx_b, name_b)), None) =
+                (nodes.next(), nodes.next(), nodes.next())
+            else {
+                return None;
+            };
+            let passing = matches!(
+                (name_a.0, name_b.0),
+                (InOut::In, InOut::Out)
+                    | (InOut::Out, InOut::In)
+                    | (InOut::Undirected, InOut::Undirected)
+            );
+            let middle = self.0.cospan().middle();
+            let left_to_middle = self.0.cospan().left_to_middle();
+            (passing && middle[left_to_middle[idx_a]] == middle[left_to_middle[idx_b]])
+                .then(|| (name_a.clone(), name_b.clone()))
+        }) {
+            self.0
+                .merge_middle_by_names(&[(Left(node_a.clone()), Left(node_b.clone()))])
+                .expect("Both nodes are there and their wires have the same label");
+            self.0.delete_boundary_node_by_name(Left(node_a));
+            self.0.delete_boundary_node_by_name(Left(node_b));
+        }
+        self.0 = self.0.to_corelation();
+        before - self.0.cospan().middle().len()
+    }
+
     #[allow(dead_code)]
     pub fn delete_boundary_node(
         &mut self,
@@ -132,7 +550,47 @@ where
         /*
         change the labels with the function f
         */
-        WiringDiagram::new(self.0.map(f))
+        WiringDiagram::from_named_cospan(self.0.map(f))
+    }
+
+    #[allow(dead_code)]
+    pub fn rename_ports<F, G, NewInter, NewIntra>(
+        &self,
+        rename_circle: F,
+        rename_node: G,
+    ) -> Result<WiringDiagram<Lambda, NewInter, NewIntra>, Error>
+    where
+        F: Fn(&InterCircle) -> NewInter,
+        G: Fn(&IntraCircle) -> NewIntra,
+        NewInter: Eq + Clone,
+        NewIntra: Eq + Clone,
+    {
+        /*
+        change the names of the internal circles with rename_circle
+        and the names of the nodes on every circle with rename_node
+        the orientations and the wiring stay the same
+        an error if two nodes on the same circle end up with the same name
+            which includes two internal circles given the same name
+            when they have nodes with the same name
+        */
+        let renamed = WiringDiagram(self.0.rename_ports(
+            |(io, circle, intra)| (*io, rename_circle(circle), rename_node(intra)),
+            |(io, intra)| (*io, rename_node(intra)),
+        ));
+        let inner = renamed.inner_ports();
+        let outer = renamed.outer_ports();
+        let repeated = inner.iter().enumerate().any(|(idx, name)| {
+            inner[..idx]
+                .iter()
+                .any(|earlier| earlier.1 == name.1 && earlier.2 == name.2)
+        }) || outer
+            .iter()
+            .enumerate()
+            .any(|(idx, name)| outer[..idx].iter().any(|earlier| earlier.1 == name.1));
+        if repeated {
+            return Err("Renaming gave two nodes on the same circle the same name".into());
+        }
+        Ok(renamed)
     }
 
     #[allow(dead_code)]
@@ -151,8 +609,17 @@ where
         so that the external circle of internal_other is interpreted as C
         the new internal circles of self are all the old internal circles except for C
             and all the internal circles of internal_other
+        the node (io, which_circle, intra) of C is glued to the node (io, intra)
+            of the external circle of internal_other
+            with the same orientation because an input to the circle C
+            is an input to the diagram filling it
+        the internal nodes afterwards are those of internal_other in their order
+            followed by the remaining ones of self in their original order
+        an error and no change if the nodes of C and the external nodes of internal_other
+            do not match up exactly or if internal_other uses a circle name
+            that self still has
         */
-        let found_nodes: Vec<_> = NamedCospan::find_nodes_by_name_predicate(
+        let on_circle: Vec<_> = NamedCospan::find_nodes_by_name_predicate(
             &self.0,
             |z| z.1 == which_circle,
             |_| false,
@@ -161,61 +628,2197 @@ where
         .iter()
         .filter_map(|x| x.left())
         .collect();
+        let unaffected: Vec<_> = (0..self.0.left_names().len())
+            .filter(|idx| !on_circle.contains(idx))
+            .collect();
+        let self_domain = self.0.domain();
+        let self_inner_interface_unaffected: Vec<Lambda> =
+            unaffected.iter().map(|idx| self_domain[*idx]).collect();
+        let self_inner_names_unaffected: Vec<_> = unaffected
+            .iter()
+            .map(|idx| self.0.left_names()[*idx])
+            .collect();
 
-        let mut self_inner_interface_unaffected = self.0.domain();
-        remove_multiple(&mut self_inner_interface_unaffected, found_nodes.clone());
-        let mut self_inner_names_unaffected = self.0.left_names().clone();
-        remove_multiple(&mut self_inner_names_unaffected, found_nodes);
+        let clashes = internal_other.0.left_names().iter().any(|other_name| {
+            self_inner_names_unaffected
+                .iter()
+                .any(|self_name| self_name.1 == other_name.1)
+        });
+        if clashes {
+            return Err(
+                "An internal circle of the substituted diagram is already an internal circle"
+                    .to_string(),
+            );
+        }
 
-        // todo handle orientations
+        /*
+        where each node on the right of internal_other and the identity next to it
+            needs to end up so that it lines up with the left of self
+        */
+        let mut targets = Vec::with_capacity(self.0.left_names().len());
+        for (io, intra) in internal_other.0.right_names() {
+            let Some(target) = on_circle.iter().find(|idx| {
+                let name = self.0.left_names()[**idx];
+                name.0 == *io && name.2 == *intra
+            }) else {
+                return Err(
+                    "A node on the external circle of the substituted diagram was not on the circle being replaced"
+                        .to_string(),
+                );
+            };
+            targets.push(*target);
+        }
+        targets.extend(unaffected.iter().copied());
+        let p = necessary_permutation(
+            &targets,
+            &(0..self.0.left_names().len()).collect::<Vec<_>>(),
+        )?;
 
         internal_other.0.monoidal(NamedCospan::identity(
             &self_inner_interface_unaffected,
             &self_inner_names_unaffected,
             |left_name| (left_name, (left_name.0, left_name.2)),
         ));
+        internal_other.0.permute_side(&p, true);
+        self.0 = internal_other.0.compose(&self.0)?;
+        Ok(())
+    }
+}
 
-        let p = necessary_permutation(
-            internal_other.0.right_names(),
-            &self
-                .0
+type Sweep<Lambda, InterCircle> =
+    GenericMonoidalMorphism<FrobeniusOperation<Lambda, InterCircle>, Lambda>;
+type SweepLayer<Lambda, InterCircle> =
+    GenericMonoidalMorphismLayer<FrobeniusOperation<Lambda, InterCircle>, Lambda>;
+
+impl<Lambda, InterCircle, IntraCircle> WiringDiagram<Lambda, InterCircle, IntraCircle>
+where
+    Lambda: Eq + Copy + Debug,
+    InterCircle: Eq + Copy,
+    IntraCircle: Eq + Clone,
+{
+    #[allow(dead_code)]
+    pub fn to_monoidal_morphism(&self) -> Result<Sweep<Lambda, InterCircle>, Error> {
+        /*
+        sweep through a directed wiring diagram from its inputs to its outputs
+            and write it as layers of Frobenius operations
+        the domain is the In nodes of the external circle in order
+            and the codomain is its Out nodes in order
+        each internal circle becomes an UnSpecifiedBox labelled by the circle
+            from its In nodes to its Out nodes
+        the circles are swept in the order of circles except that
+            a circle waits until everything feeding it has been swept
+        a wire read by several nodes is copied with Comultiplication
+            one read by nothing is ended with Counit
+            and the wires are moved into place with SymmetricBraiding
+        an error if a wire has an Undirected node or is not driven by exactly one node
+            or if there is a feedback loop through the internal circles
+        */
+        let cospan = self.0.cospan();
+        let middle = cospan.middle();
+        let ends = self.wire_ends();
+        for (wire, wire_ends) in ends.iter().enumerate() {
+            if wire_ends.undirected > 0 {
+                return Err(format!("Wire {} has an Undirected node", wire).into());
+            }
+            if wire_ends.drivers != 1 {
+                return Err(format!(
+                    "Wire {} is driven by {} nodes rather than one",
+                    wire, wire_ends.drivers
+                )
+                .into());
+            }
+        }
+
+        let outer_wires = |which: InOut| -> Vec<usize> {
+            self.0
+                .right_names()
+                .iter()
+                .zip(cospan.right_to_middle())
+                .filter(|((io, _), _)| *io == which)
+                .map(|(_, wire)| *wire)
+                .collect()
+        };
+        let circle_wires = |circle: InterCircle, which: InOut| -> Vec<usize> {
+            self.0
                 .left_names()
                 .iter()
-                .map(|z| (z.0, z.2))
-                .collect::<Vec<_>>(),
+                .zip(cospan.left_to_middle())
+                .filter(|((io, which_circle, _), _)| *io == which && *which_circle == circle)
+                .map(|(_, wire)| *wire)
+                .collect()
+        };
+
+        let mut circles_left = self.circles();
+        let mut driven = vec![false; middle.len()];
+        let mut strands = outer_wires(InOut::In);
+        strands.iter().for_each(|wire| driven[*wire] = true);
+        let readers: Vec<usize> = ends.iter().map(|wire_ends| wire_ends.readers).collect();
+        let mut layers = Vec::new();
+        let fresh = strands.clone();
+        strands = sweep_fan_out(&mut layers, strands, &fresh, &readers, middle);
+        while !circles_left.is_empty() {
+            let Some(next) = circles_left.iter().position(|circle| {
+                circle_wires(*circle, InOut::In)
+                    .iter()
+                    .all(|wire| driven[*wire])
+            }) else {
+                return Err("There is a feedback loop through the internal circles".into());
+            };
+            let circle = circles_left.remove(next);
+            let inputs = circle_wires(circle, InOut::In);
+            let outputs = circle_wires(circle, InOut::Out);
+            strands = sweep_to_front(&mut layers, strands, &inputs, middle);
+            let mut blocks = vec![FrobeniusOperation::UnSpecifiedBox(
+                circle,
+                inputs.iter().map(|wire| middle[*wire]).collect(),
+                outputs.iter().map(|wire| middle[*wire]).collect(),
+            )];
+            blocks.extend(
+                strands[inputs.len()..]
+                    .iter()
+                    .map(|wire| FrobeniusOperation::Identity(middle[*wire])),
+            );
+            layers.push(SweepLayer::from_blocks(blocks));
+            outputs.iter().for_each(|wire| driven[*wire] = true);
+            strands = outputs
+                .iter()
+                .copied()
+                .chain(strands.drain(inputs.len()..))
+                .collect();
+            strands = sweep_fan_out(&mut layers, strands, &outputs, &readers, middle);
+        }
+        let outputs = outer_wires(InOut::Out);
+        strands = sweep_to_front(&mut layers, strands, &outputs, middle);
+        if layers.is_empty() && !strands.is_empty() {
+            layers.push(SweepLayer::from_blocks(
+                strands
+                    .iter()
+                    .map(|wire| FrobeniusOperation::Identity(middle[*wire]))
+                    .collect(),
+            ));
+        }
+        Sweep::try_from(layers)
+    }
+}
+
+fn sweep_fan_out<Lambda, InterCircle>(
+    layers: &mut Vec<SweepLayer<Lambda, InterCircle>>,
+    mut strands: Vec<usize>,
+    fresh: &[usize],
+    readers: &[usize],
+    middle: &[Lambda],
+) -> Vec<usize>
+where
+    Lambda: Eq + Copy + Debug,
+    InterCircle: Eq + Copy,
+{
+    /*
+    the wires in fresh have just been driven so have a single strand each
+    give each of them a strand for every node it is read by
+    a strand whose wire is read by nothing is ended
+    and a strand whose wire is read more than once is copied
+        doubling up layer by layer until there are enough
+    the other strands go straight through
+    */
+    let mut have = vec![1; middle.len()];
+    loop {
+        let mut blocks = Vec::with_capacity(strands.len());
+        let mut next_strands = Vec::with_capacity(strands.len());
+        let mut changed = false;
+        for wire in &strands {
+            let label = middle[*wire];
+            if !fresh.contains(wire) || (readers[*wire] > 0 && have[*wire] >= readers[*wire]) {
+                blocks.push(FrobeniusOperation::Identity(label));
+                next_strands.push(*wire);
+            } else if readers[*wire] == 0 {
+                blocks.push(FrobeniusOperation::Counit(label));
+                changed = true;
+            } else {
+                blocks.push(FrobeniusOperation::Comultiplication(label));
+                next_strands.extend([*wire, *wire]);
+                have[*wire] += 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+        layers.push(SweepLayer::from_blocks(blocks));
+        strands = next_strands;
+    }
+    strands
+}
+
+fn sweep_to_front<Lambda, InterCircle>(
+    layers: &mut Vec<SweepLayer<Lambda, InterCircle>>,
+    mut strands: Vec<usize>,
+    wanted: &[usize],
+    middle: &[Lambda],
+) -> Vec<usize>
+where
+    Lambda: Eq + Copy + Debug,
+    InterCircle: Eq + Copy,
+{
+    /*
+    move a strand of each of the wanted wires to the front in that order
+        with the rest after them in the order they were in
+    by layers of braidings of neighbours that are the wrong way around
+    */
+    let mut destination: Vec<Option<usize>> = vec![None; strands.len()];
+    for (position, wire) in wanted.iter().enumerate() {
+        let found = (0..strands.len())
+            .find(|idx| destination[*idx].is_none() && strands[*idx] == *wire)
+            .expect("every wire should have a strand for each node it goes to");
+        destination[found] = Some(position);
+    }
+    let mut next_position = wanted.len();
+    let mut destination: Vec<usize> = destination
+        .into_iter()
+        .map(|position| {
+            position.unwrap_or_else(|| {
+                next_position += 1;
+                next_position - 1
+            })
+        })
+        .collect();
+    while !destination.is_sorted() {
+        let mut blocks = Vec::with_capacity(strands.len());
+        let mut idx = 0;
+        while idx < strands.len() {
+            if idx + 1 < strands.len() && destination[idx] > destination[idx + 1] {
+                blocks.push(FrobeniusOperation::SymmetricBraiding(
+                    middle[strands[idx]],
+                    middle[strands[idx + 1]],
+                ));
+                strands.swap(idx, idx + 1);
+                destination.swap(idx, idx + 1);
+                idx += 2;
+            } else {
+                blocks.push(FrobeniusOperation::Identity(middle[strands[idx]]));
+                idx += 1;
+            }
+        }
+        layers.push(SweepLayer::from_blocks(blocks));
+    }
+    strands
+}
+
+impl<Lambda, InterCircle, IntraCircle> WiringDiagram<Lambda, InterCircle, IntraCircle>
+where
+    Lambda: Eq + Copy + Debug,
+    InterCircle: Eq + Clone,
+    IntraCircle: Eq + Clone,
+{
+    #[allow(dead_code)]
+    pub fn interpret<C>(&self, fill: impl Fn(&InterCircle) -> Result<C, Error>) -> Result<C, Error>
+    where
+        C: HypergraphCategory<Lambda>,
+    {
+        /*
+        evaluate the whole diagram in a hypergraph category
+            given what to put in each internal circle
+        the filling of a circle goes from the types of its In nodes in order
+            to the types of its Out and Undirected nodes in order
+        the answer goes from the In nodes of the external circle
+            to its Out and Undirected nodes in the same way
+        the fillings are put side by side and every wire becomes
+            a spider from the nodes feeding it to the nodes it feeds
+            with one extra leg carried past the fillings so the two halves meet up
+        by spider fusion that is the same as a single spider on all the nodes of the wire
+            so wires feeding back into the circle they come from are fine
+        */
+        let cospan = self.0.cospan();
+        let middle = cospan.middle();
+        let inner_wires = cospan.left_to_middle();
+        let outer_wires = cospan.right_to_middle();
+        let inner_types = |idces: &[usize]| -> Vec<Lambda> {
+            idces.iter().map(|idx| middle[inner_wires[*idx]]).collect()
+        };
+
+        let mut fillings = C::identity(&vec![]);
+        let mut inner_ins = Vec::new();
+        let mut inner_outs = Vec::new();
+        for circle in self.circles() {
+            let (ins, outs): (Vec<usize>, Vec<usize>) = (0..inner_wires.len())
+                .filter(|idx| self.inner_ports()[*idx].1 == circle)
+                .partition(|idx| self.inner_ports()[*idx].0 == InOut::In);
+            let filling = fill(&circle)?;
+            let (domain, codomain) = (inner_types(&ins), inner_types(&outs));
+            if filling.domain() != domain || filling.codomain() != codomain {
+                return Err(format!(
+                    "A circle was filled with something from {:?} to {:?} but its nodes need {:?} to {:?}",
+                    filling.domain(),
+                    filling.codomain(),
+                    domain,
+                    codomain
+                )
+                .into());
+            }
+            fillings.monoidal(filling);
+            inner_ins.extend(ins);
+            inner_outs.extend(outs);
+        }
+        let (outer_ins, outer_outs): (Vec<usize>, Vec<usize>) =
+            (0..outer_wires.len()).partition(|idx| self.outer_ports()[*idx].0 == InOut::In);
+
+        let carried: Vec<usize> = (0..middle.len()).collect();
+        let wires_of = |idces: &[usize], wires: &[usize]| -> Vec<usize> {
+            idces.iter().map(|idx| wires[*idx]).collect()
+        };
+        let front: C = wire_spiders(
+            middle,
+            &wires_of(&outer_ins, outer_wires),
+            &[wires_of(&inner_ins, inner_wires), carried.clone()].concat(),
+        )?;
+        let back: C = wire_spiders(
+            middle,
+            &[wires_of(&inner_outs, inner_wires), carried].concat(),
+            &wires_of(&outer_outs, outer_wires),
         )?;
+        front
+            .compose(&tensor_pair(fillings, C::identity(&middle.to_vec())))?
+            .compose(&back)
+    }
+}
 
-        internal_other.0.permute_side(&p, true);
-        self.0 = self.0.compose(&internal_other.0)?;
+fn wire_spiders<Lambda, C>(
+    middle: &[Lambda],
+    sources: &[usize],
+    targets: &[usize],
+) -> Result<C, Error>
+where
+    Lambda: Eq + Copy + Debug,
+    C: HypergraphCategory<Lambda>,
+{
+    /*
+    sources and targets say which wire each leg on either side is on
+    gather the sources by wire, put a spider on each wire
+        and then spread its outputs back out to where the targets are
+    */
+    let by_wire = |legs: &[usize]| -> Vec<usize> {
+        let mut sorted: Vec<usize> = (0..legs.len()).collect();
+        sorted.sort_by_key(|idx| legs[*idx]);
+        sorted
+    };
+    let reorder = |from: &[usize], to: &[usize], legs: &[usize]| -> Result<C, Error> {
+        let types: Vec<Lambda> = from.iter().map(|idx| middle[legs[*idx]]).collect();
+        let p = necessary_permutation(from, to)?.inv();
+        Ok(C::from_permutation(p, &types, true))
+    };
+    let in_order = |legs: &[usize]| -> Vec<usize> { (0..legs.len()).collect() };
+
+    let mut spiders = C::identity(&vec![]);
+    for (wire, type_) in middle.iter().enumerate() {
+        let count = |legs: &[usize]| legs.iter().filter(|w| **w == wire).count();
+        spiders.monoidal(C::spider(*type_, count(sources), count(targets))?);
+    }
+    let gather = reorder(&in_order(sources), &by_wire(sources), sources)?;
+    let spread = reorder(&by_wire(targets), &in_order(targets), targets)?;
+    gather.compose(&spiders)?.compose(&spread)
+}
+
+/*
+a wiring diagram where some of the internal circles are filled in
+    by another wiring diagram which may itself be nested
+the rest of the internal circles are left opaque
+flatten does all the operadic substitutions from the inside out
+*/
+#[derive(Clone)]
+#[allow(dead_code)]
+pub struct NestedWiringDiagram<
+    Lambda: Eq + Copy + Debug,
+    InterCircle: Eq + Clone,
+    IntraCircle: Eq + Clone,
+> {
+    diagram: WiringDiagram<Lambda, InterCircle, IntraCircle>,
+    fillings: Vec<(InterCircle, Self)>,
+}
+
+impl<Lambda, InterCircle, IntraCircle> NestedWiringDiagram<Lambda, InterCircle, IntraCircle>
+where
+    Lambda: Eq + Copy + Debug,
+    InterCircle: Eq + Copy,
+    IntraCircle: Eq + Copy,
+{
+    #[allow(dead_code)]
+    pub fn new(diagram: WiringDiagram<Lambda, InterCircle, IntraCircle>) -> Self {
+        /*
+        nothing filled in yet
+        */
+        Self {
+            diagram,
+            fillings: vec![],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn diagram(&self) -> &WiringDiagram<Lambda, InterCircle, IntraCircle> {
+        &self.diagram
+    }
+
+    #[allow(dead_code)]
+    pub fn filling(&self, which_circle: &InterCircle) -> Option<&Self> {
+        self.fillings
+            .iter()
+            .find(|(circle, _)| circle == which_circle)
+            .map(|(_, inside)| inside)
+    }
+
+    #[allow(dead_code)]
+    pub fn opaque_circles(&self) -> Vec<InterCircle> {
+        /*
+        the circles that are left after flattening
+        in the order that the flattened diagram has them
+        */
+        let mut answer = self.diagram.circles();
+        for (circle, inside) in &self.fillings {
+            let mut inner = inside.opaque_circles();
+            inner.extend(answer.into_iter().filter(|z| z != circle));
+            answer = inner;
+        }
+        answer
+    }
+
+    #[allow(dead_code)]
+    pub fn fill(&mut self, which_circle: InterCircle, inside: Self) -> Result<(), Error> {
+        /*
+        put inside into the circle which_circle
+        the external circle of inside must have exactly the nodes of which_circle
+            with the same orientations and labels
+        an error and no change if there is no such circle, it is already filled
+            or the nodes do not match
+        */
+        if self.filling(&which_circle).is_some() {
+            return Err("That internal circle was already filled".into());
+        }
+        let domain = self.diagram.0.domain();
+        let on_circle: Vec<(InOut, IntraCircle, Lambda)> = self
+            .diagram
+            .inner_ports()
+            .iter()
+            .zip(domain)
+            .filter(|((_, circle, _), _)| *circle == which_circle)
+            .map(|((io, _, intra), label)| (*io, *intra, label))
+            .collect();
+        if on_circle.is_empty() {
+            return Err("There is no internal circle with nodes to fill".into());
+        }
+        let codomain = inside.diagram.0.codomain();
+        let outside: Vec<(InOut, IntraCircle, Lambda)> = inside
+            .diagram
+            .outer_ports()
+            .iter()
+            .zip(codomain)
+            .map(|((io, intra), label)| (*io, *intra, label))
+            .collect();
+        if on_circle.len() != outside.len() || on_circle.iter().any(|z| !outside.contains(z)) {
+            return Err(
+                "The external circle of the filling does not match the circle being filled".into(),
+            );
+        }
+        self.fillings.push((which_circle, inside));
         Ok(())
     }
+
+    #[allow(dead_code)]
+    pub fn flatten(&self) -> Result<WiringDiagram<Lambda, InterCircle, IntraCircle>, Error> {
+        /*
+        flatten every filling and substitute it into its circle
+            in the order the circles were filled
+        so the internal circles are all the opaque ones at every level
+        an error if the same name is used for circles at different levels
+            that both end up in the flattened diagram
+            because they would become indistinguishable
+        */
+        let mut answer = self.diagram.clone();
+        for (circle, inside) in &self.fillings {
+            answer.operadic_substitution(*circle, inside.flatten()?)?;
+        }
+        Ok(answer)
+    }
 }
 
-mod test {
+/*
+how to_svg arranges the internal circles inside the external one
+*/
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[allow(dead_code)]
+pub enum SvgLayout {
+    Ring,
+    Row,
+}
 
-    #[test]
-    fn no_input_example() {
-        use super::{InOut, WiringDiagram};
-        use crate::named_cospan::NamedCospan;
-        use either::Right;
-        let unchanged_right_names = vec![
-            (InOut::In, 0),
-            (InOut::Out, 1),
-            (InOut::In, 2),
-            (InOut::Out, 3),
-            (InOut::Out, 4),
-        ];
-        let mut example = WiringDiagram::<_, (), _>::new(NamedCospan::new(
-            vec![],
-            vec![0, 1, 2, 2, 0],
-            vec![true, true, false],
-            vec![],
-            unchanged_right_names.clone(),
-        ));
-        assert_eq!(*example.0.right_names(), unchanged_right_names);
-        example.change_boundary_node_name(Right(((InOut::In, 0), (InOut::Out, 0))));
-        let changed_names = example.0.right_names();
-        assert_eq!(changed_names[0], (InOut::Out, 0));
-        assert_eq!(changed_names[1..], unchanged_right_names[1..]);
+fn point_on_circle(center: (f64, f64), radius: f64, idx: usize, count: usize) -> (f64, f64) {
+    /*
+    the idx'th of count evenly spaced points starting from the left going clockwise
+    with y pointing down as in SVG
+    */
+    let angle = PI + 2.0 * PI * (idx as f64) / (count as f64);
+    (
+        center.0 + radius * angle.cos(),
+        center.1 + radius * angle.sin(),
+    )
+}
+
+impl<Lambda, InterCircle, IntraCircle> WiringDiagram<Lambda, InterCircle, IntraCircle>
+where
+    Lambda: Eq + Copy + Debug,
+    InterCircle: Eq + Clone + Debug,
+    IntraCircle: Eq + Clone + Debug,
+{
+    fn circles_in_layout_order(&self, wire_order: &[usize]) -> Vec<InterCircle> {
+        /*
+        the internal circles sorted by where their wires are on average in wire_order
+        wire_order is an order of the middle of the cospan as from layout_middle_order
+            which keeps the wires in the same order as the nodes they go to
+        so circles that share wires or go to nearby outer nodes end up next to each other
+        */
+        let mut rank = vec![0; wire_order.len()];
+        for (position, wire) in wire_order.iter().enumerate() {
+            rank[*wire] = position;
+        }
+        let cospan = self.0.cospan();
+        let average_rank = |which_circle: &InterCircle| {
+            let ranks: Vec<f64> = self
+                .0
+                .left_names()
+                .iter()
+                .zip(cospan.left_to_middle())
+                .filter(|((_, circle, _), _)| circle == which_circle)
+                .map(|(_, wire)| rank[*wire] as f64)
+                .collect();
+            ranks.iter().sum::<f64>() / ranks.len() as f64
+        };
+        let mut circles: Vec<(f64, InterCircle)> = self
+            .circles()
+            .into_iter()
+            .map(|circle| (average_rank(&circle), circle))
+            .collect();
+        circles.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        circles.into_iter().map(|(_, circle)| circle).collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn to_dot(&self) -> String {
+        /*
+        a graphviz digraph with the external circle as the outermost cluster
+        each internal circle is a cluster inside it labelled by its InterCircle
+            the clusters and the wires are written in the order from layout_middle_order
+            which graphviz mostly keeps so there are fewer crossings
+        each node on a circle is drawn as a small circle labelled by its IntraCircle
+        each wire is a point inside the external circle
+            with an edge to or from every node on it labelled by the wire's label
+        the edges point the way the wire is driven as in direction_problems
+            and have no arrowhead for Undirected nodes
+        */
+        let cospan = self.0.cospan();
+        let wire_order = cospan.layout_middle_order();
+        let mut dot =
+            String::from("digraph {\n    subgraph cluster_outer {\n        label=\"outer\";\n");
+        for (idx, (_, intra)) in self.0.right_names().iter().enumerate() {
+            dot.push_str(&format!(
+                "        outer_{} [shape=circle, label=\"{}\"];\n",
+                idx,
+                dot_escape(&format!("{:?}", intra))
+            ));
+        }
+        for (circle_num, circle) in self.circles_in_layout_order(&wire_order).iter().enumerate() {
+            dot.push_str(&format!(
+                "        subgraph cluster_{} {{\n            label=\"{}\";\n",
+                circle_num,
+                dot_escape(&format!("{:?}", circle))
+            ));
+            for (idx, (_, _, intra)) in self
+                .0
+                .left_names()
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, which_circle, _))| which_circle == circle)
+            {
+                dot.push_str(&format!(
+                    "            inner_{} [shape=circle, label=\"{}\"];\n",
+                    idx,
+                    dot_escape(&format!("{:?}", intra))
+                ));
+            }
+            dot.push_str("        }\n");
+        }
+        for idx in &wire_order {
+            dot.push_str(&format!("        wire_{} [shape=point];\n", idx));
+        }
+        dot.push_str("    }\n");
+
+        let inner = self
+            .0
+            .left_names()
+            .iter()
+            .map(|(io, _, _)| *io)
+            .zip(cospan.left_to_middle())
+            .enumerate()
+            .map(|(idx, (io, wire))| (format!("inner_{}", idx), io, *wire));
+        let outer = self
+            .0
+            .right_names()
+            .iter()
+            .map(|(io, _)| io.flipped())
+            .zip(cospan.right_to_middle())
+            .enumerate()
+            .map(|(idx, (io, wire))| (format!("outer_{}", idx), io, *wire));
+        for (node, io, wire) in inner.chain(outer) {
+            let label = dot_escape(&format!("{:?}", cospan.middle()[wire]));
+            let edge = match io {
+                InOut::Out => format!("{} -> wire_{} [label=\"{}\"]", node, wire, label),
+                InOut::In => format!("wire_{} -> {} [label=\"{}\"]", wire, node, label),
+                InOut::Undirected => {
+                    format!("{} -> wire_{} [label=\"{}\", dir=none]", node, wire, label)
+                }
+            };
+            dot.push_str(&format!("    {};\n", edge));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    #[allow(dead_code)]
+    pub fn to_svg(&self, layout: SvgLayout) -> String {
+        self.to_svg_pinned(layout, &[])
+            .expect("With nothing pinned there is nothing to conflict")
+    }
+
+    #[allow(dead_code)]
+    pub fn to_svg_pinned(
+        &self,
+        layout: SvgLayout,
+        pinned: &[(usize, usize)],
+    ) -> Result<String, Error> {
+        /*
+        a standalone SVG picture with the external circle as a big circle
+        the internal circles are inside it placed according to layout
+            and labelled in the middle by their InterCircle
+        the nodes are dots around their circle starting from the left going clockwise
+            white for In, black for Out and gray for Undirected
+            labelled by their IntraCircle
+        each wire is a dot placed at the average of the nodes it touches
+            and of its place in the order from layout_middle_order
+            with those places evenly spaced on a track between the internal circles and the external one
+            so wires keep that order and one with no nodes is not lost in the middle
+            and there is a spline to each of those nodes leaving its circle at a right angle
+            and the label of the wire as its title
+        the internal circles are placed in the order of their wires as in to_dot
+        each (w,p) in pinned keeps wire w, the w'th node of the middle of the cospan,
+            at position p of that order as in layout_middle_order_pinned
+            which also moves the circles on it
+        so a picture redrawn after an edit keeps those wires and circles where they were
+        */
+        const OUTER_RADIUS: f64 = 200.0;
+        const TRACK_RADIUS: f64 = 0.7 * OUTER_RADIUS;
+        const MARGIN: f64 = 40.0;
+        const SPLINE_PULL: f64 = 30.0;
+        let cospan = self.0.cospan();
+        let wire_order = cospan.layout_middle_order_pinned(pinned)?;
+        let circles = self.circles_in_layout_order(&wire_order);
+        let centers_and_radii: Vec<((f64, f64), f64)> = match (layout, circles.len()) {
+            (_, 0) => vec![],
+            (_, 1) => vec![((0.0, 0.0), 0.4 * OUTER_RADIUS)],
+            (SvgLayout::Ring, count) => {
+                let ring = 0.5 * OUTER_RADIUS;
+                let radius = (0.4 * OUTER_RADIUS).min(0.8 * ring * (PI / count as f64).sin());
+                (0..count)
+                    .map(|idx| (point_on_circle((0.0, 0.0), ring, idx, count), radius))
+                    .collect()
+            }
+            (SvgLayout::Row, count) => {
+                let spacing = 1.6 * OUTER_RADIUS / count as f64;
+                let radius = (0.4 * spacing).min(0.4 * OUTER_RADIUS);
+                (0..count)
+                    .map(|idx| {
+                        let x = -0.8 * OUTER_RADIUS + spacing * (idx as f64 + 0.5);
+                        ((x, 0.0), radius)
+                    })
+                    .collect()
+            }
+        };
+
+        /*
+        where each node is, which way is out of its circle and its fill
+        */
+        let mut inner_places = Vec::with_capacity(self.0.left_names().len());
+        for (io, which_circle, _) in self.0.left_names() {
+            let circle_num = circles
+                .iter()
+                .position(|circle| circle == which_circle)
+                .expect("every internal node is on one of the circles");
+            let (center, radius) = centers_and_radii[circle_num];
+            let on_this_circle = self
+                .0
+                .left_names()
+                .iter()
+                .filter(|(_, circle, _)| circle == which_circle)
+                .count();
+            let idx_on_circle = inner_places
+                .iter()
+                .filter(|(num, _, _)| *num == circle_num)
+                .count();
+            let place = point_on_circle(center, radius, idx_on_circle, on_this_circle);
+            let outward = ((place.0 - center.0) / radius, (place.1 - center.1) / radius);
+            inner_places.push((circle_num, place, (outward, *io)));
+        }
+        let outer_count = self.0.right_names().len();
+        let outer_places = self
+            .0
+            .right_names()
+            .iter()
+            .enumerate()
+            .map(|(idx, (io, _))| {
+                let place = point_on_circle((0.0, 0.0), OUTER_RADIUS, idx, outer_count);
+                let inward = (-place.0 / OUTER_RADIUS, -place.1 / OUTER_RADIUS);
+                (place, (inward, *io))
+            });
+        let places: Vec<_> = inner_places
+            .iter()
+            .map(|(_, place, direction)| (*place, *direction))
+            .chain(outer_places)
+            .collect();
+        let wires: Vec<usize> = cospan
+            .left_to_middle()
+            .iter()
+            .chain(cospan.right_to_middle())
+            .copied()
+            .collect();
+        let names: Vec<String> = self
+            .0
+            .left_names()
+            .iter()
+            .map(|(_, _, intra)| format!("{:?}", intra))
+            .chain(
+                self.0
+                    .right_names()
+                    .iter()
+                    .map(|(_, intra)| format!("{:?}", intra)),
+            )
+            .collect();
+
+        let wire_count = cospan.middle().len();
+        let mut wire_places = vec![(0.0, 0.0); wire_count];
+        let mut touching = vec![1; wire_count];
+        for (position, wire) in wire_order.iter().enumerate() {
+            wire_places[*wire] = point_on_circle((0.0, 0.0), TRACK_RADIUS, position, wire_count);
+        }
+        for ((place, _), wire) in places.iter().zip(wires.iter()) {
+            wire_places[*wire].0 += place.0;
+            wire_places[*wire].1 += place.1;
+            touching[*wire] += 1;
+        }
+        for (wire_place, count) in wire_places.iter_mut().zip(touching) {
+            wire_place.0 /= count as f64;
+            wire_place.1 /= count as f64;
+        }
+
+        let size = 2.0 * (OUTER_RADIUS + MARGIN);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{:.1} {:.1} {:.1} {:.1}\" width=\"{:.1}\" height=\"{:.1}\">\n",
+            -size / 2.0,
+            -size / 2.0,
+            size,
+            size,
+            size,
+            size
+        );
+        svg.push_str(&format!(
+            "  <circle cx=\"0.0\" cy=\"0.0\" r=\"{:.1}\" fill=\"none\" stroke=\"black\"/>\n",
+            OUTER_RADIUS
+        ));
+        for (circle, ((cx, cy), radius)) in circles.iter().zip(centers_and_radii.iter()) {
+            svg.push_str(&format!(
+                "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"none\" stroke=\"black\"/>\n",
+                cx, cy, radius
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+                cx,
+                cy,
+                xml_escape(&format!("{:?}", circle))
+            ));
+        }
+        for ((place, (direction, _)), wire) in places.iter().zip(wires.iter()) {
+            let wire_place = wire_places[*wire];
+            let pulled = (
+                place.0 + SPLINE_PULL * direction.0,
+                place.1 + SPLINE_PULL * direction.1,
+            );
+            svg.push_str(&format!(
+                "  <path d=\"M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}\" fill=\"none\" stroke=\"black\"><title>{}</title></path>\n",
+                place.0,
+                place.1,
+                pulled.0,
+                pulled.1,
+                wire_place.0,
+                wire_place.1,
+                xml_escape(&format!("{:?}", cospan.middle()[*wire]))
+            ));
+        }
+        for (wire_place, label) in wire_places.iter().zip(cospan.middle()) {
+            svg.push_str(&format!(
+                "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3.0\" fill=\"black\"><title>{}</title></circle>\n",
+                wire_place.0,
+                wire_place.1,
+                xml_escape(&format!("{:?}", label))
+            ));
+        }
+        for ((place, (direction, io)), name) in places.iter().zip(names.iter()) {
+            let fill = match io {
+                InOut::In => "white",
+                InOut::Out => "black",
+                InOut::Undirected => "gray",
+            };
+            svg.push_str(&format!(
+                "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4.0\" fill=\"{}\" stroke=\"black\"/>\n",
+                place.0, place.1, fill
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" text-anchor=\"middle\">{}</text>\n",
+                place.0 - 12.0 * direction.0,
+                place.1 - 12.0 * direction.1,
+                xml_escape(name)
+            ));
+        }
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+}
+
+/*
+the tables of an undirected wiring diagram as written by Catlab
+each row may carry its 1-based _id and any attributes we do not use
+such as the names of the boxes or the variables on the junctions are ignored
+*/
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct CatlabWiringDiagram<Lambda> {
+    #[serde(rename = "Box", default)]
+    boxes: Vec<CatlabRow>,
+    #[serde(rename = "Port", default)]
+    ports: Vec<CatlabPort<Lambda>>,
+    #[serde(rename = "OuterPort", default)]
+    outer_ports: Vec<CatlabOuterPort<Lambda>>,
+    #[serde(rename = "Junction", default)]
+    junctions: Vec<CatlabJunction<Lambda>>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct CatlabRow {
+    #[serde(rename = "_id")]
+    id: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct CatlabPort<Lambda> {
+    #[serde(rename = "_id")]
+    id: Option<usize>,
+    #[serde(rename = "box")]
+    which_box: usize,
+    junction: usize,
+    port_type: Option<Lambda>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct CatlabOuterPort<Lambda> {
+    #[serde(rename = "_id")]
+    id: Option<usize>,
+    outer_junction: usize,
+    outer_port_type: Option<Lambda>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct CatlabJunction<Lambda> {
+    #[serde(rename = "_id")]
+    id: Option<usize>,
+    junction_type: Option<Lambda>,
+}
+
+#[cfg(feature = "serde")]
+fn catlab_ids_in_order<I>(ids: I, table: &str) -> Result<(), Error>
+where
+    I: Iterator<Item = Option<usize>>,
+{
+    for (idx, id) in ids.enumerate() {
+        if id.is_some_and(|id| id != idx + 1) {
+            return Err(format!("Row {} of the {} table has the wrong _id", idx + 1, table).into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+fn catlab_junction(junction: usize, junction_count: usize) -> Result<usize, Error> {
+    if junction == 0 || junction > junction_count {
+        return Err(format!("There is no junction {}", junction).into());
+    }
+    Ok(junction - 1)
+}
+
+#[cfg(feature = "serde")]
+impl<Lambda> WiringDiagram<Lambda, usize, usize>
+where
+    Lambda: Eq + Copy + Debug + Default + serde::de::DeserializeOwned,
+{
+    #[allow(dead_code)]
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        /*
+        read an undirected wiring diagram in the JSON that Catlab writes
+            with the tables Box, Port, OuterPort and Junction
+        the junctions become the middle of the cospan in order
+            labelled by their junction_type or the default if they have none
+        box b becomes internal circle b-1
+        a port becomes the Undirected node on the circle of its box
+            named by its position among the ports of that box
+        the outer ports become the Undirected nodes of the external circle
+            named by their position
+        the references in the tables are 1-based as in Catlab
+        an error if the JSON does not have this shape, a reference is out of range
+            or a port has a type that disagrees with its junction
+        */
+        let tables: CatlabWiringDiagram<Lambda> =
+            serde_json::from_str(json).map_err(|e| e.to_string())?;
+        catlab_ids_in_order(tables.boxes.iter().map(|row| row.id), "Box")?;
+        catlab_ids_in_order(tables.ports.iter().map(|row| row.id), "Port")?;
+        catlab_ids_in_order(tables.outer_ports.iter().map(|row| row.id), "OuterPort")?;
+        catlab_ids_in_order(tables.junctions.iter().map(|row| row.id), "Junction")?;
+
+        let middle: Vec<Lambda> = tables
+            .junctions
+            .iter()
+            .map(|junction| junction.junction_type.unwrap_or_default())
+            .collect();
+        let check_type = |port_type: Option<Lambda>, junction: usize| match port_type {
+            Some(port_type) if port_type != middle[junction] => Err(Error::from(format!(
+                "A port of type {:?} is on junction {} of type {:?}",
+                port_type,
+                junction + 1,
+                middle[junction]
+            ))),
+            _ => Ok(()),
+        };
+
+        let mut left = Vec::with_capacity(tables.ports.len());
+        let mut left_names = Vec::with_capacity(tables.ports.len());
+        let mut ports_so_far = vec![0; tables.boxes.len()];
+        for port in tables.ports {
+            if port.which_box == 0 || port.which_box > tables.boxes.len() {
+                return Err(format!("There is no box {}", port.which_box).into());
+            }
+            let circle = port.which_box - 1;
+            let junction = catlab_junction(port.junction, middle.len())?;
+            check_type(port.port_type, junction)?;
+            left.push(junction);
+            left_names.push((InOut::Undirected, circle, ports_so_far[circle]));
+            ports_so_far[circle] += 1;
+        }
+
+        let mut right = Vec::with_capacity(tables.outer_ports.len());
+        let mut right_names = Vec::with_capacity(tables.outer_ports.len());
+        for (idx, outer_port) in tables.outer_ports.into_iter().enumerate() {
+            let junction = catlab_junction(outer_port.outer_junction, middle.len())?;
+            check_type(outer_port.outer_port_type, junction)?;
+            right.push(junction);
+            right_names.push((InOut::Undirected, idx));
+        }
+
+        Ok(Self(NamedCospan::new(
+            left,
+            right,
+            middle,
+            left_names,
+            right_names,
+        )))
+    }
+}
+
+/*
+the layout that to_json writes
+bump this whenever that layout changes
+*/
+#[cfg(feature = "serde")]
+#[allow(dead_code)]
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct JsonJunction<Lambda> {
+    label: Lambda,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct JsonPort<'a, IntraCircle> {
+    name: &'a IntraCircle,
+    direction: InOut,
+    junction: usize,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct JsonBox<'a, InterCircle, IntraCircle> {
+    name: InterCircle,
+    ports: Vec<JsonPort<'a, IntraCircle>>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct JsonWiringDiagram<'a, Lambda, InterCircle, IntraCircle> {
+    schema_version: u32,
+    junctions: Vec<JsonJunction<Lambda>>,
+    boxes: Vec<JsonBox<'a, InterCircle, IntraCircle>>,
+    outer_ports: Vec<JsonPort<'a, IntraCircle>>,
+}
+
+#[cfg(feature = "serde")]
+impl<Lambda, InterCircle, IntraCircle> WiringDiagram<Lambda, InterCircle, IntraCircle>
+where
+    Lambda: Eq + Copy + Debug + serde::Serialize,
+    InterCircle: Eq + Clone + serde::Serialize,
+    IntraCircle: Eq + Clone + serde::Serialize,
+{
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Result<String, Error> {
+        /*
+        an object with
+            schema_version which is JSON_SCHEMA_VERSION
+            junctions, the wires in order as objects with their label
+            boxes, the internal circles in the order of circles
+                as objects with their name and their ports
+            outer_ports, the nodes of the external circle in order
+        each port is an object with its name, its direction
+            as one of "In", "Out" or "Undirected"
+            and the 0-based index of the junction it is wired to
+        for example a single box 'f' whose only port is wired straight to the outside is
+            {"schema_version":1,"junctions":[{"label":"x"}],
+             "boxes":[{"name":"f","ports":[{"name":0,"direction":"Out","junction":0}]}],
+             "outer_ports":[{"name":0,"direction":"Out","junction":0}]}
+        an error only if a label or a name fails to serialize
+        */
+        let cospan = self.0.cospan();
+        let inner = self.0.left_names().iter().zip(cospan.left_to_middle());
+        let boxes = self
+            .circles()
+            .into_iter()
+            .map(|circle| {
+                let ports = inner
+                    .clone()
+                    .filter(|((_, which_circle, _), _)| *which_circle == circle)
+                    .map(|((direction, _, name), junction)| JsonPort {
+                        name,
+                        direction: *direction,
+                        junction: *junction,
+                    })
+                    .collect();
+                JsonBox {
+                    name: circle,
+                    ports,
+                }
+            })
+            .collect();
+        let outer_ports = self
+            .0
+            .right_names()
+            .iter()
+            .zip(cospan.right_to_middle())
+            .map(|((direction, name), junction)| JsonPort {
+                name,
+                direction: *direction,
+                junction: *junction,
+            })
+            .collect();
+        let written = JsonWiringDiagram {
+            schema_version: JSON_SCHEMA_VERSION,
+            junctions: cospan
+                .middle()
+                .iter()
+                .map(|label| JsonJunction { label: *label })
+                .collect(),
+            boxes,
+            outer_ports,
+        };
+        serde_json::to_string(&written).map_err(|e| Error::from(e.to_string()))
+    }
+}
+
+mod test {
+
+    #[test]
+    fn no_input_example() {
+        use super::{InOut, WiringDiagram};
+        use crate::named_cospan::NamedCospan;
+        use either::Right;
+        let unchanged_right_names = vec![
+            (InOut::In, 0),
+            (InOut::Out, 1),
+            (InOut::In, 2),
+            (InOut::Out, 3),
+            (InOut::Out, 4),
+        ];
+        let mut example = WiringDiagram::<_, (), _>::from_named_cospan(NamedCospan::new(
+            vec![],
+            vec![0, 1, 2, 2, 0],
+            vec![true, true, false],
+            vec![],
+            unchanged_right_names.clone(),
+        ));
+        assert_eq!(*example.0.right_names(), unchanged_right_names);
+        example.change_boundary_node_name(Right(((InOut::In, 0), (InOut::Out, 0))));
+        let changed_names = example.0.right_names();
+        assert_eq!(changed_names[0], (InOut::Out, 0));
+        assert_eq!(changed_names[1..], unchanged_right_names[1..]);
+    }
+
+    #[test]
+    fn nested_substitution() {
+        use super::{InOut, WiringDiagram};
+        use crate::named_cospan::NamedCospan;
+        use either::{Either, Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+        type Name = Either<(InOut, char, usize), (InOut, usize)>;
+        let series = |first: char, second: char| {
+            /*
+            the outer input goes into first, first into second and second to the outer output
+            */
+            W::from_named_cospan(NamedCospan::new(
+                vec![0, 1, 1, 2],
+                vec![0, 2],
+                vec!['x', 'x', 'x'],
+                vec![
+                    (InOut::In, first, 0),
+                    (InOut::Out, first, 1),
+                    (InOut::In, second, 0),
+                    (InOut::Out, second, 1),
+                ],
+                vec![(InOut::In, 0), (InOut::Out, 1)],
+            ))
+        };
+        let wire = || {
+            W::from_named_cospan(NamedCospan::new(
+                vec![],
+                vec![0, 0],
+                vec!['x'],
+                vec![],
+                vec![(InOut::In, 0), (InOut::Out, 1)],
+            ))
+        };
+        let connected = |diagram: &W, first: Name, second: Name| {
+            let (left_nodes, _, right_nodes, graph) =
+                diagram.0.to_graph(|lambda| (lambda, ()), |_, _| {});
+            let node = |name: Name| match name {
+                Left(name) => {
+                    left_nodes[diagram
+                        .0
+                        .left_names()
+                        .iter()
+                        .position(|z| *z == name)
+                        .unwrap()]
+                }
+                Right(name) => {
+                    right_nodes[diagram
+                        .0
+                        .right_names()
+                        .iter()
+                        .position(|z| *z == name)
+                        .unwrap()]
+                }
+            };
+            let middle_of = |name: Name| graph.neighbors_undirected(node(name)).next().unwrap();
+            middle_of(first) == middle_of(second)
+        };
+        let all_names = |diagram: &W| {
+            diagram
+                .0
+                .left_names()
+                .iter()
+                .map(|z| Left(*z))
+                .chain(diagram.0.right_names().iter().map(|z| Right(*z)))
+                .collect::<Vec<Name>>()
+        };
+
+        /*
+        fill a with c then d and then fill c with a plain wire
+        */
+        let mut outer = series('a', 'b');
+        outer.operadic_substitution('a', series('c', 'd')).unwrap();
+        assert_eq!(
+            *outer.0.left_names(),
+            vec![
+                (InOut::In, 'c', 0),
+                (InOut::Out, 'c', 1),
+                (InOut::In, 'd', 0),
+                (InOut::Out, 'd', 1),
+                (InOut::In, 'b', 0),
+                (InOut::Out, 'b', 1),
+            ]
+        );
+        assert!(connected(
+            &outer,
+            Right((InOut::In, 0)),
+            Left((InOut::In, 'c', 0))
+        ));
+        assert!(connected(
+            &outer,
+            Left((InOut::Out, 'd', 1)),
+            Left((InOut::In, 'b', 0))
+        ));
+        outer.operadic_substitution('c', wire()).unwrap();
+        assert_eq!(
+            *outer.0.left_names(),
+            vec![
+                (InOut::In, 'd', 0),
+                (InOut::Out, 'd', 1),
+                (InOut::In, 'b', 0),
+                (InOut::Out, 'b', 1),
+            ]
+        );
+        assert!(connected(
+            &outer,
+            Right((InOut::In, 0)),
+            Left((InOut::In, 'd', 0))
+        ));
+        assert!(!connected(
+            &outer,
+            Right((InOut::In, 0)),
+            Right((InOut::Out, 1))
+        ));
+
+        /*
+        substituting the other way around gives the same diagram
+        */
+        let mut inner = series('c', 'd');
+        inner.operadic_substitution('c', wire()).unwrap();
+        let mut other_order = series('a', 'b');
+        other_order.operadic_substitution('a', inner).unwrap();
+        assert_eq!(*other_order.0.left_names(), *outer.0.left_names());
+        assert_eq!(*other_order.0.right_names(), *outer.0.right_names());
+        assert!(other_order.is_isomorphic_to(&outer));
+        for first in all_names(&outer) {
+            for second in all_names(&outer) {
+                assert_eq!(
+                    connected(&outer, first, second),
+                    connected(&other_order, first, second)
+                );
+            }
+        }
+
+        /*
+        filling every circle with a wire leaves a wire
+        */
+        outer.operadic_substitution('d', wire()).unwrap();
+        outer.operadic_substitution('b', wire()).unwrap();
+        assert!(outer.0.left_names().is_empty());
+        assert!(connected(
+            &outer,
+            Right((InOut::In, 0)),
+            Right((InOut::Out, 1))
+        ));
+    }
+
+    #[test]
+    fn substitution_failures() {
+        use super::{InOut, WiringDiagram};
+        use crate::named_cospan::NamedCospan;
+        type W = WiringDiagram<char, char, usize>;
+        let one_circle = |circle: char, outer_names: Vec<(InOut, usize)>| {
+            W::from_named_cospan(NamedCospan::new(
+                vec![0, 1],
+                vec![0, 1],
+                vec!['x', 'x'],
+                vec![(InOut::In, circle, 0), (InOut::Out, circle, 1)],
+                outer_names,
+            ))
+        };
+        let two_circles = || {
+            let mut answer = one_circle('a', vec![(InOut::In, 0), (InOut::Out, 1)]);
+            answer.add_boundary_node_unconnected('x', either::Left((InOut::In, 'b', 0)));
+            answer
+        };
+        let before = two_circles().0.to_compact_string();
+
+        /*
+        the orientations have to agree
+        */
+        let mut outer = two_circles();
+        assert!(outer
+            .operadic_substitution('a', one_circle('c', vec![(InOut::Out, 0), (InOut::Out, 1)]))
+            .is_err());
+        assert_eq!(outer.0.to_compact_string(), before);
+
+        /*
+        not enough nodes on the outside of what is put in
+        */
+        assert!(outer
+            .operadic_substitution(
+                'a',
+                W::from_named_cospan(NamedCospan::new(
+                    vec![],
+                    vec![0],
+                    vec!['x'],
+                    vec![],
+                    vec![(InOut::In, 0)]
+                ))
+            )
+            .is_err());
+        assert_eq!(outer.0.to_compact_string(), before);
+
+        /*
+        b is still there so can not come in with the substituted diagram
+        */
+        assert!(outer
+            .operadic_substitution('a', one_circle('b', vec![(InOut::In, 0), (InOut::Out, 1)]))
+            .is_err());
+        assert_eq!(outer.0.to_compact_string(), before);
+        assert!(outer
+            .operadic_substitution('a', one_circle('c', vec![(InOut::In, 0), (InOut::Out, 1)]))
+            .is_ok());
+    }
+
+    #[test]
+    fn building_and_orientations() {
+        use super::{InOut, WiringDiagram};
+        use either::{Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+        let through = || vec![(InOut::In, 0, 'x'), (InOut::Out, 1, 'x')];
+        let mut diagram = W::new(through(), vec![('f', through()), ('g', through())]).unwrap();
+        assert_eq!(diagram.circles(), vec!['f', 'g']);
+        assert_eq!(
+            diagram.circle_ports(&'g'),
+            vec![(InOut::In, 0), (InOut::Out, 1)]
+        );
+        assert_eq!(diagram.outer_ports(), [(InOut::In, 0), (InOut::Out, 1)]);
+        assert_eq!(diagram.inner_ports().len(), 4);
+        assert!(diagram.check_orientations(false).is_ok());
+        assert!(diagram.check_orientations(true).is_err());
+
+        /*
+        the outer input through f then g and out
+        */
+        diagram.connect_pair(Right((InOut::In, 0)), Left((InOut::In, 'f', 0)));
+        diagram.connect_pair(Left((InOut::Out, 'f', 1)), Left((InOut::In, 'g', 0)));
+        diagram.connect_pair(Left((InOut::Out, 'g', 1)), Right((InOut::Out, 1)));
+        assert_eq!(diagram.check_orientations(true), Ok(()));
+        assert_eq!(diagram.named_cospan().cospan().middle().len(), 3);
+
+        /*
+        filling f with a wire built the same way keeps it consistent
+        */
+        let mut wire = W::new(through(), vec![]).unwrap();
+        wire.connect_pair(Right((InOut::In, 0)), Right((InOut::Out, 1)));
+        diagram.operadic_substitution('f', wire).unwrap();
+        assert_eq!(diagram.circles(), vec!['g']);
+        assert_eq!(diagram.check_orientations(true), Ok(()));
+
+        /*
+        two outputs on the same wire
+        */
+        let mut clash = W::new(through(), vec![('f', through()), ('g', through())]).unwrap();
+        clash.connect_pair(Left((InOut::Out, 'f', 1)), Left((InOut::Out, 'g', 1)));
+        assert!(clash.check_orientations(false).is_err());
+        let mut undirected = W::new(
+            vec![(InOut::Undirected, 0, 'x')],
+            vec![('f', vec![(InOut::Undirected, 0, 'x')])],
+        )
+        .unwrap();
+        undirected.connect_pair(
+            Right((InOut::Undirected, 0)),
+            Left((InOut::Undirected, 'f', 0)),
+        );
+        assert_eq!(undirected.check_orientations(true), Ok(()));
+
+        assert!(W::new(through(), vec![('f', through()), ('f', vec![])]).is_err());
+        assert!(W::new(through(), vec![('f', vec![(InOut::In, 0, 'x'); 2])]).is_err());
+        assert!(W::new(vec![(InOut::In, 0, 'x'), (InOut::Out, 0, 'x')], vec![]).is_err());
+    }
+
+    #[test]
+    fn direction_checking() {
+        use super::{DirectionProblem, InOut, WiringDiagram};
+        use crate::error::Error;
+        use either::{Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+        let through = || vec![(InOut::In, 0, 'x'), (InOut::Out, 1, 'x')];
+        let mut diagram = W::new(through(), vec![('f', through())]).unwrap();
+        /*
+        before wiring the outer input drives nothing, the outer output is not driven
+            and the same the other way around for f
+        */
+        assert_eq!(
+            diagram.direction_problems(),
+            vec![
+                (0, DirectionProblem::Undriven),
+                (1, DirectionProblem::Unread),
+                (2, DirectionProblem::Unread),
+                (3, DirectionProblem::Undriven),
+            ]
+        );
+        assert_eq!(
+            diagram.check_directions(),
+            Err(Error::from(
+                "Badly directed wires: wire 0 is not driven, wire 1 drives nothing, \
+                wire 2 drives nothing, wire 3 is not driven"
+            ))
+        );
+
+        diagram
+            .connect_pair_directed(Right((InOut::In, 0)), Left((InOut::In, 'f', 0)))
+            .unwrap();
+        assert!(diagram
+            .connect_pair_directed(Right((InOut::In, 0)), Left((InOut::Out, 'f', 1)))
+            .is_err());
+        assert!(diagram
+            .connect_pair_directed(Right((InOut::In, 7)), Left((InOut::Out, 'f', 1)))
+            .is_err());
+        diagram
+            .connect_pair_directed(Left((InOut::Out, 'f', 1)), Right((InOut::Out, 1)))
+            .unwrap();
+        assert_eq!(diagram.check_directions(), Ok(()));
+
+        /*
+        an output of f fanning out to two outer outputs is fine
+            but an unconnected outer input is not
+        */
+        let mut fan_out = W::new(
+            vec![
+                (InOut::In, 0, 'x'),
+                (InOut::Out, 1, 'x'),
+                (InOut::Out, 2, 'x'),
+            ],
+            vec![('f', through())],
+        )
+        .unwrap();
+        fan_out
+            .connect_pair_directed(Left((InOut::Out, 'f', 1)), Right((InOut::Out, 1)))
+            .unwrap();
+        fan_out
+            .connect_pair_directed(Left((InOut::Out, 'f', 1)), Right((InOut::Out, 2)))
+            .unwrap();
+        assert_eq!(
+            fan_out.direction_problems(),
+            vec![
+                (0, DirectionProblem::Undriven),
+                (2, DirectionProblem::Unread)
+            ]
+        );
+
+        let mut undirected = W::new(
+            vec![(InOut::Undirected, 0, 'x'), (InOut::In, 1, 'y')],
+            vec![('f', vec![(InOut::In, 0, 'y')])],
+        )
+        .unwrap();
+        assert!(undirected
+            .connect_pair_directed(Right((InOut::Undirected, 0)), Left((InOut::In, 'f', 0)))
+            .is_err());
+        undirected
+            .connect_pair_directed(Right((InOut::In, 1)), Left((InOut::In, 'f', 0)))
+            .unwrap();
+        assert_eq!(
+            undirected.direction_problems(),
+            vec![
+                (1, DirectionProblem::Undirected(1)),
+                (1, DirectionProblem::Undriven),
+                (1, DirectionProblem::Unread),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn catlab_import() {
+        use super::{InOut, WiringDiagram};
+        /*
+        two boxes in series with a third junction that only the second box sees
+        */
+        let read = WiringDiagram::<u8, usize, usize>::from_json(include_str!(
+            "../fixtures/catlab/series.json"
+        ))
+        .unwrap();
+        assert_eq!(read.circles(), vec![0, 1]);
+        assert_eq!(
+            read.circle_ports(&1),
+            vec![
+                (InOut::Undirected, 0),
+                (InOut::Undirected, 1),
+                (InOut::Undirected, 2)
+            ]
+        );
+        assert_eq!(
+            read.outer_ports(),
+            &[(InOut::Undirected, 0), (InOut::Undirected, 1)]
+        );
+        let cospan = read.named_cospan().cospan();
+        assert_eq!(cospan.middle(), &[1, 2, 1]);
+        assert_eq!(cospan.left_to_middle(), &[0, 1, 1, 0, 2]);
+        assert_eq!(cospan.right_to_middle(), &[0, 0]);
+
+        /*
+        untyped diagrams without _id get the default label everywhere
+        */
+        let untyped = WiringDiagram::<(), usize, usize>::from_json(
+            r#"{"Box":[{}],"Port":[{"box":1,"junction":1}],"OuterPort":[{"outer_junction":1}],"Junction":[{}]}"#,
+        )
+        .unwrap();
+        assert_eq!(untyped.inner_ports(), &[(InOut::Undirected, 0, 0)]);
+        assert_eq!(untyped.named_cospan().cospan().middle(), &[()]);
+
+        let bad_reference = WiringDiagram::<u8, usize, usize>::from_json(
+            r#"{"Box":[{"_id":1}],"Port":[{"_id":1,"box":1,"junction":2}],"OuterPort":[],"Junction":[{"_id":1}]}"#,
+        );
+        assert!(bad_reference.is_err());
+        let bad_type = WiringDiagram::<u8, usize, usize>::from_json(
+            r#"{"Box":[],"Port":[],"OuterPort":[{"_id":1,"outer_junction":1,"outer_port_type":3}],"Junction":[{"_id":1,"junction_type":4}]}"#,
+        );
+        assert!(bad_type.is_err());
+        let bad_id = WiringDiagram::<u8, usize, usize>::from_json(
+            r#"{"Box":[{"_id":2}],"Port":[],"OuterPort":[],"Junction":[]}"#,
+        );
+        assert!(bad_id.is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_export() {
+        use super::{InOut, WiringDiagram};
+        use crate::named_cospan::NamedCospan;
+        let diagram = WiringDiagram::<char, char, usize>::from_named_cospan(NamedCospan::new(
+            vec![0, 1, 1],
+            vec![0, 2],
+            vec!['x', 'y', 'z'],
+            vec![
+                (InOut::In, 'f', 0),
+                (InOut::Out, 'f', 1),
+                (InOut::In, 'g', 0),
+            ],
+            vec![(InOut::In, 0), (InOut::Undirected, 1)],
+        ));
+        let written: serde_json::Value = serde_json::from_str(&diagram.to_json().unwrap()).unwrap();
+        assert_eq!(
+            written,
+            serde_json::json!({
+                "schema_version": 1,
+                "junctions": [{"label": "x"}, {"label": "y"}, {"label": "z"}],
+                "boxes": [
+                    {"name": "f", "ports": [
+                        {"name": 0, "direction": "In", "junction": 0},
+                        {"name": 1, "direction": "Out", "junction": 1}
+                    ]},
+                    {"name": "g", "ports": [
+                        {"name": 0, "direction": "In", "junction": 1}
+                    ]}
+                ],
+                "outer_ports": [
+                    {"name": 0, "direction": "In", "junction": 0},
+                    {"name": 1, "direction": "Undirected", "junction": 2}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn dot_export() {
+        use super::{InOut, WiringDiagram};
+        use crate::named_cospan::NamedCospan;
+        let diagram = WiringDiagram::<char, char, usize>::from_named_cospan(NamedCospan::new(
+            vec![0, 1, 1],
+            vec![0, 2],
+            vec!['x', 'y', 'z'],
+            vec![
+                (InOut::In, 'f', 0),
+                (InOut::Out, 'f', 1),
+                (InOut::In, '"', 0),
+            ],
+            vec![(InOut::In, 0), (InOut::Undirected, 1)],
+        ));
+        let dot = diagram.to_dot();
+        assert!(dot.starts_with("digraph {\n    subgraph cluster_outer {"));
+        assert!(dot.contains("subgraph cluster_0 {\n            label=\"'f'\";"));
+        assert!(dot.contains("subgraph cluster_1 {\n            label=\"'\\\"'\";"));
+        assert!(!dot.contains("cluster_2"));
+        assert_eq!(dot.matches("[shape=point]").count(), 3);
+        assert_eq!(dot.matches(" -> ").count(), 5);
+        assert!(dot.contains("wire_0 -> inner_0 [label=\"'x'\"];"));
+        assert!(dot.contains("inner_1 -> wire_1 [label=\"'y'\"];"));
+        assert!(dot.contains("wire_1 -> inner_2 [label=\"'y'\"];"));
+        assert!(dot.contains("outer_0 -> wire_0 [label=\"'x'\"];"));
+        assert!(dot.contains("outer_1 -> wire_2 [label=\"'z'\", dir=none];"));
+
+        /*
+        the wires and circles are written in the order from layout_middle_order
+            rather than the order they were made in
+        */
+        let reordered = WiringDiagram::<char, char, usize>::from_named_cospan(NamedCospan::new(
+            vec![1, 0, 2, 2],
+            vec![],
+            vec!['x', 'y', 'z'],
+            vec![
+                (InOut::In, 'f', 0),
+                (InOut::In, 'g', 0),
+                (InOut::In, 'f', 1),
+                (InOut::In, 'f', 2),
+            ],
+            vec![],
+        ));
+        let dot = reordered.to_dot();
+        assert!(dot.contains("subgraph cluster_0 {\n            label=\"'g'\";"));
+        assert!(dot.contains("subgraph cluster_1 {\n            label=\"'f'\";"));
+        let wire_at = |idx: usize| dot.find(&format!("wire_{} [shape=point]", idx)).unwrap();
+        assert!(wire_at(1) < wire_at(0) && wire_at(0) < wire_at(2));
+
+        let empty = WiringDiagram::<char, char, usize>::new(vec![], vec![])
+            .unwrap()
+            .to_dot();
+        assert_eq!(
+            empty,
+            "digraph {\n    subgraph cluster_outer {\n        label=\"outer\";\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn svg_export() {
+        use super::{InOut, SvgLayout, WiringDiagram};
+        use crate::named_cospan::NamedCospan;
+        /*
+        one circle whose only node is wired straight to the only outside node
+        so everything lies on the horizontal line through the middle
+        */
+        let single = WiringDiagram::<char, char, usize>::from_named_cospan(NamedCospan::new(
+            vec![0],
+            vec![0],
+            vec!['x'],
+            vec![(InOut::In, 'f', 0)],
+            vec![(InOut::In, 0)],
+        ));
+        let svg = single.to_svg(SvgLayout::Ring);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("<circle cx=\"0.0\" cy=\"0.0\" r=\"80.0\""));
+        assert!(svg.contains("<path d=\"M -80.0 0.0 Q -110.0 0.0 -140.0 0.0\""));
+        assert!(svg.contains("<path d=\"M -200.0 0.0 Q -170.0 0.0 -140.0 0.0\""));
+        assert!(svg.contains(
+            "<circle cx=\"-140.0\" cy=\"0.0\" r=\"3.0\" fill=\"black\"><title>'x'</title>"
+        ));
+        assert!(svg.contains(">'f'</text>"));
+
+        /*
+        three circles where the last two share a wire and a node name needs escaping
+        */
+        let three = WiringDiagram::<char, char, &str>::from_named_cospan(NamedCospan::new(
+            vec![0, 1, 1, 2],
+            vec![0, 2],
+            vec!['x', 'y', 'z'],
+            vec![
+                (InOut::In, 'f', "a"),
+                (InOut::Out, 'g', "<b>"),
+                (InOut::In, 'h', "a"),
+                (InOut::Out, 'h', "c"),
+            ],
+            vec![(InOut::In, "i"), (InOut::Out, "o")],
+        ));
+        for layout in [SvgLayout::Ring, SvgLayout::Row] {
+            let svg = three.to_svg(layout);
+            assert_eq!(svg.matches("<path ").count(), 6);
+            assert_eq!(svg.matches("r=\"3.0\"").count(), 3);
+            assert_eq!(svg.matches("r=\"4.0\"").count(), 6);
+            assert_eq!(svg.matches("fill=\"none\" stroke=\"black\"/>").count(), 4);
+            assert!(svg.contains("&quot;&lt;b&gt;&quot;"));
+            assert!(!svg.contains("<b>"));
+        }
+        let row = three.to_svg(SvgLayout::Row);
+        assert!(row.contains("<text x=\"0.0\" y=\"0.0\" text-anchor=\"middle\">'g'</text>"));
+
+        /*
+        pinning the wire on h and the outside to the front of the order
+            moves h next to f and g to the end of the row
+        */
+        assert_eq!(three.to_svg_pinned(SvgLayout::Row, &[]), Ok(row.clone()));
+        let pinned = three.to_svg_pinned(SvgLayout::Row, &[(2, 0)]).unwrap();
+        assert!(pinned.contains("<text x=\"0.0\" y=\"0.0\" text-anchor=\"middle\">'h'</text>"));
+        assert!(pinned.contains("<text x=\"106.7\" y=\"0.0\" text-anchor=\"middle\">'g'</text>"));
+        assert!(three.to_svg_pinned(SvgLayout::Row, &[(2, 3)]).is_err());
+        assert!(three
+            .to_svg_pinned(SvgLayout::Ring, &[(0, 1), (1, 1)])
+            .is_err());
+
+        /*
+        a wire with no nodes sits on the track for the wires instead of in the middle
+        */
+        let loose = WiringDiagram::<char, char, usize>::from_named_cospan(NamedCospan::new(
+            vec![],
+            vec![],
+            vec!['x'],
+            vec![],
+            vec![],
+        ));
+        assert!(loose
+            .to_svg(SvgLayout::Ring)
+            .contains("<circle cx=\"-140.0\" cy=\"0.0\" r=\"3.0\" fill=\"black\">"));
+    }
+
+    #[test]
+    fn nested_diagrams() {
+        use super::{InOut, NestedWiringDiagram, WiringDiagram};
+        use either::{Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+        type N = NestedWiringDiagram<char, char, usize>;
+        let through = || vec![(InOut::In, 0, 'x'), (InOut::Out, 1, 'x')];
+        let series = |first: char, second: char| {
+            let mut answer =
+                W::new(through(), vec![(first, through()), (second, through())]).unwrap();
+            answer.connect_pair(Right((InOut::In, 0)), Left((InOut::In, first, 0)));
+            answer.connect_pair(Left((InOut::Out, first, 1)), Left((InOut::In, second, 0)));
+            answer.connect_pair(Left((InOut::Out, second, 1)), Right((InOut::Out, 1)));
+            answer
+        };
+        let mut wire = W::new(through(), vec![]).unwrap();
+        wire.connect_pair(Right((InOut::In, 0)), Right((InOut::Out, 1)));
+
+        /*
+        a in series with b where b is c in series with d and d is just a wire
+        */
+        let mut inner = N::new(series('c', 'd'));
+        inner.fill('d', N::new(wire.clone())).unwrap();
+        assert!(inner.fill('d', N::new(wire.clone())).is_err());
+        assert!(inner.fill('z', N::new(wire.clone())).is_err());
+        let mut top = N::new(series('a', 'b'));
+        assert!(top
+            .fill(
+                'a',
+                N::new(W::new(vec![(InOut::In, 0, 'x')], vec![]).unwrap())
+            )
+            .is_err());
+        top.fill('b', inner).unwrap();
+        assert_eq!(
+            top.filling(&'b').unwrap().diagram().circles(),
+            vec!['c', 'd']
+        );
+        assert!(top.filling(&'a').is_none());
+        assert_eq!(top.opaque_circles(), vec!['c', 'a']);
+
+        /*
+        the same as substituting by hand
+        */
+        let flat = top.flatten().unwrap();
+        assert_eq!(flat.circles(), vec!['c', 'a']);
+        let mut by_hand = series('a', 'b');
+        let mut c_then_wire = series('c', 'd');
+        c_then_wire
+            .operadic_substitution('d', wire.clone())
+            .unwrap();
+        by_hand.operadic_substitution('b', c_then_wire).unwrap();
+        assert!(flat.is_isomorphic_to(&by_hand));
+        assert_eq!(flat.check_directions(), Ok(()));
+
+        /*
+        a circle name used again at a lower level clashes once flattened
+        */
+        let mut clashing = N::new(series('a', 'b'));
+        clashing.fill('b', N::new(series('a', 'e'))).unwrap();
+        assert!(clashing.flatten().is_err());
+    }
+
+    #[test]
+    fn merging_junctions() {
+        use super::{InOut, WiringDiagram};
+        use either::{Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+        let through = || vec![(InOut::In, 0, 'x'), (InOut::Out, 1, 'x')];
+        let mut diagram = W::new(through(), vec![('f', through()), ('g', through())]).unwrap();
+        /*
+        the outer input fans out to both f and g whose outputs both go out
+        */
+        let quotient = diagram
+            .merge_junctions(&[
+                (Right((InOut::In, 0)), Left((InOut::In, 'f', 0))),
+                (Left((InOut::In, 'g', 0)), Left((InOut::In, 'f', 0))),
+                (Left((InOut::Out, 'f', 1)), Right((InOut::Out, 1))),
+            ])
+            .unwrap();
+        assert_eq!(quotient, vec![0, 1, 0, 2, 0, 1]);
+        assert_eq!(diagram.named_cospan().cospan().middle().len(), 3);
+        assert!(diagram.check_orientations(true).is_ok());
+        assert!(diagram
+            .merge_junctions(&[(Left((InOut::Out, 'g', 1)), Right((InOut::Out, 1)))])
+            .is_ok());
+        assert!(diagram.check_orientations(false).is_err());
+        assert!(diagram
+            .merge_junctions(&[(Left((InOut::Out, 'h', 1)), Right((InOut::Out, 1)))])
+            .is_err());
+    }
+
+    #[test]
+    fn simplifying() {
+        use super::{InOut, WiringDiagram};
+        use either::{Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+        let through = || vec![(InOut::In, 0, 'x'), (InOut::Out, 1, 'x')];
+        /*
+        the output of f fed back into its input with f filled by a plain wire
+            leaves a closed loop with no nodes on it
+        */
+        let mut feedback = W::new(
+            vec![(InOut::In, 0, 'y')],
+            vec![('f', through()), ('g', vec![(InOut::In, 0, 'y')])],
+        )
+        .unwrap();
+        feedback.connect_pair(Left((InOut::Out, 'f', 1)), Left((InOut::In, 'f', 0)));
+        feedback.connect_pair(Right((InOut::In, 0)), Left((InOut::In, 'g', 0)));
+        let mut wire = W::new(through(), vec![]).unwrap();
+        wire.connect_pair(Right((InOut::In, 0)), Right((InOut::Out, 1)));
+        feedback.operadic_substitution('f', wire).unwrap();
+        assert_eq!(feedback.named_cospan().cospan().middle(), &['x', 'y']);
+        let unchanged = feedback.clone();
+        assert_eq!(feedback.simplify(), 1);
+        assert_eq!(feedback.named_cospan().cospan().middle(), &['y']);
+        assert_eq!(feedback.circles(), vec!['g']);
+        assert!(feedback.check_directions().is_ok());
+        assert!(!feedback.is_isomorphic_to(&unchanged));
+        assert_eq!(feedback.simplify(), 0);
+
+        /*
+        f and g each have one wire in and one out of the same label
+            so they are junctions of degree 2 and the outer input goes straight to h
+        h has two nodes of different directions on wires of the same label
+            and k has two nodes on wires of different labels so they both stay
+        */
+        let mut chain = W::new(
+            vec![(InOut::In, 0, 'x'), (InOut::Out, 1, 'y')],
+            vec![
+                ('f', through()),
+                ('g', through()),
+                ('h', vec![(InOut::In, 0, 'x'), (InOut::In, 1, 'x')]),
+                ('k', vec![(InOut::In, 0, 'x'), (InOut::Out, 1, 'y')]),
+            ],
+        )
+        .unwrap();
+        chain.connect_pair(Right((InOut::In, 0)), Left((InOut::In, 'f', 0)));
+        chain.connect_pair(Left((InOut::Out, 'f', 1)), Left((InOut::In, 'g', 0)));
+        chain.connect_pair(Left((InOut::Out, 'g', 1)), Left((InOut::In, 'h', 0)));
+        chain.connect_pair(Left((InOut::In, 'h', 1)), Left((InOut::In, 'k', 0)));
+        chain.connect_pair(Left((InOut::Out, 'k', 1)), Right((InOut::Out, 1)));
+        assert_eq!(chain.named_cospan().cospan().middle().len(), 5);
+        assert_eq!(chain.simplify(), 2);
+        let mut circles = chain.circles();
+        circles.sort();
+        assert_eq!(circles, vec!['h', 'k']);
+        assert_eq!(chain.named_cospan().cospan().middle().len(), 3);
+        let h_in = chain
+            .inner_ports()
+            .iter()
+            .position(|name| *name == (InOut::In, 'h', 0))
+            .unwrap();
+        let cospan = chain.named_cospan().cospan();
+        assert_eq!(cospan.right_to_middle()[0], cospan.left_to_middle()[h_in]);
+        assert_eq!(chain.simplify(), 0);
+    }
+
+    #[test]
+    fn renaming_and_relabelling() {
+        use super::{InOut, WiringDiagram};
+        use either::{Left, Right};
+        let through = || vec![(InOut::In, 0, 'x'), (InOut::Out, 1, 'x')];
+        let mut diagram =
+            WiringDiagram::<char, char, usize>::new(through(), vec![('f', through())]).unwrap();
+        diagram.connect_pair(Right((InOut::In, 0)), Left((InOut::In, 'f', 0)));
+        let renamed = diagram
+            .rename_ports(|circle| circle.to_string(), |node| ["in", "out"][*node])
+            .unwrap();
+        assert_eq!(renamed.circles(), vec!["f".to_string()]);
+        assert_eq!(
+            renamed.outer_ports(),
+            &[(InOut::In, "in"), (InOut::Out, "out")]
+        );
+        assert_eq!(
+            renamed.named_cospan().cospan().left_to_middle(),
+            diagram.named_cospan().cospan().left_to_middle()
+        );
+        assert!(diagram.rename_ports(|circle| *circle, |_| 0).is_err());
+        let relabelled = renamed.map(|label| label as u32);
+        assert_eq!(
+            relabelled.named_cospan().cospan().middle(),
+            &[120, 120, 120]
+        );
+    }
+
+    #[test]
+    fn feedback_loops() {
+        use super::{InOut, WiringDiagram};
+        use either::{Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+        let through = |label| vec![(InOut::In, 0, label), (InOut::Out, 1, label)];
+        /*
+        f with its own in and out on the outside and another wire of g passing by
+        */
+        let mut diagram = W::new(
+            vec![
+                (InOut::In, 0, 'x'),
+                (InOut::In, 2, 'y'),
+                (InOut::Out, 1, 'x'),
+            ],
+            vec![('f', through('x')), ('g', vec![(InOut::In, 0, 'y')])],
+        )
+        .unwrap();
+        diagram.connect_pair(Right((InOut::In, 0)), Left((InOut::In, 'f', 0)));
+        diagram.connect_pair(Left((InOut::Out, 'f', 1)), Right((InOut::Out, 1)));
+        diagram.connect_pair(Right((InOut::In, 2)), Left((InOut::In, 'g', 0)));
+        assert_eq!(diagram.check_directions(), Ok(()));
+
+        assert!(diagram.connect((InOut::In, 0), (InOut::In, 2)).is_err());
+        assert!(diagram.connect((InOut::In, 2), (InOut::Out, 1)).is_err());
+        assert!(diagram.connect((InOut::In, 0), (InOut::Out, 7)).is_err());
+        assert!(diagram.connect((InOut::In, 0), (InOut::In, 0)).is_err());
+        assert_eq!(diagram.outer_ports().len(), 3);
+
+        diagram.connect((InOut::Out, 1), (InOut::In, 0)).unwrap();
+        assert_eq!(diagram.outer_ports(), &[(InOut::In, 2)]);
+        assert_eq!(diagram.check_directions(), Ok(()));
+        let cospan = diagram.named_cospan().cospan();
+        assert_eq!(cospan.left_to_middle()[0], cospan.left_to_middle()[1]);
+        assert_eq!(cospan.middle()[cospan.left_to_middle()[0]], 'x');
+        assert_eq!(cospan.right_to_middle(), &[cospan.left_to_middle()[2]]);
+
+        /*
+        a plain wire traced becomes a closed loop
+        */
+        let mut wire = W::new(through('x'), vec![]).unwrap();
+        wire.connect_pair(Right((InOut::In, 0)), Right((InOut::Out, 1)));
+        wire.connect((InOut::In, 0), (InOut::Out, 1)).unwrap();
+        assert!(wire.outer_ports().is_empty());
+        assert_eq!(wire.simplify(), 1);
+    }
+
+    #[test]
+    fn sweeping_into_layers() {
+        use super::{InOut, WiringDiagram};
+        use crate::category::ComposableMutating;
+        use crate::frobenius::FrobeniusOperation;
+        use crate::monoidal::GenericMonoidalMorphism;
+        use either::{Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+        type M = GenericMonoidalMorphism<FrobeniusOperation<char, char>, char>;
+        /*
+        push symbolic values through the layers
+        a box f turns its inputs a,b into f0(a,b), f1(a,b) and so on
+        */
+        let run = |morphism: &M, inputs: Vec<String>| {
+            let mut values = inputs;
+            for layer in morphism.layers() {
+                let mut next = Vec::new();
+                let mut rest = values.into_iter();
+                for block in &layer.blocks {
+                    match block {
+                        FrobeniusOperation::Identity(_) => next.push(rest.next().unwrap()),
+                        FrobeniusOperation::Counit(_) => {
+                            rest.next().unwrap();
+                        }
+                        FrobeniusOperation::Comultiplication(_) => {
+                            let value = rest.next().unwrap();
+                            next.extend([value.clone(), value]);
+                        }
+                        FrobeniusOperation::SymmetricBraiding(_, _) => {
+                            let first = rest.next().unwrap();
+                            next.push(rest.next().unwrap());
+                            next.push(first);
+                        }
+                        FrobeniusOperation::UnSpecifiedBox(name, ins, outs) => {
+                            let args: Vec<String> =
+                                (0..ins.len()).map(|_| rest.next().unwrap()).collect();
+                            next.extend(
+                                (0..outs.len())
+                                    .map(|k| format!("{}{}({})", name, k, args.join(","))),
+                            );
+                        }
+                        _ => panic!("the sweep should not make {:?}", block),
+                    }
+                }
+                assert!(rest.next().is_none());
+                values = next;
+            }
+            values
+        };
+
+        /*
+        outer inputs x and y, f takes y then x, g takes the output of f twice
+        the outputs are g's output, f's output and y again
+        */
+        let mut diagram = W::new(
+            vec![
+                (InOut::In, 0, 'a'),
+                (InOut::In, 1, 'a'),
+                (InOut::Out, 2, 'a'),
+                (InOut::Out, 3, 'b'),
+                (InOut::Out, 4, 'a'),
+            ],
+            vec![
+                (
+                    'g',
+                    vec![
+                        (InOut::In, 0, 'b'),
+                        (InOut::In, 1, 'b'),
+                        (InOut::Out, 2, 'a'),
+                    ],
+                ),
+                (
+                    'f',
+                    vec![
+                        (InOut::In, 0, 'a'),
+                        (InOut::In, 1, 'a'),
+                        (InOut::Out, 2, 'b'),
+                    ],
+                ),
+            ],
+        )
+        .unwrap();
+        diagram.connect_pair(Right((InOut::In, 1)), Left((InOut::In, 'f', 0)));
+        diagram.connect_pair(Right((InOut::In, 0)), Left((InOut::In, 'f', 1)));
+        diagram.connect_pair(Left((InOut::Out, 'f', 2)), Left((InOut::In, 'g', 0)));
+        diagram.connect_pair(Left((InOut::Out, 'f', 2)), Left((InOut::In, 'g', 1)));
+        diagram.connect_pair(Left((InOut::Out, 'f', 2)), Right((InOut::Out, 3)));
+        diagram.connect_pair(Left((InOut::Out, 'g', 2)), Right((InOut::Out, 2)));
+        diagram.connect_pair(Right((InOut::In, 1)), Right((InOut::Out, 4)));
+        assert_eq!(diagram.check_directions(), Ok(()));
+
+        let swept = diagram.to_monoidal_morphism().unwrap();
+        assert_eq!(swept.domain(), vec!['a', 'a']);
+        assert_eq!(swept.codomain(), vec!['a', 'b', 'a']);
+        let f = "f0(y,x)";
+        assert_eq!(
+            run(&swept, vec!["x".to_string(), "y".to_string()]),
+            vec![format!("g0({},{})", f, f), f.to_string(), "y".to_string()]
+        );
+        let boxes: Vec<_> = swept
+            .iter_boxes()
+            .filter_map(|(_, block)| match block {
+                FrobeniusOperation::UnSpecifiedBox(name, _, _) => Some(*name),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(boxes, vec!['f', 'g']);
+
+        /*
+        an input nobody reads is ended and a bare wire is an identity
+        */
+        let mut unread = W::new(
+            vec![
+                (InOut::In, 0, 'a'),
+                (InOut::In, 1, 'a'),
+                (InOut::Out, 2, 'a'),
+            ],
+            vec![],
+        )
+        .unwrap();
+        unread.connect_pair(Right((InOut::In, 1)), Right((InOut::Out, 2)));
+        let swept = unread.to_monoidal_morphism().unwrap();
+        assert_eq!(
+            run(&swept, vec!["x".to_string(), "y".to_string()]),
+            vec!["y".to_string()]
+        );
+        let mut wire = W::new(vec![(InOut::In, 0, 'a'), (InOut::Out, 1, 'a')], vec![]).unwrap();
+        wire.connect_pair(Right((InOut::In, 0)), Right((InOut::Out, 1)));
+        let swept = wire.to_monoidal_morphism().unwrap();
+        assert_eq!(swept.depth(), 1);
+        assert_eq!(swept.domain(), vec!['a']);
+
+        /*
+        a loop from f back into itself and an undriven wire can not be swept
+        */
+        let mut looped = W::new(
+            vec![],
+            vec![('f', vec![(InOut::In, 0, 'a'), (InOut::Out, 1, 'a')])],
+        )
+        .unwrap();
+        looped.connect_pair(Left((InOut::Out, 'f', 1)), Left((InOut::In, 'f', 0)));
+        assert!(looped.to_monoidal_morphism().is_err());
+        let undriven = W::new(vec![(InOut::Out, 0, 'a')], vec![]).unwrap();
+        assert!(undriven.to_monoidal_morphism().is_err());
+    }
+
+    #[test]
+    fn hypergraph_interpretation() {
+        use super::{InOut, WiringDiagram};
+        use crate::category::{Composable, HasIdentity};
+        use crate::cospan::Cospan;
+        use crate::frobenius::HypergraphCategory;
+        use crate::monoidal::tensor_pair;
+        use either::{Left, Right};
+        type W = WiringDiagram<char, char, usize>;
+
+        /*
+        f then g with the input also copied out
+        */
+        let f = Cospan::new(vec![0], vec![1], vec!['a', 'b']);
+        let g = Cospan::new(vec![0], vec![1, 1], vec!['b', 'a']);
+        let mut diagram = W::new(
+            vec![
+                (InOut::In, 0, 'a'),
+                (InOut::Out, 1, 'a'),
+                (InOut::Out, 2, 'a'),
+                (InOut::Out, 3, 'a'),
+            ],
+            vec![
+                (
+                    'g',
+                    vec![
+                        (InOut::Out, 0, 'a'),
+                        (InOut::In, 1, 'b'),
+                        (InOut::Out, 2, 'a'),
+                    ],
+                ),
+                ('f', vec![(InOut::In, 0, 'a'), (InOut::Out, 1, 'b')]),
+            ],
+        )
+        .unwrap();
+        diagram.connect_pair(Right((InOut::In, 0)), Left((InOut::In, 'f', 0)));
+        diagram.connect_pair(Right((InOut::In, 0)), Right((InOut::Out, 3)));
+        diagram.connect_pair(Left((InOut::Out, 'f', 1)), Left((InOut::In, 'g', 1)));
+        diagram.connect_pair(Left((InOut::Out, 'g', 0)), Right((InOut::Out, 1)));
+        diagram.connect_pair(Left((InOut::Out, 'g', 2)), Right((InOut::Out, 2)));
+        let fill = |circle: &char| match circle {
+            'f' => Ok(f.clone()),
+            _ => Ok(g.clone()),
+        };
+        let interpreted: Cospan<char> = diagram.interpret(fill).unwrap();
+        let id_a = Cospan::identity(&vec!['a']);
+        let expected = Cospan::split('a')
+            .compose(&tensor_pair(f.clone(), id_a.clone()))
+            .unwrap()
+            .compose(&tensor_pair(g.clone(), id_a))
+            .unwrap();
+        assert!(interpreted.is_isomorphic_to(&expected));
+
+        /*
+        a filling with the wrong types is refused
+        */
+        assert!(diagram
+            .interpret(|_| Ok(Cospan::<char>::identity(&vec!['a'])))
+            .is_err());
+
+        /*
+        feeding the output of f back into its input closes the loop
+            leaving a single middle node and nothing on the outside
+        */
+        let mut looped = W::new(
+            vec![],
+            vec![('f', vec![(InOut::In, 0, 'a'), (InOut::Out, 1, 'a')])],
+        )
+        .unwrap();
+        looped.connect_pair(Left((InOut::Out, 'f', 1)), Left((InOut::In, 'f', 0)));
+        let traced: Cospan<char> = looped
+            .interpret(|_| Ok(Cospan::identity(&vec!['a'])))
+            .unwrap();
+        assert!(traced.is_isomorphic_to(&Cospan::new(vec![], vec![], vec!['a'])));
+
+        /*
+        with no circles at all a bare wire is an identity
+        */
+        let mut wire = W::new(vec![(InOut::In, 0, 'b'), (InOut::Out, 1, 'b')], vec![]).unwrap();
+        wire.connect_pair(Right((InOut::In, 0)), Right((InOut::Out, 1)));
+        let bare: Cospan<char> = wire.interpret(|_| unreachable!()).unwrap();
+        assert!(bare.is_isomorphic_to(&Cospan::identity(&vec!['b'])));
     }
 }